@@ -1,5 +1,6 @@
 use rspotify::client::SpotifyBuilder;
 use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder};
+use rspotify::prelude::*;
 
 use std::collections::HashSet;
 
@@ -45,7 +46,7 @@ async fn main() {
     spotify.prompt_for_user_token().await.unwrap();
 
     // Running the requests
-    let history = spotify.current_user_recently_played(10).await;
+    let history = spotify.current_user_recently_played(10, None).await;
 
     println!("Response: {:?}", history);
 }
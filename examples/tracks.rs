@@ -1,5 +1,6 @@
 use rspotify::client::SpotifyBuilder;
 use rspotify::oauth2::CredentialsBuilder;
+use rspotify::prelude::*;
 
 #[tokio::main]
 async fn main() {
@@ -1,5 +1,6 @@
 use rspotify::client::SpotifyBuilder;
 use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder};
+use rspotify::prelude::*;
 
 use std::collections::HashSet;
 
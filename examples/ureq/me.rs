@@ -1,47 +1,23 @@
-use rspotify::client::SpotifyBuilder;
-use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder};
-
-use std::collections::HashSet;
+use rspotify::client::Spotify;
+use rspotify::prelude::*;
 
 fn main() {
     // You can use any logger for debugging.
     env_logger::init();
 
-    // Set RSPOTIFY_CLIENT_ID, RSPOTIFY_CLIENT_SECRET and
-    // RSPOTIFY_REDIRECT_URI in an .env file or export them manually:
+    // Set RSPOTIFY_CLIENT_ID, RSPOTIFY_CLIENT_SECRET, RSPOTIFY_REDIRECT_URI
+    // and RSPOTIFY_SCOPE in an .env file or export them manually:
     //
     // export RSPOTIFY_CLIENT_ID="your client_id"
     // export RSPOTIFY_CLIENT_SECRET="secret"
+    // export RSPOTIFY_REDIRECT_URI="http://localhost:8888/callback"
+    // export RSPOTIFY_SCOPE="user-read-playback-state"
     //
-    // These will then be read with `from_env`.
-    //
-    // Otherwise, set client_id and client_secret explictly:
-    //
-    // let creds = CredentialsBuilder::default()
-    //     .client_id("this-is-my-client-id")
-    //     .client_secret("this-is-my-client-secret")
-    //     .build()
-    //     .unwrap();
-    let creds = CredentialsBuilder::from_env().build().unwrap();
-
-    // Or set the redirect_uri explictly:
-    //
-    // let oauth = OAuthBuilder::default()
-    //     .redirect_uri("http://localhost:8888/callback")
-    //     .build()
-    //     .unwrap();
-    let mut scope = HashSet::new();
-    scope.insert("user-read-playback-state".to_owned());
-    let oauth = OAuthBuilder::from_env().scope(scope).build().unwrap();
-
-    let mut spotify = SpotifyBuilder::default()
-        .credentials(creds)
-        .oauth(oauth)
-        .build()
-        .unwrap();
-
-    // Obtaining the access token
-    spotify.prompt_for_user_token().unwrap();
+    // `from_env_interactive` will read them, use the cached token if
+    // possible, and otherwise ask for the user's authorization in the
+    // terminal. A custom `AuthorizationPrompt` may be used instead with
+    // `from_env_with_prompt` when there's no terminal available.
+    let spotify = Spotify::from_env_interactive().unwrap();
 
     let user = spotify.me();
     println!("Request: {:?}", user);
@@ -1,6 +1,7 @@
 use rspotify::client::SpotifyBuilder;
 use rspotify::model::{Country, Market, SearchType};
 use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder};
+use rspotify::prelude::*;
 
 use std::collections::HashSet;
 
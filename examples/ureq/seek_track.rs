@@ -1,7 +1,9 @@
 use rspotify::client::SpotifyBuilder;
 use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder};
+use rspotify::prelude::*;
 
 use std::collections::HashSet;
+use std::time::Duration;
 
 fn main() {
     // You can use any logger for debugging.
@@ -43,7 +45,7 @@ fn main() {
     // Obtaining the access token
     spotify.prompt_for_user_token().unwrap();
 
-    match spotify.seek_track(25000, None) {
+    match spotify.seek_track(Duration::from_secs(25), None) {
         Ok(_) => println!("change to previous playback successful"),
         Err(_) => eprintln!("change to previous playback failed"),
     }
@@ -15,7 +15,8 @@ use rocket_contrib::json;
 use rocket_contrib::json::JsonValue;
 use rocket_contrib::templates::Template;
 use rspotify::client::{ClientError, SpotifyBuilder};
-use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder, TokenBuilder};
+use rspotify::oauth2::{AuthorizeUrlOptions, CredentialsBuilder, OAuthBuilder, TokenBuilder};
+use rspotify::prelude::*;
 
 use std::fs;
 use std::{
@@ -152,7 +153,12 @@ fn index(mut cookies: Cookies) -> AppResponse {
         }
         Err(ClientError::InvalidAuth(msg)) => {
             println!("InvalidAuth msg {:?}", msg);
-            let auth_url = spotify.get_authorize_url(true).unwrap();
+            let auth_url = spotify
+                .get_authorize_url(&AuthorizeUrlOptions {
+                    show_dialog: true,
+                    ..Default::default()
+                })
+                .unwrap();
             context.insert("auth_url", auth_url);
             AppResponse::Template(Template::render("authorize", context))
         }
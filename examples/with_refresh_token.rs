@@ -17,6 +17,7 @@
 
 use rspotify::client::{Spotify, SpotifyBuilder};
 use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder};
+use rspotify::prelude::*;
 
 // Sample request that will follow some artists, print the user's
 // followed artists, and then unfollow the artists.
@@ -0,0 +1,212 @@
+//! Obtains and refreshes user tokens from the terminal, to provision the
+//! token cache of headless applications like daemons, which can then keep
+//! refreshing it on their own.
+//!
+//! The credentials and the redirect URI are read from the environment, like
+//! in [`CredentialsBuilder::from_env`] and [`OAuthBuilder::from_env`], or from
+//! an `.env` file. The secret isn't needed with `--pkce`.
+
+use rspotify::client::{ClientError, ClientResult, Spotify, SpotifyBuilder};
+use rspotify::oauth2::{
+    generate_code_verifier, CredentialsBuilder, OAuthBuilder, Token, TokenBuilder,
+};
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+const USAGE: &str = "\
+Usage: rspotify-auth <command> [options]
+
+Commands:
+    authorize    Asks for the user's authorization and saves the token
+    refresh      Refreshes the cached token with its refresh token
+    show         Prints the cached token
+    help         Prints this message
+
+Options:
+    --cache <path>      The token cache file, .spotify_token_cache.json by default
+    --scope <scopes>    The scopes to authorize, separated by spaces, instead of
+                        RSPOTIFY_SCOPE
+    --pkce              Authorizes with PKCE, without the client secret
+    --print             Prints the token instead of saving it
+
+Environment:
+    RSPOTIFY_CLIENT_ID, RSPOTIFY_CLIENT_SECRET, RSPOTIFY_REDIRECT_URI and
+    RSPOTIFY_SCOPE, which may also be set in an .env file.";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Authorize,
+    Refresh,
+    Show,
+    Help,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    cache: Option<PathBuf>,
+    scope: Option<String>,
+    pkce: bool,
+    print: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = match args.next().as_deref() {
+        Some("authorize") => Command::Authorize,
+        Some("refresh") => Command::Refresh,
+        Some("show") => Command::Show,
+        Some("help") | Some("--help") | Some("-h") => Command::Help,
+        Some(command) => return Err(format!("unknown command `{}`", command)),
+        None => return Err("missing command".to_owned()),
+    };
+
+    let mut parsed = Args {
+        command,
+        cache: None,
+        scope: None,
+        pkce: false,
+        print: false,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cache" => {
+                let path = args.next().ok_or("missing value of `--cache`")?;
+                parsed.cache = Some(PathBuf::from(path));
+            }
+            "--scope" => parsed.scope = Some(args.next().ok_or("missing value of `--scope`")?),
+            "--pkce" => parsed.pkce = true,
+            "--print" => parsed.print = true,
+            arg => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// The client for the given options, without a token.
+fn client(args: &Args) -> ClientResult<Spotify> {
+    // Without a secret the client ID is sent in the token requests instead,
+    // which is what clients authorized with PKCE need to refresh them.
+    let mut creds = CredentialsBuilder::from_env();
+    if args.pkce || env::var("RSPOTIFY_CLIENT_SECRET").is_err() {
+        creds.secret("");
+    }
+    let creds = creds.build().map_err(ClientError::InvalidAuth)?;
+
+    let mut oauth = OAuthBuilder::from_env();
+    if let Some(scope) = &args.scope {
+        oauth.scope(scope.split_whitespace().map(|x| x.to_owned()).collect());
+    }
+    if args.pkce {
+        oauth.code_verifier(generate_code_verifier());
+    }
+    let oauth = oauth.build().map_err(ClientError::InvalidAuth)?;
+
+    let mut spotify = SpotifyBuilder::default();
+    spotify.credentials(creds).oauth(oauth);
+    if let Some(cache) = &args.cache {
+        spotify.cache_path(cache.clone());
+    }
+    spotify.build().map_err(ClientError::InvalidAuth)
+}
+
+fn cached_token(spotify: &Spotify) -> ClientResult<Token> {
+    TokenBuilder::from_cache(&spotify.cache_path)
+        .build()
+        .map_err(|_| {
+            ClientError::CacheFile(format!(
+                "no token cached in {}",
+                spotify.cache_path.display()
+            ))
+        })
+}
+
+fn print_token(token: &Token) -> ClientResult<()> {
+    println!("{}", serde_json::to_string_pretty(token)?);
+    Ok(())
+}
+
+/// Prints the token or saves it into the cache file, as requested.
+fn finish(spotify: &Spotify, args: &Args) -> ClientResult<()> {
+    let token = spotify.token.as_ref().expect("the token was just obtained");
+    if args.print {
+        print_token(token)
+    } else {
+        spotify.write_token_cache()?;
+        eprintln!("Token saved in {}", spotify.cache_path.display());
+        Ok(())
+    }
+}
+
+async fn run(args: Args) -> ClientResult<()> {
+    if args.command == Command::Help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let mut spotify = client(&args)?;
+    if args.command == Command::Show {
+        return print_token(&cached_token(&spotify)?);
+    }
+
+    if args.command == Command::Refresh {
+        let refresh_token = cached_token(&spotify)?.refresh_token.ok_or_else(|| {
+            ClientError::CacheFile("the cached token can't be refreshed".to_owned())
+        })?;
+        match spotify
+            .refresh_user_token_without_cache(&refresh_token)
+            .await
+        {
+            // The token is still valid for the remaining scopes.
+            Ok(()) | Err(ClientError::ScopesReduced { .. }) => {}
+            Err(err) => return Err(err),
+        }
+    } else {
+        spotify.prompt_for_user_token_without_cache().await?;
+    }
+    finish(&spotify, &args)
+}
+
+#[tokio::main]
+async fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(err) = run(args).await {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse(&["authorize", "--pkce", "--scope", "user-read-private"]).unwrap();
+        assert_eq!(args.command, Command::Authorize);
+        assert!(args.pkce);
+        assert!(!args.print);
+        assert_eq!(args.scope.as_deref(), Some("user-read-private"));
+
+        let args = parse(&["refresh", "--cache", "token.json", "--print"]).unwrap();
+        assert_eq!(args.command, Command::Refresh);
+        assert_eq!(args.cache, Some(PathBuf::from("token.json")));
+        assert!(args.print);
+
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["login"]).is_err());
+        assert!(parse(&["show", "--cache"]).is_err());
+        assert!(parse(&["show", "--verbose"]).is_err());
+    }
+}
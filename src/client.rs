@@ -1,20 +1,42 @@
 //! Client to Spotify API endpoint
 
-use chrono::prelude::*;
 use derive_builder::Builder;
-use log::error;
 use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use serde_json::map::Map;
-use serde_json::{json, Value};
+use serde_json::Value;
 use thiserror::Error;
 
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use super::http::{HTTPClient, Query};
-use super::json_insert;
+use super::http::retry::default_sleeper;
+use super::http::single_flight::SingleFlight;
+use super::http::Headers;
 use super::model::*;
-use super::oauth2::{Credentials, OAuth, Token};
+use super::oauth2::{Credentials, OAuth, RenewalLock, Token, TokenCache};
+use super::scopes::scopes_for;
+
+pub use super::endpoints::{BaseClient, OAuthClient};
+pub use super::http::dynamic::{DynBaseHTTPClient, DynHTTPClient};
+pub use super::http::hook::{
+    DownloadProgress, IncomingResponse, OutgoingRequest, ProgressHook, RateLimitHook,
+    RateLimitInfo, RequestHook, ResponseHook,
+};
+#[cfg(feature = "replay")]
+pub use super::http::replay::{
+    Cassette, Interaction, RecordedRequest, RecordedResponse, ReplayClient, ReplayMode, REDACTED,
+};
+#[cfg(feature = "client-ureq")]
+pub use super::http::retry::ThreadSleeper;
+#[cfg(feature = "client-reqwest")]
+pub use super::http::retry::TokioSleeper;
+pub use super::http::retry::{Deadline, RetryPolicy, Sleeper};
+pub use super::http::scheduler::{Scheduler, SchedulerSession};
+pub use super::http::{
+    BaseHTTPClient, BodyLimits, BodyMetrics, Conditional, ConnectionConfig, Form, HTTPClient, Query,
+};
 
 /// Possible errors returned from the `rspotify` client.
 #[derive(Debug, Error)]
@@ -26,25 +48,46 @@ pub enum ClientError {
     #[error("request unauthorized")]
     Unauthorized,
 
+    /// Raised before making a request to the API when the client has no
+    /// access token yet.
+    #[error(
+        "no access token: set `token` in the builder, or obtain one with \
+         `request_client_token` or `prompt_for_user_token` first"
+    )]
+    NotAuthenticated,
+
     #[error("exceeded request limit")]
     RateLimited(Option<usize>),
 
-    #[error("request error: {0}")]
-    Request(String),
+    /// Raised when the request couldn't be sent or its response couldn't be
+    /// read, like when the connection fails. The underlying error of the HTTP
+    /// client is its source.
+    #[error("request error")]
+    Request {
+        /// The URL of the request, if known.
+        endpoint: Option<String>,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// Raised when no response was received in time, like when requesting a
+    /// token with `token_timeout`.
+    #[error("request timed out")]
+    Timeout,
 
     #[error("status code {0}: {1}")]
     StatusCode(u16, String),
 
-    #[error("spotify error: {0}")]
+    #[error("spotify error")]
     API(#[from] APIError),
 
-    #[error("json parse error: {0}")]
+    #[error("json parse error")]
     ParseJSON(#[from] serde_json::Error),
 
-    #[error("url parse error: {0}")]
+    #[error("url parse error")]
     ParseURL(#[from] url::ParseError),
 
-    #[error("input/output error: {0}")]
+    #[error("input/output error")]
     IO(#[from] std::io::Error),
 
     #[cfg(feature = "cli")]
@@ -53,10 +96,128 @@ pub enum ClientError {
 
     #[error("cache file error: {0}")]
     CacheFile(String),
+
+    /// Raised when a refreshed token was granted fewer scopes than requested.
+    #[error("the refreshed token is missing the scopes {missing:?}")]
+    ScopesReduced { missing: Vec<String> },
+
+    /// Raised before making a request when one of the given IDs is invalid.
+    #[error("id error")]
+    InvalidId(#[from] IdError),
+
+    #[cfg(feature = "replay")]
+    #[error("replay error: {0}")]
+    Replay(String),
+
+    /// The response body was longer than the given limit, in bytes.
+    #[error("response body larger than {0} bytes")]
+    BodyTooLarge(usize),
+
+    /// A [`PageCursor`](crate::pagination::PageCursor) of the wrong kind was
+    /// given to resume a paginated endpoint.
+    #[error("invalid page cursor: {0}")]
+    InvalidCursor(String),
+
+    /// Raised before making a request when a parameter is out of the range
+    /// allowed by the endpoint, like a `limit` over 50.
+    #[error("invalid {name}: {value} is not between {min} and {max}")]
+    InvalidParameter {
+        name: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+
+    /// Raised before making a request when one of its headers can't be sent,
+    /// like one added by a request hook with a line break in its value.
+    #[error("invalid header {name:?}: {reason}")]
+    InvalidHeader { name: String, reason: &'static str },
+
+    /// The device isn't among the user's available devices.
+    #[error("device not found: {0}")]
+    DeviceNotFound(String),
+
+    /// The device can't be controlled through the Web API.
+    #[error("device is restricted: {0}")]
+    DeviceRestricted(String),
+
+    /// Raised before making a request when `check_scopes` is enabled and the
+    /// token lacks the scopes required by the endpoint.
+    #[error("insufficient scope: requires {needed:?}, but the token holds {held:?}")]
+    InsufficientScope {
+        needed: Vec<String>,
+        held: Vec<String>,
+    },
 }
 
 pub type ClientResult<T> = Result<T, ClientError>;
 
+impl ClientError {
+    /// The HTTP status code of the failed request, if it was answered by
+    /// Spotify. The same errors are reported differently by each backend, so
+    /// this should be used instead of matching the variants.
+    #[doc(alias = "status")]
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            ClientError::Unauthorized => Some(401),
+            ClientError::RateLimited(_) => Some(429),
+            ClientError::StatusCode(code, _) => Some(*code),
+            ClientError::API(APIError::Regular { status, .. })
+            | ClientError::API(APIError::Player { status, .. }) => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// The URL of the request that failed, if known. Only the errors raised
+    /// while sending the request, and the ones returned by Spotify with an
+    /// error object, include it.
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            ClientError::Request { endpoint, .. } => endpoint.as_deref(),
+            ClientError::API(err) => err.endpoint(),
+            _ => None,
+        }
+    }
+
+    /// A [`ClientError::Request`] for the request to `endpoint` that failed
+    /// with `err`.
+    pub(in crate) fn request<E>(endpoint: Option<String>, err: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        ClientError::Request {
+            endpoint,
+            source: err.into(),
+        }
+    }
+}
+
+/// Errors are converted to the closest kind of IO error, keeping them as the
+/// inner error, like for the `Read` implementations built on the client. IO
+/// errors are unwrapped.
+impl From<ClientError> for std::io::Error {
+    fn from(err: ClientError) -> Self {
+        use std::io::ErrorKind;
+
+        let kind = match err {
+            ClientError::IO(err) => return err,
+            ClientError::Timeout => ErrorKind::TimedOut,
+            ClientError::Unauthorized
+            | ClientError::NotAuthenticated
+            | ClientError::InsufficientScope { .. } => ErrorKind::PermissionDenied,
+            ClientError::ParseJSON(_) | ClientError::BodyTooLarge(_) => ErrorKind::InvalidData,
+            ClientError::ParseURL(_)
+            | ClientError::InvalidId(_)
+            | ClientError::InvalidCursor(_)
+            | ClientError::InvalidParameter { .. } => ErrorKind::InvalidInput,
+            ClientError::DeviceNotFound(_) => ErrorKind::NotFound,
+            ref err if err.status_code() == Some(404) => ErrorKind::NotFound,
+            _ => ErrorKind::Other,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
 /// Matches errors that are returned from the Spotfiy
 /// API as part of the JSON response object.
 #[derive(Debug, Error, Deserialize)]
@@ -64,7 +225,13 @@ pub enum APIError {
     /// See [Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-errorobject)
     #[error("{status}: {message}")]
     #[serde(alias = "error")]
-    Regular { status: u16, message: String },
+    Regular {
+        status: u16,
+        message: String,
+        /// The URL of the request, which isn't part of the response.
+        #[serde(skip)]
+        endpoint: Option<String>,
+    },
 
     /// See [Play Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-playererrorobject)
     #[error("{status} ({reason}): {message}")]
@@ -73,17 +240,118 @@ pub enum APIError {
         status: u16,
         message: String,
         reason: String,
+        /// The URL of the request, which isn't part of the response.
+        #[serde(skip)]
+        endpoint: Option<String>,
     },
 }
 
+impl APIError {
+    /// The URL of the request that failed, if known.
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            APIError::Regular { endpoint, .. } | APIError::Player { endpoint, .. } => {
+                endpoint.as_deref()
+            }
+        }
+    }
+
+    /// The same error, for the request to `url`. Custom HTTP clients can use
+    /// it when parsing the error objects.
+    pub fn with_endpoint(mut self, url: String) -> Self {
+        match &mut self {
+            APIError::Regular { endpoint, .. } | APIError::Player { endpoint, .. } => {
+                *endpoint = Some(url)
+            }
+        }
+        self
+    }
+}
+
 pub const DEFAULT_API_PREFIX: &str = "https://api.spotify.com/v1/";
 pub const DEFAULT_CACHE_PATH: &str = ".spotify_token_cache.json";
+pub const DEFAULT_CACHE_KEY: &str = "default";
+pub const DEFAULT_AUTHORIZE_URL: &str = "https://accounts.spotify.com/authorize";
+pub const DEFAULT_TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+/// The maximum number of URIs accepted by [`OAuthClient::start_playback`].
+pub const PLAYBACK_URIS_LIMIT: usize = 100;
+/// The shortest time between the volume changes of
+/// [`OAuthClient::fade_volume`].
+pub const FADE_VOLUME_INTERVAL: Duration = Duration::from_millis(500);
+/// How long [`OAuthClient::transfer_playback_to`] waits for a device that
+/// just appeared before trying again.
+pub const TRANSFER_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The maximum number of items that can be added to a playlist in a single
+/// request. Longer lists are split in multiple requests.
+pub const PLAYLIST_ITEMS_LIMIT: usize = 100;
+/// The maximum `limit` of most of the paginated endpoints.
+pub const PAGE_LIMIT: u32 = 50;
+/// The maximum number of tracks returned by [`BaseClient::recommendations`].
+pub const RECOMMENDATIONS_LIMIT: u32 = 100;
+/// The offsets of [`BaseClient::search`] have to be lower than this.
+pub const SEARCH_OFFSET_LIMIT: u32 = 100_000;
+/// The maximum number of playlists per page, used when requesting all of
+/// them.
+pub(in crate) const PLAYLISTS_PAGE_SIZE: u32 = 50;
+/// The maximum number of recently played tracks per page.
+pub(in crate) const RECENTLY_PLAYED_PAGE_SIZE: u32 = 50;
+/// The maximum number of albums of an artist per page.
+pub(in crate) const ARTIST_ALBUMS_PAGE_SIZE: u32 = 50;
+/// The maximum number of tracks of an album per page.
+pub(in crate) const ALBUM_TRACKS_PAGE_SIZE: u32 = 50;
+/// The maximum number of saved albums per page.
+pub(in crate) const SAVED_ALBUMS_PAGE_SIZE: u32 = 50;
+/// The maximum number of saved tracks per page.
+pub(in crate) const SAVED_TRACKS_PAGE_SIZE: u32 = 50;
+/// The maximum number of saved shows per page.
+pub(in crate) const SAVED_SHOWS_PAGE_SIZE: u32 = 50;
+/// The maximum number of saved audiobooks per page.
+pub(in crate) const SAVED_AUDIOBOOKS_PAGE_SIZE: u32 = 50;
+/// The maximum number of items of a playlist per page, which is also how many
+/// come with the playlist.
+pub(in crate) const PLAYLIST_TRACKS_PAGE_SIZE: u32 = 100;
+/// The maximum number of track IDs per request when removing saved tracks
+/// with [`OAuthClient::remove_saved_tracks_matching`], or checking them with
+/// [`OAuthClient::current_user_saved_tracks_contains_map`].
+pub const SAVED_TRACKS_IDS_LIMIT: usize = 50;
+/// The maximum number of album IDs per request when saving, removing or
+/// checking saved albums. Longer lists are split in multiple requests.
+pub const SAVED_ALBUMS_IDS_LIMIT: usize = 20;
+/// The maximum number of audiobook IDs per request when saving, removing or
+/// checking saved audiobooks. Longer lists are split in multiple requests.
+pub const SAVED_AUDIOBOOKS_IDS_LIMIT: usize = 50;
+/// The maximum number of artist IDs per request when getting multiple
+/// artists. Longer lists are split in multiple requests.
+pub const ARTISTS_IDS_LIMIT: usize = 50;
+/// The maximum number of show IDs per request when checking saved shows with
+/// [`OAuthClient::check_users_saved_shows_map`].
+pub const SAVED_SHOWS_IDS_LIMIT: usize = 50;
+/// The maximum number of artist IDs per request when checking followed
+/// artists with [`OAuthClient::user_artist_check_follow_map`].
+pub const FOLLOWED_ARTISTS_IDS_LIMIT: usize = 50;
+/// The maximum size in bytes of the previews downloaded with
+/// [`Spotify::download_preview`]. They're 30 seconds long, which is usually
+/// less than half of this.
+#[cfg(feature = "previews")]
+pub const PREVIEW_SIZE_LIMIT: usize = 2 * 1024 * 1024;
 
 /// Spotify API object
+///
+/// It's `Send` and `Sync`, so it can be cloned into tasks or stored in the
+/// state of a web framework without wrappers. The HTTP client and the state
+/// that changes while it's used, like the token renewed automatically, the
+/// cached profile or the requests in flight, are behind `Arc`s shared between
+/// the clones, so a token renewed by one of them is used by all.
+///
+/// The configuration is copied into each clone instead, including `token`,
+/// the credentials and the hooks, so modifying it in a clone doesn't affect
+/// the rest. To avoid these copies, like when cloning it for every request
+/// of a web service, it can be wrapped into an `Arc` as well.
 #[derive(Builder, Debug, Clone)]
 pub struct Spotify {
-    /// Internal member to perform requests to the Spotify API.
-    #[builder(setter(skip))]
+    /// Internal member to perform requests to the Spotify API. A custom one
+    /// can be configured with [`BaseHTTPClient::from_native`].
+    #[builder(default)]
     pub(in crate) http: HTTPClient,
 
     /// The access token information required for requests to the Spotify API.
@@ -106,19 +374,196 @@ pub struct Spotify {
     #[builder(setter(into), default = "String::from(DEFAULT_API_PREFIX)")]
     pub prefix: String,
 
+    /// The URL of the accounts service where the user authorizes the client,
+    /// [`DEFAULT_AUTHORIZE_URL`] by default. It may be changed independently
+    /// of the API prefix, like when OAuth is brokered through a proxy.
+    #[builder(setter(into), default = "String::from(DEFAULT_AUTHORIZE_URL)")]
+    pub authorize_url: String,
+
+    /// The URL of the accounts service where the access tokens are requested,
+    /// [`DEFAULT_TOKEN_URL`] by default.
+    #[builder(setter(into), default = "String::from(DEFAULT_TOKEN_URL)")]
+    pub token_url: String,
+
     /// The cache file path, in case it's used. By default it's
     /// [`DEFAULT_CACHE_PATH`](DEFAULT_API_PREFIX).
     #[builder(default = r#"PathBuf::from(DEFAULT_CACHE_PATH)"#)]
     pub cache_path: PathBuf,
+
+    /// Where the tokens are cached instead of the file at `cache_path`, like
+    /// a [`MemoryTokenCache`](crate::oauth2::MemoryTokenCache) shared by the
+    /// clients of all the users of a web service. None is set by default.
+    #[builder(setter(strip_option), default)]
+    pub token_cache: Option<Arc<dyn TokenCache>>,
+
+    /// The key of the token of this client in the `token_cache`, like the ID
+    /// of its user. By default it's [`DEFAULT_CACHE_KEY`].
+    #[builder(setter(into), default = "String::from(DEFAULT_CACHE_KEY)")]
+    pub cache_key: String,
+
+    /// Whether the tokens renewed automatically with the Client Credentials
+    /// Flow are also read from and saved into the cache file. Disabled by
+    /// default.
+    #[builder(default)]
+    pub cache_renewed_tokens: bool,
+
+    /// The token obtained automatically once the configured one has expired,
+    /// shared between the clones of the client.
+    #[builder(setter(skip))]
+    pub(crate) renewed_token: Arc<Mutex<Option<Token>>>,
+
+    /// Held while the token is being renewed automatically.
+    #[builder(setter(skip))]
+    pub(in crate) renewal_lock: Arc<RenewalLock>,
+
+    /// Whether the current user's country is used as the market or country
+    /// in the endpoints where none is given, so that the results match what
+    /// the user sees in their app. The profile is only requested once, and
+    /// it requires a user token with the `user-read-private` scope. Disabled
+    /// by default.
+    #[builder(default)]
+    pub market_from_profile: bool,
+
+    /// Whether the current user's explicit content settings are followed:
+    /// when their filter is enabled, the explicit tracks, shows and episodes
+    /// are removed from the results of the search and the recommendations,
+    /// and by [`BaseClient::filter_explicit`]. The profile is only requested
+    /// once, and it requires a user token with the `user-read-private`
+    /// scope. Disabled by default.
+    #[builder(default)]
+    pub explicit_from_profile: bool,
+
+    /// The language of the localized fields in the responses, like the names
+    /// of the categories or the descriptions of the shows, sent as the
+    /// `Accept-Language` header of every request to the API. It's a language
+    /// tag like `es` or `es-MX`, and the browse endpoints with a `locale`
+    /// parameter can override it per request. The requests fail with
+    /// [`ClientError::InvalidHeader`] if it contains control characters, like
+    /// line breaks. By default Spotify answers in English or in the language
+    /// of the market.
+    #[builder(setter(into, strip_option), default)]
+    pub language: Option<String>,
+
+    /// The profile of the current user, cached once requested for
+    /// `market_from_profile` or `explicit_from_profile`.
+    #[builder(setter(skip))]
+    pub(in crate) profile: Arc<Mutex<Option<PrivateUser>>>,
+
+    /// The device used by the player endpoints when none is given, like
+    /// `DeviceSelector::Name("Office".to_owned())` for a daemon that controls
+    /// a single speaker. A device selected by name is looked up in the
+    /// available devices the first time, and its ID is reused afterwards.
+    /// By default the active device is used.
+    #[builder(setter(strip_option), default)]
+    pub default_device: Option<DeviceSelector>,
+
+    /// The ID of the `default_device`, cached once looked up.
+    #[builder(setter(skip))]
+    pub(in crate) default_device_id: Arc<Mutex<Option<String>>>,
+
+    /// Whether the scopes of the token are checked against the ones required
+    /// by each endpoint before making the request, as listed in
+    /// [`endpoint_scopes`](crate::scopes::endpoint_scopes). The check is
+    /// skipped when the token holds no scopes, since they're unknown.
+    /// Disabled by default.
+    #[builder(default)]
+    pub check_scopes: bool,
+
+    /// Whether identical GET requests made at the same time, like from
+    /// multiple tasks requesting the same artist, are merged into a single
+    /// one whose response is shared. If it fails, the rest of the requests
+    /// are made separately. Disabled by default.
+    #[builder(default)]
+    pub dedup_requests: bool,
+
+    /// The maximum time to wait for each request to the accounts service,
+    /// made when requesting or refreshing a token, so that a hung service
+    /// doesn't block the application forever. [`ClientError::Timeout`] is
+    /// returned once it's exceeded. The time the user takes to authorize the
+    /// application in a prompt isn't included, see `prompt_timeout` for that.
+    /// In the async version, the requests can also be cancelled by dropping
+    /// their future. No timeout is set by default.
+    #[builder(setter(strip_option), default)]
+    pub token_timeout: Option<Duration>,
+
+    /// The maximum time to wait for the user to authorize the application in
+    /// an [`AuthorizationPrompt`](crate::oauth2::AuthorizationPrompt), like
+    /// the [`CliPrompt`](crate::oauth2::CliPrompt), after which
+    /// [`ClientError::Timeout`] is returned. In the async version the
+    /// prompt's future is dropped. The blocking prompts can't be interrupted,
+    /// so they're time-boxed with `AuthorizationPrompt::prompt_with_timeout`,
+    /// which the prompts of this crate implement by waiting in a separate
+    /// thread. No timeout is set by default.
+    #[builder(setter(strip_option), default)]
+    pub prompt_timeout: Option<Duration>,
+
+    /// How the requests to the API that fail because of the rate limit or a
+    /// server error are retried. Retries are disabled by default. To bound
+    /// the total time of a request including its retries, see
+    /// [`Spotify::with_deadline`].
+    #[builder(default)]
+    pub retry_policy: RetryPolicy,
+
+    /// How the client waits between the retries, for the deadlines in async
+    /// mode, and in the endpoints that space out their requests, like
+    /// [`OAuthClient::fade_volume`]. The default one depends on the client
+    /// feature, which is `TokioSleeper` with `client-reqwest`, so a custom
+    /// async HTTP client running under another runtime needs its own.
+    #[builder(default = "default_sleeper()")]
+    pub sleeper: Arc<dyn Sleeper>,
+
+    /// A function run right before each request to the API is sent, which
+    /// can add headers to it, like the signatures required by an API
+    /// gateway. None is set by default.
+    #[builder(setter(strip_option), default)]
+    pub request_hook: Option<RequestHook>,
+
+    /// A function run whenever a request to the API is rate limited, even if
+    /// it's retried, so that it can be monitored. None is set by default.
+    #[builder(setter(strip_option), default)]
+    pub rate_limit_hook: Option<RateLimitHook>,
+
+    /// The session of the shared [`Scheduler`] that the requests to the API
+    /// belong to, so that they take turns with the requests of the clients
+    /// of other users under a global rate limit. None is set by default, so
+    /// the requests are sent right away.
+    #[builder(setter(strip_option), default)]
+    pub scheduler: Option<SchedulerSession>,
+
+    /// The GET requests in flight, for `dedup_requests`.
+    #[builder(setter(skip))]
+    pub(in crate) single_flight: SingleFlight,
 }
 
-// Endpoint-related methods for the client.
+// Configuration-related methods for the client.
 impl Spotify {
-    /// Returns the access token, or an error in case it's not configured.
-    pub(in crate) fn get_token(&self) -> ClientResult<&Token> {
-        self.token
-            .as_ref()
-            .ok_or_else(|| ClientError::InvalidAuth("no access token configured".to_string()))
+    /// Replaces the HTTP client with one that records or replays its
+    /// interactions, like `ReplayClient::replay("tests/cassettes/me.json")`.
+    #[cfg(feature = "replay")]
+    pub fn with_replay(mut self, http: ReplayClient) -> Self {
+        #[cfg(feature = "dyn-http")]
+        let http = http.into();
+        self.http = http;
+        self
+    }
+
+    /// Returns the access token, or [`ClientError::NotAuthenticated`] in case
+    /// there's none.
+    ///
+    /// The configured token takes precedence, unless it's expired and a new
+    /// one has been obtained automatically.
+    pub(crate) fn get_token(&self) -> ClientResult<Token> {
+        match &self.token {
+            Some(tok) if !tok.is_expired() => return Ok(tok.clone()),
+            _ => {}
+        }
+
+        self.renewed_token
+            .lock()
+            .unwrap()
+            .clone()
+            .or_else(|| self.token.clone())
+            .ok_or(ClientError::NotAuthenticated)
     }
 
     /// Returns the credentials, or an error in case it's not configured.
@@ -134,2008 +579,504 @@ impl Spotify {
             .as_ref()
             .ok_or_else(|| ClientError::InvalidAuth("no oauth configured".to_string()))
     }
+}
 
-    /// TODO: should be moved into a custom type
-    fn get_uri(&self, _type: Type, _id: &str) -> String {
-        format!("spotify:{}:{}", _type.to_string(), self.get_id(_type, _id))
+#[maybe_async]
+impl BaseClient for Spotify {
+    #[inline]
+    async fn endpoint_get(&self, url: &str, payload: &Query) -> ClientResult<String> {
+        let headers = self.auth_headers().await?;
+        self.get(url, Some(&headers), payload, None).await
     }
 
-    /// Converts a JSON response from Spotify into its model.
-    fn convert_result<'a, T: Deserialize<'a>>(&self, input: &'a str) -> ClientResult<T> {
-        serde_json::from_str::<T>(input).map_err(Into::into)
+    /// Deserializes the response into the model without decoding it into a
+    /// `String` first, which is preferred for large responses, such as
+    /// paginated ones.
+    #[inline]
+    async fn endpoint_get_as<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        let headers = self.auth_headers().await?;
+        self.get_json(url, Some(&headers), payload, None).await
+    }
+
+    /// Returns the market to use in an endpoint, which is the current user's
+    /// country when none is given and `market_from_profile` is enabled.
+    async fn default_market(&self, market: Option<Market>) -> ClientResult<Option<Market>> {
+        if market.is_some() || !self.market_from_profile {
+            return Ok(market);
+        }
+
+        let country = self.cached_profile().await?.country;
+        Ok(country.map(Market::Country))
     }
 
-    /// Get spotify id by type and id
-    /// TODO: should be rewritten and moved into a separate type for IDs
-    fn get_id(&self, _type: Type, id: &str) -> String {
-        let mut _id = id.to_owned();
-        let fields: Vec<&str> = _id.split(':').collect();
-        let len = fields.len();
-        if len >= 3 {
-            if _type.to_string() != fields[len - 2] {
-                error!(
-                    "expected id of type {:?} but found type {:?} {:?}",
-                    _type,
-                    fields[len - 2],
-                    _id
-                );
-            } else {
-                return fields[len - 1].to_owned();
-            }
-        }
-        let sfields: Vec<&str> = _id.split('/').collect();
-        let len: usize = sfields.len();
-        if len >= 3 {
-            if _type.to_string() != sfields[len - 2] {
-                error!(
-                    "expected id of type {:?} but found type {:?} {:?}",
-                    _type,
-                    sfields[len - 2],
-                    _id
-                );
-            } else {
-                return sfields[len - 1].to_owned();
-            }
+    /// Returns whether the explicit items are removed, which is when
+    /// `explicit_from_profile` is enabled and the current user's filter too.
+    async fn filters_explicit(&self) -> ClientResult<bool> {
+        if !self.explicit_from_profile {
+            return Ok(false);
         }
-        _id.to_owned()
+
+        Ok(self.cached_profile().await?.filters_explicit())
     }
+}
 
-    /// Append device ID to an API path.
-    fn append_device_id(&self, path: &str, device_id: Option<String>) -> String {
-        let mut new_path = path.to_string();
-        if let Some(_device_id) = device_id {
-            if path.contains('?') {
-                new_path.push_str(&format!("&device_id={}", _device_id));
-            } else {
-                new_path.push_str(&format!("?device_id={}", _device_id));
-            }
-        }
-        new_path
+#[maybe_async]
+impl OAuthClient for Spotify {
+    #[inline]
+    async fn endpoint_post(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let headers = self.auth_headers().await?;
+        self.post(url, Some(&headers), payload, None).await
     }
 
-    /// Returns a single track given the track's ID, URI or URL.
-    ///
-    /// Parameters:
-    /// - track_id - a spotify URI, URL or ID
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-track)
-    #[maybe_async]
-    pub async fn track(&self, track_id: &str) -> ClientResult<FullTrack> {
-        let trid = self.get_id(Type::Track, track_id);
-        let url = format!("tracks/{}", trid);
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn endpoint_put(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let headers = self.auth_headers().await?;
+        self.put(url, Some(&headers), payload, None).await
     }
 
-    /// Returns a list of tracks given a list of track IDs, URIs, or URLs.
-    ///
-    /// Parameters:
-    /// - track_ids - a list of spotify URIs, URLs or IDs
-    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-several-tracks)
-    #[maybe_async]
-    pub async fn tracks<'a>(
-        &self,
-        track_ids: impl IntoIterator<Item = &'a str>,
-        market: Option<Market>,
-    ) -> ClientResult<Vec<FullTrack>> {
-        // TODO: this can be improved
-        let mut ids: Vec<String> = vec![];
-        for track_id in track_ids {
-            ids.push(self.get_id(Type::Track, track_id));
-        }
+    #[inline]
+    async fn endpoint_delete(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let headers = self.auth_headers().await?;
+        self.delete(url, Some(&headers), payload, None).await
+    }
+
+    /// Returns an error in case `check_scopes` is enabled and the token
+    /// doesn't hold the scopes required by the given endpoint.
+    fn check_scopes(&self, endpoint: &str) -> ClientResult<()> {
+        let required = match scopes_for(endpoint) {
+            Some(required) if self.check_scopes => required,
+            _ => return Ok(()),
+        };
+        let held = match self.get_token() {
+            Ok(tok) if !tok.scope.is_empty() => tok.scope,
+            _ => return Ok(()),
+        };
 
-        let mut params = Query::new();
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
+        if required.is_satisfied_by(&held) {
+            return Ok(());
         }
+        let mut held = held.into_iter().collect::<Vec<_>>();
+        held.sort();
+        Err(ClientError::InsufficientScope {
+            needed: required.scopes().iter().map(|s| s.to_string()).collect(),
+            held,
+        })
+    }
+
+    /// Returns the ID of the current user from the cached profile, which is
+    /// shared with `market_from_profile` and `explicit_from_profile`.
+    async fn current_user_id(&self) -> ClientResult<String> {
+        Ok(self.cached_profile().await?.id)
+    }
+
+    /// Returns the device to use in the player endpoints, which is the
+    /// `default_device` when none is given. Fails with
+    /// [`ClientError::DeviceNotFound`] if it isn't available.
+    async fn default_device(&self, device_id: Option<String>) -> ClientResult<Option<String>> {
+        let selector = match &self.default_device {
+            Some(selector) if device_id.is_none() => selector,
+            _ => return Ok(device_id),
+        };
+        let name = match selector {
+            DeviceSelector::Id(id) => return Ok(Some(id.clone())),
+            DeviceSelector::Name(name) => name,
+        };
 
-        let url = format!("tracks/?ids={}", ids.join(","));
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result::<FullTracks>(&result).map(|x| x.tracks)
+        let cached = self.default_device_id.lock().unwrap().clone();
+        if cached.is_some() {
+            return Ok(cached);
+        }
+        let id = self
+            .device()
+            .await?
+            .into_iter()
+            .find(|device| selector.matches(device))
+            .and_then(|device| device.id)
+            .ok_or_else(|| ClientError::DeviceNotFound(name.clone()))?;
+        *self.default_device_id.lock().unwrap() = Some(id.clone());
+        Ok(Some(id))
     }
 
-    /// Returns a single artist given the artist's ID, URI or URL.
-    ///
-    /// Parameters:
-    /// - artist_id - an artist ID, URI or URL
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artist)
-    #[maybe_async]
-    pub async fn artist(&self, artist_id: &str) -> ClientResult<FullArtist> {
-        let trid = self.get_id(Type::Artist, artist_id);
-        let url = format!("artists/{}", trid);
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
+    /// Waits with the configured `sleeper`.
+    async fn sleep(&self, duration: Duration) {
+        self.sleeper.sleep(duration).await
     }
+}
 
-    /// Returns a list of artists given the artist IDs, URIs, or URLs.
-    ///
-    /// Parameters:
-    /// - artist_ids - a list of artist IDs, URIs or URLs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-artists)
-    #[maybe_async]
-    pub async fn artists<'a>(
-        &self,
-        artist_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<Vec<FullArtist>> {
-        let mut ids: Vec<String> = vec![];
-        for artist_id in artist_ids {
-            ids.push(self.get_id(Type::Artist, artist_id));
+impl Spotify {
+    /// Downloads the 30 seconds MP3 preview of the track with the configured
+    /// HTTP client, or returns `None` if it doesn't have one. Previews larger
+    /// than [`PREVIEW_SIZE_LIMIT`] fail with [`ClientError::BodyTooLarge`].
+    ///
+    /// Note: this method requires the `previews` feature.
+    #[cfg(feature = "previews")]
+    #[maybe_async]
+    pub async fn download_preview(&self, track: &FullTrack) -> ClientResult<Option<Vec<u8>>> {
+        match &track.preview_url {
+            Some(url) => self
+                .http
+                .get_bytes(url, None, PREVIEW_SIZE_LIMIT)
+                .await
+                .map(Some),
+            None => Ok(None),
         }
-        let url = format!("artists/?ids={}", ids.join(","));
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-
-        self.convert_result::<FullArtists>(&result)
-            .map(|x| x.artists)
     }
 
-    /// Get Spotify catalog information about an artist's albums.
-    ///
-    /// Parameters:
-    /// - artist_id - the artist ID, URI or URL
-    /// - album_type - 'album', 'single', 'appears_on', 'compilation'
-    /// - market - limit the response to one particular country.
-    /// - limit  - the number of albums to return
-    /// - offset - the index of the first album to return
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-albums)
+    /// Returns the profile of the current user, which is only requested the
+    /// first time.
     #[maybe_async]
-    pub async fn artist_albums(
-        &self,
-        artist_id: &str,
-        album_type: Option<AlbumType>,
-        market: Option<Market>,
-        limit: Option<u32>,
-        offset: Option<u32>,
-    ) -> ClientResult<Page<SimplifiedAlbum>> {
-        let mut params = Query::new();
-        if let Some(limit) = limit {
-            params.insert("limit".to_owned(), limit.to_string());
-        }
-        if let Some(album_type) = album_type {
-            params.insert("album_type".to_owned(), album_type.to_string());
-        }
-        if let Some(offset) = offset {
-            params.insert("offset".to_owned(), offset.to_string());
-        }
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
+    pub(in crate) async fn cached_profile(&self) -> ClientResult<PrivateUser> {
+        let cached = self.profile.lock().unwrap().clone();
+        match cached {
+            Some(profile) => Ok(profile),
+            None => {
+                let profile = self.me().await?;
+                *self.profile.lock().unwrap() = Some(profile.clone());
+                Ok(profile)
+            }
         }
-        let trid = self.get_id(Type::Artist, artist_id);
-        let url = format!("artists/{}/albums", trid);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
     }
 
-    /// Get Spotify catalog information about an artist's top 10 tracks by
-    /// country.
+    /// Returns a view of the client whose requests fail once the deadline
+    /// is reached, like `spotify.with_deadline(Duration::from_millis(500))`.
+    /// The deadline includes the retries of the requests and the waits
+    /// between them, so that latency-sensitive services can degrade instead
+    /// of waiting out a long `Retry-After`.
     ///
-    /// Parameters:
-    /// - artist_id - the artist ID, URI or URL
-    /// - market - limit the response to one particular country.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-top-tracks)
-    #[maybe_async]
-    pub async fn artist_top_tracks(
-        &self,
-        artist_id: &str,
-        market: Market,
-    ) -> ClientResult<Vec<FullTrack>> {
-        let mut params = Query::with_capacity(1);
+    /// Each endpoint called through the view may make multiple requests,
+    /// all of which share the same deadline. In the blocking version, the
+    /// deadline is only checked before each attempt.
+    pub fn with_deadline<D: Into<Deadline>>(&self, deadline: D) -> WithDeadline<'_> {
+        WithDeadline {
+            spotify: self,
+            deadline: deadline.into(),
+        }
+    }
+}
 
-        params.insert("market".to_owned(), market.to_string());
+// Metadata of the responses.
+impl Spotify {
+    /// Returns a view of the client that keeps the metadata of the last
+    /// response received through it, like how long it took or its headers,
+    /// which is useful to investigate slow requests or to include the
+    /// request ID in the support tickets for Spotify. The endpoints are
+    /// called through it as usual, and then
+    /// [`WithMeta::last_meta`] returns the metadata.
+    ///
+    /// The view is meant to be used for one request at a time, and the
+    /// metadata of the endpoints that make multiple requests is the one of
+    /// their last request. Custom HTTP clients need to implement
+    /// [`BaseHTTPClient::with_response_hook`] for it to be available.
+    pub fn with_meta(&self) -> WithMeta {
+        let responses = Arc::new(Mutex::new(Vec::<IncomingResponse>::new()));
+        let hook = {
+            let responses = Arc::clone(&responses);
+            let prefix = self.prefix.clone();
+            // The requests to the accounts service aren't included.
+            ResponseHook::new(move |response| {
+                if response.url.starts_with(&prefix) {
+                    responses.lock().unwrap().push(response.clone());
+                }
+            })
+        };
 
-        let trid = self.get_id(Type::Artist, artist_id);
-        let url = format!("artists/{}/top-tracks", trid);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result::<FullTracks>(&result).map(|x| x.tracks)
+        let mut spotify = self.clone();
+        spotify.http = spotify.http.with_response_hook(hook);
+        WithMeta {
+            spotify,
+            responses,
+            meta: Arc::default(),
+        }
     }
+}
 
-    /// Get Spotify catalog information about artists similar to an identified
-    /// artist. Similarity is based on analysis of the Spotify community's
-    /// listening history.
-    ///
-    /// Parameters:
-    /// - artist_id - the artist ID, URI or URL
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-related-artists)
-    #[maybe_async]
-    pub async fn artist_related_artists(&self, artist_id: &str) -> ClientResult<Vec<FullArtist>> {
-        let trid = self.get_id(Type::Artist, artist_id);
-        let url = format!("artists/{}/related-artists", trid);
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result::<FullArtists>(&result)
-            .map(|x| x.artists)
+/// The metadata of a response from the API, returned by
+/// [`WithMeta::last_meta`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// The URL of the request.
+    pub url: String,
+    /// The HTTP status code, which may be an error.
+    pub status: u16,
+    /// How long the request took, including its retries and the waits
+    /// between them.
+    pub duration: Duration,
+    /// How many times the request was retried.
+    pub retries: u32,
+    /// The headers of the response, with lowercase names.
+    pub headers: Headers,
+}
+
+impl ResponseMeta {
+    /// The identifier of the request given by the server with the
+    /// `X-Request-Id` header, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        self.headers.get("x-request-id").map(String::as_str)
     }
+}
 
-    /// Returns a single album given the album's ID, URIs or URL.
-    ///
-    /// Parameters:
-    /// - album_id - the album ID, URI or URL
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-album)
-    #[maybe_async]
-    pub async fn album(&self, album_id: &str) -> ClientResult<FullAlbum> {
-        let trid = self.get_id(Type::Album, album_id);
-        let url = format!("albums/{}", trid);
+/// A view of the client that keeps the metadata of its last response,
+/// returned by [`Spotify::with_meta`]. The endpoints are called through it as
+/// usual.
+#[derive(Clone, Debug)]
+pub struct WithMeta {
+    spotify: Spotify,
+    /// The responses received during the current request, including the ones
+    /// that were retried.
+    responses: Arc<Mutex<Vec<IncomingResponse>>>,
+    meta: Arc<Mutex<Option<ResponseMeta>>>,
+}
 
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
+impl WithMeta {
+    /// The metadata of the last request made through the view, or `None` if
+    /// no response was received, like when it couldn't be sent.
+    pub fn last_meta(&self) -> Option<ResponseMeta> {
+        self.meta.lock().unwrap().clone()
     }
 
-    /// Returns a list of albums given the album IDs, URIs, or URLs.
-    ///
-    /// Parameters:
-    /// - albums_ids - a list of album IDs, URIs or URLs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-albums)
-    #[maybe_async]
-    pub async fn albums<'a>(
-        &self,
-        album_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<Vec<FullAlbum>> {
-        let mut ids: Vec<String> = vec![];
-        for album_id in album_ids {
-            ids.push(self.get_id(Type::Album, album_id));
-        }
-        let url = format!("albums/?ids={}", ids.join(","));
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result::<FullAlbums>(&result).map(|x| x.albums)
+    fn start(&self) -> Instant {
+        self.responses.lock().unwrap().clear();
+        Instant::now()
     }
 
-    /// Search for an Item. Get Spotify catalog information about artists,
-    /// albums, tracks or playlists that match a keyword string.
-    ///
-    /// Parameters:
-    /// - q - the search query
-    /// - limit  - the number of items to return
-    /// - offset - the index of the first item to return
-    /// - type - the type of item to return. One of 'artist', 'album', 'track',
-    ///  'playlist', 'show' or 'episode'
-    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token.
-    /// - include_external: Optional.Possible values: audio. If
-    ///   include_external=audio is specified the response will include any
-    ///   relevant audio content that is hosted externally.  
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
-    #[maybe_async]
-    pub async fn search<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        q: &str,
-        _type: SearchType,
-        limit: L,
-        offset: O,
-        market: Option<Market>,
-        include_external: Option<IncludeExternal>,
-    ) -> ClientResult<SearchResult> {
-        let mut params = Query::with_capacity(4);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(10).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        params.insert("q".to_owned(), q.to_owned());
-        params.insert("type".to_owned(), _type.to_string());
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        if let Some(include_external) = include_external {
-            params.insert("include_external".to_owned(), include_external.to_string());
-        }
+    fn finish(&self, started: Instant) {
+        let responses = std::mem::take(&mut *self.responses.lock().unwrap());
+        let retries = responses.len().saturating_sub(1) as u32;
+        let meta = responses.into_iter().last().map(|response| ResponseMeta {
+            url: response.url,
+            status: response.status,
+            duration: started.elapsed(),
+            retries,
+            headers: response.headers,
+        });
+        *self.meta.lock().unwrap() = meta;
+    }
+}
 
-        let result = self.endpoint_get("search", &params).await?;
-        self.convert_result(&result)
+#[maybe_async]
+impl BaseClient for WithMeta {
+    async fn endpoint_get(&self, url: &str, payload: &Query) -> ClientResult<String> {
+        let started = self.start();
+        let result = self.spotify.endpoint_get(url, payload).await;
+        self.finish(started);
+        result
     }
 
-    /// Get Spotify catalog information about an album's tracks.
-    ///
-    /// Parameters:
-    /// - album_id - the album ID, URI or URL
-    /// - limit  - the number of items to return
-    /// - offset - the index of the first item to return
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-albums-tracks)
-    #[maybe_async]
-    pub async fn album_track<L: Into<Option<u32>>, O: Into<Option<u32>>>(
+    async fn endpoint_get_as<T: DeserializeOwned>(
         &self,
-        album_id: &str,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<SimplifiedTrack>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(50).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        let trid = self.get_id(Type::Album, album_id);
-        let url = format!("albums/{}/tracks", trid);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
+        url: &str,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        let started = self.start();
+        let result = self.spotify.endpoint_get_as(url, payload).await;
+        self.finish(started);
+        result
     }
 
-    /// Gets basic profile information about a Spotify User.
-    ///
-    /// Parameters:
-    /// - user - the id of the usr
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-profile)
-    #[maybe_async]
-    pub async fn user(&self, user_id: &str) -> ClientResult<PublicUser> {
-        let url = format!("users/{}", user_id);
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn default_market(&self, market: Option<Market>) -> ClientResult<Option<Market>> {
+        self.spotify.default_market(market).await
     }
 
-    /// Get full details about Spotify playlist.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-playlist)
-    #[maybe_async]
-    pub async fn playlist(
-        &self,
-        playlist_id: &str,
-        fields: Option<&str>,
-        market: Option<Market>,
-    ) -> ClientResult<FullPlaylist> {
-        let mut params = Query::new();
-        if let Some(fields) = fields {
-            params.insert("fields".to_owned(), fields.to_owned());
-        }
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
+    #[inline]
+    async fn filters_explicit(&self) -> ClientResult<bool> {
+        self.spotify.filters_explicit().await
+    }
+}
 
-        let plid = self.get_id(Type::Playlist, playlist_id);
-        let url = format!("playlists/{}", plid);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
+#[maybe_async]
+impl OAuthClient for WithMeta {
+    async fn endpoint_post(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let started = self.start();
+        let result = self.spotify.endpoint_post(url, payload).await;
+        self.finish(started);
+        result
     }
 
-    /// Get current user playlists without required getting his profile.
-    ///
-    /// Parameters:
-    /// - limit  - the number of items to return
-    /// - offset - the index of the first item to return
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-list-of-current-users-playlists)
-    #[maybe_async]
-    pub async fn current_user_playlists<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<SimplifiedPlaylist>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(50).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-
-        let result = self.endpoint_get("me/playlists", &params).await?;
-        self.convert_result(&result)
+    async fn endpoint_put(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let started = self.start();
+        let result = self.spotify.endpoint_put(url, payload).await;
+        self.finish(started);
+        result
     }
 
-    /// Gets playlists of a user.
-    ///
-    /// Parameters:
-    /// - user_id - the id of the usr
-    /// - limit  - the number of items to return
-    /// - offset - the index of the first item to return
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-list-users-playlists)
-    #[maybe_async]
-    pub async fn user_playlists<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        user_id: &str,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<SimplifiedPlaylist>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(50).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        let url = format!("users/{}/playlists", user_id);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
+    async fn endpoint_delete(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let started = self.start();
+        let result = self.spotify.endpoint_delete(url, payload).await;
+        self.finish(started);
+        result
     }
 
-    /// Gets playlist of a user.
-    ///
-    /// Parameters:
-    /// - user_id - the id of the user
-    /// - playlist_id - the id of the playlist
-    /// - fields - which fields to return
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-list-users-playlists)
-    #[maybe_async]
-    pub async fn user_playlist(
-        &self,
-        user_id: &str,
-        playlist_id: Option<&mut str>,
-        fields: Option<&str>,
-    ) -> ClientResult<FullPlaylist> {
-        let mut params = Query::new();
-        if let Some(fields) = fields {
-            params.insert("fields".to_owned(), fields.to_string());
-        }
-        match playlist_id {
-            Some(playlist_id) => {
-                let plid = self.get_id(Type::Playlist, playlist_id);
-                let url = format!("users/{}/playlists/{}", user_id, plid);
-                let result = self.endpoint_get(&url, &params).await?;
-                self.convert_result(&result)
-            }
-            None => {
-                let url = format!("users/{}/starred", user_id);
-                let result = self.endpoint_get(&url, &params).await?;
-                self.convert_result(&result)
-            }
-        }
+    #[inline]
+    fn check_scopes(&self, endpoint: &str) -> ClientResult<()> {
+        self.spotify.check_scopes(endpoint)
     }
 
-    /// Get full details of the tracks of a playlist owned by a user.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    /// - fields - which fields to return
-    /// - limit - the maximum number of tracks to return
-    /// - offset - the index of the first track to return
-    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-playlists-tracks)
-    #[maybe_async]
-    pub async fn playlist_tracks<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        playlist_id: &str,
-        fields: Option<&str>,
-        limit: L,
-        offset: O,
-        market: Option<Market>,
-    ) -> ClientResult<Page<PlaylistItem>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(50).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        if let Some(fields) = fields {
-            params.insert("fields".to_owned(), fields.to_owned());
-        }
-        let plid = self.get_id(Type::Playlist, playlist_id);
-        let url = format!("playlists/{}/tracks", plid);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn current_user_id(&self) -> ClientResult<String> {
+        self.spotify.current_user_id().await
     }
 
-    /// Creates a playlist for a user.
-    ///
-    /// Parameters:
-    /// - user_id - the id of the user
-    /// - name - the name of the playlist
-    /// - public - is the created playlist public
-    /// - description - the description of the playlist
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-create-playlist)
-    #[maybe_async]
-    pub async fn user_playlist_create<P: Into<Option<bool>>, D: Into<Option<String>>>(
-        &self,
-        user_id: &str,
-        name: &str,
-        public: P,
-        description: D,
-    ) -> ClientResult<FullPlaylist> {
-        let public = public.into().unwrap_or(true);
-        let description = description.into().unwrap_or_else(|| "".to_owned());
-        let params = json!({
-            "name": name,
-            "public": public,
-            "description": description
-        });
-        let url = format!("users/{}/playlists", user_id);
-        let result = self.endpoint_post(&url, &params).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn default_device(&self, device_id: Option<String>) -> ClientResult<Option<String>> {
+        self.spotify.default_device(device_id).await
     }
 
-    /// Changes a playlist's name and/or public/private state.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    /// - name - optional name of the playlist
-    /// - public - optional is the playlist public
-    /// - collaborative - optional is the playlist collaborative
-    /// - description - optional description of the playlist
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-change-playlist-details)
-    #[maybe_async]
-    pub async fn playlist_change_detail(
-        &self,
-        playlist_id: &str,
-        name: Option<&str>,
-        public: Option<bool>,
-        description: Option<String>,
-        collaborative: Option<bool>,
-    ) -> ClientResult<String> {
-        let mut params = json!({});
-        if let Some(name) = name {
-            json_insert!(params, "name", name);
-        }
-        if let Some(public) = public {
-            json_insert!(params, "public", public);
-        }
-        if let Some(collaborative) = collaborative {
-            json_insert!(params, "collaborative", collaborative);
-        }
-        if let Some(description) = description {
-            json_insert!(params, "description", description);
-        }
-        let url = format!("playlists/{}", playlist_id);
-        self.endpoint_put(&url, &params).await
+    #[inline]
+    async fn sleep(&self, duration: Duration) {
+        self.spotify.sleep(duration).await
     }
+}
 
-    /// Unfollows (deletes) a playlist for a user.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-unfollow-playlist)
-    #[maybe_async]
-    pub async fn playlist_unfollow(&self, playlist_id: &str) -> ClientResult<String> {
-        let url = format!("playlists/{}/followers", playlist_id);
-        self.endpoint_delete(&url, &json!({})).await
+/// A view of the client with a deadline for its requests, returned by
+/// [`Spotify::with_deadline`]. The endpoints are called through it as usual.
+#[derive(Clone, Copy, Debug)]
+pub struct WithDeadline<'a> {
+    spotify: &'a Spotify,
+    deadline: Deadline,
+}
+
+impl WithDeadline<'_> {
+    /// The deadline of the requests.
+    pub fn deadline(&self) -> Deadline {
+        self.deadline
     }
 
-    /// Adds tracks to a playlist.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    /// - track_ids - a list of track URIs, URLs or IDs
-    /// - position - the position to add the tracks
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-add-tracks-to-playlist)
+    /// The same as [`Spotify::get_if_none_match`], within the deadline.
     #[maybe_async]
-    pub async fn playlist_add_tracks<'a>(
+    pub async fn get_if_none_match<T: DeserializeOwned>(
         &self,
-        playlist_id: &str,
-        track_ids: impl IntoIterator<Item = &'a str>,
-        position: Option<i32>,
-    ) -> ClientResult<PlaylistResult> {
-        let plid = self.get_id(Type::Playlist, playlist_id);
-        let uris: Vec<String> = track_ids
-            .into_iter()
-            .map(|id| self.get_uri(Type::Track, id))
-            .collect();
-        let mut params = json!({ "uris": uris });
-        if let Some(position) = position {
-            json_insert!(params, "position", position);
-        }
-        let url = format!("playlists/{}/tracks", plid);
-        let result = self.endpoint_post(&url, &params).await?;
-        self.convert_result(&result)
+        url: &str,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<T>> {
+        self.spotify
+            .get_conditional(url, etag, Some(self.deadline))
+            .await
     }
+}
 
-    /// Replace all tracks in a playlist
-    ///
-    /// Parameters:
-    /// - user - the id of the user
-    /// - playlist_id - the id of the playlist
-    /// - tracks - the list of track ids to add to the playlist
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-reorder-or-replace-playlists-tracks)
-    #[maybe_async]
-    pub async fn playlist_replace_tracks<'a>(
-        &self,
-        playlist_id: &str,
-        track_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let plid = self.get_id(Type::Playlist, playlist_id);
-        let uris: Vec<String> = track_ids
-            .into_iter()
-            .map(|id| self.get_uri(Type::Track, id))
-            .collect();
-        // let mut params = Map::new();
-        // params.insert("uris".to_owned(), uris.into());
-        let params = json!({ "uris": uris });
-        let url = format!("playlists/{}/tracks", plid);
-        self.endpoint_put(&url, &params).await?;
-
-        Ok(())
+#[maybe_async]
+impl BaseClient for WithDeadline<'_> {
+    #[inline]
+    async fn endpoint_get(&self, url: &str, payload: &Query) -> ClientResult<String> {
+        let headers = self.spotify.auth_headers().await?;
+        self.spotify
+            .get(url, Some(&headers), payload, Some(self.deadline))
+            .await
     }
 
-    /// Reorder tracks in a playlist.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    /// - range_start - the position of the first track to be reordered
-    /// - range_length - optional the number of tracks to be reordered (default:
-    ///   1)
-    /// - insert_before - the position where the tracks should be inserted
-    /// - snapshot_id - optional playlist's snapshot ID
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-reorder-or-replace-playlists-tracks)
-    #[maybe_async]
-    pub async fn playlist_reorder_tracks<R: Into<Option<u32>>>(
+    #[inline]
+    async fn endpoint_get_as<T: DeserializeOwned>(
         &self,
-        playlist_id: &str,
-        range_start: i32,
-        range_length: R,
-        insert_before: i32,
-        snapshot_id: Option<String>,
-    ) -> ClientResult<PlaylistResult> {
-        let plid = self.get_id(Type::Playlist, playlist_id);
-        let mut params = json! ({
-            "range_start": range_start,
-            "range_length": range_length.into().unwrap_or(1),
-            "insert_before": insert_before
-        });
-        if let Some(snapshot_id) = snapshot_id {
-            json_insert!(params, "snapshot_id", snapshot_id);
-        }
+        url: &str,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        let headers = self.spotify.auth_headers().await?;
+        self.spotify
+            .get_json(url, Some(&headers), payload, Some(self.deadline))
+            .await
+    }
 
-        let url = format!("playlists/{}/tracks", plid);
-        let result = self.endpoint_put(&url, &params).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn default_market(&self, market: Option<Market>) -> ClientResult<Option<Market>> {
+        self.spotify.default_market(market).await
     }
 
-    /// Removes all occurrences of the given tracks from the given playlist.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    /// - track_ids - the list of track ids to add to the playlist
-    /// - snapshot_id - optional id of the playlist snapshot
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-tracks-playlist)
-    #[maybe_async]
-    pub async fn playlist_remove_all_occurrences_of_tracks<'a>(
-        &self,
-        playlist_id: &str,
-        track_ids: impl IntoIterator<Item = &'a str>,
-        snapshot_id: Option<String>,
-    ) -> ClientResult<PlaylistResult> {
-        let plid = self.get_id(Type::Playlist, playlist_id);
-        let uris: Vec<String> = track_ids
-            .into_iter()
-            .map(|id| self.get_uri(Type::Track, id))
-            .collect();
-
-        // TODO: this can be improved
-        let mut tracks: Vec<Map<String, Value>> = vec![];
-        for uri in uris {
-            let mut map = Map::new();
-            map.insert("uri".to_owned(), uri.into());
-            tracks.push(map);
-        }
-        let mut params = json!({ "tracks": tracks });
-        if let Some(snapshot_id) = snapshot_id {
-            json_insert!(params, "snapshot_id", snapshot_id);
-        }
-        let url = format!("playlists/{}/tracks", plid);
-        let result = self.endpoint_delete(&url, &params).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn filters_explicit(&self) -> ClientResult<bool> {
+        self.spotify.filters_explicit().await
     }
+}
 
-    /// Removes specfic occurrences of the given tracks from the given playlist.
-    ///
-    /// Parameters:
-    /// - playlist_id: the id of the playlist
-    /// - tracks: an array of map containing Spotify URIs of the tracks to
-    ///   remove with their current positions in the playlist. For example:
-    ///
-    /// ```json
-    /// {
-    ///    "tracks":[
-    ///       {
-    ///          "uri":"spotify:track:4iV5W9uYEdYUVa79Axb7Rh",
-    ///          "positions":[
-    ///             0,
-    ///             3
-    ///          ]
-    ///       },
-    ///       {
-    ///          "uri":"spotify:track:1301WleyT98MSxVHPZCA6M",
-    ///          "positions":[
-    ///             7
-    ///          ]
-    ///       }
-    ///    ]
-    /// }
-    /// ```
-    /// - snapshot_id: optional id of the playlist snapshot
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-tracks-playlist)
-    #[maybe_async]
-    pub async fn playlist_remove_specific_occurrences_of_tracks(
-        &self,
-        playlist_id: &str,
-        tracks: Vec<Map<String, Value>>,
-        snapshot_id: Option<String>,
-    ) -> ClientResult<PlaylistResult> {
-        // TODO: this can be improved
-        let plid = self.get_id(Type::Playlist, playlist_id);
-        let mut ftracks: Vec<Map<String, Value>> = vec![];
-        for track in tracks {
-            let mut map = Map::new();
-            if let Some(_uri) = track.get("uri") {
-                let uri = self.get_uri(Type::Track, &_uri.as_str().unwrap().to_owned());
-                map.insert("uri".to_owned(), uri.into());
-            }
-            if let Some(_position) = track.get("position") {
-                map.insert("position".to_owned(), _position.to_owned());
-            }
-            ftracks.push(map);
-        }
+#[maybe_async]
+impl OAuthClient for WithDeadline<'_> {
+    #[inline]
+    async fn endpoint_post(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let headers = self.spotify.auth_headers().await?;
+        self.spotify
+            .post(url, Some(&headers), payload, Some(self.deadline))
+            .await
+    }
 
-        let mut params = json!({ "tracks": ftracks });
-        if let Some(snapshot_id) = snapshot_id {
-            json_insert!(params, "snapshot_id", snapshot_id);
-        }
-        let url = format!("playlists/{}/tracks", plid);
-        let result = self.endpoint_delete(&url, &params).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn endpoint_put(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let headers = self.spotify.auth_headers().await?;
+        self.spotify
+            .put(url, Some(&headers), payload, Some(self.deadline))
+            .await
     }
 
-    /// Add the current authenticated user as a follower of a playlist.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-follow-playlist)
-    #[maybe_async]
-    pub async fn playlist_follow<P: Into<Option<bool>>>(
-        &self,
-        playlist_id: &str,
-        public: P,
-    ) -> ClientResult<()> {
-        let url = format!("playlists/{}/followers", playlist_id);
-
-        self.endpoint_put(
-            &url,
-            &json! ({
-                "public": public.into().unwrap_or(true)
-            }),
-        )
-        .await?;
-
-        Ok(())
+    #[inline]
+    async fn endpoint_delete(&self, url: &str, payload: &Value) -> ClientResult<String> {
+        let headers = self.spotify.auth_headers().await?;
+        self.spotify
+            .delete(url, Some(&headers), payload, Some(self.deadline))
+            .await
     }
 
-    /// Check to see if the given users are following the given playlist.
-    ///
-    /// Parameters:
-    /// - playlist_id - the id of the playlist
-    /// - user_ids - the ids of the users that you want to
-    /// check to see if they follow the playlist. Maximum: 5 ids.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-if-user-follows-playlist)
-    #[maybe_async]
-    pub async fn playlist_check_follow(
-        &self,
-        playlist_id: &str,
-        user_ids: &[String],
-    ) -> ClientResult<Vec<bool>> {
-        if user_ids.len() > 5 {
-            error!("The maximum length of user ids is limited to 5 :-)");
-        }
-        let url = format!(
-            "playlists/{}/followers/contains?ids={}",
-            playlist_id,
-            user_ids.join(",")
-        );
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
+    #[inline]
+    fn check_scopes(&self, endpoint: &str) -> ClientResult<()> {
+        self.spotify.check_scopes(endpoint)
     }
 
-    /// Get detailed profile information about the current user.
-    /// An alias for the 'current_user' method.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-current-users-profile)
-    #[maybe_async]
-    pub async fn me(&self) -> ClientResult<PrivateUser> {
-        let result = self.endpoint_get("me/", &Query::new()).await?;
-        self.convert_result(&result)
+    #[inline]
+    async fn current_user_id(&self) -> ClientResult<String> {
+        self.spotify.current_user_id().await
     }
 
-    /// Get detailed profile information about the current user.
-    /// An alias for the 'me' method.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-current-users-profile)
-    #[maybe_async]
-    pub async fn current_user(&self) -> ClientResult<PrivateUser> {
-        self.me().await
+    #[inline]
+    async fn default_device(&self, device_id: Option<String>) -> ClientResult<Option<String>> {
+        self.spotify.default_device(device_id).await
     }
 
-    /// Get information about the current users currently playing track.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
-    #[maybe_async]
-    pub async fn current_user_playing_track(
-        &self,
-    ) -> ClientResult<Option<CurrentlyPlayingContext>> {
-        let result = self
-            .get("me/player/currently-playing", None, &Query::new())
-            .await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            self.convert_result(&result)
-        }
+    #[inline]
+    async fn sleep(&self, duration: Duration) {
+        self.spotify.sleep(duration).await
     }
+}
 
-    /// Gets a list of the albums saved in the current authorized user's
-    /// "Your Music" library
-    ///
-    /// Parameters:
-    /// - limit - the number of albums to return
-    /// - offset - the index of the first album to return
-    /// - market - Provide this parameter if you want to apply Track Relinking.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-albums)
-    #[maybe_async]
-    pub async fn current_user_saved_albums<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<SavedAlbum>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        let result = self.endpoint_get("me/albums", &params).await?;
-        self.convert_result(&result)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oauth2::TokenBuilder;
+
+    use chrono::Utc;
+
+    use static_assertions::assert_impl_all;
+
+    // The client is meant to be cloned into tasks and stored in the state of
+    // web frameworks, which requires all of these.
+    assert_impl_all!(Spotify: Clone, Send, Sync);
+    assert_impl_all!(HTTPClient: Clone, Send, Sync);
+    assert_impl_all!(ClientError: Send, Sync);
+
+    /// The futures of the endpoints can be spawned into multi-threaded
+    /// runtimes.
+    #[cfg(feature = "__async")]
+    #[allow(dead_code)]
+    fn futures_are_send(spotify: &mut Spotify) {
+        fn assert_send<T: Send>(_: T) {}
+        assert_send(spotify.track("4uLU6hMCjMI75M1A2tKUQC"));
+        assert_send(spotify.current_user_saved_tracks_contains_map(vec!["4uLU6hMCjMI75M1A2tKUQC"]));
+        assert_send(spotify.refresh_user_token_without_cache("refresh-token"));
+        assert_send(spotify.prompt_for_user_token_with(&|_: &str| Ok(String::new())));
+        assert_send(spotify.start_playback_queued(
+            None,
+            Vec::new(),
+            Duration::from_millis(0),
+            |_, _| (),
+        ));
+        assert_send(crate::playback::sleep_timer(
+            spotify,
+            Duration::from_secs(60),
+            None,
+        ));
     }
 
-    /// Get a list of the songs saved in the current Spotify user's "Your Music"
-    /// library.
-    ///
-    /// Parameters:
-    /// - limit - the number of tracks to return
-    /// - offset - the index of the first track to return
-    /// - market - Provide this parameter if you want to apply Track Relinking.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-tracks)
-    #[maybe_async]
-    pub async fn current_user_saved_tracks<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<SavedTrack>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        let result = self.endpoint_get("me/tracks", &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Gets a list of the artists followed by the current authorized user.
-    ///
-    /// Parameters:
-    /// - limit - the number of tracks to return
-    /// - after - the last artist ID retrieved from the previous request
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-followed)
-    #[maybe_async]
-    pub async fn current_user_followed_artists<L: Into<Option<u32>>>(
-        &self,
-        limit: L,
-        after: Option<String>,
-    ) -> ClientResult<CursorBasedPage<FullArtist>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("type".to_owned(), Type::Artist.to_string());
-        if let Some(after) = after {
-            params.insert("after".to_owned(), after);
-        }
-
-        let result = self.endpoint_get("me/following", &params).await?;
-        self.convert_result::<CursorPageFullArtists>(&result)
-            .map(|x| x.artists)
-    }
-
-    /// Remove one or more tracks from the current user's "Your Music" library.
-    ///
-    /// Parameters:
-    /// - track_ids - a list of track URIs, URLs or IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-tracks-user)
-    #[maybe_async]
-    pub async fn current_user_saved_tracks_delete<'a>(
-        &self,
-        track_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let uris: Vec<String> = track_ids
-            .into_iter()
-            .map(|id| self.get_id(Type::Track, id))
-            .collect();
-        let url = format!("me/tracks/?ids={}", uris.join(","));
-        self.endpoint_delete(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Check if one or more tracks is already saved in the current Spotify
-    /// user’s "Your Music" library.
-    ///
-    /// Parameters:
-    /// - track_ids - a list of track URIs, URLs or IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-tracks)
-    #[maybe_async]
-    pub async fn current_user_saved_tracks_contains<'a>(
-        &self,
-        track_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<Vec<bool>> {
-        let uris: Vec<String> = track_ids
-            .into_iter()
-            .map(|id| self.get_id(Type::Track, id))
-            .collect();
-        let url = format!("me/tracks/contains/?ids={}", uris.join(","));
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
-    }
-
-    /// Save one or more tracks to the current user's "Your Music" library.
-    ///
-    /// Parameters:
-    /// - track_ids - a list of track URIs, URLs or IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-tracks-user)
-    #[maybe_async]
-    pub async fn current_user_saved_tracks_add<'a>(
-        &self,
-        track_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let uris: Vec<String> = track_ids
-            .into_iter()
-            .map(|id| self.get_id(Type::Track, id))
-            .collect();
-        let url = format!("me/tracks/?ids={}", uris.join(","));
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Get the current user's top artists.
-    ///
-    /// Parameters:
-    /// - limit - the number of entities to return
-    /// - offset - the index of the first entity to return
-    /// - time_range - Over what time frame are the affinities computed
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-top-artists-and-tracks)
-    #[maybe_async]
-    pub async fn current_user_top_artists<
-        L: Into<Option<u32>>,
-        O: Into<Option<u32>>,
-        T: Into<Option<TimeRange>>,
-    >(
-        &self,
-        limit: L,
-        offset: O,
-        time_range: T,
-    ) -> ClientResult<Page<FullArtist>> {
-        let mut params = Query::with_capacity(3);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        params.insert(
-            "time_range".to_owned(),
-            time_range
-                .into()
-                .unwrap_or(TimeRange::MediumTerm)
-                .to_string(),
-        );
-        let result = self.endpoint_get(&"me/top/artists", &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get the current user's top tracks.
-    ///
-    /// Parameters:
-    /// - limit - the number of entities to return
-    /// - offset - the index of the first entity to return
-    /// - time_range - Over what time frame are the affinities computed
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-top-artists-and-tracks)
-    #[maybe_async]
-    pub async fn current_user_top_tracks<
-        L: Into<Option<u32>>,
-        O: Into<Option<u32>>,
-        T: Into<Option<TimeRange>>,
-    >(
-        &self,
-        limit: L,
-        offset: O,
-        time_range: T,
-    ) -> ClientResult<Page<FullTrack>> {
-        let mut params = Query::with_capacity(3);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        params.insert(
-            "time_range".to_owned(),
-            time_range
-                .into()
-                .unwrap_or(TimeRange::MediumTerm)
-                .to_string(),
-        );
-        let result = self.endpoint_get("me/top/tracks", &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get the current user's recently played tracks.
-    ///
-    /// Parameters:
-    /// - limit - the number of entities to return
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-the-users-currently-playing-track)
-    #[maybe_async]
-    pub async fn current_user_recently_played<L: Into<Option<u32>>>(
-        &self,
-        limit: L,
-    ) -> ClientResult<CursorBasedPage<PlayHistory>> {
-        let mut params = Query::with_capacity(1);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(50).to_string());
-        let result = self
-            .endpoint_get("me/player/recently-played", &params)
-            .await?;
-        self.convert_result(&result)
-    }
-
-    /// Add one or more albums to the current user's "Your Music" library.
-    ///
-    /// Parameters:
-    /// - album_ids - a list of album URIs, URLs or IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-albums-user)
-    #[maybe_async]
-    pub async fn current_user_saved_albums_add<'a>(
-        &self,
-        album_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let uris: Vec<String> = album_ids
-            .into_iter()
-            .map(|id| self.get_id(Type::Album, id))
-            .collect();
-        let url = format!("me/albums/?ids={}", uris.join(","));
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Remove one or more albums from the current user's "Your Music" library.
-    ///
-    /// Parameters:
-    /// - album_ids - a list of album URIs, URLs or IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-albums-user)
-    #[maybe_async]
-    pub async fn current_user_saved_albums_delete<'a>(
-        &self,
-        album_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let uris: Vec<String> = album_ids
-            .into_iter()
-            .map(|id| self.get_id(Type::Album, id))
-            .collect();
-        let url = format!("me/albums/?ids={}", uris.join(","));
-        self.endpoint_delete(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Check if one or more albums is already saved in the current Spotify
-    /// user’s "Your Music” library.
-    ///
-    /// Parameters:
-    /// - album_ids - a list of album URIs, URLs or IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-albums)
-    #[maybe_async]
-    pub async fn current_user_saved_albums_contains<'a>(
-        &self,
-        album_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<Vec<bool>> {
-        let uris: Vec<String> = album_ids
-            .into_iter()
-            .map(|id| self.get_id(Type::Album, id))
-            .collect();
-        let url = format!("me/albums/contains/?ids={}", uris.join(","));
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
-    }
-
-    /// Follow one or more artists.
-    ///
-    /// Parameters:
-    /// - artist_ids - a list of artist IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-follow-artists-users)
-    #[maybe_async]
-    pub async fn user_follow_artists<'a>(
-        &self,
-        artist_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let url = format!(
-            "me/following?type=artist&ids={}",
-            artist_ids.into_iter().collect::<Vec<_>>().join(",")
-        );
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Unfollow one or more artists.
-    ///
-    /// Parameters:
-    /// - artist_ids - a list of artist IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-unfollow-artists-users)
-    #[maybe_async]
-    pub async fn user_unfollow_artists<'a>(
-        &self,
-        artist_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let url = format!(
-            "me/following?type=artist&ids={}",
-            artist_ids.into_iter().collect::<Vec<_>>().join(",")
-        );
-        self.endpoint_delete(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Check to see if the current user is following one or more artists or
-    /// other Spotify users.
-    ///
-    /// Parameters:
-    /// - artist_ids - the ids of the users that you want to
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-current-user-follows)
-    #[maybe_async]
-    pub async fn user_artist_check_follow<'a>(
-        &self,
-        artsit_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<Vec<bool>> {
-        let url = format!(
-            "me/following/contains?type=artist&ids={}",
-            artsit_ids.into_iter().collect::<Vec<_>>().join(",")
-        );
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
-    }
-
-    /// Follow one or more users.
-    ///
-    /// Parameters:
-    /// - user_ids - a list of artist IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-follow-artists-users)
-    #[maybe_async]
-    pub async fn user_follow_users<'a>(
-        &self,
-        user_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let url = format!(
-            "me/following?type=user&ids={}",
-            user_ids.into_iter().collect::<Vec<_>>().join(",")
-        );
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Unfollow one or more users.
-    ///
-    /// Parameters:
-    /// - user_ids - a list of artist IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-unfollow-artists-users)
-    #[maybe_async]
-    pub async fn user_unfollow_users<'a>(
-        &self,
-        user_ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<()> {
-        let url = format!(
-            "me/following?type=user&ids={}",
-            user_ids.into_iter().collect::<Vec<_>>().join(",")
-        );
-        self.endpoint_delete(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Get a list of Spotify featured playlists.
-    ///
-    /// Parameters:
-    /// - locale - The desired language, consisting of a lowercase ISO 639
-    ///   language code and an uppercase ISO 3166-1 alpha-2 country code,
-    ///   joined by an underscore.
-    /// - country - An ISO 3166-1 alpha-2 country code or the string from_token.
-    /// - timestamp - A timestamp in ISO 8601 format: yyyy-MM-ddTHH:mm:ss. Use
-    ///   this parameter to specify the user's local time to get results
-    ///   tailored for that specific date and time in the day
-    /// - limit - The maximum number of items to return. Default: 20.
-    ///   Minimum: 1. Maximum: 50
-    /// - offset - The index of the first item to return. Default: 0
-    ///   (the first object). Use with limit to get the next set of
-    ///   items.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-featured-playlists)
-    #[maybe_async]
-    pub async fn featured_playlists<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        locale: Option<String>,
-        country: Option<Market>,
-        timestamp: Option<DateTime<Utc>>,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<FeaturedPlaylists> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        if let Some(locale) = locale {
-            params.insert("locale".to_owned(), locale);
-        }
-        if let Some(market) = country {
-            params.insert("country".to_owned(), market.to_string());
-        }
-        if let Some(timestamp) = timestamp {
-            params.insert("timestamp".to_owned(), timestamp.to_rfc3339());
-        }
-        let result = self
-            .endpoint_get("browse/featured-playlists", &params)
-            .await?;
-        self.convert_result(&result)
-    }
-
-    /// Get a list of new album releases featured in Spotify.
-    ///
-    /// Parameters:
-    /// - country - An ISO 3166-1 alpha-2 country code or string from_token.
-    /// - limit - The maximum number of items to return. Default: 20.
-    ///   Minimum: 1. Maximum: 50
-    /// - offset - The index of the first item to return. Default: 0 (the first
-    ///   object). Use with limit to get the next set of items.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-new-releases)
-    #[maybe_async]
-    pub async fn new_releases<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        country: Option<Market>,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<SimplifiedAlbum>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        if let Some(market) = country {
-            params.insert("country".to_owned(), market.to_string());
-        }
-
-        let result = self.endpoint_get("browse/new-releases", &params).await?;
-        self.convert_result::<PageSimpliedAlbums>(&result)
-            .map(|x| x.albums)
-    }
-
-    /// Get a list of new album releases featured in Spotify
-    ///
-    /// Parameters:
-    /// - country - An ISO 3166-1 alpha-2 country code or string from_token.
-    /// - locale - The desired language, consisting of an ISO 639 language code
-    ///   and an ISO 3166-1 alpha-2 country code, joined by an underscore.
-    /// - limit - The maximum number of items to return. Default: 20.
-    ///   Minimum: 1. Maximum: 50
-    /// - offset - The index of the first item to return. Default: 0 (the first
-    ///   object). Use with limit to get the next set of items.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-categories)
-    #[maybe_async]
-    pub async fn categories<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        locale: Option<String>,
-        country: Option<Market>,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<Category>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        if let Some(locale) = locale {
-            params.insert("locale".to_owned(), locale);
-        }
-        if let Some(market) = country {
-            params.insert("country".to_owned(), market.to_string());
-        }
-        let result = self.endpoint_get("browse/categories", &params).await?;
-        self.convert_result::<PageCategory>(&result)
-            .map(|x| x.categories)
-    }
-
-    /// Get a list of playlists in a category in Spotify
-    ///
-    /// Parameters:
-    /// - category_id - The category id to get playlists from.
-    /// - country - An ISO 3166-1 alpha-2 country code or the string from_token.
-    /// - limit - The maximum number of items to return. Default: 20.
-    ///   Minimum: 1. Maximum: 50
-    /// - offset - The index of the first item to return. Default: 0 (the first
-    ///   object). Use with limit to get the next set of items.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-categories-playlists)
-    #[maybe_async]
-    pub async fn category_playlists<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        category_id: &str,
-        country: Option<Market>,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<SimplifiedPlaylist>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        if let Some(market) = country {
-            params.insert("country".to_owned(), market.to_string());
-        }
-
-        let url = format!("browse/categories/{}/playlists", category_id);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result::<CategoryPlaylists>(&result)
-            .map(|x| x.playlists)
-    }
-
-    /// Get Recommendations Based on Seeds
-    ///
-    /// Parameters:
-    /// - seed_artists - a list of artist IDs, URIs or URLs
-    /// - seed_tracks - a list of artist IDs, URIs or URLs
-    /// - seed_genres - a list of genre names. Available genres for
-    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token. If provided, all
-    ///   results will be playable in this country.
-    /// - limit - The maximum number of items to return. Default: 20.
-    ///   Minimum: 1. Maximum: 100
-    /// - min/max/target_<attribute> - For the tuneable track attributes listed
-    ///   in the documentation, these values provide filters and targeting on
-    ///   results.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recommendations)
-    #[maybe_async]
-    pub async fn recommendations<L: Into<Option<u32>>>(
-        &self,
-        seed_artists: Option<Vec<String>>,
-        seed_genres: Option<Vec<String>>,
-        seed_tracks: Option<Vec<String>>,
-        limit: L,
-        market: Option<Market>,
-        payload: &Map<String, Value>,
-    ) -> ClientResult<Recommendations> {
-        let mut params = Query::with_capacity(payload.len() + 1);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        // TODO: this probably can be improved.
-        let attributes = [
-            "acousticness",
-            "danceability",
-            "duration_ms",
-            "energy",
-            "instrumentalness",
-            "key",
-            "liveness",
-            "loudness",
-            "mode",
-            "popularity",
-            "speechiness",
-            "tempo",
-            "time_signature",
-            "valence",
-        ];
-        let prefixes = ["min", "max", "target"];
-        for attribute in attributes.iter() {
-            for prefix in prefixes.iter() {
-                let param = format!("{}_{}", prefix, attribute);
-                if let Some(value) = payload.get(&param) {
-                    // TODO: not sure if this `to_string` is what we want. It
-                    // might add quotes to the strings.
-                    params.insert(param, value.to_string());
-                }
-            }
-        }
-
-        if let Some(seed_artists) = seed_artists {
-            let seed_artists_ids = seed_artists
-                .iter()
-                .map(|id| self.get_id(Type::Artist, id))
-                .collect::<Vec<_>>();
-            params.insert("seed_artists".to_owned(), seed_artists_ids.join(","));
-        }
-        if let Some(seed_genres) = seed_genres {
-            params.insert("seed_genres".to_owned(), seed_genres.join(","));
-        }
-        if let Some(seed_tracks) = seed_tracks {
-            let seed_tracks_ids = seed_tracks
-                .iter()
-                .map(|id| self.get_id(Type::Track, id))
-                .collect::<Vec<_>>();
-            params.insert("seed_tracks".to_owned(), seed_tracks_ids.join(","));
-        }
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        let result = self.endpoint_get("recommendations", &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get audio features for a track
-    ///
-    /// Parameters:
-    /// - track - track URI, URL or ID
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-features)
-    #[maybe_async]
-    pub async fn track_features(&self, track: &str) -> ClientResult<AudioFeatures> {
-        let track_id = self.get_id(Type::Track, track);
-        let url = format!("audio-features/{}", track_id);
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get Audio Features for Several Tracks
-    ///
-    /// Parameters:
-    /// - tracks a list of track URIs, URLs or IDs
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-several-audio-features)
-    #[maybe_async]
-    pub async fn tracks_features<'a>(
-        &self,
-        tracks: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<Option<Vec<AudioFeatures>>> {
-        let ids: Vec<String> = tracks
-            .into_iter()
-            .map(|track| self.get_id(Type::Track, track))
-            .collect();
-        let url = format!("audio-features/?ids={}", ids.join(","));
-
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            self.convert_result::<Option<AudioFeaturesPayload>>(&result)
-                .map(|option_payload| option_payload.map(|x| x.audio_features))
-        }
-    }
-
-    /// Get Audio Analysis for a Track
-    ///
-    /// Parameters:
-    /// - track_id - a track URI, URL or ID
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
-    #[maybe_async]
-    pub async fn track_analysis(&self, track: &str) -> ClientResult<AudioAnalysis> {
-        let trid = self.get_id(Type::Track, track);
-        let url = format!("audio-analysis/{}", trid);
-        let result = self.endpoint_get(&url, &Query::new()).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get a User’s Available Devices
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-users-available-devices)
-    #[maybe_async]
-    pub async fn device(&self) -> ClientResult<Vec<Device>> {
-        let result = self
-            .endpoint_get("me/player/devices", &Query::new())
-            .await?;
-        self.convert_result::<DevicePayload>(&result)
-            .map(|x| x.devices)
-    }
-
-    /// Get Information About The User’s Current Playback
-    ///
-    /// Parameters:
-    /// - market: Optional. an ISO 3166-1 alpha-2 country code or the string from_token.
-    /// - additional_types: Optional. A comma-separated list of item types that
-    ///   your client supports besides the default track type. Valid types are:
-    ///   `track` and `episode`.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-information-about-the-users-current-playback)
-    #[maybe_async]
-    pub async fn current_playback(
-        &self,
-        market: Option<Market>,
-        additional_types: Option<Vec<AdditionalType>>,
-    ) -> ClientResult<Option<CurrentPlaybackContext>> {
-        let mut params = Query::new();
-        if let Some(market) = market {
-            params.insert("country".to_owned(), market.to_string());
-        }
-        if let Some(additional_types) = additional_types {
-            params.insert(
-                "additional_types".to_owned(),
-                additional_types
-                    .iter()
-                    .map(|x| x.to_string())
-                    .collect::<Vec<_>>()
-                    .join(","),
-            );
-        }
-
-        let result = self.endpoint_get("me/player", &params).await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            self.convert_result(&result)
-        }
-    }
-
-    /// Get the User’s Currently Playing Track
-    ///
-    /// Parameters:
-    /// - market: Optional. an ISO 3166-1 alpha-2 country code or the string from_token.
-    /// - additional_types: Optional. A comma-separated list of item types that
-    ///   your client supports besides the default track type. Valid types are:
-    ///   `track` and `episode`.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
-    #[maybe_async]
-    pub async fn current_playing(
-        &self,
-        market: Option<Market>,
-        additional_types: Option<Vec<AdditionalType>>,
-    ) -> ClientResult<Option<CurrentlyPlayingContext>> {
-        let mut params = Query::new();
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        if let Some(additional_types) = additional_types {
-            params.insert(
-                "additional_types".to_owned(),
-                additional_types
-                    .iter()
-                    .map(|x| x.to_string())
-                    .collect::<Vec<_>>()
-                    .join(","),
-            );
-        }
-
-        let result = self
-            .get("me/player/currently-playing", None, &params)
-            .await?;
-        if result.is_empty() {
-            Ok(None)
-        } else {
-            self.convert_result(&result)
-        }
-    }
-
-    /// Transfer a User’s Playback.
-    ///
-    /// Note: Although an array is accepted, only a single device_id is
-    /// currently supported. Supplying more than one will return 400 Bad Request
-    ///
-    /// Parameters:
-    /// - device_id - transfer playback to this device
-    /// - force_play - true: after transfer, play. false:
-    ///   keep current state.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-transfer-a-users-playback)
-    #[maybe_async]
-    pub async fn transfer_playback<T: Into<Option<bool>>>(
-        &self,
-        device_id: &str,
-        force_play: T,
-    ) -> ClientResult<()> {
-        self.endpoint_put(
-            "me/player",
-            &json! ({
-                "device_ids": vec![device_id.to_owned()],
-                "play": force_play.into().unwrap_or(true)
-            }),
-        )
-        .await?;
-
-        Ok(())
-    }
-
-    /// Start/Resume a User’s Playback.
-    ///
-    /// Provide a `context_uri` to start playback or a album, artist, or
-    /// playlist. Provide a `uris` list to start playback of one or more tracks.
-    /// Provide `offset` as {"position": <int>} or {"uri": "<track uri>"} to
-    /// start playback at a particular offset.
-    ///
-    /// Parameters:
-    /// - device_id - device target for playback
-    /// - context_uri - spotify context uri to play
-    /// - uris - spotify track uris
-    /// - offset - offset into context by index or track
-    /// - position_ms - Indicates from what position to start playback.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-start-a-users-playback)
-    #[maybe_async]
-    pub async fn start_playback(
-        &self,
-        device_id: Option<String>,
-        context_uri: Option<String>,
-        uris: Option<Vec<String>>,
-        offset: Option<super::model::Offset>,
-        position_ms: Option<u32>,
-    ) -> ClientResult<()> {
-        if context_uri.is_some() && uris.is_some() {
-            error!("specify either contexxt uri or uris, not both");
-        }
-        let mut params = json!({});
-        if let Some(context_uri) = context_uri {
-            json_insert!(params, "context_uri", context_uri);
-        }
-        if let Some(uris) = uris {
-            json_insert!(params, "uris", uris);
-        }
-        if let Some(offset) = offset {
-            if let Some(position) = offset.position {
-                json_insert!(params, "offset", json!({ "position": position }));
-            } else if let Some(uri) = offset.uri {
-                json_insert!(params, "offset", json!({ "uri": uri }));
-            }
-        }
-        if let Some(position_ms) = position_ms {
-            json_insert!(params, "position_ms", position_ms);
-        };
-        let url = self.append_device_id("me/player/play", device_id);
-        self.endpoint_put(&url, &params).await?;
-
-        Ok(())
-    }
-
-    /// Pause a User’s Playback.
-    ///
-    /// Parameters:
-    /// - device_id - device target for playback
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-pause-a-users-playback)
-    #[maybe_async]
-    pub async fn pause_playback(&self, device_id: Option<String>) -> ClientResult<()> {
-        let url = self.append_device_id("me/player/pause", device_id);
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Skip User’s Playback To Next Track.
-    ///
-    /// Parameters:
-    /// - device_id - device target for playback
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-skip-users-playback-to-next-track)
-    #[maybe_async]
-    pub async fn next_track(&self, device_id: Option<String>) -> ClientResult<()> {
-        let url = self.append_device_id("me/player/next", device_id);
-        self.endpoint_post(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Skip User’s Playback To Previous Track.
-    ///
-    /// Parameters:
-    /// - device_id - device target for playback
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-skip-users-playback-to-previous-track)
-    #[maybe_async]
-    pub async fn previous_track(&self, device_id: Option<String>) -> ClientResult<()> {
-        let url = self.append_device_id("me/player/previous", device_id);
-        self.endpoint_post(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Seek To Position In Currently Playing Track.
-    ///
-    /// Parameters:
-    /// - position_ms - position in milliseconds to seek to
-    /// - device_id - device target for playback
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-seek-to-position-in-currently-playing-track)
-    #[maybe_async]
-    pub async fn seek_track(
-        &self,
-        position_ms: u32,
-        device_id: Option<String>,
-    ) -> ClientResult<()> {
-        let url = self.append_device_id(
-            &format!("me/player/seek?position_ms={}", position_ms),
-            device_id,
-        );
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Set Repeat Mode On User’s Playback.
-    ///
-    /// Parameters:
-    /// - state - `track`, `context`, or `off`
-    /// - device_id - device target for playback
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-set-repeat-mode-on-users-playback)
-    #[maybe_async]
-    pub async fn repeat(&self, state: RepeatState, device_id: Option<String>) -> ClientResult<()> {
-        let url = self.append_device_id(
-            &format!("me/player/repeat?state={}", state.to_string()),
-            device_id,
-        );
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Set Volume For User’s Playback.
-    ///
-    /// Parameters:
-    /// - volume_percent - volume between 0 and 100
-    /// - device_id - device target for playback
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-set-volume-for-users-playback)
-    #[maybe_async]
-    pub async fn volume(&self, volume_percent: u8, device_id: Option<String>) -> ClientResult<()> {
-        if volume_percent > 100u8 {
-            error!("volume must be between 0 and 100, inclusive");
-        }
-        let url = self.append_device_id(
-            &format!("me/player/volume?volume_percent={}", volume_percent),
-            device_id,
-        );
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Toggle Shuffle For User’s Playback.
-    ///
-    /// Parameters:
-    /// - state - true or false
-    /// - device_id - device target for playback
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-toggle-shuffle-for-users-playback)
-    #[maybe_async]
-    pub async fn shuffle(&self, state: bool, device_id: Option<String>) -> ClientResult<()> {
-        let url = self.append_device_id(&format!("me/player/shuffle?state={}", state), device_id);
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Add an item to the end of the user's playback queue.
-    ///
-    /// Parameters:
-    /// - uri - The uri of the item to add, Track or Episode
-    /// - device id - The id of the device targeting
-    /// - If no device ID provided the user's currently active device is
-    ///   targeted
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-add-to-queue)
-    #[maybe_async]
-    pub async fn add_item_to_queue(
-        &self,
-        item: String,
-        device_id: Option<String>,
-    ) -> ClientResult<()> {
-        let url = self.append_device_id(&format!("me/player/queue?uri={}", &item), device_id);
-        self.endpoint_post(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Add a show or a list of shows to a user’s library.
-    ///
-    /// Parameters:
-    /// - ids(Required) A comma-separated list of Spotify IDs for the shows to
-    ///   be added to the user’s library.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-shows-user)
-    #[maybe_async]
-    pub async fn save_shows<'a>(&self, ids: impl IntoIterator<Item = &'a str>) -> ClientResult<()> {
-        let joined_ids = ids.into_iter().collect::<Vec<_>>().join(",");
-        let url = format!("me/shows/?ids={}", joined_ids);
-        self.endpoint_put(&url, &json!({})).await?;
-
-        Ok(())
-    }
-
-    /// Get a list of shows saved in the current Spotify user’s library.
-    /// Optional parameters can be used to limit the number of shows returned.
-    ///
-    /// Parameters:
-    /// - limit(Optional). The maximum number of shows to return. Default: 20.
-    ///   Minimum: 1. Maximum: 50.
-    /// - offset(Optional). The index of the first show to return. Default: 0
-    ///   (the first object). Use with limit to get the next set of shows.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-shows)
-    #[maybe_async]
-    pub async fn get_saved_show<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        limit: L,
-        offset: O,
-    ) -> ClientResult<Page<Show>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        let result = self.endpoint_get("me/shows", &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get Spotify catalog information for a single show identified by its unique Spotify ID.
-    ///
-    /// Path Parameters:
-    /// - id: The Spotify ID for the show.
-    ///
-    /// Query Parameters
-    /// - market(Optional): An ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-show)
-    #[maybe_async]
-    pub async fn get_a_show(&self, id: String, market: Option<Market>) -> ClientResult<FullShow> {
-        let mut params = Query::new();
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        let url = format!("shows/{}", id);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get Spotify catalog information for multiple shows based on their
-    /// Spotify IDs.
-    ///
-    /// Query Parameters
-    /// - ids(Required) A comma-separated list of the Spotify IDs for the shows. Maximum: 50 IDs.
-    /// - market(Optional) An ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-shows)
-    #[maybe_async]
-    pub async fn get_several_shows<'a>(
-        &self,
-        ids: impl IntoIterator<Item = &'a str>,
-        market: Option<Market>,
-    ) -> ClientResult<Vec<SimplifiedShow>> {
-        // TODO: This can probably be better
-        let mut params = Query::with_capacity(1);
-        params.insert(
-            "ids".to_owned(),
-            ids.into_iter().collect::<Vec<_>>().join(","),
-        );
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        let result = self.endpoint_get("shows", &params).await?;
-        self.convert_result::<SeversalSimplifiedShows>(&result)
-            .map(|x| x.shows)
-    }
-
-    /// Get Spotify catalog information about an show’s episodes. Optional
-    /// parameters can be used to limit the number of episodes returned.
-    ///
-    /// Path Parameters
-    /// - id: The Spotify ID for the show.
-    ///
-    /// Query Parameters
-    /// - limit: Optional. The maximum number of episodes to return. Default: 20. Minimum: 1. Maximum: 50.
-    /// - offset: Optional. The index of the first episode to return. Default: 0 (the first object). Use with limit to get the next set of episodes.
-    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-shows-episodes)
-    #[maybe_async]
-    pub async fn get_shows_episodes<L: Into<Option<u32>>, O: Into<Option<u32>>>(
-        &self,
-        id: String,
-        limit: L,
-        offset: O,
-        market: Option<Market>,
-    ) -> ClientResult<Page<SimplifiedEpisode>> {
-        let mut params = Query::with_capacity(2);
-        params.insert("limit".to_owned(), limit.into().unwrap_or(20).to_string());
-        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        let url = format!("shows/{}/episodes", id);
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get Spotify catalog information for a single episode identified by its unique Spotify ID.
-    ///
-    /// Path Parameters
-    /// - id: The Spotify ID for the episode.
-    ///
-    /// Query Parameters
-    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-episode)
-    #[maybe_async]
-    pub async fn get_an_episode(
-        &self,
-        id: String,
-        market: Option<Market>,
-    ) -> ClientResult<FullEpisode> {
-        let url = format!("episodes/{}", id);
-        let mut params = Query::new();
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-
-        let result = self.endpoint_get(&url, &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Get Spotify catalog information for multiple episodes based on their Spotify IDs.
-    ///
-    /// Query Parameters
-    /// - ids: Required. A comma-separated list of the Spotify IDs for the episodes. Maximum: 50 IDs.
-    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-episodes)
-    #[maybe_async]
-    pub async fn get_several_episodes<'a>(
-        &self,
-        ids: impl IntoIterator<Item = &'a str>,
-        market: Option<Market>,
-    ) -> ClientResult<SeveralEpisodes> {
-        let mut params = Query::with_capacity(1);
-        params.insert(
-            "ids".to_owned(),
-            ids.into_iter().collect::<Vec<_>>().join(","),
-        );
-        if let Some(market) = market {
-            params.insert("market".to_owned(), market.to_string());
-        }
-        let result = self.endpoint_get("episodes", &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Check if one or more shows is already saved in the current Spotify user’s library.
-    ///
-    /// Query Parameters
-    /// - ids: Required. A comma-separated list of the Spotify IDs for the shows. Maximum: 50 IDs.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-shows)
-    #[maybe_async]
-    pub async fn check_users_saved_shows<'a>(
-        &self,
-        ids: impl IntoIterator<Item = &'a str>,
-    ) -> ClientResult<Vec<bool>> {
-        let mut params = Query::with_capacity(1);
-        params.insert(
-            "ids".to_owned(),
-            ids.into_iter().collect::<Vec<_>>().join(","),
-        );
-        let result = self.endpoint_get("me/shows/contains", &params).await?;
-        self.convert_result(&result)
-    }
-
-    /// Delete one or more shows from current Spotify user's library.
-    /// Changes to a user's saved shows may not be visible in other Spotify applications immediately.
-    ///
-    /// Query Parameters
-    /// - ids: Required. A comma-separated list of Spotify IDs for the shows to be deleted from the user’s library.
-    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
-    ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-shows-user)
-    #[maybe_async]
-    pub async fn remove_users_saved_shows<'a>(
-        &self,
-        ids: impl IntoIterator<Item = &'a str>,
-        market: Option<Market>,
-    ) -> ClientResult<()> {
-        let joined_ids = ids.into_iter().collect::<Vec<_>>().join(",");
-        let url = format!("me/shows?ids={}", joined_ids);
-        let mut params = json!({});
-        if let Some(market) = market {
-            json_insert!(params, "country", market.to_string());
-        }
-        self.endpoint_delete(&url, &params).await?;
-
-        Ok(())
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
     #[test]
     fn test_parse_response_code() {
         let url = "http://localhost:8888/callback?code=AQD0yXvFEOvw&state=sN#_=_";
@@ -2145,49 +1086,64 @@ mod tests {
     }
 
     #[test]
-    fn test_get_id() {
-        // Assert artist
+    fn test_not_authenticated() {
         let spotify = SpotifyBuilder::default().build().unwrap();
-        let artist_id = "spotify:artist:2WX2uTcsvV5OnS0inACecP";
-        let id = spotify.get_id(Type::Artist, artist_id);
-        assert_eq!("2WX2uTcsvV5OnS0inACecP", &id);
-
-        // Assert album
-        let artist_id_a = "spotify/album/2WX2uTcsvV5OnS0inACecP";
-        assert_eq!(
-            "2WX2uTcsvV5OnS0inACecP",
-            &spotify.get_id(Type::Album, artist_id_a)
-        );
-
-        // Mismatch type
-        let artist_id_b = "spotify:album:2WX2uTcsvV5OnS0inACecP";
-        assert_eq!(
-            "spotify:album:2WX2uTcsvV5OnS0inACecP",
-            &spotify.get_id(Type::Artist, artist_id_b)
-        );
-
-        // Could not split
-        let artist_id_c = "spotify-album-2WX2uTcsvV5OnS0inACecP";
-        assert_eq!(
-            "spotify-album-2WX2uTcsvV5OnS0inACecP",
-            &spotify.get_id(Type::Artist, artist_id_c)
-        );
-
-        let playlist_id = "spotify:playlist:59ZbFPES4DQwEjBpWHzrtC";
-        assert_eq!(
-            "59ZbFPES4DQwEjBpWHzrtC",
-            &spotify.get_id(Type::Playlist, playlist_id)
-        );
+        assert!(matches!(
+            spotify.get_token(),
+            Err(ClientError::NotAuthenticated)
+        ));
     }
 
     #[test]
-    fn test_get_uri() {
-        let spotify = SpotifyBuilder::default().build().unwrap();
-        let track_id1 = "spotify:track:4iV5W9uYEdYUVa79Axb7Rh";
-        let track_id2 = "1301WleyT98MSxVHPZCA6M";
-        let uri1 = spotify.get_uri(Type::Track, track_id1);
-        let uri2 = spotify.get_uri(Type::Track, track_id2);
-        assert_eq!(track_id1, uri1);
-        assert_eq!("spotify:track:1301WleyT98MSxVHPZCA6M", &uri2);
+    fn test_error_chain() {
+        use std::error::Error;
+
+        let api: APIError =
+            serde_json::from_str(r#"{"error": {"status": 404, "message": "Not found."}}"#).unwrap();
+        let url = "https://api.spotify.com/v1/tracks/id".to_owned();
+        let err = ClientError::from(api.with_endpoint(url.clone()));
+        assert_eq!(err.status_code(), Some(404));
+        assert_eq!(err.endpoint(), Some(url.as_str()));
+        assert_eq!(err.to_string(), "spotify error");
+        assert_eq!(err.source().unwrap().to_string(), "404: Not found.");
+
+        let err = ClientError::request(Some(url.clone()), "connection refused");
+        assert_eq!(err.endpoint(), Some(url.as_str()));
+        assert_eq!(err.status_code(), None);
+        assert_eq!(err.source().unwrap().to_string(), "connection refused");
+
+        let io_err = std::io::Error::from(err);
+        assert_eq!(io_err.kind(), std::io::ErrorKind::Other);
+        assert!(io_err.get_ref().unwrap().is::<ClientError>());
+        let io_err = std::io::Error::from(ClientError::Timeout);
+        assert_eq!(io_err.kind(), std::io::ErrorKind::TimedOut);
+        let inner = std::io::Error::new(std::io::ErrorKind::BrokenPipe, "closed");
+        let io_err = std::io::Error::from(ClientError::IO(inner));
+        assert_eq!(io_err.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn test_check_scopes() {
+        let tok = TokenBuilder::default()
+            .access_token("test-access-token")
+            .expires_at(Utc::now() + chrono::Duration::seconds(3600))
+            .scope(["user-library-read".to_owned()].iter().cloned().collect())
+            .build()
+            .unwrap();
+        let spotify = SpotifyBuilder::default()
+            .token(tok)
+            .check_scopes(true)
+            .build()
+            .unwrap();
+
+        assert!(spotify.check_scopes("track").is_ok());
+        assert!(spotify.check_scopes("current_user_saved_tracks").is_ok());
+        match spotify.check_scopes("current_user_saved_tracks_add") {
+            Err(ClientError::InsufficientScope { needed, held }) => {
+                assert_eq!(needed, vec!["user-library-modify"]);
+                assert_eq!(held, vec!["user-library-read"]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
     }
 }
@@ -0,0 +1,1191 @@
+//! The endpoints that don't access the user's data.
+
+use super::{
+    check_limit, check_param, convert_result, discography_album, fetch_playlists, get_id,
+    is_duplicate_release,
+};
+use crate::client::{
+    ClientResult, Query, ALBUM_TRACKS_PAGE_SIZE, ARTISTS_IDS_LIMIT, ARTIST_ALBUMS_PAGE_SIZE,
+    PAGE_LIMIT, PLAYLISTS_PAGE_SIZE, PLAYLIST_TRACKS_PAGE_SIZE, RECOMMENDATIONS_LIMIT,
+    SEARCH_OFFSET_LIMIT,
+};
+use crate::http::encode_param;
+use crate::model::*;
+use crate::pagination::paginate;
+
+use chrono::{DateTime, Utc};
+use log::error;
+use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
+use serde_json::map::Map;
+use serde_json::Value;
+use std::collections::HashSet;
+
+#[cfg(feature = "__async")]
+use futures::future;
+#[cfg(feature = "__async")]
+use futures::stream::{Stream, StreamExt};
+
+/// The endpoints of the Spotify API that don't access the user's data, like
+/// the catalog ones, so they can be used with the token of any flow,
+/// including the Client Credentials one.
+///
+/// They're implemented over [`BaseClient::endpoint_get`] and
+/// [`BaseClient::endpoint_get_as`], so any client that implements these
+/// inherits all of them, like one that answers from fixtures in tests.
+#[maybe_async]
+pub trait BaseClient: Send + Sync {
+    /// The wrapper for the GET endpoints, which also includes the required
+    /// authentication. `url` may be relative to the prefix of the API.
+    async fn endpoint_get(&self, url: &str, payload: &Query) -> ClientResult<String>;
+
+    /// Requests an endpoint of the API, deserializing its response into any
+    /// type. This makes it possible to use leaner models than the ones in
+    /// [`model`](crate::model), like with only the fields that are needed,
+    /// which skips the rest when deserializing large responses. It's also
+    /// useful for the endpoints that aren't supported yet.
+    ///
+    /// `url` is relative to the [`prefix`](crate::client::Spotify::prefix) of the client,
+    /// and the request is authenticated like the rest of endpoints.
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "client-reqwest")]
+    /// # async fn run(spotify: rspotify::client::Spotify) {
+    /// use rspotify::client::Query;
+    /// use rspotify::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct TrackName {
+    ///     name: String,
+    /// }
+    ///
+    /// let track: TrackName = spotify
+    ///     .endpoint_get_as("tracks/11dFghVXANMlKmJXsNCbNl", &Query::new())
+    ///     .await
+    ///     .unwrap();
+    /// println!("{}", track.name);
+    /// # }
+    /// ```
+    ///
+    /// By default, the response of [`BaseClient::endpoint_get`] is
+    /// deserialized, but clients may deserialize it as it's read instead.
+    async fn endpoint_get_as<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        let body = self.endpoint_get(url, payload).await?;
+        convert_result(&body)
+    }
+
+    /// Returns the market to use in an endpoint when none is given, which is
+    /// none by default.
+    async fn default_market(&self, market: Option<Market>) -> ClientResult<Option<Market>> {
+        Ok(market)
+    }
+
+    /// Returns whether the explicit items have to be removed from the
+    /// results, which is never by default.
+    async fn filters_explicit(&self) -> ClientResult<bool> {
+        Ok(false)
+    }
+
+    /// Removes the explicit items, like tracks or episodes, if the current
+    /// user's settings demand it, as told by [`BaseClient::filters_explicit`].
+    /// The search and the recommendations are already filtered.
+    async fn filter_explicit<T: Explicit + Send>(&self, mut items: Vec<T>) -> ClientResult<Vec<T>> {
+        if self.filters_explicit().await? {
+            items.retain(|item| !item.is_explicit());
+        }
+        Ok(items)
+    }
+
+    /// Returns a single track given the track's ID, URI or URL.
+    ///
+    /// Parameters:
+    /// - track_id - a spotify URI, URL or ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-track)
+    async fn track(&self, track_id: &str) -> ClientResult<FullTrack> {
+        let trid = get_id(Type::Track, track_id)?;
+        let url = format!("tracks/{}", trid);
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Returns a list of tracks given a list of track IDs, URIs, or URLs.
+    ///
+    /// Parameters:
+    /// - track_ids - a list of spotify URIs, URLs or IDs
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-several-tracks)
+    async fn tracks<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<Vec<FullTrack>> {
+        // TODO: this can be improved
+        let mut ids: Vec<String> = vec![];
+        for track_id in track_ids {
+            ids.push(get_id(Type::Track, track_id)?);
+        }
+
+        let mut params = Query::new();
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+
+        let url = format!("tracks/?ids={}", ids.join(","));
+        let result = self.endpoint_get(&url, &params).await?;
+        convert_result::<FullTracks>(&result).map(|x| x.tracks)
+    }
+
+    /// Returns a single artist given the artist's ID, URI or URL.
+    ///
+    /// Parameters:
+    /// - artist_id - an artist ID, URI or URL
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artist)
+    async fn artist(&self, artist_id: &str) -> ClientResult<FullArtist> {
+        let trid = get_id(Type::Artist, artist_id)?;
+        let url = format!("artists/{}", trid);
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Returns a list of artists given the artist IDs, URIs, or URLs, in the
+    /// same order.
+    ///
+    /// Parameters:
+    /// - artist_ids - a list of artist IDs, URIs or URLs, split in requests
+    ///   of up to [`ARTISTS_IDS_LIMIT`]
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-artists)
+    async fn artists<'a>(
+        &self,
+        artist_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<FullArtist>> {
+        let ids: Vec<String> = artist_ids
+            .into_iter()
+            .map(|id| get_id(Type::Artist, id))
+            .collect::<ClientResult<_>>()?;
+        let mut artists = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(ARTISTS_IDS_LIMIT) {
+            let url = format!("artists/?ids={}", chunk.join(","));
+            let result = self.endpoint_get(&url, &Query::new()).await?;
+            artists.extend(convert_result::<FullArtists>(&result)?.artists);
+        }
+
+        Ok(artists)
+    }
+
+    /// Get Spotify catalog information about an artist's albums.
+    ///
+    /// Parameters:
+    /// - artist_id - the artist ID, URI or URL
+    /// - album_group - 'album', 'single', 'appears_on', 'compilation'
+    /// - market - limit the response to one particular country.
+    /// - limit  - the number of albums to return
+    /// - offset - the index of the first album to return
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-albums)
+    async fn artist_albums(
+        &self,
+        artist_id: &str,
+        album_group: Option<AlbumGroup>,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SimplifiedAlbum>> {
+        let mut params = Query::new();
+        let limit = limit
+            .map(|limit| check_limit(limit, ARTIST_ALBUMS_PAGE_SIZE))
+            .transpose()?;
+        params.insert_opt("limit", limit);
+        params.insert_opt("include_groups", album_group);
+        params.insert_opt("offset", offset);
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        let trid = get_id(Type::Artist, artist_id)?;
+        let url = format!("artists/{}/albums", trid);
+        self.endpoint_get_as(&url, &params).await
+    }
+
+    /// Returns every album of an artist, of all the groups, requested in
+    /// pages as they're consumed. The releases of the same album in
+    /// different markets are only returned once, considering the albums with
+    /// the same name and release date duplicates.
+    ///
+    /// Parameters:
+    /// - artist_id - the artist ID, URI or URL
+    /// - market - limit the response to one particular country
+    /// - with_tracks - also request all the tracks of each album
+    #[cfg(feature = "__async")]
+    fn artist_discography<'a>(
+        &'a self,
+        artist_id: &'a str,
+        market: Option<Market>,
+        with_tracks: bool,
+    ) -> impl Stream<Item = ClientResult<DiscographyAlbum>> + 'a {
+        let mut seen = HashSet::new();
+        paginate(
+            move |limit, offset| {
+                self.artist_albums(artist_id, None, market.clone(), Some(limit), Some(offset))
+            },
+            ARTIST_ALBUMS_PAGE_SIZE,
+        )
+        .filter(move |album| future::ready(!is_duplicate_release(&mut seen, album)))
+        .then(move |album| async move { discography_album(self, album?, with_tracks).await })
+    }
+
+    /// Returns every album of an artist, of all the groups, requested in
+    /// pages as they're consumed. The releases of the same album in
+    /// different markets are only returned once, considering the albums with
+    /// the same name and release date duplicates.
+    ///
+    /// Parameters:
+    /// - artist_id - the artist ID, URI or URL
+    /// - market - limit the response to one particular country
+    /// - with_tracks - also request all the tracks of each album
+    #[cfg(feature = "__sync")]
+    fn artist_discography<'a>(
+        &'a self,
+        artist_id: &'a str,
+        market: Option<Market>,
+        with_tracks: bool,
+    ) -> impl Iterator<Item = ClientResult<DiscographyAlbum>> + 'a {
+        let mut seen = HashSet::new();
+        paginate(
+            move |limit, offset| {
+                self.artist_albums(artist_id, None, market.clone(), Some(limit), Some(offset))
+            },
+            ARTIST_ALBUMS_PAGE_SIZE,
+        )
+        .filter(move |album| !is_duplicate_release(&mut seen, album))
+        .map(move |album| discography_album(self, album?, with_tracks))
+    }
+
+    /// Get Spotify catalog information about an artist's top 10 tracks by
+    /// country.
+    ///
+    /// Parameters:
+    /// - artist_id - the artist ID, URI or URL
+    /// - market - limit the response to one particular country.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-top-tracks)
+    async fn artist_top_tracks(
+        &self,
+        artist_id: &str,
+        market: Market,
+    ) -> ClientResult<Vec<FullTrack>> {
+        let mut params = Query::with_capacity(1);
+
+        params.insert("market".to_owned(), market.to_string());
+
+        let trid = get_id(Type::Artist, artist_id)?;
+        let url = format!("artists/{}/top-tracks", trid);
+        let result = self.endpoint_get(&url, &params).await?;
+        convert_result::<FullTracks>(&result).map(|x| x.tracks)
+    }
+
+    /// Get Spotify catalog information about artists similar to an identified
+    /// artist. Similarity is based on analysis of the Spotify community's
+    /// listening history.
+    ///
+    /// Parameters:
+    /// - artist_id - the artist ID, URI or URL
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-related-artists)
+    async fn artist_related_artists(&self, artist_id: &str) -> ClientResult<Vec<FullArtist>> {
+        let trid = get_id(Type::Artist, artist_id)?;
+        let url = format!("artists/{}/related-artists", trid);
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result::<FullArtists>(&result).map(|x| x.artists)
+    }
+
+    /// Returns a single album given the album's ID, URIs or URL.
+    ///
+    /// Parameters:
+    /// - album_id - the album ID, URI or URL
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-album)
+    async fn album(&self, album_id: &str) -> ClientResult<FullAlbum> {
+        let trid = get_id(Type::Album, album_id)?;
+        let url = format!("albums/{}", trid);
+
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Returns the markets where an album can be played, as ISO 3166-1
+    /// alpha-2 country codes, given the album's ID, URI or URL. It's
+    /// requested without a market, since Spotify only lists them then.
+    ///
+    /// Parameters:
+    /// - album_id - the album ID, URI or URL
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-album)
+    async fn playable_markets(&self, album_id: &str) -> ClientResult<Vec<String>> {
+        let album = self.album(album_id).await?;
+        Ok(album.available_markets)
+    }
+
+    /// Returns a list of albums given the album IDs, URIs, or URLs.
+    ///
+    /// Parameters:
+    /// - albums_ids - a list of album IDs, URIs or URLs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-albums)
+    async fn albums<'a>(
+        &self,
+        album_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<FullAlbum>> {
+        let mut ids: Vec<String> = vec![];
+        for album_id in album_ids {
+            ids.push(get_id(Type::Album, album_id)?);
+        }
+        let url = format!("albums/?ids={}", ids.join(","));
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result::<FullAlbums>(&result).map(|x| x.albums)
+    }
+
+    /// Like [`BaseClient::track`], but returning a [`LiteTrack`], which skips
+    /// most of the fields when deserializing.
+    ///
+    /// Note: this method requires the `lite-models` feature.
+    #[cfg(feature = "lite-models")]
+    async fn track_lite(&self, track_id: &str) -> ClientResult<LiteTrack> {
+        let trid = get_id(Type::Track, track_id)?;
+        let url = format!("tracks/{}", trid);
+        self.endpoint_get_as(&url, &Query::new()).await
+    }
+
+    /// Like [`BaseClient::tracks`], but returning [`LiteTrack`]s.
+    ///
+    /// Note: this method requires the `lite-models` feature.
+    #[cfg(feature = "lite-models")]
+    async fn tracks_lite<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<Vec<LiteTrack>> {
+        let ids: Vec<String> = track_ids
+            .into_iter()
+            .map(|id| get_id(Type::Track, id))
+            .collect::<ClientResult<_>>()?;
+
+        let mut params = Query::new();
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+
+        let url = format!("tracks/?ids={}", ids.join(","));
+        let result: LiteTracks = self.endpoint_get_as(&url, &params).await?;
+        Ok(result.tracks)
+    }
+
+    /// Like [`BaseClient::artist`], but returning a [`LiteArtist`].
+    ///
+    /// Note: this method requires the `lite-models` feature.
+    #[cfg(feature = "lite-models")]
+    async fn artist_lite(&self, artist_id: &str) -> ClientResult<LiteArtist> {
+        let trid = get_id(Type::Artist, artist_id)?;
+        let url = format!("artists/{}", trid);
+        self.endpoint_get_as(&url, &Query::new()).await
+    }
+
+    /// Like [`BaseClient::artists`], but returning [`LiteArtist`]s.
+    ///
+    /// Note: this method requires the `lite-models` feature.
+    #[cfg(feature = "lite-models")]
+    async fn artists_lite<'a>(
+        &self,
+        artist_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<LiteArtist>> {
+        let ids: Vec<String> = artist_ids
+            .into_iter()
+            .map(|id| get_id(Type::Artist, id))
+            .collect::<ClientResult<_>>()?;
+        let mut artists = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(ARTISTS_IDS_LIMIT) {
+            let url = format!("artists/?ids={}", chunk.join(","));
+            let result: LiteArtists = self.endpoint_get_as(&url, &Query::new()).await?;
+            artists.extend(result.artists);
+        }
+
+        Ok(artists)
+    }
+
+    /// Like [`BaseClient::album`], but returning a [`LiteAlbum`], which
+    /// also skips the tracks of the album.
+    ///
+    /// Note: this method requires the `lite-models` feature.
+    #[cfg(feature = "lite-models")]
+    async fn album_lite(&self, album_id: &str) -> ClientResult<LiteAlbum> {
+        let trid = get_id(Type::Album, album_id)?;
+        let url = format!("albums/{}", trid);
+        self.endpoint_get_as(&url, &Query::new()).await
+    }
+
+    /// Like [`BaseClient::albums`], but returning [`LiteAlbum`]s.
+    ///
+    /// Note: this method requires the `lite-models` feature.
+    #[cfg(feature = "lite-models")]
+    async fn albums_lite<'a>(
+        &self,
+        album_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<LiteAlbum>> {
+        let ids: Vec<String> = album_ids
+            .into_iter()
+            .map(|id| get_id(Type::Album, id))
+            .collect::<ClientResult<_>>()?;
+        let url = format!("albums/?ids={}", ids.join(","));
+        let result: LiteAlbums = self.endpoint_get_as(&url, &Query::new()).await?;
+        Ok(result.albums)
+    }
+
+    /// Search for an Item. Get Spotify catalog information about artists,
+    /// albums, tracks or playlists that match a keyword string.
+    ///
+    /// Parameters:
+    /// - q - the search query
+    /// - limit  - the number of items to return
+    /// - offset - the index of the first item to return
+    /// - type - the type of item to return. One of 'artist', 'album', 'track',
+    ///  'playlist', 'show' or 'episode'
+    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - include_external: Optional.Possible values: audio. If
+    ///   include_external=audio is specified the response will include any
+    ///   relevant audio content that is hosted externally.  
+    ///
+    /// The explicit items are removed if [`BaseClient::filters_explicit`] says
+    /// so.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
+    async fn search<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        q: &str,
+        _type: SearchType,
+        limit: L,
+        offset: O,
+        market: Option<Market>,
+        include_external: Option<IncludeExternal>,
+    ) -> ClientResult<SearchResult> {
+        let mut params = Query::with_capacity(4);
+        let limit = check_limit(limit.into().unwrap_or(10), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        let offset = check_param(
+            "offset",
+            offset.into().unwrap_or(0),
+            0,
+            SEARCH_OFFSET_LIMIT - 1,
+        )?;
+        params.insert("offset", offset.to_string());
+        params.insert("q".to_owned(), q.to_owned());
+        params.insert("type".to_owned(), _type.to_string());
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        params.insert_opt("include_external", include_external);
+
+        let mut result: SearchResult = self.endpoint_get_as("search", &params).await?;
+        if self.filters_explicit().await? {
+            result.remove_explicit();
+        }
+        Ok(result)
+    }
+
+    /// Get Spotify catalog information about an album's tracks.
+    ///
+    /// Parameters:
+    /// - album_id - the album ID, URI or URL
+    /// - limit  - the number of items to return
+    /// - offset - the index of the first item to return
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-albums-tracks)
+    async fn album_track<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        album_id: &str,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<SimplifiedTrack>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(50), ALBUM_TRACKS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        let trid = get_id(Type::Album, album_id)?;
+        let url = format!("albums/{}/tracks", trid);
+        self.endpoint_get_as(&url, &params).await
+    }
+
+    /// Gets basic profile information about a Spotify User.
+    ///
+    /// Parameters:
+    /// - user - the id of the usr
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-profile)
+    async fn user(&self, user_id: &str) -> ClientResult<PublicUser> {
+        let url = format!("users/{}", encode_param(user_id));
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Get full details about Spotify playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-playlist)
+    async fn playlist(
+        &self,
+        playlist_id: &str,
+        fields: Option<&str>,
+        market: Option<Market>,
+    ) -> ClientResult<FullPlaylist> {
+        let mut params = Query::new();
+        params.insert_opt("fields", fields);
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let url = format!("playlists/{}", plid);
+        let result = self.endpoint_get(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Gets several playlists, since there's no endpoint to request them
+    /// together. Up to `concurrency` of them are requested at the same time,
+    /// and they're returned in the same order as their IDs. The first error
+    /// fails the whole request.
+    ///
+    /// `concurrency` must be at least 1, or
+    /// [`ClientError::InvalidParameter`](crate::client::ClientError::InvalidParameter)
+    /// is returned. The blocking client can't make requests at the same time,
+    /// so it always requests them one at a time, whatever `concurrency` is.
+    ///
+    /// With `fields`, only the given fields of each playlist are returned, to
+    /// keep the responses small, so they must be deserialized into a model
+    /// with just those fields, or into a [`Value`]. Otherwise, they're whole
+    /// [`FullPlaylist`]s.
+    ///
+    /// Parameters:
+    /// - playlist_ids - the ids of the playlists
+    /// - fields - which fields to return
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - concurrency - the maximum number of requests at the same time
+    async fn playlists<'a, T: DeserializeOwned + Send>(
+        &self,
+        playlist_ids: impl IntoIterator<Item = &'a PlaylistId> + Send + 'a,
+        fields: Option<&str>,
+        market: Option<Market>,
+        concurrency: u32,
+    ) -> ClientResult<Vec<T>> {
+        let concurrency = check_param("concurrency", concurrency, 1, u32::MAX)?;
+        let urls = playlist_ids
+            .into_iter()
+            .map(|id| format!("playlists/{}", id.id()))
+            .collect::<Vec<_>>();
+        let mut params = Query::new();
+        params.insert_opt("fields", fields);
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+
+        fetch_playlists(self, urls, &params, concurrency as usize).await
+    }
+
+    /// Get the current images of a playlist, without requesting the whole
+    /// playlist object.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-playlist-cover)
+    async fn playlist_cover_image(&self, playlist_id: &str) -> ClientResult<Vec<Image>> {
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let url = format!("playlists/{}/images", plid);
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Gets playlists of a user.
+    ///
+    /// Parameters:
+    /// - user_id - the id of the usr
+    /// - limit  - the number of items to return
+    /// - offset - the index of the first item to return
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-list-users-playlists)
+    async fn user_playlists<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        user_id: &str,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<SimplifiedPlaylist>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(50), PLAYLISTS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        let url = format!("users/{}/playlists", encode_param(user_id));
+        self.endpoint_get_as(&url, &params).await
+    }
+
+    /// Returns all the playlists of a user, requested in pages as they're
+    /// consumed. See [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__async")]
+    fn user_playlists_all<'a>(
+        &'a self,
+        user_id: &'a str,
+    ) -> impl Stream<Item = ClientResult<SimplifiedPlaylist>> + 'a {
+        paginate(
+            move |limit, offset| self.user_playlists(user_id, limit, offset),
+            PLAYLISTS_PAGE_SIZE,
+        )
+    }
+
+    /// Returns all the playlists of a user, requested in pages as they're
+    /// consumed. See [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__sync")]
+    fn user_playlists_all<'a>(
+        &'a self,
+        user_id: &'a str,
+    ) -> impl Iterator<Item = ClientResult<SimplifiedPlaylist>> + 'a {
+        paginate(
+            move |limit, offset| self.user_playlists(user_id, limit, offset),
+            PLAYLISTS_PAGE_SIZE,
+        )
+    }
+
+    /// Gets playlist of a user.
+    ///
+    /// Parameters:
+    /// - user_id - the id of the user
+    /// - playlist_id - the id of the playlist
+    /// - fields - which fields to return
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-list-users-playlists)
+    async fn user_playlist(
+        &self,
+        user_id: &str,
+        playlist_id: Option<&mut str>,
+        fields: Option<&str>,
+    ) -> ClientResult<FullPlaylist> {
+        let mut params = Query::new();
+        params.insert_opt("fields", fields);
+        match playlist_id {
+            Some(playlist_id) => {
+                let plid = get_id(Type::Playlist, playlist_id)?;
+                let url = format!("users/{}/playlists/{}", encode_param(user_id), plid);
+                let result = self.endpoint_get(&url, &params).await?;
+                convert_result(&result)
+            }
+            None => {
+                let url = format!("users/{}/starred", encode_param(user_id));
+                let result = self.endpoint_get(&url, &params).await?;
+                convert_result(&result)
+            }
+        }
+    }
+
+    /// Get full details of the tracks of a playlist owned by a user.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - fields - which fields to return
+    /// - limit - the maximum number of tracks to return
+    /// - offset - the index of the first track to return
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-playlists-tracks)
+    async fn playlist_tracks<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        playlist_id: &str,
+        fields: Option<&str>,
+        limit: L,
+        offset: O,
+        market: Option<Market>,
+    ) -> ClientResult<Page<PlaylistItem>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(50), PLAYLIST_TRACKS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        params.insert_opt("fields", fields);
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let url = format!("playlists/{}/tracks", plid);
+        self.endpoint_get_as(&url, &params).await
+    }
+
+    /// Returns all the items of a playlist, requested in pages as they're
+    /// consumed. See [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__async")]
+    fn playlist_tracks_all<'a>(
+        &'a self,
+        playlist_id: &'a str,
+        market: Option<Market>,
+    ) -> impl Stream<Item = ClientResult<PlaylistItem>> + 'a {
+        paginate(
+            move |limit, offset| {
+                self.playlist_tracks(playlist_id, None, limit, offset, market.clone())
+            },
+            PLAYLIST_TRACKS_PAGE_SIZE,
+        )
+    }
+
+    /// Returns all the items of a playlist, requested in pages as they're
+    /// consumed. See [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__sync")]
+    fn playlist_tracks_all<'a>(
+        &'a self,
+        playlist_id: &'a str,
+        market: Option<Market>,
+    ) -> impl Iterator<Item = ClientResult<PlaylistItem>> + 'a {
+        paginate(
+            move |limit, offset| {
+                self.playlist_tracks(playlist_id, None, limit, offset, market.clone())
+            },
+            PLAYLIST_TRACKS_PAGE_SIZE,
+        )
+    }
+
+    /// Gets the full details of a simplified playlist, like the ones of
+    /// [`OAuthClient::current_user_playlists`
+    /// ](crate::client::OAuthClient::current_user_playlists), with all its
+    /// items instead of only the first page. The remaining items are
+    /// requested in pages after the playlist, which takes
+    /// [`SimplifiedPlaylist::hydration_requests`] requests in total.
+    ///
+    /// Parameters:
+    /// - playlist - the simplified playlist
+    /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
+    async fn hydrate_playlist(
+        &self,
+        playlist: &SimplifiedPlaylist,
+        market: Option<Market>,
+    ) -> ClientResult<FullPlaylist> {
+        let mut full = self.playlist(&playlist.id, None, market.clone()).await?;
+        while let Some(offset) = full.tracks.next_offset() {
+            let page = self
+                .playlist_tracks(
+                    &playlist.id,
+                    None,
+                    PLAYLIST_TRACKS_PAGE_SIZE,
+                    offset,
+                    market.clone(),
+                )
+                .await?;
+            full.tracks.items.extend(page.items);
+            full.tracks.next = page.next;
+            full.tracks.total = page.total;
+        }
+        Ok(full)
+    }
+
+    /// Check to see if the given users are following the given playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - user_ids - the ids of the users that you want to
+    /// check to see if they follow the playlist. Maximum: 5 ids.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-if-user-follows-playlist)
+    async fn playlist_check_follow(
+        &self,
+        playlist_id: &str,
+        user_ids: &[String],
+    ) -> ClientResult<Vec<bool>> {
+        if user_ids.len() > 5 {
+            error!("The maximum length of user ids is limited to 5 :-)");
+        }
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let user_ids: Vec<String> = user_ids.iter().map(|id| encode_param(id)).collect();
+        let url = format!(
+            "playlists/{}/followers/contains?ids={}",
+            plid,
+            user_ids.join(",")
+        );
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Get a list of Spotify featured playlists.
+    ///
+    /// Parameters:
+    /// - locale - The desired language, like `es_MX`. It takes precedence
+    ///   over the language of the client, like
+    ///   [`Spotify::language`](crate::client::Spotify::language).
+    /// - country - An ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - timestamp - A timestamp in ISO 8601 format: yyyy-MM-ddTHH:mm:ss. Use
+    ///   this parameter to specify the user's local time to get results
+    ///   tailored for that specific date and time in the day
+    /// - limit - The maximum number of items to return. Default: 20.
+    ///   Minimum: 1. Maximum: 50
+    /// - offset - The index of the first item to return. Default: 0
+    ///   (the first object). Use with limit to get the next set of
+    ///   items.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-featured-playlists)
+    async fn featured_playlists<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        locale: Option<Locale>,
+        country: Option<Market>,
+        timestamp: Option<DateTime<Utc>>,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<FeaturedPlaylists> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        params.insert_opt("locale", locale);
+        let country = self.default_market(country).await?;
+        params.insert_opt("country", country);
+        params.insert_opt("timestamp", timestamp.map(|t| t.to_rfc3339()));
+        self.endpoint_get_as("browse/featured-playlists", &params)
+            .await
+    }
+
+    /// Get a list of new album releases featured in Spotify.
+    ///
+    /// Parameters:
+    /// - country - An ISO 3166-1 alpha-2 country code or string from_token.
+    /// - limit - The maximum number of items to return. Default: 20.
+    ///   Minimum: 1. Maximum: 50
+    /// - offset - The index of the first item to return. Default: 0 (the first
+    ///   object). Use with limit to get the next set of items.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-new-releases)
+    async fn new_releases<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        country: Option<Market>,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<SimplifiedAlbum>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        let country = self.default_market(country).await?;
+        params.insert_opt("country", country);
+
+        self.endpoint_get_as::<PageSimpliedAlbums>("browse/new-releases", &params)
+            .await
+            .map(|x| x.albums)
+    }
+
+    /// Get a list of new album releases featured in Spotify
+    ///
+    /// Parameters:
+    /// - country - An ISO 3166-1 alpha-2 country code or string from_token.
+    /// - locale - The desired language, like `es_MX`. It takes precedence
+    ///   over the language of the client, like
+    ///   [`Spotify::language`](crate::client::Spotify::language).
+    /// - limit - The maximum number of items to return. Default: 20.
+    ///   Minimum: 1. Maximum: 50
+    /// - offset - The index of the first item to return. Default: 0 (the first
+    ///   object). Use with limit to get the next set of items.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-categories)
+    async fn categories<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        locale: Option<Locale>,
+        country: Option<Market>,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<Category>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        params.insert_opt("locale", locale);
+        let country = self.default_market(country).await?;
+        params.insert_opt("country", country);
+        self.endpoint_get_as::<PageCategory>("browse/categories", &params)
+            .await
+            .map(|x| x.categories)
+    }
+
+    /// Get a list of playlists in a category in Spotify
+    ///
+    /// Parameters:
+    /// - category_id - The category id to get playlists from.
+    /// - country - An ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - limit - The maximum number of items to return. Default: 20.
+    ///   Minimum: 1. Maximum: 50
+    /// - offset - The index of the first item to return. Default: 0 (the first
+    ///   object). Use with limit to get the next set of items.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-categories-playlists)
+    async fn category_playlists<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        category_id: &str,
+        country: Option<Market>,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<SimplifiedPlaylist>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        let country = self.default_market(country).await?;
+        params.insert_opt("country", country);
+
+        let url = format!("browse/categories/{}/playlists", encode_param(category_id));
+        self.endpoint_get_as::<CategoryPlaylists>(&url, &params)
+            .await
+            .map(|x| x.playlists)
+    }
+
+    /// Get Recommendations Based on Seeds
+    ///
+    /// Parameters:
+    /// - seed_artists - a list of artist IDs, URIs or URLs
+    /// - seed_tracks - a list of artist IDs, URIs or URLs
+    /// - seed_genres - a list of genre names. Available genres for
+    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token. If provided, all
+    ///   results will be playable in this country.
+    /// - limit - The maximum number of items to return. Default: 20.
+    ///   Minimum: 1. Maximum: 100
+    /// - min/max/target_<attribute> - For the tuneable track attributes listed
+    ///   in the documentation, these values provide filters and targeting on
+    ///   results.
+    ///
+    /// The explicit tracks are removed if [`BaseClient::filters_explicit`]
+    /// says so.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recommendations)
+    async fn recommendations<L: Into<Option<u32>> + Send>(
+        &self,
+        seed_artists: Option<Vec<String>>,
+        seed_genres: Option<Vec<String>>,
+        seed_tracks: Option<Vec<String>>,
+        limit: L,
+        market: Option<Market>,
+        payload: &Map<String, Value>,
+    ) -> ClientResult<Recommendations> {
+        let mut params = Query::with_capacity(payload.len() + 1);
+        let limit = check_limit(limit.into().unwrap_or(20), RECOMMENDATIONS_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        // TODO: this probably can be improved.
+        let attributes = [
+            "acousticness",
+            "danceability",
+            "duration_ms",
+            "energy",
+            "instrumentalness",
+            "key",
+            "liveness",
+            "loudness",
+            "mode",
+            "popularity",
+            "speechiness",
+            "tempo",
+            "time_signature",
+            "valence",
+        ];
+        let prefixes = ["min", "max", "target"];
+        for attribute in attributes.iter() {
+            for prefix in prefixes.iter() {
+                let param = format!("{}_{}", prefix, attribute);
+                if let Some(value) = payload.get(&param) {
+                    // TODO: not sure if this `to_string` is what we want. It
+                    // might add quotes to the strings.
+                    params.insert(param, value.to_string());
+                }
+            }
+        }
+
+        if let Some(seed_artists) = seed_artists {
+            let seed_artists_ids = seed_artists
+                .iter()
+                .map(|id| get_id(Type::Artist, id))
+                .collect::<ClientResult<Vec<_>>>()?;
+            params.insert("seed_artists".to_owned(), seed_artists_ids.join(","));
+        }
+        if let Some(seed_genres) = seed_genres {
+            params.insert("seed_genres".to_owned(), seed_genres.join(","));
+        }
+        if let Some(seed_tracks) = seed_tracks {
+            let seed_tracks_ids = seed_tracks
+                .iter()
+                .map(|id| get_id(Type::Track, id))
+                .collect::<ClientResult<Vec<_>>>()?;
+            params.insert("seed_tracks".to_owned(), seed_tracks_ids.join(","));
+        }
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        let result = self.endpoint_get("recommendations", &params).await?;
+        let mut recommendations: Recommendations = convert_result(&result)?;
+        recommendations.tracks = self.filter_explicit(recommendations.tracks).await?;
+        Ok(recommendations)
+    }
+
+    /// Get audio features for a track
+    ///
+    /// Parameters:
+    /// - track - track URI, URL or ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-features)
+    async fn track_features(&self, track: &str) -> ClientResult<AudioFeatures> {
+        let track_id = get_id(Type::Track, track)?;
+        let url = format!("audio-features/{}", track_id);
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Get Audio Features for Several Tracks
+    ///
+    /// Parameters:
+    /// - tracks a list of track URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-several-audio-features)
+    async fn tracks_features<'a>(
+        &self,
+        tracks: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Option<Vec<AudioFeatures>>> {
+        let ids: Vec<String> = tracks
+            .into_iter()
+            .map(|track| get_id(Type::Track, track))
+            .collect::<ClientResult<_>>()?;
+        let url = format!("audio-features/?ids={}", ids.join(","));
+
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        if result.is_empty() {
+            Ok(None)
+        } else {
+            convert_result::<Option<AudioFeaturesPayload>>(&result)
+                .map(|option_payload| option_payload.map(|x| x.audio_features))
+        }
+    }
+
+    /// Get Audio Analysis for a Track
+    ///
+    /// Parameters:
+    /// - track_id - a track URI, URL or ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
+    async fn track_analysis(&self, track: &str) -> ClientResult<AudioAnalysis> {
+        let trid = get_id(Type::Track, track)?;
+        let url = format!("audio-analysis/{}", trid);
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Get Spotify catalog information for a single show identified by its unique Spotify ID.
+    ///
+    /// Path Parameters:
+    /// - id: The Spotify ID for the show.
+    ///
+    /// Query Parameters
+    /// - market(Optional): An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-show)
+    async fn get_a_show(&self, id: String, market: Option<Market>) -> ClientResult<FullShow> {
+        let mut params = Query::new();
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        let url = format!("shows/{}", get_id(Type::Show, &id)?);
+        let result = self.endpoint_get(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Get Spotify catalog information for multiple shows based on their
+    /// Spotify IDs.
+    ///
+    /// Query Parameters
+    /// - ids(Required) A comma-separated list of the Spotify IDs for the shows. Maximum: 50 IDs.
+    /// - market(Optional) An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-shows)
+    async fn get_several_shows<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<Vec<SimplifiedShow>> {
+        // TODO: This can probably be better
+        let mut params = Query::with_capacity(1);
+        params.insert(
+            "ids".to_owned(),
+            ids.into_iter().collect::<Vec<_>>().join(","),
+        );
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        let result = self.endpoint_get("shows", &params).await?;
+        convert_result::<SeversalSimplifiedShows>(&result).map(|x| x.shows)
+    }
+
+    /// Get Spotify catalog information about an show’s episodes. Optional
+    /// parameters can be used to limit the number of episodes returned.
+    ///
+    /// Path Parameters
+    /// - id: The Spotify ID for the show.
+    ///
+    /// Query Parameters
+    /// - limit: Optional. The maximum number of episodes to return. Default: 20. Minimum: 1. Maximum: 50.
+    /// - offset: Optional. The index of the first episode to return. Default: 0 (the first object). Use with limit to get the next set of episodes.
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-shows-episodes)
+    async fn get_shows_episodes<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        id: String,
+        limit: L,
+        offset: O,
+        market: Option<Market>,
+    ) -> ClientResult<Page<SimplifiedEpisode>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        let url = format!("shows/{}/episodes", get_id(Type::Show, &id)?);
+        self.endpoint_get_as(&url, &params).await
+    }
+
+    /// Get Spotify catalog information for a single episode identified by its unique Spotify ID.
+    ///
+    /// Path Parameters
+    /// - id: The Spotify ID for the episode.
+    ///
+    /// Query Parameters
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-episode)
+    async fn get_an_episode(
+        &self,
+        id: String,
+        market: Option<Market>,
+    ) -> ClientResult<FullEpisode> {
+        let url = format!("episodes/{}", get_id(Type::Episode, &id)?);
+        let mut params = Query::new();
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+
+        let result = self.endpoint_get(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Get Spotify catalog information for multiple episodes based on their Spotify IDs.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the episodes. Maximum: 50 IDs.
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-episodes)
+    async fn get_several_episodes<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<SeveralEpisodes> {
+        let mut params = Query::with_capacity(1);
+        params.insert(
+            "ids".to_owned(),
+            ids.into_iter().collect::<Vec<_>>().join(","),
+        );
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        let result = self.endpoint_get("episodes", &params).await?;
+        convert_result(&result)
+    }
+}
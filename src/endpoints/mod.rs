@@ -0,0 +1,366 @@
+//! The endpoints of the Spotify API, grouped in traits implemented over the
+//! HTTP wrappers of a client.
+//!
+//! [`BaseClient`] has the endpoints that don't access the user's data, which
+//! work with any kind of token, and [`OAuthClient`] the ones that need the
+//! user's authorization. A client only has to implement these wrappers to
+//! inherit all the endpoints, like [`Spotify`](crate::client::Spotify) does.
+//! They're also re-exported in the [`prelude`](crate::prelude).
+
+mod base;
+mod oauth;
+
+pub use base::BaseClient;
+pub use oauth::OAuthClient;
+
+use crate::client::{
+    ClientError, ClientResult, Query, ALBUM_TRACKS_PAGE_SIZE, PLAYLISTS_PAGE_SIZE,
+    PLAYLIST_ITEMS_LIMIT, RECENTLY_PLAYED_PAGE_SIZE,
+};
+use crate::model::*;
+use crate::pagination::CursorPage;
+
+use chrono::prelude::*;
+use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// Get spotify id by type and id, which may also be a URI or URL. An error is
+/// returned if it's of another type or has invalid characters.
+/// TODO: should be rewritten and moved into a separate type for IDs
+pub(in crate) fn get_id(_type: Type, id: &str) -> ClientResult<String> {
+    Ok(parse_id(_type, id)?)
+}
+
+/// TODO: should be moved into a custom type
+pub(in crate) fn get_uri(_type: Type, _id: &str) -> ClientResult<String> {
+    Ok(format!(
+        "spotify:{}:{}",
+        _type.to_string(),
+        get_id(_type, _id)?
+    ))
+}
+
+/// Checks that the `limit` of a paginated endpoint is between 1 and `max`,
+/// the size of its largest page.
+pub(in crate) fn check_limit(limit: u32, max: u32) -> ClientResult<u32> {
+    check_param("limit", limit, 1, max)
+}
+
+/// Checks that a parameter is within the range allowed by the endpoint, so
+/// that it fails before the request instead of with a vague `400 Bad
+/// Request` from Spotify.
+pub(in crate) fn check_param(
+    name: &'static str,
+    value: u32,
+    min: u32,
+    max: u32,
+) -> ClientResult<u32> {
+    if min <= value && value <= max {
+        Ok(value)
+    } else {
+        Err(ClientError::InvalidParameter {
+            name,
+            value,
+            min,
+            max,
+        })
+    }
+}
+
+/// Converts a JSON response from Spotify into its model.
+pub(in crate) fn convert_result<'a, T: Deserialize<'a>>(input: &'a str) -> ClientResult<T> {
+    serde_json::from_str::<T>(input).map_err(Into::into)
+}
+
+/// Append device ID to an API path.
+pub(in crate) fn append_device_id(path: &str, device_id: Option<String>) -> String {
+    let mut new_path = path.to_string();
+    if let Some(device_id) = device_id {
+        let mut query = Query::new();
+        query.insert("device_id", device_id);
+        new_path.push(if path.contains('?') { '&' } else { '?' });
+        new_path.push_str(&query.encode());
+    }
+    new_path
+}
+
+/// Checks whether each of the IDs is in the user's library, or followed by
+/// them, with the `contains` endpoint at `url`. The IDs are split in requests
+/// of up to `limit`, and any other parameter of the endpoint is in `params`.
+///
+/// The results are by ID, so that they don't depend on the order of the
+/// input.
+#[maybe_async]
+pub(in crate) async fn contains_map<'a, C, T>(
+    client: &C,
+    url: &str,
+    params: &Query,
+    ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    limit: usize,
+) -> ClientResult<HashMap<T, bool>>
+where
+    C: OAuthClient + ?Sized,
+    T: AnyId + FromStr<Err = IdError> + Clone + Eq + Hash,
+{
+    let ids = ids
+        .into_iter()
+        .map(T::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut result = HashMap::with_capacity(ids.len());
+    for chunk in ids.chunks(limit) {
+        let mut params = params.clone();
+        let joined = chunk.iter().map(AnyId::id).collect::<Vec<_>>().join(",");
+        params.insert("ids", joined);
+        let contains = client.endpoint_get(url, &params).await?;
+        let contains = convert_result::<Vec<bool>>(&contains)?;
+        result.extend(chunk.iter().cloned().zip(contains));
+    }
+    Ok(result)
+}
+
+/// Wraps an album of [`BaseClient::artist_discography`], requesting all of
+/// its tracks if `with_tracks` is set.
+#[maybe_async]
+pub(in crate) async fn discography_album<C: BaseClient + ?Sized>(
+    client: &C,
+    album: SimplifiedAlbum,
+    with_tracks: bool,
+) -> ClientResult<DiscographyAlbum> {
+    let tracks = match &album.id {
+        Some(id) if with_tracks => {
+            let mut tracks = Vec::new();
+            let mut offset = Some(0);
+            while let Some(current) = offset {
+                let page = client
+                    .album_track(id, ALBUM_TRACKS_PAGE_SIZE, current)
+                    .await?;
+                offset = page.next_offset();
+                tracks.extend(page.items);
+            }
+            Some(tracks)
+        }
+        _ => None,
+    };
+
+    Ok(DiscographyAlbum { album, tracks })
+}
+
+/// Requests the playlists of [`BaseClient::playlists`] at their `urls`, with
+/// up to `concurrency` requests at the same time, keeping their order.
+#[cfg(feature = "__async")]
+pub(in crate) async fn fetch_playlists<C, T>(
+    client: &C,
+    urls: Vec<String>,
+    params: &Query,
+    concurrency: usize,
+) -> ClientResult<Vec<T>>
+where
+    C: BaseClient + ?Sized,
+    T: DeserializeOwned + Send,
+{
+    use futures::stream::{self, StreamExt, TryStreamExt};
+
+    stream::iter(urls)
+        .map(|url| async move { client.endpoint_get_as(&url, params).await })
+        .buffered(concurrency)
+        .try_collect()
+        .await
+}
+
+/// Requests the playlists of [`BaseClient::playlists`] at their `urls` one
+/// at a time.
+#[cfg(feature = "__sync")]
+pub(in crate) fn fetch_playlists<C, T>(
+    client: &C,
+    urls: Vec<String>,
+    params: &Query,
+    _concurrency: usize,
+) -> ClientResult<Vec<T>>
+where
+    C: BaseClient + ?Sized,
+    T: DeserializeOwned + Send,
+{
+    urls.iter()
+        .map(|url| client.endpoint_get_as(url, params))
+        .collect()
+}
+
+/// Requests all the playlists of the current user, keeping the ones owned
+/// by the ID given by [`OAuthClient::current_user_id`], or the rest if
+/// `owned` is false.
+#[maybe_async]
+pub(in crate) async fn current_user_playlists_by_owner<C: OAuthClient + ?Sized>(
+    client: &C,
+    owned: bool,
+) -> ClientResult<Vec<SimplifiedPlaylist>> {
+    let user_id = client.current_user_id().await?;
+    let mut playlists = Vec::new();
+    let mut offset = Some(0);
+    while let Some(current) = offset {
+        let page = client
+            .current_user_playlists(PLAYLISTS_PAGE_SIZE, current)
+            .await?;
+        offset = page.next_offset();
+        playlists.extend(
+            page.items
+                .into_iter()
+                .filter(|playlist| (playlist.owner.id == user_id) == owned),
+        );
+    }
+    Ok(playlists)
+}
+
+/// Creates the playlist of [`OAuthClient::create_playlists`] unless it
+/// already was, and adds the tracks that weren't yet, updating the progress
+/// in `def` after each request.
+#[maybe_async]
+pub(in crate) async fn import_playlist<C: OAuthClient + ?Sized>(
+    client: &C,
+    def: &mut PlaylistDef,
+) -> ClientResult<()> {
+    let playlist_id = match &def.playlist_id {
+        Some(id) => id.clone(),
+        None => {
+            let user_id = client.current_user_id().await?;
+            let playlist = client
+                .user_playlist_create(&user_id, &def.name, None, def.description.clone())
+                .await?;
+            def.playlist_id = Some(playlist.id.clone());
+            playlist.id
+        }
+    };
+
+    let pending = def.tracks[def.added.min(def.tracks.len())..].to_vec();
+    for chunk in pending.chunks(PLAYLIST_ITEMS_LIMIT) {
+        client
+            .playlist_add_tracks(&playlist_id, chunk.iter().map(String::as_str), None)
+            .await?;
+        def.added += chunk.len();
+    }
+    Ok(())
+}
+
+/// Requests the page of recently played tracks before the given cursor,
+/// stopping at the plays at or before `after`. Spotify only keeps a
+/// limited history, so the paging ends once a page comes back empty.
+#[maybe_async]
+pub(in crate) async fn recently_played_page<C: OAuthClient + ?Sized>(
+    client: &C,
+    before: Option<DateTime<Utc>>,
+    after: Option<DateTime<Utc>>,
+) -> ClientResult<CursorPage<PlayHistory, Option<DateTime<Utc>>>> {
+    let page = client
+        .current_user_recently_played(RECENTLY_PLAYED_PAGE_SIZE, before.map(TimeLimits::Before))
+        .await?;
+    let received = page.items.len();
+    let mut items = page.items;
+    if let Some(after) = after {
+        items.retain(|play| play.played_at > after);
+    }
+
+    let next = match items.last() {
+        Some(last) if items.len() == received && page.next.is_some() => Some(Some(last.played_at)),
+        _ => None,
+    };
+    Ok((items, next))
+}
+
+/// Whether an album with the same name and release date has already been
+/// seen, adding it otherwise. Errors are never considered duplicates.
+pub(in crate) fn is_duplicate_release(
+    seen: &mut HashSet<(String, Option<String>)>,
+    album: &ClientResult<SimplifiedAlbum>,
+) -> bool {
+    match album {
+        Ok(album) => {
+            let release = album.release_date.as_ref().map(ToString::to_string);
+            !seen.insert((album.name.to_lowercase(), release))
+        }
+        Err(_) => false,
+    }
+}
+
+/// Whether the play is of the same track as the previous one, updating `last`
+/// with its URI. Errors are never considered repeated.
+pub(in crate) fn is_repeated_play(
+    last: &mut Option<String>,
+    play: &ClientResult<PlayHistory>,
+) -> bool {
+    match play {
+        Ok(play) => last.replace(play.track.uri.clone()).as_ref() == Some(&play.track.uri),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientError;
+
+    #[test]
+    fn test_get_id() {
+        // Assert artist
+        let artist_id = "spotify:artist:2WX2uTcsvV5OnS0inACecP";
+        let id = get_id(Type::Artist, artist_id).unwrap();
+        assert_eq!("2WX2uTcsvV5OnS0inACecP", &id);
+
+        // Assert album
+        let artist_id_a = "spotify/album/2WX2uTcsvV5OnS0inACecP";
+        assert_eq!(
+            "2WX2uTcsvV5OnS0inACecP",
+            &get_id(Type::Album, artist_id_a).unwrap()
+        );
+
+        // Mismatch type
+        let artist_id_b = "spotify:album:2WX2uTcsvV5OnS0inACecP";
+        assert!(matches!(
+            get_id(Type::Artist, artist_id_b),
+            Err(ClientError::InvalidId(IdError::InvalidType { .. }))
+        ));
+
+        // Could not split
+        let artist_id_c = "spotify-album-2WX2uTcsvV5OnS0inACecP";
+        assert!(matches!(
+            get_id(Type::Artist, artist_id_c),
+            Err(ClientError::InvalidId(IdError::InvalidId(_)))
+        ));
+
+        let playlist_id = "spotify:playlist:59ZbFPES4DQwEjBpWHzrtC";
+        assert_eq!(
+            "59ZbFPES4DQwEjBpWHzrtC",
+            &get_id(Type::Playlist, playlist_id).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_uri() {
+        let track_id1 = "spotify:track:4iV5W9uYEdYUVa79Axb7Rh";
+        let track_id2 = "1301WleyT98MSxVHPZCA6M";
+        let uri1 = get_uri(Type::Track, track_id1).unwrap();
+        let uri2 = get_uri(Type::Track, track_id2).unwrap();
+        assert_eq!(track_id1, uri1);
+        assert_eq!("spotify:track:1301WleyT98MSxVHPZCA6M", &uri2);
+    }
+
+    #[test]
+    fn test_check_param() {
+        assert_eq!(check_limit(50, 50).unwrap(), 50);
+        assert!(matches!(
+            check_limit(0, 50),
+            Err(ClientError::InvalidParameter {
+                name: "limit",
+                min: 1,
+                ..
+            })
+        ));
+        let err = check_param("offset", 100_000, 0, 99_999).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid offset: 100000 is not between 0 and 99999"
+        );
+    }
+}
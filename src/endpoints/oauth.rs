@@ -0,0 +1,2072 @@
+//! The endpoints that need the user's authorization.
+
+use super::{
+    append_device_id, check_limit, contains_map, convert_result, current_user_playlists_by_owner,
+    get_id, get_uri, import_playlist, is_repeated_play, recently_played_page, BaseClient,
+};
+use crate::client::{
+    APIError, ClientError, ClientResult, Query, FADE_VOLUME_INTERVAL, FOLLOWED_ARTISTS_IDS_LIMIT,
+    PAGE_LIMIT, PLAYBACK_URIS_LIMIT, PLAYLISTS_PAGE_SIZE, PLAYLIST_ITEMS_LIMIT,
+    RECENTLY_PLAYED_PAGE_SIZE, SAVED_ALBUMS_IDS_LIMIT, SAVED_ALBUMS_PAGE_SIZE,
+    SAVED_AUDIOBOOKS_IDS_LIMIT, SAVED_AUDIOBOOKS_PAGE_SIZE, SAVED_SHOWS_IDS_LIMIT,
+    SAVED_SHOWS_PAGE_SIZE, SAVED_TRACKS_IDS_LIMIT, SAVED_TRACKS_PAGE_SIZE, TRANSFER_RETRY_DELAY,
+};
+use crate::http::encode_param;
+use crate::http::retry::default_sleeper;
+use crate::json_insert;
+use crate::model::*;
+use crate::pagination::{paginate, paginate_cursor};
+
+use chrono::{DateTime, Utc};
+use log::error;
+use maybe_async::maybe_async;
+use serde_json::map::Map;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+#[cfg(feature = "__async")]
+use futures::future;
+#[cfg(feature = "__async")]
+use futures::stream::{Stream, StreamExt};
+
+/// The endpoints of the Spotify API that access the user's data, so they need
+/// a token obtained with the user's authorization, besides the ones in
+/// [`BaseClient`].
+///
+/// They're implemented over the HTTP wrappers of both traits, so any client
+/// that implements these inherits all of them.
+#[maybe_async]
+pub trait OAuthClient: BaseClient {
+    /// The wrapper for the POST endpoints, which also includes the required
+    /// authentication.
+    async fn endpoint_post(&self, url: &str, payload: &Value) -> ClientResult<String>;
+
+    /// The wrapper for the PUT endpoints, which also includes the required
+    /// authentication.
+    async fn endpoint_put(&self, url: &str, payload: &Value) -> ClientResult<String>;
+
+    /// The wrapper for the DELETE endpoints, which also includes the required
+    /// authentication.
+    async fn endpoint_delete(&self, url: &str, payload: &Value) -> ClientResult<String>;
+
+    /// Returns an error in case the token doesn't hold the scopes required by
+    /// the given endpoint, named after its method, so that it's not
+    /// requested. Nothing is checked by default.
+    fn check_scopes(&self, endpoint: &str) -> ClientResult<()> {
+        let _ = endpoint;
+        Ok(())
+    }
+
+    /// Get current user playlists without required getting his profile.
+    ///
+    /// Parameters:
+    /// - limit  - the number of items to return
+    /// - offset - the index of the first item to return
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-list-of-current-users-playlists)
+    async fn current_user_playlists<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<SimplifiedPlaylist>> {
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(50), PLAYLISTS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+
+        self.endpoint_get_as("me/playlists", &params).await
+    }
+
+    /// Returns all the playlists of the current user, requested in pages as
+    /// they're consumed. See [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__async")]
+    fn current_user_playlists_all(
+        &self,
+    ) -> impl Stream<Item = ClientResult<SimplifiedPlaylist>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_playlists(limit, offset),
+            PLAYLISTS_PAGE_SIZE,
+        )
+    }
+
+    /// Returns all the playlists of the current user, requested in pages as
+    /// they're consumed. See [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__sync")]
+    fn current_user_playlists_all(
+        &self,
+    ) -> impl Iterator<Item = ClientResult<SimplifiedPlaylist>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_playlists(limit, offset),
+            PLAYLISTS_PAGE_SIZE,
+        )
+    }
+
+    /// Gets all the playlists created by the current user, filtering the
+    /// ones in their library by owner.
+    async fn current_user_owned_playlists(&self) -> ClientResult<Vec<SimplifiedPlaylist>> {
+        current_user_playlists_by_owner(self, true).await
+    }
+
+    /// Gets all the playlists the current user follows but doesn't own,
+    /// filtering the ones in their library by owner.
+    async fn current_user_followed_playlists(&self) -> ClientResult<Vec<SimplifiedPlaylist>> {
+        current_user_playlists_by_owner(self, false).await
+    }
+
+    /// Creates a playlist for a user.
+    ///
+    /// Parameters:
+    /// - user_id - the id of the user
+    /// - name - the name of the playlist
+    /// - public - is the created playlist public
+    /// - description - the description of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-create-playlist)
+    async fn user_playlist_create<P: Into<Option<bool>> + Send, D: Into<Option<String>> + Send>(
+        &self,
+        user_id: &str,
+        name: &str,
+        public: P,
+        description: D,
+    ) -> ClientResult<FullPlaylist> {
+        self.check_scopes("user_playlist_create")?;
+        let public = public.into().unwrap_or(true);
+        let description = description.into().unwrap_or_else(|| "".to_owned());
+        let params = json!({
+            "name": name,
+            "public": public,
+            "description": description
+        });
+        let url = format!("users/{}/playlists", encode_param(user_id));
+        let result = self.endpoint_post(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Creates the given playlists for the current user and adds their
+    /// tracks in requests of up to [`PLAYLIST_ITEMS_LIMIT`], like when
+    /// migrating the playlists of a folder from another service.
+    ///
+    /// A failure only stops the import of that playlist: each of them is
+    /// returned with its error, if any, and its progress. The definitions of
+    /// the incomplete ones can be given again to resume where they stopped,
+    /// without creating the playlists again or adding the same tracks twice,
+    /// and the complete ones are skipped.
+    ///
+    /// Parameters:
+    /// - defs - the playlists to create
+    async fn create_playlists(&self, defs: Vec<PlaylistDef>) -> ClientResult<Vec<PlaylistImport>> {
+        self.check_scopes("create_playlists")?;
+        let mut imports = Vec::with_capacity(defs.len());
+        for mut def in defs {
+            let error = import_playlist(self, &mut def).await.err();
+            imports.push(PlaylistImport { def, error });
+        }
+        Ok(imports)
+    }
+
+    /// Changes a playlist's name, public/private state, description and/or
+    /// collaborative state. Only the fields given are sent, so that the
+    /// omitted ones are left unchanged, and the description can be removed
+    /// with `Some("")`.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - name - optional name of the playlist
+    /// - public - optional is the playlist public
+    /// - description - optional description of the playlist
+    /// - collaborative - optional is the playlist collaborative, which
+    ///   requires it to not be public
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-change-playlist-details)
+    async fn playlist_change_detail(
+        &self,
+        playlist_id: &str,
+        name: Option<&str>,
+        public: Option<bool>,
+        description: Option<&str>,
+        collaborative: Option<bool>,
+    ) -> ClientResult<String> {
+        self.check_scopes("playlist_change_detail")?;
+        let mut params = json!({});
+        if let Some(name) = name {
+            json_insert!(params, "name", name);
+        }
+        if let Some(public) = public {
+            json_insert!(params, "public", public);
+        }
+        if let Some(collaborative) = collaborative {
+            json_insert!(params, "collaborative", collaborative);
+        }
+        if let Some(description) = description {
+            json_insert!(params, "description", description);
+        }
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let url = format!("playlists/{}", plid);
+        self.endpoint_put(&url, &params).await
+    }
+
+    /// Unfollows (deletes) a playlist for a user.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-unfollow-playlist)
+    async fn playlist_unfollow(&self, playlist_id: &str) -> ClientResult<String> {
+        self.check_scopes("playlist_unfollow")?;
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let url = format!("playlists/{}/followers", plid);
+        self.endpoint_delete(&url, &json!({})).await
+    }
+
+    /// Unfollows all the playlists in the current user's library for which
+    /// `predicate` returns true, like the empty ones or the ones created by
+    /// some app, and returns them. With `dry_run`, nothing is unfollowed, so
+    /// that the playlists can be reviewed first.
+    ///
+    /// All the playlists are requested before unfollowing any of them, so
+    /// that the pages don't shift while they're read.
+    async fn unfollow_all_playlists_matching<F>(
+        &self,
+        predicate: F,
+        dry_run: bool,
+    ) -> ClientResult<Vec<SimplifiedPlaylist>>
+    where
+        F: Fn(&SimplifiedPlaylist) -> bool + Send + Sync,
+    {
+        if !dry_run {
+            self.check_scopes("unfollow_all_playlists_matching")?;
+        }
+        let mut matching = Vec::new();
+        let mut offset = Some(0);
+        while let Some(current) = offset {
+            let page = self
+                .current_user_playlists(PLAYLISTS_PAGE_SIZE, current)
+                .await?;
+            offset = page.next_offset();
+            matching.extend(
+                page.items
+                    .into_iter()
+                    .filter(|playlist| predicate(playlist)),
+            );
+        }
+
+        if !dry_run {
+            for playlist in &matching {
+                self.playlist_unfollow(&playlist.id).await?;
+            }
+        }
+        Ok(matching)
+    }
+
+    /// Adds tracks to a playlist.
+    ///
+    /// Any number of tracks may be given: they're added in requests of up to
+    /// [`PLAYLIST_ITEMS_LIMIT`], each one inserted after the previous one if
+    /// a position is given, so that they end up in the same order. The
+    /// snapshot ID of the playlist after the last request is returned.
+    ///
+    /// The requests stop at the first one that fails, whose error is
+    /// returned, but the tracks of the previous ones stay in the playlist.
+    /// Retrying the call would add those twice, so
+    /// [`OAuthClient::create_playlists`] should be used instead when the
+    /// progress has to be resumed.
+    ///
+    /// Local files can't be added through the API, so their URIs fail with
+    /// [`IdError::LocalFile`](crate::model::IdError::LocalFile) before any
+    /// request is made.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - track_ids - a list of track URIs, URLs or IDs
+    /// - position - the position to add the tracks
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-add-tracks-to-playlist)
+    async fn playlist_add_tracks<'a>(
+        &self,
+        playlist_id: &str,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+        position: Option<i32>,
+    ) -> ClientResult<PlaylistResult> {
+        self.check_scopes("playlist_add_tracks")?;
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let uris: Vec<String> = track_ids
+            .into_iter()
+            .map(|id| get_uri(Type::Track, id))
+            .collect::<ClientResult<_>>()?;
+        let url = format!("playlists/{}/tracks", plid);
+
+        // An empty list is still sent, to get the snapshot ID.
+        let chunks = if uris.is_empty() {
+            vec![&uris[..]]
+        } else {
+            uris.chunks(PLAYLIST_ITEMS_LIMIT).collect()
+        };
+        let mut result = None;
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let mut params = json!({ "uris": chunk });
+            if let Some(position) = position {
+                let position = position + (i * PLAYLIST_ITEMS_LIMIT) as i32;
+                json_insert!(params, "position", position);
+            }
+            let response = self.endpoint_post(&url, &params).await?;
+            result = Some(convert_result(&response)?);
+        }
+        Ok(result.unwrap())
+    }
+
+    /// Replace all tracks in a playlist
+    ///
+    /// Parameters:
+    /// - user - the id of the user
+    /// - playlist_id - the id of the playlist
+    /// - tracks - the list of track ids to add to the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-reorder-or-replace-playlists-tracks)
+    async fn playlist_replace_tracks<'a>(
+        &self,
+        playlist_id: &str,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("playlist_replace_tracks")?;
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let uris: Vec<String> = track_ids
+            .into_iter()
+            .map(|id| get_uri(Type::Track, id))
+            .collect::<ClientResult<_>>()?;
+        // let mut params = Map::new();
+        // params.insert("uris".to_owned(), uris.into());
+        let params = json!({ "uris": uris });
+        let url = format!("playlists/{}/tracks", plid);
+        self.endpoint_put(&url, &params).await?;
+
+        Ok(())
+    }
+
+    /// Reorder tracks in a playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - range_start - the position of the first track to be reordered
+    /// - range_length - optional the number of tracks to be reordered (default:
+    ///   1)
+    /// - insert_before - the position where the tracks should be inserted
+    /// - snapshot_id - optional playlist's snapshot ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-reorder-or-replace-playlists-tracks)
+    async fn playlist_reorder_tracks<R: Into<Option<u32>> + Send>(
+        &self,
+        playlist_id: &str,
+        range_start: i32,
+        range_length: R,
+        insert_before: i32,
+        snapshot_id: Option<String>,
+    ) -> ClientResult<PlaylistResult> {
+        self.check_scopes("playlist_reorder_tracks")?;
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let mut params = json! ({
+            "range_start": range_start,
+            "range_length": range_length.into().unwrap_or(1),
+            "insert_before": insert_before
+        });
+        if let Some(snapshot_id) = snapshot_id {
+            json_insert!(params, "snapshot_id", snapshot_id);
+        }
+
+        let url = format!("playlists/{}/tracks", plid);
+        let result = self.endpoint_put(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Removes all occurrences of the given tracks from the given playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - track_ids - the list of track ids to add to the playlist
+    /// - snapshot_id - optional id of the playlist snapshot
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-tracks-playlist)
+    async fn playlist_remove_all_occurrences_of_tracks<'a>(
+        &self,
+        playlist_id: &str,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+        snapshot_id: Option<String>,
+    ) -> ClientResult<PlaylistResult> {
+        self.check_scopes("playlist_remove_all_occurrences_of_tracks")?;
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let uris: Vec<String> = track_ids
+            .into_iter()
+            .map(|id| get_uri(Type::Track, id))
+            .collect::<ClientResult<_>>()?;
+
+        // TODO: this can be improved
+        let mut tracks: Vec<Map<String, Value>> = vec![];
+        for uri in uris {
+            let mut map = Map::new();
+            map.insert("uri".to_owned(), uri.into());
+            tracks.push(map);
+        }
+        let mut params = json!({ "tracks": tracks });
+        if let Some(snapshot_id) = snapshot_id {
+            json_insert!(params, "snapshot_id", snapshot_id);
+        }
+        let url = format!("playlists/{}/tracks", plid);
+        let result = self.endpoint_delete(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Removes specfic occurrences of the given tracks from the given playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id: the id of the playlist
+    /// - tracks: an array of map containing Spotify URIs of the tracks to
+    ///   remove with their current positions in the playlist. For example:
+    ///
+    /// ```json
+    /// {
+    ///    "tracks":[
+    ///       {
+    ///          "uri":"spotify:track:4iV5W9uYEdYUVa79Axb7Rh",
+    ///          "positions":[
+    ///             0,
+    ///             3
+    ///          ]
+    ///       },
+    ///       {
+    ///          "uri":"spotify:track:1301WleyT98MSxVHPZCA6M",
+    ///          "positions":[
+    ///             7
+    ///          ]
+    ///       }
+    ///    ]
+    /// }
+    /// ```
+    /// - snapshot_id: optional id of the playlist snapshot
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-tracks-playlist)
+    async fn playlist_remove_specific_occurrences_of_tracks(
+        &self,
+        playlist_id: &str,
+        tracks: Vec<Map<String, Value>>,
+        snapshot_id: Option<String>,
+    ) -> ClientResult<PlaylistResult> {
+        self.check_scopes("playlist_remove_specific_occurrences_of_tracks")?;
+        // TODO: this can be improved
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let mut ftracks: Vec<Map<String, Value>> = vec![];
+        for track in tracks {
+            let mut map = Map::new();
+            if let Some(_uri) = track.get("uri") {
+                let uri = get_uri(Type::Track, &_uri.as_str().unwrap().to_owned())?;
+                map.insert("uri".to_owned(), uri.into());
+            }
+            if let Some(_position) = track.get("position") {
+                map.insert("position".to_owned(), _position.to_owned());
+            }
+            ftracks.push(map);
+        }
+
+        let mut params = json!({ "tracks": ftracks });
+        if let Some(snapshot_id) = snapshot_id {
+            json_insert!(params, "snapshot_id", snapshot_id);
+        }
+        let url = format!("playlists/{}/tracks", plid);
+        let result = self.endpoint_delete(&url, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Add the current authenticated user as a follower of a playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-follow-playlist)
+    async fn playlist_follow<P: Into<Option<bool>> + Send>(
+        &self,
+        playlist_id: &str,
+        public: P,
+    ) -> ClientResult<()> {
+        self.check_scopes("playlist_follow")?;
+        let plid = get_id(Type::Playlist, playlist_id)?;
+        let url = format!("playlists/{}/followers", plid);
+
+        self.endpoint_put(
+            &url,
+            &json! ({
+                "public": public.into().unwrap_or(true)
+            }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get detailed profile information about the current user.
+    /// An alias for the 'current_user' method.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-current-users-profile)
+    async fn me(&self) -> ClientResult<PrivateUser> {
+        let result = self.endpoint_get("me/", &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Get detailed profile information about the current user.
+    /// An alias for the 'me' method.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-current-users-profile)
+    async fn current_user(&self) -> ClientResult<PrivateUser> {
+        self.me().await
+    }
+
+    /// Returns the ID of the current user, which is requested with
+    /// [`OAuthClient::me`] by default. The client caches it, so that it's
+    /// only requested once.
+    async fn current_user_id(&self) -> ClientResult<String> {
+        Ok(self.me().await?.id)
+    }
+
+    /// Returns the device to use in the player endpoints when none is given,
+    /// which is none by default, so that the active one is used.
+    async fn default_device(&self, device_id: Option<String>) -> ClientResult<Option<String>> {
+        Ok(device_id)
+    }
+
+    /// Waits for the given duration in the endpoints that space out their
+    /// requests, like [`OAuthClient::transfer_playback_to`]. It uses the
+    /// sleeper of the client feature by default, while the client uses its
+    /// [`sleeper`](crate::client::Spotify::sleeper), so that custom runtimes
+    /// and sleepers are honoured.
+    async fn sleep(&self, duration: Duration) {
+        default_sleeper().sleep(duration).await
+    }
+
+    /// Checks whether the current user follows the given playlist, with the
+    /// ID given by [`OAuthClient::current_user_id`].
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-if-user-follows-playlist)
+    async fn is_following_playlist(&self, playlist_id: &str) -> ClientResult<bool> {
+        self.check_scopes("is_following_playlist")?;
+        let user_id = self.current_user_id().await?;
+        let following = self.playlist_check_follow(playlist_id, &[user_id]).await?;
+        Ok(following.first().copied().unwrap_or(false))
+    }
+
+    /// Get information about the current users currently playing track.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
+    async fn current_user_playing_track(&self) -> ClientResult<Option<CurrentlyPlayingContext>> {
+        self.check_scopes("current_user_playing_track")?;
+        let result = self
+            .endpoint_get("me/player/currently-playing", &Query::new())
+            .await?;
+        if result.is_empty() {
+            Ok(None)
+        } else {
+            convert_result(&result)
+        }
+    }
+
+    /// Gets a list of the albums saved in the current authorized user's
+    /// "Your Music" library
+    ///
+    /// Parameters:
+    /// - limit - the number of albums to return
+    /// - offset - the index of the first album to return
+    /// - market - Provide this parameter if you want to apply Track Relinking.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-albums)
+    async fn current_user_saved_albums<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        limit: L,
+        offset: O,
+        market: Option<Market>,
+    ) -> ClientResult<Page<SavedAlbum>> {
+        self.check_scopes("current_user_saved_albums")?;
+        let mut params = Query::with_capacity(3);
+        let limit = check_limit(limit.into().unwrap_or(20), SAVED_ALBUMS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        self.endpoint_get_as("me/albums", &params).await
+    }
+
+    /// Returns all the albums saved in the current user's library, requested
+    /// in pages as they're consumed. See
+    /// [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__async")]
+    fn current_user_saved_albums_all(
+        &self,
+        market: Option<Market>,
+    ) -> impl Stream<Item = ClientResult<SavedAlbum>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_saved_albums(limit, offset, market.clone()),
+            SAVED_ALBUMS_PAGE_SIZE,
+        )
+    }
+
+    /// Returns all the albums saved in the current user's library, requested
+    /// in pages as they're consumed. See
+    /// [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__sync")]
+    fn current_user_saved_albums_all(
+        &self,
+        market: Option<Market>,
+    ) -> impl Iterator<Item = ClientResult<SavedAlbum>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_saved_albums(limit, offset, market.clone()),
+            SAVED_ALBUMS_PAGE_SIZE,
+        )
+    }
+
+    /// Get a list of the songs saved in the current Spotify user's "Your Music"
+    /// library.
+    ///
+    /// Parameters:
+    /// - limit - the number of tracks to return
+    /// - offset - the index of the first track to return
+    /// - market - Provide this parameter if you want to apply Track Relinking.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-tracks)
+    async fn current_user_saved_tracks<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<SavedTrack>> {
+        self.check_scopes("current_user_saved_tracks")?;
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), SAVED_TRACKS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        self.endpoint_get_as("me/tracks", &params).await
+    }
+
+    /// Finds the changes in the user's saved tracks since `previous`, the
+    /// snapshot returned by the last sync, or all of them if it's `None`.
+    ///
+    /// The tracks are requested from the most recently saved until one that
+    /// was already in the snapshot is found, so an incremental sync usually
+    /// takes a single request. Removed tracks can't be noticed that way, so
+    /// with `full` all the pages are requested instead, and the tracks in the
+    /// snapshot that weren't found are returned as removed.
+    ///
+    /// Parameters:
+    /// - previous - the snapshot of the last sync
+    /// - full - whether to request all the saved tracks to find the removed ones
+    async fn sync_saved_tracks(
+        &self,
+        previous: Option<&SavedTracksSnapshot>,
+        full: bool,
+    ) -> ClientResult<SavedTracksSync> {
+        self.check_scopes("sync_saved_tracks")?;
+        let empty = SavedTracksSnapshot::default();
+        let previous = previous.unwrap_or(&empty);
+        let known: HashSet<&str> = previous.ids.iter().map(String::as_str).collect();
+
+        let mut added = Vec::new();
+        let mut found = HashSet::new();
+        let mut ids = Vec::new();
+        let mut latest_added_at = None;
+        let mut total;
+        let mut offset = 0;
+        'pages: loop {
+            let page = self
+                .current_user_saved_tracks(SAVED_TRACKS_PAGE_SIZE, offset)
+                .await?;
+            total = page.total;
+            for saved in page.items {
+                // Local tracks don't have IDs, so they can't be told apart.
+                let id = match &saved.track.id {
+                    Some(id) => id.clone(),
+                    None => continue,
+                };
+                latest_added_at = latest_added_at.or(Some(saved.added_at));
+                if known.contains(id.as_str()) {
+                    if !full {
+                        break 'pages;
+                    }
+                } else {
+                    added.push(saved);
+                }
+                found.insert(id.clone());
+                ids.push(id);
+            }
+            if page.next.is_none() {
+                break;
+            }
+            offset += SAVED_TRACKS_PAGE_SIZE;
+        }
+
+        let removed = if full {
+            previous
+                .ids
+                .iter()
+                .filter(|id| !found.contains(id.as_str()))
+                .cloned()
+                .collect()
+        } else {
+            ids.extend(
+                previous
+                    .ids
+                    .iter()
+                    .filter(|id| !found.contains(id.as_str()))
+                    .cloned(),
+            );
+            Vec::new()
+        };
+        let incomplete = !full && ids.len() > total as usize;
+
+        Ok(SavedTracksSync {
+            added,
+            removed,
+            incomplete,
+            snapshot: SavedTracksSnapshot {
+                ids,
+                latest_added_at: latest_added_at.or(previous.latest_added_at),
+            },
+        })
+    }
+
+    /// Gets a list of the artists followed by the current authorized user.
+    ///
+    /// Parameters:
+    /// - limit - the number of tracks to return
+    /// - after - the last artist ID retrieved from the previous request
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-followed)
+    async fn current_user_followed_artists<L: Into<Option<u32>> + Send>(
+        &self,
+        limit: L,
+        after: Option<String>,
+    ) -> ClientResult<CursorBasedPage<FullArtist>> {
+        self.check_scopes("current_user_followed_artists")?;
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("type".to_owned(), Type::Artist.to_string());
+        params.insert_opt("after", after);
+
+        self.endpoint_get_as::<CursorPageFullArtists>("me/following", &params)
+            .await
+            .map(|x| x.artists)
+    }
+
+    /// Remove one or more tracks from the current user's "Your Music" library.
+    ///
+    /// Parameters:
+    /// - track_ids - a list of track URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-tracks-user)
+    async fn current_user_saved_tracks_delete<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("current_user_saved_tracks_delete")?;
+        let uris: Vec<String> = track_ids
+            .into_iter()
+            .map(|id| get_id(Type::Track, id))
+            .collect::<ClientResult<_>>()?;
+        let url = format!("me/tracks/?ids={}", uris.join(","));
+        self.endpoint_delete(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Removes all the tracks in the current user's library for which
+    /// `predicate` returns true, like the unplayable ones or the ones of
+    /// some artist, and returns them. With `dry_run`, nothing is removed, so
+    /// that the tracks can be reviewed first. Local tracks are skipped.
+    ///
+    /// All the saved tracks are requested before removing any of them, so
+    /// that the pages don't shift while they're read. They're then removed
+    /// in requests of up to [`SAVED_TRACKS_IDS_LIMIT`]. If one of them fails,
+    /// its error is returned and the tracks of the previous requests remain
+    /// removed, so the call can be repeated to remove the rest.
+    async fn remove_saved_tracks_matching<F>(
+        &self,
+        predicate: F,
+        dry_run: bool,
+    ) -> ClientResult<Vec<SavedTrack>>
+    where
+        F: Fn(&SavedTrack) -> bool + Send + Sync,
+    {
+        if !dry_run {
+            self.check_scopes("remove_saved_tracks_matching")?;
+        }
+        let mut matching = Vec::new();
+        let mut offset = Some(0);
+        while let Some(current) = offset {
+            let page = self
+                .current_user_saved_tracks(SAVED_TRACKS_PAGE_SIZE, current)
+                .await?;
+            offset = page.next_offset();
+            matching.extend(
+                page.items
+                    .into_iter()
+                    .filter(|saved| saved.track.id.is_some() && predicate(saved)),
+            );
+        }
+
+        if !dry_run {
+            let ids = matching
+                .iter()
+                .filter_map(|saved| saved.track.id.as_deref())
+                .collect::<Vec<_>>();
+            for chunk in ids.chunks(SAVED_TRACKS_IDS_LIMIT) {
+                self.current_user_saved_tracks_delete(chunk.iter().copied())
+                    .await?;
+            }
+        }
+        Ok(matching)
+    }
+
+    /// Check if one or more tracks is already saved in the current Spotify
+    /// user’s "Your Music" library.
+    ///
+    /// Parameters:
+    /// - track_ids - a list of track URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-tracks)
+    async fn current_user_saved_tracks_contains<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        self.check_scopes("current_user_saved_tracks_contains")?;
+        let uris: Vec<String> = track_ids
+            .into_iter()
+            .map(|id| get_id(Type::Track, id))
+            .collect::<ClientResult<_>>()?;
+        let url = format!("me/tracks/contains/?ids={}", uris.join(","));
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Like [`OAuthClient::current_user_saved_tracks_contains`], but returning whether each track is saved
+    /// by its ID, so that the results don't depend on the order of the input.
+    /// The IDs are split in requests of up to [`SAVED_TRACKS_IDS_LIMIT`].
+    async fn current_user_saved_tracks_contains_map<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<HashMap<TrackId, bool>> {
+        self.check_scopes("current_user_saved_tracks_contains_map")?;
+        let params = Query::new();
+        contains_map(
+            self,
+            "me/tracks/contains",
+            &params,
+            track_ids,
+            SAVED_TRACKS_IDS_LIMIT,
+        )
+        .await
+    }
+
+    /// Save one or more tracks to the current user's "Your Music" library.
+    ///
+    /// Parameters:
+    /// - track_ids - a list of track URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-tracks-user)
+    async fn current_user_saved_tracks_add<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("current_user_saved_tracks_add")?;
+        let uris: Vec<String> = track_ids
+            .into_iter()
+            .map(|id| get_id(Type::Track, id))
+            .collect::<ClientResult<_>>()?;
+        let url = format!("me/tracks/?ids={}", uris.join(","));
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Get the current user's top artists.
+    ///
+    /// Parameters:
+    /// - limit - the number of entities to return
+    /// - offset - the index of the first entity to return
+    /// - time_range - Over what time frame are the affinities computed
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-top-artists-and-tracks)
+    async fn current_user_top_artists<
+        L: Into<Option<u32>> + Send,
+        O: Into<Option<u32>> + Send,
+        T: Into<Option<TimeRange>> + Send,
+    >(
+        &self,
+        limit: L,
+        offset: O,
+        time_range: T,
+    ) -> ClientResult<Page<FullArtist>> {
+        self.check_scopes("current_user_top_artists")?;
+        let mut params = Query::with_capacity(3);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        params.insert(
+            "time_range".to_owned(),
+            time_range
+                .into()
+                .unwrap_or(TimeRange::MediumTerm)
+                .to_string(),
+        );
+        self.endpoint_get_as(&"me/top/artists", &params).await
+    }
+
+    /// Get the current user's top tracks.
+    ///
+    /// Parameters:
+    /// - limit - the number of entities to return
+    /// - offset - the index of the first entity to return
+    /// - time_range - Over what time frame are the affinities computed
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-top-artists-and-tracks)
+    async fn current_user_top_tracks<
+        L: Into<Option<u32>> + Send,
+        O: Into<Option<u32>> + Send,
+        T: Into<Option<TimeRange>> + Send,
+    >(
+        &self,
+        limit: L,
+        offset: O,
+        time_range: T,
+    ) -> ClientResult<Page<FullTrack>> {
+        self.check_scopes("current_user_top_tracks")?;
+        let mut params = Query::with_capacity(3);
+        let limit = check_limit(limit.into().unwrap_or(20), PAGE_LIMIT)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        params.insert(
+            "time_range".to_owned(),
+            time_range
+                .into()
+                .unwrap_or(TimeRange::MediumTerm)
+                .to_string(),
+        );
+        self.endpoint_get_as("me/top/tracks", &params).await
+    }
+
+    /// Get the current user's recently played tracks.
+    ///
+    /// Parameters:
+    /// - limit - the number of entities to return
+    /// - time_limit - only return the plays before or after the given time
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
+    async fn current_user_recently_played<L: Into<Option<u32>> + Send>(
+        &self,
+        limit: L,
+        time_limit: Option<TimeLimits>,
+    ) -> ClientResult<CursorBasedPage<PlayHistory>> {
+        self.check_scopes("current_user_recently_played")?;
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(50), RECENTLY_PLAYED_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.extend(time_limit.map(|limit| limit.to_param()));
+        self.endpoint_get_as("me/player/recently-played", &params)
+            .await
+    }
+
+    /// Returns the recently played tracks of the current user, from the most
+    /// recent one backwards, requested in pages as they're consumed. See
+    /// [`paginate_cursor`](crate::pagination::paginate_cursor).
+    ///
+    /// Parameters:
+    /// - after - only return the plays after the given time
+    /// - dedup - collapse consecutive plays of the same track into the most
+    ///   recent one
+    #[cfg(feature = "__async")]
+    fn current_user_recently_played_all(
+        &self,
+        after: Option<DateTime<Utc>>,
+        dedup: bool,
+    ) -> impl Stream<Item = ClientResult<PlayHistory>> + '_ {
+        let mut last = None;
+        paginate_cursor(
+            move |before| recently_played_page(self, before, after),
+            None,
+        )
+        .filter(move |play| future::ready(!(dedup && is_repeated_play(&mut last, play))))
+    }
+
+    /// Returns the recently played tracks of the current user, from the most
+    /// recent one backwards, requested in pages as they're consumed. See
+    /// [`paginate_cursor`](crate::pagination::paginate_cursor).
+    ///
+    /// Parameters:
+    /// - after - only return the plays after the given time
+    /// - dedup - collapse consecutive plays of the same track into the most
+    ///   recent one
+    #[cfg(feature = "__sync")]
+    fn current_user_recently_played_all(
+        &self,
+        after: Option<DateTime<Utc>>,
+        dedup: bool,
+    ) -> impl Iterator<Item = ClientResult<PlayHistory>> + '_ {
+        let mut last = None;
+        paginate_cursor(
+            move |before| recently_played_page(self, before, after),
+            None,
+        )
+        .filter(move |play| !(dedup && is_repeated_play(&mut last, play)))
+    }
+
+    /// Returns the tracks played by the current user within a window of
+    /// time, from the most recent one backwards, requested in pages as
+    /// they're consumed, starting at `before`. See
+    /// [`current_user_recently_played_all`](OAuthClient::current_user_recently_played_all).
+    ///
+    /// Parameters:
+    /// - after - only return the plays after the given time
+    /// - before - only return the plays before the given time
+    #[cfg(feature = "__async")]
+    fn current_user_recently_played_between(
+        &self,
+        after: DateTime<Utc>,
+        before: DateTime<Utc>,
+    ) -> impl Stream<Item = ClientResult<PlayHistory>> + '_ {
+        paginate_cursor(
+            move |before| recently_played_page(self, before, Some(after)),
+            Some(before),
+        )
+    }
+
+    /// Returns the tracks played by the current user within a window of
+    /// time, from the most recent one backwards, requested in pages as
+    /// they're consumed, starting at `before`. See
+    /// [`current_user_recently_played_all`](OAuthClient::current_user_recently_played_all).
+    ///
+    /// Parameters:
+    /// - after - only return the plays after the given time
+    /// - before - only return the plays before the given time
+    #[cfg(feature = "__sync")]
+    fn current_user_recently_played_between(
+        &self,
+        after: DateTime<Utc>,
+        before: DateTime<Utc>,
+    ) -> impl Iterator<Item = ClientResult<PlayHistory>> + '_ {
+        paginate_cursor(
+            move |before| recently_played_page(self, before, Some(after)),
+            Some(before),
+        )
+    }
+
+    /// Add one or more albums to the current user's "Your Music" library.
+    ///
+    /// The requests stop at the first one that fails, and the albums of the
+    /// previous ones stay saved. Saving an album twice has no effect, so the
+    /// whole call can be retried.
+    ///
+    /// Parameters:
+    /// - album_ids - a list of album URIs, URLs or IDs, split in requests of
+    ///   up to [`SAVED_ALBUMS_IDS_LIMIT`]
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-albums-user)
+    async fn current_user_saved_albums_add<'a>(
+        &self,
+        album_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("current_user_saved_albums_add")?;
+        let uris: Vec<String> = album_ids
+            .into_iter()
+            .map(|id| get_id(Type::Album, id))
+            .collect::<ClientResult<_>>()?;
+        for chunk in uris.chunks(SAVED_ALBUMS_IDS_LIMIT) {
+            let url = format!("me/albums/?ids={}", chunk.join(","));
+            self.endpoint_put(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove one or more albums from the current user's "Your Music" library.
+    ///
+    /// If one of the requests fails, the albums of the previous ones have
+    /// already been removed. Removing them again has no effect, so the whole
+    /// call can be retried.
+    ///
+    /// Parameters:
+    /// - album_ids - a list of album URIs, URLs or IDs, split in requests of
+    ///   up to [`SAVED_ALBUMS_IDS_LIMIT`]
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-albums-user)
+    async fn current_user_saved_albums_delete<'a>(
+        &self,
+        album_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("current_user_saved_albums_delete")?;
+        let uris: Vec<String> = album_ids
+            .into_iter()
+            .map(|id| get_id(Type::Album, id))
+            .collect::<ClientResult<_>>()?;
+        for chunk in uris.chunks(SAVED_ALBUMS_IDS_LIMIT) {
+            let url = format!("me/albums/?ids={}", chunk.join(","));
+            self.endpoint_delete(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if one or more albums is already saved in the current Spotify
+    /// user’s "Your Music” library.
+    ///
+    /// Parameters:
+    /// - album_ids - a list of album URIs, URLs or IDs, split in requests of
+    ///   up to [`SAVED_ALBUMS_IDS_LIMIT`]
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-albums)
+    async fn current_user_saved_albums_contains<'a>(
+        &self,
+        album_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        self.check_scopes("current_user_saved_albums_contains")?;
+        let uris: Vec<String> = album_ids
+            .into_iter()
+            .map(|id| get_id(Type::Album, id))
+            .collect::<ClientResult<_>>()?;
+        let mut contains = Vec::with_capacity(uris.len());
+        for chunk in uris.chunks(SAVED_ALBUMS_IDS_LIMIT) {
+            let url = format!("me/albums/contains/?ids={}", chunk.join(","));
+            let result = self.endpoint_get(&url, &Query::new()).await?;
+            contains.extend(convert_result::<Vec<bool>>(&result)?);
+        }
+        Ok(contains)
+    }
+
+    /// Like [`OAuthClient::current_user_saved_albums_contains`], but returning whether each album is saved
+    /// by its ID, so that the results don't depend on the order of the input.
+    /// The IDs are split in requests of up to [`SAVED_ALBUMS_IDS_LIMIT`].
+    async fn current_user_saved_albums_contains_map<'a>(
+        &self,
+        album_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<HashMap<AlbumId, bool>> {
+        self.check_scopes("current_user_saved_albums_contains_map")?;
+        let params = Query::new();
+        contains_map(
+            self,
+            "me/albums/contains",
+            &params,
+            album_ids,
+            SAVED_ALBUMS_IDS_LIMIT,
+        )
+        .await
+    }
+
+    /// Follow one or more artists.
+    ///
+    /// Parameters:
+    /// - artist_ids - a list of artist IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-follow-artists-users)
+    async fn user_follow_artists<'a>(
+        &self,
+        artist_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("user_follow_artists")?;
+        let url = format!(
+            "me/following?type=artist&ids={}",
+            artist_ids
+                .into_iter()
+                .map(|id| get_id(Type::Artist, id))
+                .collect::<ClientResult<Vec<_>>>()?
+                .join(",")
+        );
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Unfollow one or more artists.
+    ///
+    /// Parameters:
+    /// - artist_ids - a list of artist IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-unfollow-artists-users)
+    async fn user_unfollow_artists<'a>(
+        &self,
+        artist_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("user_unfollow_artists")?;
+        let url = format!(
+            "me/following?type=artist&ids={}",
+            artist_ids
+                .into_iter()
+                .map(|id| get_id(Type::Artist, id))
+                .collect::<ClientResult<Vec<_>>>()?
+                .join(",")
+        );
+        self.endpoint_delete(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Check to see if the current user is following one or more artists or
+    /// other Spotify users.
+    ///
+    /// Parameters:
+    /// - artist_ids - the ids of the users that you want to
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-current-user-follows)
+    async fn user_artist_check_follow<'a>(
+        &self,
+        artsit_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        self.check_scopes("user_artist_check_follow")?;
+        let url = format!(
+            "me/following/contains?type=artist&ids={}",
+            artsit_ids
+                .into_iter()
+                .map(|id| get_id(Type::Artist, id))
+                .collect::<ClientResult<Vec<_>>>()?
+                .join(",")
+        );
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Like [`OAuthClient::user_artist_check_follow`], but returning whether each artist is followed
+    /// by its ID, so that the results don't depend on the order of the input.
+    /// The IDs are split in requests of up to [`FOLLOWED_ARTISTS_IDS_LIMIT`].
+    async fn user_artist_check_follow_map<'a>(
+        &self,
+        artist_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<HashMap<ArtistId, bool>> {
+        self.check_scopes("user_artist_check_follow_map")?;
+        let mut params = Query::with_capacity(2);
+        params.insert("type", "artist");
+        contains_map(
+            self,
+            "me/following/contains",
+            &params,
+            artist_ids,
+            FOLLOWED_ARTISTS_IDS_LIMIT,
+        )
+        .await
+    }
+
+    /// Follow one or more users.
+    ///
+    /// Parameters:
+    /// - user_ids - a list of artist IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-follow-artists-users)
+    async fn user_follow_users<'a>(
+        &self,
+        user_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("user_follow_users")?;
+        let url = format!(
+            "me/following?type=user&ids={}",
+            user_ids
+                .into_iter()
+                .map(encode_param)
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Unfollow one or more users.
+    ///
+    /// Parameters:
+    /// - user_ids - a list of artist IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-unfollow-artists-users)
+    async fn user_unfollow_users<'a>(
+        &self,
+        user_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("user_unfollow_users")?;
+        let url = format!(
+            "me/following?type=user&ids={}",
+            user_ids
+                .into_iter()
+                .map(encode_param)
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        self.endpoint_delete(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Get a User’s Available Devices
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-users-available-devices)
+    async fn device(&self) -> ClientResult<Vec<Device>> {
+        self.check_scopes("device")?;
+        let result = self
+            .endpoint_get("me/player/devices", &Query::new())
+            .await?;
+        convert_result::<DevicePayload>(&result).map(|x| x.devices)
+    }
+
+    /// Get Information About The User’s Current Playback
+    ///
+    /// Parameters:
+    /// - market: Optional. an ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - additional_types: Optional. A comma-separated list of item types that
+    ///   your client supports besides the default track type. Valid types are:
+    ///   `track` and `episode`.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-information-about-the-users-current-playback)
+    async fn current_playback(
+        &self,
+        market: Option<Market>,
+        additional_types: Option<Vec<AdditionalType>>,
+    ) -> ClientResult<Option<CurrentPlaybackContext>> {
+        self.check_scopes("current_playback")?;
+        let mut params = Query::new();
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        if let Some(additional_types) = additional_types {
+            params.insert(
+                "additional_types".to_owned(),
+                additional_types
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+
+        let result = self.endpoint_get("me/player", &params).await?;
+        if result.is_empty() {
+            Ok(None)
+        } else {
+            convert_result(&result)
+        }
+    }
+
+    /// Get the items in the user's queue, along with the currently playing
+    /// one. Tracks, episodes and local files may be included.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-queue)
+    async fn current_user_queue(&self) -> ClientResult<CurrentUserQueue> {
+        self.check_scopes("current_user_queue")?;
+        let result = self.endpoint_get("me/player/queue", &Query::new()).await?;
+        convert_result(&result)
+    }
+
+    /// Get the User’s Currently Playing Track
+    ///
+    /// Parameters:
+    /// - market: Optional. an ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - additional_types: Optional. A comma-separated list of item types that
+    ///   your client supports besides the default track type. Valid types are:
+    ///   `track` and `episode`.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
+    async fn current_playing(
+        &self,
+        market: Option<Market>,
+        additional_types: Option<Vec<AdditionalType>>,
+    ) -> ClientResult<Option<CurrentlyPlayingContext>> {
+        self.check_scopes("current_playing")?;
+        let mut params = Query::new();
+        let market = self.default_market(market).await?;
+        params.insert_opt("market", market);
+        if let Some(additional_types) = additional_types {
+            params.insert(
+                "additional_types".to_owned(),
+                additional_types
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+
+        let result = self
+            .endpoint_get("me/player/currently-playing", &params)
+            .await?;
+        if result.is_empty() {
+            Ok(None)
+        } else {
+            convert_result(&result)
+        }
+    }
+
+    /// Transfer a User’s Playback.
+    ///
+    /// Note: Although an array is accepted, only a single device_id is
+    /// currently supported. Supplying more than one will return 400 Bad Request
+    ///
+    /// Parameters:
+    /// - device_id - transfer playback to this device
+    /// - force_play - true: after transfer, play. false:
+    ///   keep current state.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-transfer-a-users-playback)
+    async fn transfer_playback<T: Into<Option<bool>> + Send>(
+        &self,
+        device_id: &str,
+        force_play: T,
+    ) -> ClientResult<()> {
+        self.check_scopes("transfer_playback")?;
+        self.endpoint_put(
+            "me/player",
+            &json! ({
+                "device_ids": vec![device_id.to_owned()],
+                "play": force_play.into().unwrap_or(true)
+            }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Transfers the playback to the device like [`Self::transfer_playback`],
+    /// but handling the device not being ready yet.
+    ///
+    /// Devices that were just turned on may take a moment to be available,
+    /// so if the device is missing or the transfer fails with `404 Not Found`,
+    /// it's tried once more after [`TRANSFER_RETRY_DELAY`].
+    ///
+    /// Parameters:
+    /// - device_id - transfer playback to this device
+    /// - force_play - true: after transfer, play. false: keep current state.
+    /// - validate - check first that the device is among the available
+    ///   ones, failing with [`ClientError::DeviceNotFound`] if it's not.
+    ///
+    /// Restricted devices fail with [`ClientError::DeviceRestricted`].
+    async fn transfer_playback_to(
+        &self,
+        device_id: &str,
+        force_play: bool,
+        validate: bool,
+    ) -> ClientResult<()> {
+        self.check_scopes("transfer_playback_to")?;
+        if validate {
+            let mut device = None;
+            for attempt in 0..2 {
+                if attempt > 0 {
+                    self.sleep(TRANSFER_RETRY_DELAY).await;
+                }
+                device = self
+                    .device()
+                    .await?
+                    .into_iter()
+                    .find(|device| device.id.as_deref() == Some(device_id));
+                if device.is_some() {
+                    break;
+                }
+            }
+
+            match device {
+                None => return Err(ClientError::DeviceNotFound(device_id.to_owned())),
+                Some(device) if device.is_restricted => {
+                    return Err(ClientError::DeviceRestricted(device_id.to_owned()))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut result = self.transfer_playback(device_id, force_play).await;
+        if matches!(&result, Err(err) if err.status_code() == Some(404)) {
+            self.sleep(TRANSFER_RETRY_DELAY).await;
+            result = self.transfer_playback(device_id, force_play).await;
+        }
+        result.map_err(|err| match err {
+            err if err.status_code() == Some(404) => {
+                ClientError::DeviceNotFound(device_id.to_owned())
+            }
+            ClientError::API(APIError::Player { ref reason, .. })
+                if reason == "DEVICE_NOT_CONTROLLABLE" =>
+            {
+                ClientError::DeviceRestricted(device_id.to_owned())
+            }
+            err => err,
+        })
+    }
+
+    /// Start/Resume a User’s Playback.
+    ///
+    /// Provide a `context_uri` to start playback or a album, artist, or
+    /// playlist. Provide a `uris` list to start playback of one or more tracks.
+    /// Provide `offset` as an [`Offset::Position`] or an [`Offset::Uri`] to
+    /// start playback at a particular offset.
+    ///
+    /// Parameters:
+    /// - device_id - device target for playback
+    /// - context_uri - spotify context uri to play
+    /// - uris - spotify track uris
+    /// - offset - offset into context by index or track
+    /// - position - Indicates from what position to start playback.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-start-a-users-playback)
+    async fn start_playback(
+        &self,
+        device_id: Option<String>,
+        context_uri: Option<String>,
+        uris: Option<Vec<String>>,
+        offset: Option<crate::model::Offset>,
+        position: Option<Duration>,
+    ) -> ClientResult<()> {
+        self.check_scopes("start_playback")?;
+        if context_uri.is_some() && uris.is_some() {
+            error!("specify either contexxt uri or uris, not both");
+        }
+        let mut params = json!({});
+        if let Some(context_uri) = context_uri {
+            json_insert!(params, "context_uri", context_uri);
+        }
+        if let Some(uris) = uris {
+            json_insert!(params, "uris", uris);
+        }
+        if let Some(offset) = offset {
+            json_insert!(params, "offset", offset);
+        }
+        if let Some(position) = position {
+            json_insert!(params, "position_ms", position.as_millis() as u64);
+        };
+        let url = append_device_id("me/player/play", self.default_device(device_id).await?);
+        self.endpoint_put(&url, &params).await?;
+
+        Ok(())
+    }
+
+    /// Starts the playback of an album, artist, playlist or show, like
+    /// [`Self::start_playback`] with a `context_uri`.
+    ///
+    /// Parameters:
+    /// - context - the album, artist, playlist or show to play
+    /// - device_id - device target for playback
+    /// - offset - offset into context by index or track
+    /// - position - Indicates from what position to start playback.
+    async fn start_context_playback<C: PlayContextId>(
+        &self,
+        context: &C,
+        device_id: Option<String>,
+        offset: Option<crate::model::Offset>,
+        position: Option<Duration>,
+    ) -> ClientResult<()> {
+        self.check_scopes("start_context_playback")?;
+        self.start_playback(device_id, Some(context.to_uri()), None, offset, position)
+            .await
+    }
+
+    /// Starts the playback of a list of tracks and episodes, like
+    /// [`Self::start_playback`] with `uris`.
+    ///
+    /// Parameters:
+    /// - items - the tracks and episodes to play
+    /// - device_id - device target for playback
+    /// - offset - offset into the items by index or track
+    /// - position - Indicates from what position to start playback.
+    async fn start_uris_playback(
+        &self,
+        items: &[&dyn PlayableId],
+        device_id: Option<String>,
+        offset: Option<crate::model::Offset>,
+        position: Option<Duration>,
+    ) -> ClientResult<()> {
+        self.check_scopes("start_uris_playback")?;
+        let uris = items.iter().map(|item| item.to_uri()).collect();
+        self.start_playback(device_id, None, Some(uris), offset, position)
+            .await
+    }
+
+    /// Starts the playback of a list of tracks of any length. Since
+    /// `start_playback` only accepts up to 100 URIs, the playback is started
+    /// with the first 100 and the rest are added to the queue one by one,
+    /// waiting `spacing` between each request to avoid being rate limited.
+    ///
+    /// Parameters:
+    /// - device_id - device target for playback
+    /// - uris - spotify track or episode uris
+    /// - spacing - time to wait between each item added to the queue
+    /// - progress - called with the number of items started or queued so
+    ///   far, and the total number of items
+    async fn start_playback_queued<F: FnMut(usize, usize) + Send>(
+        &self,
+        device_id: Option<String>,
+        uris: Vec<String>,
+        spacing: Duration,
+        mut progress: F,
+    ) -> ClientResult<()> {
+        self.check_scopes("start_playback_queued")?;
+        let total = uris.len();
+        let split = total.min(PLAYBACK_URIS_LIMIT);
+        let (first, rest) = uris.split_at(split);
+
+        self.start_playback(device_id.clone(), None, Some(first.to_vec()), None, None)
+            .await?;
+        progress(split, total);
+
+        for (i, uri) in rest.iter().enumerate() {
+            self.sleep(spacing).await;
+            self.add_item_to_queue(uri.clone(), device_id.clone())
+                .await?;
+            progress(split + i + 1, total);
+        }
+
+        Ok(())
+    }
+
+    /// Pause a User’s Playback.
+    ///
+    /// Parameters:
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-pause-a-users-playback)
+    async fn pause_playback(&self, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("pause_playback")?;
+        let url = append_device_id("me/player/pause", self.default_device(device_id).await?);
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Skip User’s Playback To Next Track.
+    ///
+    /// Parameters:
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-skip-users-playback-to-next-track)
+    async fn next_track(&self, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("next_track")?;
+        let url = append_device_id("me/player/next", self.default_device(device_id).await?);
+        self.endpoint_post(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Skip User’s Playback To Previous Track.
+    ///
+    /// Parameters:
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-skip-users-playback-to-previous-track)
+    async fn previous_track(&self, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("previous_track")?;
+        let url = append_device_id("me/player/previous", self.default_device(device_id).await?);
+        self.endpoint_post(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Seek To Position In Currently Playing Track.
+    ///
+    /// Parameters:
+    /// - position - position to seek to
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-seek-to-position-in-currently-playing-track)
+    async fn seek_track(&self, position: Duration, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("seek_track")?;
+        let url = append_device_id(
+            &format!("me/player/seek?position_ms={}", position.as_millis()),
+            self.default_device(device_id).await?,
+        );
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Set Repeat Mode On User’s Playback.
+    ///
+    /// Parameters:
+    /// - state - `track`, `context`, or `off`
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-set-repeat-mode-on-users-playback)
+    async fn repeat(&self, state: RepeatState, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("repeat")?;
+        let url = append_device_id(
+            &format!("me/player/repeat?state={}", state.to_string()),
+            self.default_device(device_id).await?,
+        );
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Set Volume For User’s Playback.
+    ///
+    /// Parameters:
+    /// - volume_percent - volume between 0 and 100
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-set-volume-for-users-playback)
+    async fn volume(&self, volume_percent: u8, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("volume")?;
+        if volume_percent > 100u8 {
+            error!("volume must be between 0 and 100, inclusive");
+        }
+        let url = append_device_id(
+            &format!("me/player/volume?volume_percent={}", volume_percent),
+            self.default_device(device_id).await?,
+        );
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Gradually changes the volume of the active device to
+    /// `target_percent` over `duration`, like for alarms or sleep timers.
+    /// The volume is set in steps at least [`FADE_VOLUME_INTERVAL`] apart,
+    /// so that long fades aren't rate limited, and the target is reached once
+    /// `duration` has passed. If the current volume is unknown, the target is
+    /// set right away.
+    ///
+    /// Parameters:
+    /// - target_percent - volume between 0 and 100 to end with
+    /// - duration - how long the fade takes
+    async fn fade_volume(&self, target_percent: u8, duration: Duration) -> ClientResult<()> {
+        self.check_scopes("fade_volume")?;
+        let target = target_percent.min(100);
+        let start = self
+            .current_playback(None, None)
+            .await?
+            .and_then(|playback| playback.device.volume_percent)
+            .map(|volume| volume.min(100) as u8);
+        let start = match start {
+            Some(start) => start,
+            None => return self.volume(target, None).await,
+        };
+
+        let change = i32::from(target) - i32::from(start);
+        let max_steps = duration.as_millis() / FADE_VOLUME_INTERVAL.as_millis();
+        let steps = (change.unsigned_abs() as u128).min(max_steps).max(1) as u32;
+        let interval = duration / steps;
+        for step in 1..=steps {
+            self.sleep(interval).await;
+            let volume = i32::from(start) + change * step as i32 / steps as i32;
+            self.volume(volume as u8, None).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Toggle Shuffle For User’s Playback.
+    ///
+    /// Parameters:
+    /// - state - true or false
+    /// - device_id - device target for playback
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-toggle-shuffle-for-users-playback)
+    async fn shuffle(&self, state: bool, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("shuffle")?;
+        let url = append_device_id(
+            &format!("me/player/shuffle?state={}", state),
+            self.default_device(device_id).await?,
+        );
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Add an item to the end of the user's playback queue.
+    ///
+    /// Parameters:
+    /// - uri - The uri of the item to add, Track or Episode
+    /// - device id - The id of the device targeting
+    /// - If no device ID provided the user's currently active device is
+    ///   targeted
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-add-to-queue)
+    async fn add_item_to_queue(&self, item: String, device_id: Option<String>) -> ClientResult<()> {
+        self.check_scopes("add_item_to_queue")?;
+        let url = append_device_id(
+            &format!("me/player/queue?uri={}", encode_param(&item)),
+            self.default_device(device_id).await?,
+        );
+        self.endpoint_post(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Adds a track or episode to the end of the user's playback queue, like
+    /// [`Self::add_item_to_queue`].
+    ///
+    /// Parameters:
+    /// - item - the track or episode to add
+    /// - device_id - device target for playback, or the active one if `None`
+    async fn add_to_queue<P: PlayableId>(
+        &self,
+        item: &P,
+        device_id: Option<String>,
+    ) -> ClientResult<()> {
+        self.check_scopes("add_to_queue")?;
+        self.add_item_to_queue(item.to_uri(), device_id).await
+    }
+
+    /// Add a show or a list of shows to a user’s library.
+    ///
+    /// Parameters:
+    /// - ids(Required) A comma-separated list of Spotify IDs for the shows to
+    ///   be added to the user’s library.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-shows-user)
+    async fn save_shows<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("save_shows")?;
+        let joined_ids = ids
+            .into_iter()
+            .map(|id| get_id(Type::Show, id))
+            .collect::<ClientResult<Vec<_>>>()?
+            .join(",");
+        let url = format!("me/shows/?ids={}", joined_ids);
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Get a list of shows saved in the current Spotify user’s library.
+    /// Optional parameters can be used to limit the number of shows returned.
+    ///
+    /// Parameters:
+    /// - limit(Optional). The maximum number of shows to return. Default: 20.
+    ///   Minimum: 1. Maximum: 50.
+    /// - offset(Optional). The index of the first show to return. Default: 0
+    ///   (the first object). Use with limit to get the next set of shows.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-shows)
+    async fn current_user_saved_shows<L: Into<Option<u32>> + Send, O: Into<Option<u32>> + Send>(
+        &self,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<Show>> {
+        self.check_scopes("current_user_saved_shows")?;
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), SAVED_SHOWS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        self.endpoint_get_as("me/shows", &params).await
+    }
+
+    /// Returns all the shows saved in the current user's library, requested
+    /// in pages as they're consumed. See
+    /// [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__async")]
+    fn current_user_saved_shows_all(&self) -> impl Stream<Item = ClientResult<Show>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_saved_shows(limit, offset),
+            SAVED_SHOWS_PAGE_SIZE,
+        )
+    }
+
+    /// Returns all the shows saved in the current user's library, requested
+    /// in pages as they're consumed. See
+    /// [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__sync")]
+    fn current_user_saved_shows_all(&self) -> impl Iterator<Item = ClientResult<Show>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_saved_shows(limit, offset),
+            SAVED_SHOWS_PAGE_SIZE,
+        )
+    }
+
+    /// Check if one or more shows is already saved in the current Spotify user’s library.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the shows. Maximum: 50 IDs.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-shows)
+    async fn check_users_saved_shows<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        self.check_scopes("check_users_saved_shows")?;
+        let mut params = Query::with_capacity(1);
+        params.insert(
+            "ids".to_owned(),
+            ids.into_iter().collect::<Vec<_>>().join(","),
+        );
+        let result = self.endpoint_get("me/shows/contains", &params).await?;
+        convert_result(&result)
+    }
+
+    /// Like [`OAuthClient::check_users_saved_shows`], but returning whether each show is saved
+    /// by its ID, so that the results don't depend on the order of the input.
+    /// The IDs are split in requests of up to [`SAVED_SHOWS_IDS_LIMIT`].
+    async fn check_users_saved_shows_map<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<HashMap<ShowId, bool>> {
+        self.check_scopes("check_users_saved_shows_map")?;
+        let params = Query::new();
+        contains_map(
+            self,
+            "me/shows/contains",
+            &params,
+            ids,
+            SAVED_SHOWS_IDS_LIMIT,
+        )
+        .await
+    }
+
+    /// Delete one or more shows from current Spotify user's library.
+    /// Changes to a user's saved shows may not be visible in other Spotify applications immediately.
+    ///
+    /// Query Parameters
+    /// - ids: Required. A comma-separated list of Spotify IDs for the shows to be deleted from the user’s library.
+    /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-shows-user)
+    async fn remove_users_saved_shows<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+        market: Option<Market>,
+    ) -> ClientResult<()> {
+        self.check_scopes("remove_users_saved_shows")?;
+        let joined_ids = ids
+            .into_iter()
+            .map(|id| get_id(Type::Show, id))
+            .collect::<ClientResult<Vec<_>>>()?
+            .join(",");
+        let mut url = format!("me/shows?ids={}", joined_ids);
+        if let Some(market) = self.default_market(market).await? {
+            url.push_str(&format!("&market={}", market.to_string()));
+        }
+        self.endpoint_delete(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Get a list of the audiobooks saved in the current Spotify user's
+    /// library.
+    ///
+    /// Parameters:
+    /// - limit(Optional). The maximum number of audiobooks to return.
+    ///   Default: 20. Minimum: 1. Maximum: 50.
+    /// - offset(Optional). The index of the first audiobook to return.
+    ///   Default: 0 (the first object). Use with limit to get the next set of
+    ///   audiobooks.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-audiobooks)
+    async fn current_user_saved_audiobooks<
+        L: Into<Option<u32>> + Send,
+        O: Into<Option<u32>> + Send,
+    >(
+        &self,
+        limit: L,
+        offset: O,
+    ) -> ClientResult<Page<SimplifiedAudiobook>> {
+        self.check_scopes("current_user_saved_audiobooks")?;
+        let mut params = Query::with_capacity(2);
+        let limit = check_limit(limit.into().unwrap_or(20), SAVED_AUDIOBOOKS_PAGE_SIZE)?;
+        params.insert("limit", limit.to_string());
+        params.insert("offset".to_owned(), offset.into().unwrap_or(0).to_string());
+        self.endpoint_get_as("me/audiobooks", &params).await
+    }
+
+    /// Returns all the audiobooks saved in the current user's library,
+    /// requested in pages as they're consumed. See
+    /// [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__async")]
+    fn current_user_saved_audiobooks_all(
+        &self,
+    ) -> impl Stream<Item = ClientResult<SimplifiedAudiobook>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_saved_audiobooks(limit, offset),
+            SAVED_AUDIOBOOKS_PAGE_SIZE,
+        )
+    }
+
+    /// Returns all the audiobooks saved in the current user's library,
+    /// requested in pages as they're consumed. See
+    /// [`paginate`](crate::pagination::paginate).
+    #[cfg(feature = "__sync")]
+    fn current_user_saved_audiobooks_all(
+        &self,
+    ) -> impl Iterator<Item = ClientResult<SimplifiedAudiobook>> + '_ {
+        paginate(
+            move |limit, offset| self.current_user_saved_audiobooks(limit, offset),
+            SAVED_AUDIOBOOKS_PAGE_SIZE,
+        )
+    }
+
+    /// Add one or more audiobooks to the current user's library.
+    ///
+    /// Like [`Self::current_user_saved_albums_add`], a failed request leaves
+    /// the audiobooks of the previous ones saved, and the call can be
+    /// retried.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs, split in
+    ///   requests of up to [`SAVED_AUDIOBOOKS_IDS_LIMIT`]
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-audiobooks-user)
+    async fn save_audiobooks<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("save_audiobooks")?;
+        let ids: Vec<String> = audiobook_ids
+            .into_iter()
+            .map(|id| get_id(Type::Audiobook, id))
+            .collect::<ClientResult<_>>()?;
+        for chunk in ids.chunks(SAVED_AUDIOBOOKS_IDS_LIMIT) {
+            let url = format!("me/audiobooks?ids={}", chunk.join(","));
+            self.endpoint_put(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove one or more audiobooks from the current user's library.
+    ///
+    /// Like [`Self::current_user_saved_albums_delete`], a failed request
+    /// leaves the audiobooks of the previous ones removed, and the call can
+    /// be retried.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs, split in
+    ///   requests of up to [`SAVED_AUDIOBOOKS_IDS_LIMIT`]
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-audiobooks-user)
+    async fn remove_saved_audiobooks<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<()> {
+        self.check_scopes("remove_saved_audiobooks")?;
+        let ids: Vec<String> = audiobook_ids
+            .into_iter()
+            .map(|id| get_id(Type::Audiobook, id))
+            .collect::<ClientResult<_>>()?;
+        for chunk in ids.chunks(SAVED_AUDIOBOOKS_IDS_LIMIT) {
+            let url = format!("me/audiobooks?ids={}", chunk.join(","));
+            self.endpoint_delete(&url, &json!({})).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if one or more audiobooks are already saved in the current
+    /// user's library, in the same order as their IDs.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs, split in
+    ///   requests of up to [`SAVED_AUDIOBOOKS_IDS_LIMIT`]
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-audiobooks)
+    async fn check_saved_audiobooks<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = &'a str> + Send + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        self.check_scopes("check_saved_audiobooks")?;
+        let ids: Vec<String> = audiobook_ids
+            .into_iter()
+            .map(|id| get_id(Type::Audiobook, id))
+            .collect::<ClientResult<_>>()?;
+        let mut contains = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(SAVED_AUDIOBOOKS_IDS_LIMIT) {
+            let mut params = Query::with_capacity(1);
+            params.insert("ids", chunk.join(","));
+            let result = self.endpoint_get("me/audiobooks/contains", &params).await?;
+            contains.extend(convert_result::<Vec<bool>>(&result)?);
+        }
+
+        Ok(contains)
+    }
+}
@@ -0,0 +1,377 @@
+//! A fake Spotify server on localhost that serves canned responses, for
+//! end-to-end tests of the clients without network access, like of the
+//! pagination, the retries or the authorization.
+//!
+//! The responses are given in advance for a method and path, and each of
+//! them is served once, in the same order, to the first request that matches
+//! it. The server listens in a background thread until it's dropped, and the
+//! requests it received can be inspected afterwards:
+//!
+//! ```
+//! # #[cfg(feature = "client-reqwest")]
+//! # #[tokio::main]
+//! # async fn main() {
+//! use rspotify::fake_server::{FakeResponse, FakeServer};
+//! use rspotify::oauth2::CredentialsBuilder;
+//! use rspotify::prelude::*;
+//!
+//! let server = FakeServer::start().unwrap();
+//! server.respond("POST", "/api/token", FakeResponse::token("fake-token"));
+//! server.respond(
+//!     "GET",
+//!     "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+//!     FakeResponse::json(r#"{"error":{"status":404,"message":"non existing id"}}"#)
+//!         .with_status(404),
+//! );
+//!
+//! let creds = CredentialsBuilder::default()
+//!     .id("client-id")
+//!     .secret("client-secret")
+//!     .build()
+//!     .unwrap();
+//! let spotify = server.builder().credentials(creds).build().unwrap();
+//! assert!(spotify.artist("0OdUWJ0sBjDrqHygGUXeCF").await.is_err());
+//!
+//! let requests = server.requests();
+//! assert_eq!(requests[0].form().get("grant_type"), Some("client_credentials"));
+//! assert_eq!(requests[1].headers["authorization"], "Bearer fake-token");
+//! assert_eq!(server.remaining(), 0);
+//! # }
+//! # #[cfg(not(feature = "client-reqwest"))]
+//! # fn main() {}
+//! ```
+//!
+//! Note: this module requires the `fake-server` feature.
+
+use crate::client::{Form, Query, SpotifyBuilder};
+use crate::http::Headers;
+
+use url::form_urlencoded;
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// A canned response of the [`FakeServer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FakeResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl FakeResponse {
+    /// A successful response with the given JSON.
+    pub fn json<B: Into<String>>(body: B) -> Self {
+        FakeResponse {
+            status: 200,
+            headers: vec![("content-type".to_owned(), "application/json".to_owned())],
+            body: body.into(),
+        }
+    }
+
+    /// A successful response without a body, like the ones of the player.
+    pub fn empty() -> Self {
+        FakeResponse::status(204)
+    }
+
+    /// A response with the given status code and no body.
+    pub fn status(status: u16) -> Self {
+        FakeResponse {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// A `429 Too Many Requests` that asks to wait for `retry_after` seconds.
+    pub fn rate_limited(retry_after: u64) -> Self {
+        FakeResponse::status(429).with_header("retry-after", retry_after.to_string())
+    }
+
+    /// The response of the accounts service with a token valid for an hour.
+    pub fn token(access_token: &str) -> Self {
+        FakeResponse::json(format!(
+            r#"{{"access_token":"{}","token_type":"Bearer","expires_in":3600,"scope":""}}"#,
+            access_token
+        ))
+    }
+
+    /// The same response with another status code.
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// The same response with another header.
+    pub fn with_header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// A request received by the [`FakeServer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReceivedRequest {
+    pub method: String,
+    /// The path of the URL, without the query.
+    pub path: String,
+    pub query: Query,
+    /// The headers, with their names in lowercase.
+    pub headers: Headers,
+    pub body: String,
+}
+
+impl ReceivedRequest {
+    /// The body parsed as a form, like the ones sent to the accounts
+    /// service.
+    pub fn form(&self) -> Form {
+        form_urlencoded::parse(self.body.as_bytes())
+            .into_owned()
+            .collect()
+    }
+
+    /// The body parsed as JSON, or `None` if it isn't.
+    pub fn json(&self) -> Option<serde_json::Value> {
+        serde_json::from_str(&self.body).ok()
+    }
+}
+
+/// A response given to the server, and whether it was served already.
+#[derive(Debug)]
+struct Route {
+    method: String,
+    path: String,
+    response: FakeResponse,
+    served: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    routes: Vec<Route>,
+    requests: Vec<ReceivedRequest>,
+}
+
+/// A local HTTP server that serves canned responses to the clients. See the
+/// [module-level documentation](self).
+///
+/// Requests without a response left get a `404 Not Found` saying so, and are
+/// recorded like the rest.
+#[derive(Debug)]
+pub struct FakeServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stopped: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl FakeServer {
+    /// Starts listening on a free port of localhost.
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let listener = {
+            let state = Arc::clone(&state);
+            let stopped = Arc::clone(&stopped);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let state = Arc::clone(&state);
+                        thread::spawn(move || {
+                            // The client may give up on the request, which
+                            // isn't an error of the server.
+                            let _ = answer(stream, &state);
+                        });
+                    }
+                }
+            })
+        };
+
+        Ok(FakeServer {
+            addr,
+            state,
+            stopped,
+            listener: Some(listener),
+        })
+    }
+
+    /// The URL of the server, like `http://127.0.0.1:41234/`.
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// The API prefix of the server, under `/v1/` like Spotify's.
+    pub fn api_prefix(&self) -> String {
+        format!("{}v1/", self.url())
+    }
+
+    /// The URL where the server grants the tokens, `/api/token`.
+    pub fn token_url(&self) -> String {
+        format!("{}api/token", self.url())
+    }
+
+    /// The URL where the user would authorize the client, `/authorize`.
+    pub fn authorize_url(&self) -> String {
+        format!("{}authorize", self.url())
+    }
+
+    /// A builder of a client that only talks to this server.
+    pub fn builder(&self) -> SpotifyBuilder {
+        let mut builder = SpotifyBuilder::default();
+        builder
+            .prefix(self.api_prefix())
+            .token_url(self.token_url())
+            .authorize_url(self.authorize_url());
+        builder
+    }
+
+    /// Serves `response` once to the next request with the method and path,
+    /// like `"/v1/me/tracks"`, after the ones given before for them.
+    pub fn respond(&self, method: &str, path: &str, response: FakeResponse) -> &Self {
+        self.state.lock().unwrap().routes.push(Route {
+            method: method.to_ascii_uppercase(),
+            path: path.to_owned(),
+            response,
+            served: false,
+        });
+        self
+    }
+
+    /// The requests received so far, in order.
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// How many of the responses haven't been served yet.
+    pub fn remaining(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.routes.iter().filter(|route| !route.served).count()
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Waking up the listener so that it notices.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+    }
+}
+
+/// Reads a single request and writes its response, closing the connection
+/// afterwards.
+fn answer(stream: TcpStream, state: &Mutex<State>) -> io::Result<()> {
+    let request = read_request(&stream)?;
+    let response = {
+        let mut state = state.lock().unwrap();
+        let response = state
+            .routes
+            .iter_mut()
+            .find(|route| {
+                !route.served && route.method == request.method && route.path == request.path
+            })
+            .map(|route| {
+                route.served = true;
+                route.response.clone()
+            });
+        let response = response.unwrap_or_else(|| {
+            FakeResponse::json(format!(
+                r#"{{"error":{{"status":404,"message":"no response left for {} {}"}}}}"#,
+                request.method, request.path
+            ))
+            .with_status(404)
+        });
+        state.requests.push(request);
+        response
+    };
+    write_response(&stream, &response)
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<ReceivedRequest> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid HTTP request");
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or_else(invalid)?.to_owned();
+    let target = parts.next().ok_or_else(invalid)?;
+    let (path, query) = match target.find('?') {
+        Some(pos) => (&target[..pos], &target[pos + 1..]),
+        None => (target, ""),
+    };
+
+    let mut headers = Headers::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_at(line.find(':').ok_or_else(invalid)?);
+        headers.insert(name.to_ascii_lowercase(), value[1..].trim().to_owned());
+    }
+
+    let length = match headers.get("content-length") {
+        Some(length) => length.parse().map_err(|_| invalid())?,
+        None => 0,
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(ReceivedRequest {
+        method,
+        path: path.to_owned(),
+        query: form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect(),
+        headers,
+        body: String::from_utf8(body).map_err(|_| invalid())?,
+    })
+}
+
+fn write_response(mut stream: &TcpStream, response: &FakeResponse) -> io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+/// The reason phrase of the status codes returned by Spotify.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
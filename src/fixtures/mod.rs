@@ -0,0 +1,239 @@
+//! Realistic sample payloads returned by the Spotify API, and constructors
+//! for the models that parse them. These are meant to be used in unit tests
+//! so that it's not necessary to write huge JSON objects by hand.
+//!
+//! Note: this module requires the `fixtures` feature.
+//!
+//! ```
+//! use rspotify::model::{FullTrack, Page, SavedTrack};
+//!
+//! let track = FullTrack::fixture();
+//! assert_eq!(track.name, "Cut To The Feeling");
+//!
+//! let page = Page::<SavedTrack>::fixture();
+//! assert_eq!(page.items[0].track, track);
+//! ```
+//!
+//! The random strings generated by Rspotify, like the OAuth state, can also
+//! be made deterministic with [`seed_random`].
+
+use crate::model::{
+    FullAlbum, FullArtist, FullTrack, Page, PrivateUser, SavedTrack, SimplifiedPlaylist,
+};
+
+use serde::de::DeserializeOwned;
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+/// Sample payload of a [full track object](FullTrack).
+pub const FULL_TRACK: &str = include_str!("full_track.json");
+/// Sample payload of a [full artist object](FullArtist).
+pub const FULL_ARTIST: &str = include_str!("full_artist.json");
+/// Sample payload of a [full album object](FullAlbum).
+pub const FULL_ALBUM: &str = include_str!("full_album.json");
+/// Sample payload of a [private user object](PrivateUser).
+pub const PRIVATE_USER: &str = include_str!("private_user.json");
+/// Sample payload of a [simplified playlist object](SimplifiedPlaylist).
+pub const SIMPLIFIED_PLAYLIST: &str = include_str!("simplified_playlist.json");
+/// Sample payload of a page of [saved tracks](SavedTrack), as returned by
+/// `current_user_saved_tracks`.
+pub const SAVED_TRACKS_PAGE: &str = include_str!("saved_tracks_page.json");
+/// Sample payload of a page of [simplified playlists](SimplifiedPlaylist), as
+/// returned by `current_user_playlists`.
+pub const SIMPLIFIED_PLAYLISTS_PAGE: &str = include_str!("simplified_playlists_page.json");
+
+/// The fixtures are known to be valid, so failing to parse them is a bug in
+/// Rspotify.
+fn parse<T: DeserializeOwned>(payload: &str) -> T {
+    serde_json::from_str(payload).expect("invalid fixture payload")
+}
+
+impl FullTrack {
+    /// Constructs a full track from the [`FULL_TRACK`] payload.
+    pub fn fixture() -> Self {
+        parse(FULL_TRACK)
+    }
+}
+
+impl FullArtist {
+    /// Constructs a full artist from the [`FULL_ARTIST`] payload.
+    pub fn fixture() -> Self {
+        parse(FULL_ARTIST)
+    }
+}
+
+impl FullAlbum {
+    /// Constructs a full album from the [`FULL_ALBUM`] payload.
+    pub fn fixture() -> Self {
+        parse(FULL_ALBUM)
+    }
+}
+
+impl PrivateUser {
+    /// Constructs a private user from the [`PRIVATE_USER`] payload.
+    pub fn fixture() -> Self {
+        parse(PRIVATE_USER)
+    }
+}
+
+impl SimplifiedPlaylist {
+    /// Constructs a simplified playlist from the [`SIMPLIFIED_PLAYLIST`]
+    /// payload.
+    pub fn fixture() -> Self {
+        parse(SIMPLIFIED_PLAYLIST)
+    }
+}
+
+impl Page<SavedTrack> {
+    /// Constructs a page of saved tracks from the [`SAVED_TRACKS_PAGE`]
+    /// payload.
+    pub fn fixture() -> Self {
+        parse(SAVED_TRACKS_PAGE)
+    }
+}
+
+impl Page<SimplifiedPlaylist> {
+    /// Constructs a page of simplified playlists from the
+    /// [`SIMPLIFIED_PLAYLISTS_PAGE`] payload.
+    pub fn fixture() -> Self {
+        parse(SIMPLIFIED_PLAYLISTS_PAGE)
+    }
+}
+
+thread_local! {
+    /// The state of the generator of random strings in this thread, if it was
+    /// seeded with [`seed_random`].
+    static RANDOM_STATE: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Makes the random strings generated in the current thread derive from
+/// `seed` instead of the randomness of the OS, so that tests of the
+/// authorization URLs are reproducible. This includes the default
+/// [`OAuth::state`](crate::oauth2::OAuth::state) and the code verifiers from
+/// [`generate_code_verifier`](crate::oauth2::generate_code_verifier). The
+/// randomness of the OS is used again once the returned guard is dropped.
+///
+/// The strings are predictable, so this must only be used in tests.
+///
+/// ```
+/// use rspotify::fixtures::seed_random;
+/// use rspotify::oauth2::generate_code_verifier;
+///
+/// let seeded = seed_random(42);
+/// let verifier = generate_code_verifier();
+/// drop(seeded);
+///
+/// let _seeded = seed_random(42);
+/// assert_eq!(generate_code_verifier(), verifier);
+/// ```
+pub fn seed_random(seed: u64) -> SeededRandom {
+    let previous = RANDOM_STATE.with(|state| state.replace(Some(seed)));
+    SeededRandom {
+        previous,
+        _thread: PhantomData,
+    }
+}
+
+/// The guard returned by [`seed_random`], which restores the previous source
+/// of randomness of the thread when dropped.
+#[must_use = "the randomness of the OS is used again once it's dropped"]
+#[derive(Debug)]
+pub struct SeededRandom {
+    previous: Option<u64>,
+    // The seed is per thread, so the guard can't be sent to another one.
+    _thread: PhantomData<*const ()>,
+}
+
+impl Drop for SeededRandom {
+    fn drop(&mut self) {
+        RANDOM_STATE.with(|state| state.set(self.previous));
+    }
+}
+
+/// Fills `buf` with the seeded generator of the thread, returning whether
+/// it was seeded.
+pub(crate) fn fill_seeded(buf: &mut [u8]) -> bool {
+    RANDOM_STATE.with(|state| match state.get() {
+        Some(mut current) => {
+            for byte in buf.iter_mut() {
+                *byte = (splitmix64(&mut current) >> 56) as u8;
+            }
+            state.set(Some(current));
+            true
+        }
+        None => false,
+    })
+}
+
+/// The [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator, which
+/// is simple and good enough for tests.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::SpotifyBuilder;
+    use crate::oauth2::{CredentialsBuilder, OAuthBuilder};
+
+    #[test]
+    fn test_fixtures_parse() {
+        assert_eq!(FullTrack::fixture().name, "Cut To The Feeling");
+        assert_eq!(FullArtist::fixture().name, "Band of Horses");
+        assert_eq!(FullAlbum::fixture().tracks.items.len(), 1);
+        assert_eq!(PrivateUser::fixture().id, "waq5aexykhm6nlv0cnwdieng0");
+        assert_eq!(SimplifiedPlaylist::fixture().tracks.total, 62);
+
+        let saved = Page::<SavedTrack>::fixture();
+        assert_eq!(saved.items[0].track, FullTrack::fixture());
+        let playlists = Page::<SimplifiedPlaylist>::fixture();
+        assert_eq!(playlists.items[0], SimplifiedPlaylist::fixture());
+    }
+
+    #[test]
+    fn test_seed_random() {
+        let authorize_url = || {
+            let oauth = OAuthBuilder::default()
+                .redirect_uri("http://127.0.0.1:8888/callback")
+                .build()
+                .unwrap();
+            let creds = CredentialsBuilder::default()
+                .id("this-is-my-client-id")
+                .secret("this-is-my-client-secret")
+                .build()
+                .unwrap();
+            SpotifyBuilder::default()
+                .credentials(creds)
+                .oauth(oauth)
+                .build()
+                .unwrap()
+                .get_authorize_url(&Default::default())
+                .unwrap()
+        };
+
+        let seeded = seed_random(7);
+        let url = authorize_url();
+        assert_eq!(
+            url,
+            "https://accounts.spotify.com/authorize?client_id=this-is-my-client-id\
+             &response_type=code&redirect_uri=http%3A%2F%2F127.0.0.1%3A8888%2Fcallback\
+             &scope=&state=LeSz1b5vIRA7XLJq"
+        );
+        {
+            let _reseeded = seed_random(7);
+            assert_eq!(authorize_url(), url);
+        }
+        // Dropping the inner guard resumes the outer generator where it was.
+        assert_ne!(authorize_url(), url);
+        drop(seeded);
+
+        let _seeded = seed_random(7);
+        assert_eq!(authorize_url(), url);
+    }
+}
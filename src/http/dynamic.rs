@@ -0,0 +1,387 @@
+//! An HTTP client chosen at runtime, like from the configuration of the
+//! application, instead of at compile time.
+
+use super::hook::ResponseHook;
+use super::{
+    BaseHTTPClient, BodyLimits, BodyMetrics, Conditional, ConnectionConfig, Form, Headers, Query,
+};
+use crate::client::ClientResult;
+
+use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The object-safe part of [`BaseHTTPClient`], for the clients used as a
+/// trait object by [`DynHTTPClient`]. In async mode the requests return boxed
+/// futures.
+///
+/// It's only needed for [`BaseHTTPClient::from_native`], since
+/// [`DynHTTPClient::new`] takes any [`BaseHTTPClient`].
+#[maybe_async]
+pub trait DynBaseHTTPClient: fmt::Debug + Send + Sync {
+    async fn get(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<String>;
+
+    async fn post(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String>;
+
+    async fn post_form(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+    ) -> ClientResult<String>;
+
+    async fn post_form_timeout(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+        timeout: Duration,
+    ) -> ClientResult<String>;
+
+    async fn put(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String>;
+
+    async fn delete(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String>;
+
+    async fn get_conditional(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<String>>;
+
+    async fn get_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        limit: usize,
+    ) -> ClientResult<Vec<u8>>;
+
+    fn body_metrics(&self) -> BodyMetrics;
+
+    /// The same as [`BaseHTTPClient::with_body_limits`], on a copy.
+    fn with_body_limits(&self, limits: BodyLimits) -> Arc<dyn DynBaseHTTPClient>;
+
+    /// The same as [`BaseHTTPClient::with_response_hook`], on a copy.
+    fn with_response_hook(&self, hook: ResponseHook) -> Arc<dyn DynBaseHTTPClient>;
+}
+
+/// A [`BaseHTTPClient`] as a [`DynBaseHTTPClient`]. It's a different type so
+/// that the methods of both traits don't clash.
+struct Erased<C>(C);
+
+impl<C: fmt::Debug> fmt::Debug for Erased<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[maybe_async]
+impl<C> DynBaseHTTPClient for Erased<C>
+where
+    C: BaseHTTPClient + Send + Sync + 'static,
+{
+    async fn get(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<String> {
+        self.0.get(url, headers, payload).await
+    }
+
+    async fn post(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        self.0.post(url, headers, payload).await
+    }
+
+    async fn post_form(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+    ) -> ClientResult<String> {
+        self.0.post_form(url, headers, payload).await
+    }
+
+    async fn post_form_timeout(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+        timeout: Duration,
+    ) -> ClientResult<String> {
+        self.0
+            .post_form_timeout(url, headers, payload, timeout)
+            .await
+    }
+
+    async fn put(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        self.0.put(url, headers, payload).await
+    }
+
+    async fn delete(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        self.0.delete(url, headers, payload).await
+    }
+
+    async fn get_conditional(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<String>> {
+        self.0.get_conditional(url, headers, payload, etag).await
+    }
+
+    async fn get_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        limit: usize,
+    ) -> ClientResult<Vec<u8>> {
+        self.0.get_bytes(url, headers, limit).await
+    }
+
+    fn body_metrics(&self) -> BodyMetrics {
+        self.0.body_metrics()
+    }
+
+    fn with_body_limits(&self, limits: BodyLimits) -> Arc<dyn DynBaseHTTPClient> {
+        Arc::new(Erased(self.0.clone().with_body_limits(limits)))
+    }
+
+    fn with_response_hook(&self, hook: ResponseHook) -> Arc<dyn DynBaseHTTPClient> {
+        Arc::new(Erased(self.0.clone().with_response_hook(hook)))
+    }
+}
+
+/// An HTTP client that wraps any other one behind a trait object, so that
+/// it can be chosen at runtime without making the types that hold it
+/// generic, like between the backend and a custom client from the
+/// configuration of the application. With the `dyn-http` feature it's the
+/// [`HTTPClient`](crate::client::HTTPClient) of [`Spotify`
+/// ](crate::client::Spotify), which then accepts any of them:
+///
+/// ```
+/// use rspotify::client::{BaseHTTPClient, ConnectionConfig, DynHTTPClient, HTTPClient};
+///
+/// struct App {
+///     http: DynHTTPClient,
+/// }
+///
+/// let pooled = std::env::var("APP_POOLED").is_ok();
+/// let backend: HTTPClient = if pooled {
+///     HTTPClient::from_config(&ConnectionConfig::default()).unwrap()
+/// } else {
+///     HTTPClient::default()
+/// };
+/// let http = DynHTTPClient::new(backend);
+/// let app = App { http };
+/// # let _ = app.http;
+/// ```
+///
+/// The responses are buffered before being deserialized, since the
+/// deserialization can't be generic through a trait object.
+#[derive(Clone)]
+pub struct DynHTTPClient(Arc<dyn DynBaseHTTPClient>);
+
+impl DynHTTPClient {
+    /// Wraps the given HTTP client.
+    pub fn new<C>(client: C) -> Self
+    where
+        C: BaseHTTPClient + Send + Sync + 'static,
+    {
+        DynHTTPClient(Arc::new(Erased(client)))
+    }
+}
+
+impl fmt::Debug for DynHTTPClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("DynHTTPClient").field(&self.0).finish()
+    }
+}
+
+// The client wrapped by default, the same one as `HTTPClient` without the
+// `dyn-http` feature.
+#[cfg(feature = "replay")]
+type DefaultClient = super::replay::ReplayClient<super::HTTPBackend>;
+#[cfg(not(feature = "replay"))]
+type DefaultClient = super::HTTPBackend;
+
+impl Default for DynHTTPClient {
+    fn default() -> Self {
+        DynHTTPClient::new(DefaultClient::default())
+    }
+}
+
+#[cfg(feature = "replay")]
+impl<C> From<super::replay::ReplayClient<C>> for DynHTTPClient
+where
+    C: BaseHTTPClient + Send + Sync + 'static,
+{
+    fn from(client: super::replay::ReplayClient<C>) -> Self {
+        DynHTTPClient::new(client)
+    }
+}
+
+#[maybe_async]
+impl BaseHTTPClient for DynHTTPClient {
+    type Native = Arc<dyn DynBaseHTTPClient>;
+
+    #[inline]
+    fn from_native(native: Self::Native) -> Self {
+        DynHTTPClient(native)
+    }
+
+    /// The default client is built with `config` and wrapped.
+    fn from_config(config: &ConnectionConfig) -> ClientResult<Self> {
+        DefaultClient::from_config(config).map(DynHTTPClient::new)
+    }
+
+    #[inline]
+    async fn get(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<String> {
+        self.0.get(url, headers, payload).await
+    }
+
+    #[inline]
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        let body = self.0.get(url, headers, payload).await?;
+        serde_json::from_str(&body).map_err(Into::into)
+    }
+
+    #[inline]
+    async fn post(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        self.0.post(url, headers, payload).await
+    }
+
+    #[inline]
+    async fn post_form(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+    ) -> ClientResult<String> {
+        self.0.post_form(url, headers, payload).await
+    }
+
+    #[inline]
+    async fn post_form_timeout(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+        timeout: Duration,
+    ) -> ClientResult<String> {
+        self.0
+            .post_form_timeout(url, headers, payload, timeout)
+            .await
+    }
+
+    #[inline]
+    async fn put(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        self.0.put(url, headers, payload).await
+    }
+
+    #[inline]
+    async fn delete(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        self.0.delete(url, headers, payload).await
+    }
+
+    #[inline]
+    async fn get_conditional(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<String>> {
+        self.0.get_conditional(url, headers, payload, etag).await
+    }
+
+    #[inline]
+    async fn get_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        limit: usize,
+    ) -> ClientResult<Vec<u8>> {
+        self.0.get_bytes(url, headers, limit).await
+    }
+
+    #[inline]
+    fn body_metrics(&self) -> BodyMetrics {
+        self.0.body_metrics()
+    }
+
+    fn with_body_limits(self, limits: BodyLimits) -> Self {
+        DynHTTPClient(self.0.with_body_limits(limits))
+    }
+
+    fn with_response_hook(self, hook: ResponseHook) -> Self {
+        DynHTTPClient(self.0.with_response_hook(hook))
+    }
+}
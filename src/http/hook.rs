@@ -0,0 +1,314 @@
+//! Hooks run around the requests to the API: before they're sent, so that
+//! headers like the signatures or correlation IDs required by API gateways
+//! can be added, when they're rate limited, for observability, when their
+//! responses are received, and while they're read, to show the progress of
+//! large downloads.
+
+use super::{Headers, Query};
+use crate::client::ClientResult;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use url::Url;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A request to the API about to be sent, as given to a [`RequestHook`].
+///
+/// It's final: the query parameters are already appended to the URL, sorted
+/// by name, and the body is serialized as it will be sent, so that they can
+/// be signed deterministically. Only headers can be added.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutgoingRequest {
+    method: &'static str,
+    url: String,
+    headers: Headers,
+    body: Vec<u8>,
+}
+
+impl OutgoingRequest {
+    pub(crate) fn new(
+        method: &'static str,
+        url: &str,
+        headers: Option<&Headers>,
+        query: Option<&Query>,
+        body: Option<&Value>,
+    ) -> ClientResult<Self> {
+        let mut url = Url::parse(url)?;
+        if let Some(query) = query.filter(|query| !query.is_empty()) {
+            let mut params = query.iter().collect::<Vec<_>>();
+            params.sort();
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        let body = match body {
+            Some(body) => serde_json::to_vec(body)?,
+            None => Vec::new(),
+        };
+
+        Ok(OutgoingRequest {
+            method,
+            url: url.into(),
+            headers: headers.cloned().unwrap_or_default(),
+            body,
+        })
+    }
+
+    /// The HTTP method, like `GET`.
+    pub fn method(&self) -> &str {
+        self.method
+    }
+
+    /// The full URL, including the query parameters.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The headers, including the authorization one.
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// The JSON body, which is empty for GET requests.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Adds a header to the request, replacing the previous value if any. The
+    /// request fails with [`ClientError::InvalidHeader`
+    /// ](crate::client::ClientError::InvalidHeader) instead of being sent if
+    /// the name isn't a valid header name or the value contains control
+    /// characters, like line breaks.
+    pub fn insert_header<K: Into<String>, V: Into<String>>(&mut self, key: K, val: V) {
+        self.headers.insert(key.into(), val.into());
+    }
+
+    pub(crate) fn into_parts(self) -> (String, Headers) {
+        (self.url, self.headers)
+    }
+}
+
+/// A function run right before each request to the API is sent, with
+/// [`Spotify::request_hook`](crate::client::Spotify::request_hook). It's run
+/// once per request, so the retries are sent with the same headers. The
+/// token requests to the accounts service aren't included.
+///
+/// ```
+/// use rspotify::client::{RequestHook, SpotifyBuilder};
+///
+/// let spotify = SpotifyBuilder::default()
+///     .request_hook(RequestHook::new(|request| {
+///         let signature = format!("{} {}", request.method(), request.url());
+///         request.insert_header("x-signature", signature);
+///     }))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct RequestHook(Arc<dyn Fn(&mut OutgoingRequest) + Send + Sync>);
+
+impl RequestHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&mut OutgoingRequest) + Send + Sync + 'static,
+    {
+        RequestHook(Arc::new(hook))
+    }
+
+    pub(crate) fn run(&self, request: &mut OutgoingRequest) {
+        (self.0)(request)
+    }
+}
+
+impl fmt::Debug for RequestHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("RequestHook")
+    }
+}
+
+/// A request to the API that failed because of the rate limit, as given to
+/// a [`RateLimitHook`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// How long Spotify asked to wait with the `Retry-After` header, if it
+    /// did.
+    pub retry_after: Option<Duration>,
+    /// The URL of the request, without its query parameters.
+    pub endpoint: String,
+    /// When the response was received.
+    pub occurred_at: DateTime<Utc>,
+    /// Whether the request is going to be retried, as configured in
+    /// [`Spotify::retry_policy`](crate::client::Spotify::retry_policy).
+    /// Otherwise, it fails with [`ClientError::RateLimited`
+    /// ](crate::client::ClientError::RateLimited).
+    pub retried: bool,
+}
+
+/// A function run whenever a request to the API is rate limited, with
+/// [`Spotify::rate_limit_hook`](crate::client::Spotify::rate_limit_hook),
+/// including the ones that are retried. It's useful to alert when a service
+/// is consistently hitting the limits.
+///
+/// ```
+/// use rspotify::client::{RateLimitHook, SpotifyBuilder};
+///
+/// let spotify = SpotifyBuilder::default()
+///     .rate_limit_hook(RateLimitHook::new(|info| {
+///         log::warn!("{} was rate limited for {:?}", info.endpoint, info.retry_after);
+///     }))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct RateLimitHook(Arc<dyn Fn(&RateLimitInfo) + Send + Sync>);
+
+impl RateLimitHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&RateLimitInfo) + Send + Sync + 'static,
+    {
+        RateLimitHook(Arc::new(hook))
+    }
+
+    pub(crate) fn run(&self, info: &RateLimitInfo) {
+        (self.0)(info)
+    }
+}
+
+impl fmt::Debug for RateLimitHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("RateLimitHook")
+    }
+}
+
+/// How much of a response body has been read, as given to a
+/// [`ProgressHook`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DownloadProgress<'a> {
+    /// The URL of the request.
+    pub url: &'a str,
+    /// The bytes of the body read so far.
+    pub received: usize,
+    /// The length of the body, if the server said it with `Content-Length`.
+    pub total: Option<usize>,
+}
+
+/// A function run each time a chunk of a response body is read by the HTTP
+/// client, configured in its [`BodyLimits`](crate::client::BodyLimits).
+///
+/// ```
+/// use rspotify::client::{BaseHTTPClient, BodyLimits, HTTPClient, ProgressHook};
+///
+/// let http: HTTPClient = HTTPClient::default().with_body_limits(BodyLimits {
+///     progress: Some(ProgressHook::new(|progress| {
+///         if let Some(total) = progress.total {
+///             println!("{}: {}/{} bytes", progress.url, progress.received, total);
+///         }
+///     })),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Clone)]
+pub struct ProgressHook(Arc<dyn Fn(&DownloadProgress) + Send + Sync>);
+
+impl ProgressHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&DownloadProgress) + Send + Sync + 'static,
+    {
+        ProgressHook(Arc::new(hook))
+    }
+
+    pub(crate) fn run(&self, progress: &DownloadProgress) {
+        (self.0)(progress)
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ProgressHook")
+    }
+}
+
+/// A response received by the HTTP client, as given to a [`ResponseHook`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncomingResponse {
+    /// The URL of the request.
+    pub url: String,
+    /// The HTTP status code, which may be an error.
+    pub status: u16,
+    /// The headers of the response, with lowercase names.
+    pub headers: Headers,
+}
+
+/// A function run by the HTTP client each time a response is received,
+/// before its body is read, configured with
+/// [`BaseHTTPClient::with_response_hook`
+/// ](crate::client::BaseHTTPClient::with_response_hook). It's what
+/// [`Spotify::with_meta`](crate::client::Spotify::with_meta) is built on.
+#[derive(Clone)]
+pub struct ResponseHook(Arc<dyn Fn(&IncomingResponse) + Send + Sync>);
+
+impl ResponseHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&IncomingResponse) + Send + Sync + 'static,
+    {
+        ResponseHook(Arc::new(hook))
+    }
+
+    pub(crate) fn run(&self, response: &IncomingResponse) {
+        (self.0)(response)
+    }
+}
+
+impl fmt::Debug for ResponseHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ResponseHook")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_outgoing_request() {
+        let mut query = Query::new();
+        query.insert("offset".to_owned(), "0".to_owned());
+        query.insert("limit".to_owned(), "50".to_owned());
+        let request = OutgoingRequest::new(
+            "GET",
+            "https://api.spotify.com/v1/me/tracks",
+            None,
+            Some(&query),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            request.url(),
+            "https://api.spotify.com/v1/me/tracks?limit=50&offset=0"
+        );
+        assert!(request.body().is_empty());
+
+        let mut request = OutgoingRequest::new(
+            "PUT",
+            "https://api.spotify.com/v1/me/player/volume?volume_percent=50",
+            None,
+            None,
+            Some(&json!({"b": 1, "a": 2})),
+        )
+        .unwrap();
+        assert_eq!(
+            request.url(),
+            "https://api.spotify.com/v1/me/player/volume?volume_percent=50"
+        );
+        assert_eq!(request.body(), br#"{"a":2,"b":1}"#);
+
+        let hook = RequestHook::new(|request| request.insert_header("x-signature", "signed"));
+        hook.run(&mut request);
+        assert_eq!(request.headers()["x-signature"], "signed");
+    }
+}
@@ -1,45 +1,74 @@
 //! The HTTP client may vary depending on which one the user configures. This
 //! module contains the required logic to use different clients interchangeably.
 
+pub(crate) mod dynamic;
+pub(crate) mod hook;
+pub(crate) mod params;
+pub(crate) mod redact;
+#[cfg(feature = "replay")]
+pub mod replay;
 #[cfg(feature = "client-reqwest")]
 mod reqwest;
+pub(crate) mod retry;
+pub(crate) mod scheduler;
+pub(crate) mod single_flight;
 #[cfg(feature = "client-ureq")]
 mod ureq;
 
-use crate::client::{ClientResult, Spotify};
+use crate::client::{ClientError, ClientResult, Spotify};
+use hook::{DownloadProgress, OutgoingRequest, ProgressHook, ResponseHook};
+use retry::{retrying, Deadline};
+use single_flight::Flight;
 
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use maybe_async::maybe_async;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 #[cfg(feature = "client-reqwest")]
-pub use self::reqwest::ReqwestClient as HTTPClient;
+pub use self::reqwest::ReqwestClient as HTTPBackend;
 #[cfg(feature = "client-ureq")]
-pub use self::ureq::UreqClient as HTTPClient;
+pub use self::ureq::UreqClient as HTTPBackend;
+
+// With the `replay` feature the backend is wrapped so that its interactions
+// can be recorded and replayed. It doesn't record anything by default. With
+// the `dyn-http` feature any client can be used, chosen at runtime.
+#[cfg(feature = "dyn-http")]
+pub use self::dynamic::DynHTTPClient as HTTPClient;
+#[cfg(all(feature = "replay", not(feature = "dyn-http")))]
+pub use self::replay::ReplayClient as HTTPClient;
+#[cfg(not(any(feature = "replay", feature = "dyn-http")))]
+pub use self::HTTPBackend as HTTPClient;
+
+pub use self::params::{Form, Query};
 
 pub type Headers = HashMap<String, String>;
-pub type Query = HashMap<String, String>;
-pub type Form = HashMap<String, String>;
 
 pub mod headers {
     use crate::oauth2::Token;
 
     // Common headers as constants
     pub const CLIENT_ID: &str = "client_id";
-    pub const CODE: &str = "code";
+    pub const CODE_CHALLENGE: &str = "code_challenge";
+    pub const CODE_CHALLENGE_METHOD: &str = "code_challenge_method";
     pub const GRANT_AUTH_CODE: &str = "authorization_code";
     pub const GRANT_CLIENT_CREDS: &str = "client_credentials";
     pub const GRANT_REFRESH_TOKEN: &str = "refresh_token";
     pub const GRANT_TYPE: &str = "grant_type";
     pub const REDIRECT_URI: &str = "redirect_uri";
-    pub const REFRESH_TOKEN: &str = "refresh_token";
     pub const RESPONSE_CODE: &str = "code";
     pub const RESPONSE_TYPE: &str = "response_type";
     pub const SCOPE: &str = "scope";
     pub const SHOW_DIALOG: &str = "show_dialog";
     pub const STATE: &str = "state";
+    pub const IF_NONE_MATCH: &str = "if-none-match";
+    pub const ETAG: &str = "etag";
+    pub const ACCEPT_LANGUAGE: &str = "accept-language";
 
     /// Generates an HTTP token authorization header with proper formatting
     pub fn bearer_auth(tok: &Token) -> (String, String) {
@@ -49,14 +78,255 @@ pub mod headers {
         (auth, value)
     }
 
-    /// Generates an HTTP basic authorization header with proper formatting
+    /// Generates an HTTP basic authorization header with proper formatting.
+    ///
+    /// As required for the client credentials by the [OAuth 2.0
+    /// spec](https://datatracker.ietf.org/doc/html/rfc6749#section-2.3.1),
+    /// both are form-urlencoded first, so that a `:` or any other special
+    /// character in them can't be confused with the separator.
     pub fn basic_auth(user: &str, password: &str) -> (String, String) {
         let auth = "authorization".to_owned();
-        let value = format!("{}:{}", user, password);
+        let value = format!("{}:{}", form_encode(user), form_encode(password));
         let value = format!("Basic {}", base64::encode(value));
 
         (auth, value)
     }
+
+    fn form_encode(val: &str) -> String {
+        url::form_urlencoded::byte_serialize(val.as_bytes()).collect()
+    }
+}
+
+/// Statistics about the response bodies read by the HTTP client, so that the
+/// cost of the buffered (read in full before being parsed) and the streamed
+/// (deserialized while being read) paths can be compared.
+///
+/// Only the blocking client streams the responses; the async one always
+/// buffers them, although the JSON ones aren't decoded into a `String`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BodyMetrics {
+    /// Number of responses read in full before being parsed.
+    pub buffered_responses: u64,
+    /// Total bytes of the responses read in full.
+    pub buffered_bytes: u64,
+    /// Number of responses deserialized while being read from the body.
+    pub streamed_responses: u64,
+    /// Total bytes of the responses deserialized while being read.
+    pub streamed_bytes: u64,
+    /// Size in bytes of the largest response body read so far.
+    pub largest_body: u64,
+}
+
+/// Thread-safe counters backing [`BodyMetrics`], shared by the clones of an
+/// HTTP client.
+#[derive(Debug, Default)]
+pub(crate) struct BodyMetricsRecorder {
+    buffered_responses: AtomicU64,
+    buffered_bytes: AtomicU64,
+    streamed_responses: AtomicU64,
+    streamed_bytes: AtomicU64,
+    largest_body: AtomicU64,
+}
+
+impl BodyMetricsRecorder {
+    pub fn record_buffered(&self, len: usize) {
+        self.buffered_responses.fetch_add(1, Ordering::Relaxed);
+        self.buffered_bytes.fetch_add(len as u64, Ordering::Relaxed);
+        self.largest_body.fetch_max(len as u64, Ordering::Relaxed);
+    }
+
+    /// Only the blocking client deserializes the responses as they're read.
+    #[cfg_attr(not(feature = "client-ureq"), allow(dead_code))]
+    pub fn record_streamed(&self, len: usize) {
+        self.streamed_responses.fetch_add(1, Ordering::Relaxed);
+        self.streamed_bytes.fetch_add(len as u64, Ordering::Relaxed);
+        self.largest_body.fetch_max(len as u64, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> BodyMetrics {
+        BodyMetrics {
+            buffered_responses: self.buffered_responses.load(Ordering::Relaxed),
+            buffered_bytes: self.buffered_bytes.load(Ordering::Relaxed),
+            streamed_responses: self.streamed_responses.load(Ordering::Relaxed),
+            streamed_bytes: self.streamed_bytes.load(Ordering::Relaxed),
+            largest_body: self.largest_body.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// How the HTTP client reuses its connections, to tune it for high throughput
+/// workloads like scraping the catalog. It's given to
+/// [`BaseHTTPClient::from_config`].
+///
+/// Reusing connections saves the TCP and TLS handshakes of each request,
+/// which usually take longer than the request itself. Many requests at the
+/// same time benefit from keeping more idle connections per host, or from
+/// HTTP/2, which sends all of them over a single connection instead. The
+/// settings that the HTTP library doesn't support are ignored: ureq neither
+/// has an idle timeout nor TCP keep-alive, and only speaks HTTP/1.1.
+///
+/// ```
+/// use rspotify::client::{BaseHTTPClient, ConnectionConfig, HTTPClient};
+///
+/// let config = ConnectionConfig {
+///     pool_max_idle_per_host: Some(32),
+///     ..Default::default()
+/// };
+/// let http: HTTPClient = HTTPClient::from_config(&config).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionConfig {
+    /// How long an idle connection is kept open to be reused, which is 90
+    /// seconds by default in reqwest.
+    pub pool_idle_timeout: Option<Duration>,
+    /// The maximum number of idle connections kept open per host, which is
+    /// unlimited by default in reqwest and 1 in ureq.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// The interval of the TCP keep-alive probes sent over idle connections,
+    /// so that they aren't closed by proxies or NATs. Disabled by default.
+    pub tcp_keepalive: Option<Duration>,
+    /// Whether HTTP/2 may be negotiated with the server. Otherwise, only
+    /// HTTP/1.1 is used. Enabled by default.
+    pub http2: bool,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        ConnectionConfig {
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            tcp_keepalive: None,
+            http2: true,
+        }
+    }
+}
+
+/// Limits on the response bodies read by the HTTP client, to protect
+/// memory-constrained environments from unbounded allocations, since some
+/// responses, like the audio analyses, can take multiple megabytes. They're
+/// configured with [`BaseHTTPClient::with_body_limits`].
+#[derive(Clone, Debug, Default)]
+pub struct BodyLimits {
+    /// The maximum size in bytes of the response bodies. Longer ones fail with
+    /// [`ClientError::BodyTooLarge`] as soon as they're known to be, without
+    /// reading the rest. There's no maximum by default.
+    pub max_size: Option<usize>,
+    /// A function run as the response bodies are read, to show the progress
+    /// of the large ones. None is set by default.
+    pub progress: Option<ProgressHook>,
+}
+
+/// Follows a response body as it's read, enforcing the [`BodyLimits`] and
+/// reporting its progress.
+#[derive(Debug)]
+pub(crate) struct BodyTracker<'a> {
+    url: &'a str,
+    progress: Option<&'a ProgressHook>,
+    max_size: Option<usize>,
+    total: Option<usize>,
+    received: usize,
+}
+
+impl<'a> BodyTracker<'a> {
+    /// Starts following the body of a response with the given
+    /// `Content-Length`, if any, which can't be longer than `limit` either.
+    pub fn new(
+        url: &'a str,
+        limits: &'a BodyLimits,
+        total: Option<u64>,
+        limit: Option<usize>,
+    ) -> ClientResult<Self> {
+        let max_size = match (limits.max_size, limit) {
+            (Some(max_size), Some(limit)) => Some(max_size.min(limit)),
+            (max_size, limit) => max_size.or(limit),
+        };
+        let total = total.map(|total| total as usize);
+        if let (Some(max_size), Some(total)) = (max_size, total) {
+            if total > max_size {
+                return Err(ClientError::BodyTooLarge(max_size));
+            }
+        }
+
+        Ok(BodyTracker {
+            url,
+            progress: limits.progress.as_ref(),
+            max_size,
+            total,
+            received: 0,
+        })
+    }
+
+    /// Records that `len` more bytes were read.
+    pub fn advance(&mut self, len: usize) -> ClientResult<()> {
+        if len == 0 {
+            return Ok(());
+        }
+        self.received += len;
+        if let Some(max_size) = self.max_size {
+            if self.received > max_size {
+                return Err(ClientError::BodyTooLarge(max_size));
+            }
+        }
+        if let Some(progress) = self.progress {
+            progress.run(&DownloadProgress {
+                url: self.url,
+                received: self.received,
+                total: self.total,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Decodes a response body, replacing the invalid UTF-8 sequences like
+/// `reqwest::Response::text` does.
+pub(crate) fn decode_body(body: Vec<u8>) -> String {
+    String::from_utf8(body)
+        .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned())
+}
+
+/// The characters escaped in the parameters interpolated into the URLs, which
+/// are all but the unreserved ones.
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Percent-encodes a parameter to be interpolated into the path or query of
+/// a URL, like a user ID.
+pub(crate) fn encode_param(param: &str) -> String {
+    utf8_percent_encode(param, SEGMENT).to_string()
+}
+
+/// Checks that the headers can be sent as they are, so that the ones given by
+/// the user, like in a request hook, fail with an error instead of making the
+/// HTTP client panic or being sent with a line break in them. The names must
+/// be [tokens](https://datatracker.ietf.org/doc/html/rfc7230#section-3.2.6),
+/// and the values can't contain control characters other than tabs.
+pub(crate) fn check_headers(headers: &Headers) -> ClientResult<()> {
+    const TOKEN_CHARS: &[u8] = b"!#$%&'*+-.^_`|~";
+
+    for (name, val) in headers {
+        let invalid = |reason| ClientError::InvalidHeader {
+            name: name.clone(),
+            reason,
+        };
+        if name.is_empty() {
+            return Err(invalid("the name is empty"));
+        }
+        if !name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || TOKEN_CHARS.contains(&b))
+        {
+            return Err(invalid("the name isn't a token"));
+        }
+        if val.bytes().any(|b| (b < b' ' && b != b'\t') || b == 0x7f) {
+            return Err(invalid("the value contains control characters"));
+        }
+    }
+
+    Ok(())
 }
 
 /// This trait represents the interface to be implemented for an HTTP client,
@@ -71,6 +341,22 @@ pub mod headers {
 /// much sense).
 #[maybe_async]
 pub trait BaseHTTPClient: Default + Clone + fmt::Debug {
+    /// The client of the underlying HTTP library, like `reqwest::Client` or
+    /// `ureq::Agent`.
+    type Native;
+
+    /// Builds the client on top of an already configured native client, so
+    /// that advanced settings like TLS or DNS resolution can be customized.
+    fn from_native(native: Self::Native) -> Self;
+
+    /// Builds a client whose connections are reused as given in `config`.
+    /// The default client is built by default, so that custom clients don't
+    /// have to support it.
+    fn from_config(config: &ConnectionConfig) -> ClientResult<Self> {
+        let _ = config;
+        Ok(Self::default())
+    }
+
     // This internal function should always be given an object value in JSON.
     async fn get(
         &self,
@@ -79,6 +365,17 @@ pub trait BaseHTTPClient: Default + Clone + fmt::Debug {
         payload: &Query,
     ) -> ClientResult<String>;
 
+    /// The same as `get`, but the response body is deserialized directly
+    /// instead of being decoded into a `String` first. This is considerably
+    /// cheaper for large responses like pages of full tracks, and the blocking
+    /// client avoids buffering them at all.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<T>;
+
     async fn post(
         &self,
         url: &str,
@@ -93,6 +390,24 @@ pub trait BaseHTTPClient: Default + Clone + fmt::Debug {
         payload: &Form,
     ) -> ClientResult<String>;
 
+    /// The same as `post_form`, but failing with [`ClientError::Timeout`
+    /// ](crate::client::ClientError::Timeout) if the response isn't received
+    /// within `timeout`. The timeout is ignored by default, so that custom
+    /// clients don't have to support it.
+    async fn post_form_timeout(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+        timeout: Duration,
+    ) -> ClientResult<String>
+    where
+        Self: Sync,
+    {
+        let _ = timeout;
+        self.post_form(url, headers, payload).await
+    }
+
     async fn put(
         &self,
         url: &str,
@@ -106,6 +421,76 @@ pub trait BaseHTTPClient: Default + Clone + fmt::Debug {
         headers: Option<&Headers>,
         payload: &Value,
     ) -> ClientResult<String>;
+
+    /// The same as `get`, but sending `etag` in the `If-None-Match` header,
+    /// if any, and returning the `ETag` of the response along with its body.
+    /// [`Conditional::NotModified`] is returned when the object hasn't
+    /// changed since. By default, the request is made without the header and
+    /// no `ETag` is returned, so that custom clients don't have to support
+    /// it.
+    async fn get_conditional(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<String>>
+    where
+        Self: Sync,
+    {
+        let _ = etag;
+        let body = self.get(url, headers, payload).await?;
+        Ok(Conditional::Fetched(body, None))
+    }
+
+    /// Downloads the raw body of the response to a GET request, like an
+    /// audio file, failing with [`ClientError::BodyTooLarge`] if it's longer
+    /// than `limit` bytes. It's unsupported by default, so that custom
+    /// clients don't have to implement it.
+    async fn get_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        limit: usize,
+    ) -> ClientResult<Vec<u8>>
+    where
+        Self: Sync,
+    {
+        let _ = (headers, limit);
+        Err(ClientError::request(
+            Some(url.to_owned()),
+            "this HTTP client doesn't support downloads",
+        ))
+    }
+
+    /// Statistics about the response bodies read by this client so far.
+    fn body_metrics(&self) -> BodyMetrics;
+
+    /// The same client, enforcing the given limits on the response bodies
+    /// it reads. They're ignored by default, so that custom clients don't
+    /// have to support them.
+    fn with_body_limits(self, limits: BodyLimits) -> Self {
+        let _ = limits;
+        self
+    }
+
+    /// The same client, running `hook` each time it receives a response. It's
+    /// ignored by default, so that custom clients don't have to support it.
+    fn with_response_hook(self, hook: ResponseHook) -> Self {
+        let _ = hook;
+        self
+    }
+}
+
+/// The result of a conditional request, made with the `ETag` of a previous
+/// response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Conditional<T> {
+    /// The object was returned, along with its `ETag` to be sent in the next
+    /// request, if any.
+    Fetched(T, Option<String>),
+    /// The object hasn't changed since the response with the given `ETag`.
+    NotModified,
 }
 
 /// HTTP-related methods for the Spotify client. It wraps the basic HTTP client
@@ -120,11 +505,14 @@ pub trait BaseHTTPClient: Default + Clone + fmt::Debug {
 /// * Endpoint wrappers: `endpoint_get`, `endpoint_post`, `endpoint_put`,
 ///   `endpoint_delete`. These append the authentication headers for endpoint
 ///   requests to reduce the code needed for endpoints and make them as concise
-///   as possible.
+///   as possible. They're the implementation of
+///   [`BaseClient`](crate::client::BaseClient) and
+///   [`OAuthClient`](crate::client::OAuthClient) for the client, found in the
+///   `client` module.
 impl Spotify {
     /// If it's a relative URL like "me", the prefix is appended to it.
     /// Otherwise, the same URL is returned.
-    fn endpoint_url(&self, url: &str) -> String {
+    pub(crate) fn endpoint_url(&self, url: &str) -> String {
         // Using the client's prefix in case it's a relative route.
         if !url.starts_with("http") {
             self.prefix.clone() + url
@@ -133,15 +521,46 @@ impl Spotify {
         }
     }
 
-    /// The headers required for authenticated requests to the API
-    fn auth_headers(&self) -> ClientResult<Headers> {
+    /// The headers required for authenticated requests to the API. A new
+    /// token is requested first in case it's needed and possible.
+    #[maybe_async]
+    pub(crate) async fn auth_headers(&self) -> ClientResult<Headers> {
+        self.request_token_if_needed().await?;
+
         let mut auth = Headers::new();
-        let (key, val) = headers::bearer_auth(self.get_token()?);
+        let (key, val) = headers::bearer_auth(&self.get_token()?);
         auth.insert(key, val);
+        if let Some(language) = &self.language {
+            auth.insert(headers::ACCEPT_LANGUAGE.to_owned(), language.clone());
+            // It's configured by the user, so it may not be a valid value.
+            check_headers(&auth)?;
+        }
 
         Ok(auth)
     }
 
+    /// Runs the request hook, if any, returning the URL and headers of the
+    /// finalized request. The query parameters are included in the URL.
+    fn run_request_hook(
+        &self,
+        method: &'static str,
+        url: &str,
+        headers: Option<&Headers>,
+        query: Option<&Query>,
+        body: Option<&Value>,
+    ) -> ClientResult<Option<(String, Headers)>> {
+        let hook = match &self.request_hook {
+            Some(hook) => hook,
+            None => return Ok(None),
+        };
+
+        let mut request = OutgoingRequest::new(method, url, headers, query, body)?;
+        hook.run(&mut request);
+        let (url, headers) = request.into_parts();
+        check_headers(&headers)?;
+        Ok(Some((url, headers)))
+    }
+
     #[inline]
     #[maybe_async]
     pub(crate) async fn get(
@@ -149,9 +568,99 @@ impl Spotify {
         url: &str,
         headers: Option<&Headers>,
         payload: &Query,
+        deadline: Option<Deadline>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        self.http.get(&url, headers, payload).await
+        let hooked = self.run_request_hook("GET", &url, headers, Some(payload), None)?;
+        let empty = Query::new();
+        let (url, headers, payload) = match &hooked {
+            Some((url, headers)) => (url.as_str(), Some(headers), &empty),
+            None => (url.as_str(), headers, payload),
+        };
+        let request = || self.http.get(url, headers, payload);
+        if !self.dedup_requests {
+            return retrying(
+                &self.retry_policy,
+                self.sleeper.as_ref(),
+                self.rate_limit_hook.as_ref(),
+                self.scheduler.as_ref(),
+                url,
+                deadline,
+                request,
+            )
+            .await;
+        }
+
+        let key = single_flight::request_key(url, headers, payload);
+        match self.single_flight.join(key) {
+            Flight::Leader(leader) => {
+                let result = retrying(
+                    &self.retry_policy,
+                    self.sleeper.as_ref(),
+                    self.rate_limit_hook.as_ref(),
+                    self.scheduler.as_ref(),
+                    url,
+                    deadline,
+                    request,
+                )
+                .await;
+                if let Ok(body) = &result {
+                    leader.finish(body);
+                }
+                result
+            }
+            // In case the other request fails, this one is made on its own,
+            // so that it gets its own error.
+            Flight::Follower(receiver) => match single_flight::wait(receiver).await {
+                Some(body) => Ok(body),
+                None => {
+                    retrying(
+                        &self.retry_policy,
+                        self.sleeper.as_ref(),
+                        self.rate_limit_hook.as_ref(),
+                        self.scheduler.as_ref(),
+                        url,
+                        deadline,
+                        request,
+                    )
+                    .await
+                }
+            },
+        }
+    }
+
+    #[inline]
+    #[maybe_async]
+    pub(crate) async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        deadline: Option<Deadline>,
+    ) -> ClientResult<T> {
+        if self.dedup_requests {
+            // The responses can only be shared before being deserialized.
+            let body = self.get(url, headers, payload, deadline).await?;
+            return serde_json::from_str(&body).map_err(Into::into);
+        }
+
+        let url = self.endpoint_url(url);
+        let hooked = self.run_request_hook("GET", &url, headers, Some(payload), None)?;
+        let empty = Query::new();
+        let (url, headers, payload) = match &hooked {
+            Some((url, headers)) => (url.as_str(), Some(headers), &empty),
+            None => (url.as_str(), headers, payload),
+        };
+        retrying(
+            &self.retry_policy,
+            self.sleeper.as_ref(),
+            self.rate_limit_hook.as_ref(),
+            self.scheduler.as_ref(),
+            url,
+            deadline,
+            || self.http.get_json(url, headers, payload),
+        )
+        .await
     }
 
     #[inline]
@@ -161,9 +670,24 @@ impl Spotify {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        deadline: Option<Deadline>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        self.http.post(&url, headers, payload).await
+        let hooked = self.run_request_hook("POST", &url, headers, None, Some(payload))?;
+        let (url, headers) = match &hooked {
+            Some((url, headers)) => (url.as_str(), Some(headers)),
+            None => (url.as_str(), headers),
+        };
+        retrying(
+            &self.retry_policy.for_method("POST"),
+            self.sleeper.as_ref(),
+            self.rate_limit_hook.as_ref(),
+            self.scheduler.as_ref(),
+            url,
+            deadline,
+            || self.http.post(url, headers, payload),
+        )
+        .await
     }
 
     #[inline]
@@ -175,7 +699,16 @@ impl Spotify {
         payload: &Form,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        self.http.post_form(&url, headers, payload).await
+        // Forms are only sent to the accounts service, so the token timeout
+        // applies to all of them.
+        match self.token_timeout {
+            Some(timeout) => {
+                self.http
+                    .post_form_timeout(&url, headers, payload, timeout)
+                    .await
+            }
+            None => self.http.post_form(&url, headers, payload).await,
+        }
     }
 
     #[inline]
@@ -185,9 +718,24 @@ impl Spotify {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        deadline: Option<Deadline>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        self.http.put(&url, headers, payload).await
+        let hooked = self.run_request_hook("PUT", &url, headers, None, Some(payload))?;
+        let (url, headers) = match &hooked {
+            Some((url, headers)) => (url.as_str(), Some(headers)),
+            None => (url.as_str(), headers),
+        };
+        retrying(
+            &self.retry_policy,
+            self.sleeper.as_ref(),
+            self.rate_limit_hook.as_ref(),
+            self.scheduler.as_ref(),
+            url,
+            deadline,
+            || self.http.put(url, headers, payload),
+        )
+        .await
     }
 
     #[inline]
@@ -197,38 +745,193 @@ impl Spotify {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        deadline: Option<Deadline>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        self.http.delete(&url, headers, payload).await
+        let hooked = self.run_request_hook("DELETE", &url, headers, None, Some(payload))?;
+        let (url, headers) = match &hooked {
+            Some((url, headers)) => (url.as_str(), Some(headers)),
+            None => (url.as_str(), headers),
+        };
+        retrying(
+            &self.retry_policy,
+            self.sleeper.as_ref(),
+            self.rate_limit_hook.as_ref(),
+            self.scheduler.as_ref(),
+            url,
+            deadline,
+            || self.http.delete(url, headers, payload),
+        )
+        .await
     }
 
-    /// The wrapper for the endpoints, which also includes the required
-    /// autentication.
-    #[inline]
+    /// The same as [`BaseClient::endpoint_get_as`], but only returning the
+    /// object if it has changed since the response with the given `etag`.
+    /// This makes it possible to implement a cache over the catalog objects
+    /// with its own policy: the `ETag` returned along with the object is
+    /// stored, and sent in the next request for the same URL. If `etag` is
+    /// `None`, the object is always returned.
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "client-reqwest")]
+    /// # async fn run(spotify: rspotify::client::Spotify, etag: Option<&str>) {
+    /// use rspotify::client::Conditional;
+    /// use rspotify::model::FullArtist;
+    ///
+    /// let url = "artists/0OdUWJ0sBjDrqHygGUXeCF";
+    /// match spotify.get_if_none_match::<FullArtist>(url, etag).await.unwrap() {
+    ///     Conditional::Fetched(artist, etag) => println!("{} ({:?})", artist.name, etag),
+    ///     Conditional::NotModified => println!("the cached artist is still valid"),
+    /// }
+    /// # }
+    /// ```
     #[maybe_async]
-    pub(crate) async fn endpoint_get(&self, url: &str, payload: &Query) -> ClientResult<String> {
-        let headers = self.auth_headers()?;
-        self.get(url, Some(&headers), payload).await
+    pub async fn get_if_none_match<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<T>> {
+        self.get_conditional(url, etag, None).await
     }
 
-    #[inline]
+    /// The implementation of [`Spotify::get_if_none_match`], retried like
+    /// the rest of the `GET` requests.
     #[maybe_async]
-    pub(crate) async fn endpoint_post(&self, url: &str, payload: &Value) -> ClientResult<String> {
-        let headers = self.auth_headers()?;
-        self.post(url, Some(&headers), payload).await
+    pub(crate) async fn get_conditional<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        deadline: Option<Deadline>,
+    ) -> ClientResult<Conditional<T>> {
+        let headers = self.auth_headers().await?;
+        let url = self.endpoint_url(url);
+        let hooked = self.run_request_hook("GET", &url, Some(&headers), None, None)?;
+        let (url, headers) = match &hooked {
+            Some((url, headers)) => (url.as_str(), headers),
+            None => (url.as_str(), &headers),
+        };
+        let empty = Query::new();
+        let response = retrying(
+            &self.retry_policy,
+            self.sleeper.as_ref(),
+            self.rate_limit_hook.as_ref(),
+            self.scheduler.as_ref(),
+            url,
+            deadline,
+            || self.http.get_conditional(url, Some(headers), &empty, etag),
+        )
+        .await?;
+        match response {
+            Conditional::Fetched(body, etag) => {
+                Ok(Conditional::Fetched(serde_json::from_str(&body)?, etag))
+            }
+            Conditional::NotModified => Ok(Conditional::NotModified),
+        }
     }
 
-    #[inline]
-    #[maybe_async]
-    pub(crate) async fn endpoint_put(&self, url: &str, payload: &Value) -> ClientResult<String> {
-        let headers = self.auth_headers()?;
-        self.put(url, Some(&headers), payload).await
+    /// Returns statistics about the response bodies read by the client, such
+    /// as how many bytes went through the buffered and the streamed paths.
+    pub fn body_metrics(&self) -> BodyMetrics {
+        self.http.body_metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_auth() {
+        let (key, val) = headers::basic_auth("id", "secret");
+        assert_eq!(key, "authorization");
+        assert_eq!(val, format!("Basic {}", base64::encode("id:secret")));
+
+        // The separator can only be the `:` between both, and the ones in the
+        // secret are encoded.
+        let (_, val) = headers::basic_auth("id", "sec:ret +/é");
+        let decoded = base64::decode(val.trim_start_matches("Basic ")).unwrap();
+        assert_eq!(
+            String::from_utf8(decoded).unwrap(),
+            "id:sec%3Aret+%2B%2F%C3%A9"
+        );
     }
 
-    #[inline]
-    #[maybe_async]
-    pub(crate) async fn endpoint_delete(&self, url: &str, payload: &Value) -> ClientResult<String> {
-        let headers = self.auth_headers()?;
-        self.delete(url, Some(&headers), payload).await
+    #[test]
+    fn test_check_headers() {
+        let mut headers = Headers::new();
+        headers.insert("x-signature".to_owned(), "GET\tsigned é".to_owned());
+        assert!(check_headers(&headers).is_ok());
+
+        for (name, val) in [
+            ("x trace", "a"),
+            ("", "a"),
+            ("x-trace", "a\nb"),
+            ("x-trace", "a\r"),
+        ] {
+            let mut headers = Headers::new();
+            headers.insert(name.to_owned(), val.to_owned());
+            assert!(matches!(
+                check_headers(&headers),
+                Err(ClientError::InvalidHeader { name: invalid, .. }) if invalid == name
+            ));
+        }
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = ConnectionConfig {
+            pool_idle_timeout: Some(Duration::from_secs(30)),
+            pool_max_idle_per_host: Some(16),
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            http2: false,
+        };
+        let http: ClientResult<HTTPClient> = HTTPClient::from_config(&config);
+        assert!(http.is_ok());
+        assert!(HTTPBackend::from_config(&ConnectionConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn test_body_tracker() {
+        let limits = BodyLimits {
+            max_size: Some(10),
+            ..Default::default()
+        };
+        assert!(matches!(
+            BodyTracker::new("url", &limits, Some(11), None),
+            Err(ClientError::BodyTooLarge(10))
+        ));
+        assert!(matches!(
+            BodyTracker::new("url", &limits, Some(8), Some(5)),
+            Err(ClientError::BodyTooLarge(5))
+        ));
+
+        // Without a length the limit is checked while the body is read.
+        let mut tracker = BodyTracker::new("url", &limits, None, None).unwrap();
+        tracker.advance(6).unwrap();
+        tracker.advance(4).unwrap();
+        assert_eq!(tracker.received, 10);
+        assert!(matches!(
+            tracker.advance(1),
+            Err(ClientError::BodyTooLarge(10))
+        ));
+
+        let unlimited = BodyLimits::default();
+        let mut tracker = BodyTracker::new("url", &unlimited, None, None).unwrap();
+        assert!(tracker.advance(usize::MAX / 2).is_ok());
+    }
+
+    #[test]
+    fn test_body_metrics_recorder() {
+        let recorder = BodyMetricsRecorder::default();
+        recorder.record_buffered(10);
+        recorder.record_streamed(300);
+        recorder.record_streamed(20);
+
+        let metrics = recorder.snapshot();
+        assert_eq!(metrics.buffered_responses, 1);
+        assert_eq!(metrics.buffered_bytes, 10);
+        assert_eq!(metrics.streamed_responses, 2);
+        assert_eq!(metrics.streamed_bytes, 320);
+        assert_eq!(metrics.largest_body, 300);
     }
 }
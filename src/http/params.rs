@@ -0,0 +1,203 @@
+//! The parameters of the requests, in the query of the URL or in the body of
+//! a form.
+//!
+//! They're sent in the order they're inserted, and inserting one that's
+//! already there replaces its value in the same position, so that there are
+//! no duplicate keys. They can also be collected from pairs of keys and
+//! values, like the ones of typed parameters such as [`TimeLimits::to_param`
+//! ](crate::model::TimeLimits::to_param):
+//!
+//! ```
+//! use rspotify::client::Query;
+//!
+//! let mut params: Query = vec![("limit", "20")].into_iter().collect();
+//! params.insert("offset", "40");
+//! params.insert_opt("market", None::<String>);
+//! params.insert("limit", "50");
+//! assert_eq!(params.encode(), "limit=50&offset=40");
+//! ```
+
+use serde::Serialize;
+use url::form_urlencoded;
+
+use std::iter::FromIterator;
+use std::ops::Index;
+use std::slice;
+
+macro_rules! define_params {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        ///
+        /// They're kept in the order they're inserted, without duplicate keys.
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(Vec<(String, String)>);
+
+        impl $name {
+            pub fn new() -> Self {
+                $name(Vec::new())
+            }
+
+            pub fn with_capacity(capacity: usize) -> Self {
+                $name(Vec::with_capacity(capacity))
+            }
+
+            /// Inserts the parameter, returning the previous value if it was
+            /// already there.
+            pub fn insert<K: Into<String>, V: Into<String>>(
+                &mut self,
+                key: K,
+                value: V,
+            ) -> Option<String> {
+                let key = key.into();
+                let value = value.into();
+                match self.0.iter_mut().find(|(param, _)| *param == key) {
+                    Some((_, previous)) => Some(std::mem::replace(previous, value)),
+                    None => {
+                        self.0.push((key, value));
+                        None
+                    }
+                }
+            }
+
+            /// Inserts the parameter only if it has a value, which is the
+            /// case of most of the optional parameters of the endpoints.
+            pub fn insert_opt<K: Into<String>, V: ToString>(&mut self, key: K, value: Option<V>) {
+                if let Some(value) = value {
+                    self.insert(key, value.to_string());
+                }
+            }
+
+            pub fn get(&self, key: &str) -> Option<&str> {
+                self.0
+                    .iter()
+                    .find(|(param, _)| param == key)
+                    .map(|(_, value)| value.as_str())
+            }
+
+            pub fn contains_key(&self, key: &str) -> bool {
+                self.get(key).is_some()
+            }
+
+            pub fn remove(&mut self, key: &str) -> Option<String> {
+                let pos = self.0.iter().position(|(param, _)| param == key)?;
+                Some(self.0.remove(pos).1)
+            }
+
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            /// The keys and values, in the order they were inserted.
+            pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+                self.0
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+            }
+
+            /// The parameters encoded as `application/x-www-form-urlencoded`,
+            /// like in the query of a URL.
+            pub fn encode(&self) -> String {
+                form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(&self.0)
+                    .finish()
+            }
+        }
+
+        impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for $name {
+            fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+                for (key, value) in iter {
+                    self.insert(key, value);
+                }
+            }
+        }
+
+        impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for $name {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+                let mut params = $name::new();
+                params.extend(iter);
+                params
+            }
+        }
+
+        impl Index<&str> for $name {
+            type Output = String;
+
+            /// The value of the parameter, like with `HashMap`.
+            ///
+            /// # Panics
+            ///
+            /// If the parameter isn't there.
+            fn index(&self, key: &str) -> &String {
+                self.0
+                    .iter()
+                    .find(|(param, _)| param == key)
+                    .map(|(_, value)| value)
+                    .expect("no parameter found for the key")
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $name {
+            type Item = &'a (String, String);
+            type IntoIter = slice::Iter<'a, (String, String)>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+    };
+}
+
+define_params!(
+    /// The parameters in the query of the URL of a request.
+    Query
+);
+
+define_params!(
+    /// The parameters in the body of a form request, like the ones to the
+    /// accounts service.
+    Form
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_order() {
+        let mut params = Query::new();
+        assert_eq!(params.insert("ids", "a,b"), None);
+        params.insert_opt("market", Some("ES"));
+        params.insert_opt("offset", None::<u32>);
+        assert_eq!(params.insert("ids", "c"), Some("a,b".to_owned()));
+        assert_eq!(
+            params.iter().collect::<Vec<_>>(),
+            vec![("ids", "c"), ("market", "ES")]
+        );
+        assert_eq!(params.remove("ids").as_deref(), Some("c"));
+        assert!(!params.contains_key("ids"));
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_encode() {
+        let params: Form = vec![
+            ("q", "artist:Sigur Rós & friends"),
+            ("redirect_uri", "http://localhost/callback?x=1"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            params.encode(),
+            "q=artist%3ASigur+R%C3%B3s+%26+friends&redirect_uri=http%3A%2F%2Flocalhost%2Fcallback%3Fx%3D1"
+        );
+        assert_eq!(
+            serde_json::to_string(&params).unwrap(),
+            r#"[["q","artist:Sigur Rós & friends"],["redirect_uri","http://localhost/callback?x=1"]]"#
+        );
+    }
+}
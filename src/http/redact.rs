@@ -0,0 +1,141 @@
+//! Removal of credentials from anything that leaves the client other than the
+//! requests themselves, like the logs or the recorded cassettes.
+
+use super::Headers;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use url::Url;
+
+/// The value that replaces credentials.
+pub const REDACTED: &str = "<redacted>";
+
+/// Headers whose values are never shown.
+const SENSITIVE_HEADERS: &[&str] = &["authorization"];
+
+/// Form, query and response fields whose values are never shown.
+pub(crate) const SENSITIVE_FIELDS: &[&str] = &[
+    "access_token",
+    "refresh_token",
+    "client_secret",
+    "code",
+    "code_verifier",
+];
+
+/// Whether the value of the header must be redacted, ignoring its case.
+pub(crate) fn is_sensitive_header(name: &str) -> bool {
+    SENSITIVE_HEADERS.contains(&name.to_lowercase().as_str())
+}
+
+/// Whether the value of the form, query or response field must be redacted.
+pub(crate) fn is_sensitive_field(name: &str) -> bool {
+    SENSITIVE_FIELDS.contains(&name)
+}
+
+/// The headers with the values of the sensitive ones redacted, sorted so that
+/// they're shown consistently.
+pub(crate) fn redact_headers(headers: &Headers) -> BTreeMap<&str, &str> {
+    headers
+        .iter()
+        .map(|(key, val)| {
+            if is_sensitive_header(key) {
+                (key.as_str(), REDACTED)
+            } else {
+                (key.as_str(), val.as_str())
+            }
+        })
+        .collect()
+}
+
+/// The URL with the values of the sensitive query parameters redacted. It's
+/// returned as is if it can't be parsed.
+pub(crate) fn redact_url(url: &str) -> String {
+    let mut parsed = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(_) => return url.to_string(),
+    };
+    if !parsed
+        .query_pairs()
+        .any(|(key, _)| is_sensitive_field(&key))
+    {
+        return url.to_string();
+    }
+
+    let pairs = parsed
+        .query_pairs()
+        .map(|(key, val)| {
+            let val = if is_sensitive_field(&key) {
+                REDACTED.to_string()
+            } else {
+                val.into_owned()
+            };
+            (key.into_owned(), val)
+        })
+        .collect::<Vec<_>>();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+/// Logs a request about to be sent by any of the backends, without its
+/// credentials.
+pub(crate) fn log_request(method: &str, url: &str, headers: Option<&Headers>) {
+    if log::log_enabled!(log::Level::Info) {
+        let headers = headers.map(redact_headers).unwrap_or_default();
+        log::info!(
+            "Making request {} {} with headers {:?}",
+            method,
+            redact_url(url),
+            headers
+        );
+    }
+}
+
+/// Formats a secret in `Debug` implementations, so that only whether it's
+/// set is shown.
+pub(crate) struct Secret<'a>(pub(crate) &'a str);
+
+impl fmt::Debug for Secret<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            f.write_str("\"\"")
+        } else {
+            f.write_str(REDACTED)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_headers() {
+        let mut headers = Headers::new();
+        headers.insert("Authorization".to_owned(), "Bearer secret".to_owned());
+        headers.insert("content-type".to_owned(), "application/json".to_owned());
+
+        let redacted = redact_headers(&headers);
+        assert_eq!(redacted["Authorization"], REDACTED);
+        assert_eq!(redacted["content-type"], "application/json");
+    }
+
+    #[test]
+    fn test_redact_url() {
+        let url =
+            "https://accounts.spotify.com/api/token?refresh_token=secret&grant_type=refresh_token";
+        let redacted = redact_url(url);
+        assert!(!redacted.contains("secret"));
+        assert!(redacted.contains("grant_type=refresh_token"));
+
+        let url = "https://api.spotify.com/v1/me/tracks/contains/?ids=a,b";
+        assert_eq!(redact_url(url), url);
+        assert_eq!(redact_url("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_secret() {
+        assert_eq!(format!("{:?}", Secret("secret")), REDACTED);
+        assert_eq!(format!("{:?}", Secret("")), "\"\"");
+    }
+}
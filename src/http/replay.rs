@@ -0,0 +1,647 @@
+//! VCR-style recording and replaying of HTTP interactions, available with the
+//! `replay` feature.
+//!
+//! [`ReplayClient`] wraps another HTTP client and can work in three modes:
+//!
+//! * [`ReplayMode::Passthrough`]: the requests are forwarded to the inner
+//!   client untouched. This is the default, so enabling the feature doesn't
+//!   change the behaviour of the Spotify client.
+//! * [`ReplayMode::Record`]: the requests are forwarded to the inner client,
+//!   and both the request and its response are saved into a [`Cassette`],
+//!   which is written to disk after each interaction.
+//! * [`ReplayMode::Replay`]: no requests are performed at all. The responses
+//!   are taken from a previously recorded cassette instead, which makes tests
+//!   deterministic and available offline.
+//!
+//! Cassettes are regular JSON files, so they can be inspected and edited by
+//! hand. Credentials are never saved: the authorization headers, the
+//! sensitive fields of the forms and the tokens in the responses are replaced
+//! with [`REDACTED`] before recording. Since headers aren't taken into
+//! account when matching requests, replaying works with any token.
+
+use super::hook::{IncomingResponse, ResponseHook};
+use super::redact::{is_sensitive_field, is_sensitive_header, SENSITIVE_FIELDS};
+use super::{
+    BaseHTTPClient, BodyLimits, BodyMetrics, Conditional, ConnectionConfig, Form, HTTPBackend,
+    Headers, Query,
+};
+use crate::client::{ClientError, ClientResult};
+
+use maybe_async::maybe_async;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The value that replaces credentials in the recorded cassettes.
+pub use super::redact::REDACTED;
+
+/// How a [`ReplayClient`] handles the requests it's given.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Requests are forwarded to the inner client without being recorded.
+    #[default]
+    Passthrough,
+    /// Requests are forwarded to the inner client and recorded.
+    Record,
+    /// Requests are answered with the recorded responses.
+    Replay,
+}
+
+/// A request, as saved in a cassette.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub body: Value,
+}
+
+impl RecordedRequest {
+    /// Whether both requests are equivalent. The headers are ignored, so that
+    /// the redacted credentials don't prevent matching.
+    fn matches(&self, other: &RecordedRequest) -> bool {
+        self.method == other.method
+            && self.url == other.url
+            && self.query == other.query
+            && self.body == other.body
+    }
+}
+
+/// The outcome of a request, as saved in a cassette.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedResponse {
+    /// A successful request with its response body.
+    Body(String),
+    /// A request that failed with an HTTP status code.
+    Status { code: u16, reason: String },
+    /// A request that failed with `401 Unauthorized`.
+    Unauthorized,
+    /// A request that failed with `429 Too Many Requests`.
+    RateLimited(Option<usize>),
+}
+
+impl RecordedResponse {
+    /// Converts the outcome of a request into its recorded version. Errors
+    /// that don't come from the Spotify API, like connection errors, can't be
+    /// replayed and are thus not recorded.
+    fn from_result(result: &ClientResult<String>) -> Option<Self> {
+        match result {
+            Ok(body) => Some(RecordedResponse::Body(redact_body(body))),
+            Err(ClientError::StatusCode(code, reason)) => Some(RecordedResponse::Status {
+                code: *code,
+                reason: reason.clone(),
+            }),
+            Err(ClientError::Unauthorized) => Some(RecordedResponse::Unauthorized),
+            Err(ClientError::RateLimited(after)) => Some(RecordedResponse::RateLimited(*after)),
+            Err(_) => None,
+        }
+    }
+
+    /// The status code of the response.
+    fn status(&self) -> u16 {
+        match self {
+            RecordedResponse::Body(_) => 200,
+            RecordedResponse::Status { code, .. } => *code,
+            RecordedResponse::Unauthorized => 401,
+            RecordedResponse::RateLimited(_) => 429,
+        }
+    }
+
+    fn into_result(self) -> ClientResult<String> {
+        match self {
+            RecordedResponse::Body(body) => Ok(body),
+            RecordedResponse::Status { code, reason } => Err(ClientError::StatusCode(code, reason)),
+            RecordedResponse::Unauthorized => Err(ClientError::Unauthorized),
+            RecordedResponse::RateLimited(after) => Err(ClientError::RateLimited(after)),
+        }
+    }
+}
+
+/// A single request and its response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A list of recorded interactions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> ClientResult<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(Into::into)
+    }
+
+    /// Writes the cassette into a JSON file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> ClientResult<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents).map_err(Into::into)
+    }
+}
+
+/// The state shared between the clones of a `ReplayClient`.
+#[derive(Debug, Default)]
+struct Tape {
+    cassette: Cassette,
+    /// Whether each of the interactions has already been replayed.
+    played: Vec<bool>,
+}
+
+/// An HTTP client that records or replays the interactions of another one.
+/// It works in passthrough mode by default, so it doesn't change the behaviour
+/// of the client until a cassette is recorded or replayed.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayClient<C = HTTPBackend> {
+    inner: C,
+    mode: ReplayMode,
+    /// Where the cassette is saved in [`ReplayMode::Record`].
+    path: Option<PathBuf>,
+    tape: Arc<Mutex<Tape>>,
+    /// Run for the replayed responses too, without any headers.
+    response_hook: Option<ResponseHook>,
+}
+
+impl<C: BaseHTTPClient> ReplayClient<C> {
+    /// Records the interactions into a new cassette saved at `path`.
+    pub fn record<P: Into<PathBuf>>(path: P) -> Self {
+        ReplayClient {
+            inner: C::default(),
+            mode: ReplayMode::Record,
+            path: Some(path.into()),
+            tape: Default::default(),
+            response_hook: None,
+        }
+    }
+
+    /// Replays the interactions in the cassette saved at `path`.
+    pub fn replay<P: AsRef<Path>>(path: P) -> ClientResult<Self> {
+        Ok(Self::from_cassette(Cassette::load(path)?))
+    }
+
+    /// Replays the interactions in the given cassette.
+    pub fn from_cassette(cassette: Cassette) -> Self {
+        let played = vec![false; cassette.interactions.len()];
+        ReplayClient {
+            inner: C::default(),
+            mode: ReplayMode::Replay,
+            path: None,
+            tape: Arc::new(Mutex::new(Tape { cassette, played })),
+            response_hook: None,
+        }
+    }
+
+    /// The mode the client is working in.
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// A copy of the interactions recorded or loaded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.tape.lock().unwrap().cassette.clone()
+    }
+
+    /// The number of interactions that haven't been replayed yet, which is
+    /// useful to detect requests that a test no longer performs.
+    pub fn remaining(&self) -> usize {
+        self.tape
+            .lock()
+            .unwrap()
+            .played
+            .iter()
+            .filter(|played| !**played)
+            .count()
+    }
+
+    /// Answers the request with the first matching interaction that hasn't
+    /// been replayed yet.
+    fn play(&self, request: &RecordedRequest) -> ClientResult<String> {
+        let mut tape = self.tape.lock().unwrap();
+        let Tape { cassette, played } = &mut *tape;
+        let found = cassette
+            .interactions
+            .iter()
+            .zip(played.iter_mut())
+            .find(|(interaction, played)| !**played && interaction.request.matches(request));
+
+        match found {
+            Some((interaction, played)) => {
+                *played = true;
+                if let Some(hook) = &self.response_hook {
+                    hook.run(&IncomingResponse {
+                        url: request.url.clone(),
+                        status: interaction.response.status(),
+                        headers: Headers::new(),
+                    });
+                }
+                interaction.response.clone().into_result()
+            }
+            None => Err(ClientError::Replay(format!(
+                "no recorded interaction for {} {}",
+                request.method, request.url
+            ))),
+        }
+    }
+
+    /// Saves the request and its outcome, and updates the cassette on disk.
+    fn store(&self, request: RecordedRequest, result: &ClientResult<String>) -> ClientResult<()> {
+        let response = match RecordedResponse::from_result(result) {
+            Some(response) => response,
+            None => return Ok(()),
+        };
+
+        let mut tape = self.tape.lock().unwrap();
+        tape.cassette
+            .interactions
+            .push(Interaction { request, response });
+        tape.played.push(true);
+        match &self.path {
+            Some(path) => tape.cassette.save(path),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Builds the recorded version of a request, with its credentials redacted.
+fn recorded_request(
+    method: &str,
+    url: &str,
+    headers: Option<&Headers>,
+    query: Option<&Query>,
+    body: Value,
+) -> RecordedRequest {
+    let headers = headers
+        .into_iter()
+        .flatten()
+        .map(|(key, val)| {
+            if is_sensitive_header(key) {
+                (key.clone(), REDACTED.to_string())
+            } else {
+                (key.clone(), val.clone())
+            }
+        })
+        .collect();
+    let query = query
+        .into_iter()
+        .flatten()
+        .map(|(key, val)| (key.clone(), val.clone()))
+        .collect();
+
+    RecordedRequest {
+        method: method.to_string(),
+        url: url.to_string(),
+        headers,
+        query,
+        body,
+    }
+}
+
+/// Forms are saved as JSON objects, with their sensitive fields redacted.
+fn form_body(payload: &Form) -> Value {
+    let fields = payload
+        .iter()
+        .map(|(key, val)| {
+            if is_sensitive_field(key) {
+                (key.to_owned(), Value::from(REDACTED))
+            } else {
+                (key.to_owned(), Value::from(val))
+            }
+        })
+        .collect();
+
+    Value::Object(fields)
+}
+
+/// Removes the tokens from a response body, which is only modified in case
+/// it's a JSON object containing any of them.
+fn redact_body(body: &str) -> String {
+    let mut json = match serde_json::from_str::<Value>(body) {
+        Ok(json @ Value::Object(_)) => json,
+        _ => return body.to_string(),
+    };
+
+    let object = json.as_object_mut().unwrap();
+    let mut redacted = false;
+    for field in SENSITIVE_FIELDS {
+        if let Some(val) = object.get_mut(*field) {
+            *val = Value::from(REDACTED);
+            redacted = true;
+        }
+    }
+
+    if redacted {
+        json.to_string()
+    } else {
+        body.to_string()
+    }
+}
+
+#[maybe_async]
+impl<C: BaseHTTPClient + Send + Sync> BaseHTTPClient for ReplayClient<C> {
+    type Native = C::Native;
+
+    /// The native client is wrapped without recording anything.
+    #[inline]
+    fn from_native(native: C::Native) -> Self {
+        ReplayClient {
+            inner: C::from_native(native),
+            ..Default::default()
+        }
+    }
+
+    /// The same as `from_native`, without recording anything.
+    #[inline]
+    fn from_config(config: &ConnectionConfig) -> ClientResult<Self> {
+        Ok(ReplayClient {
+            inner: C::from_config(config)?,
+            ..Default::default()
+        })
+    }
+
+    #[inline]
+    async fn get(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<String> {
+        let request = recorded_request("GET", url, headers, Some(payload), Value::Null);
+        match self.mode {
+            ReplayMode::Passthrough => self.inner.get(url, headers, payload).await,
+            ReplayMode::Replay => self.play(&request),
+            ReplayMode::Record => {
+                let result = self.inner.get(url, headers, payload).await;
+                self.store(request, &result)?;
+                result
+            }
+        }
+    }
+
+    #[inline]
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        // Only the passthrough mode can keep streaming the response, since
+        // recording and replaying need the body as a `String`.
+        if self.mode == ReplayMode::Passthrough {
+            return self.inner.get_json(url, headers, payload).await;
+        }
+
+        let body = self.get(url, headers, payload).await?;
+        serde_json::from_str(&body).map_err(Into::into)
+    }
+
+    #[inline]
+    async fn post(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        let request = recorded_request("POST", url, headers, None, payload.clone());
+        match self.mode {
+            ReplayMode::Passthrough => self.inner.post(url, headers, payload).await,
+            ReplayMode::Replay => self.play(&request),
+            ReplayMode::Record => {
+                let result = self.inner.post(url, headers, payload).await;
+                self.store(request, &result)?;
+                result
+            }
+        }
+    }
+
+    #[inline]
+    async fn post_form(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+    ) -> ClientResult<String> {
+        let request = recorded_request("POST", url, headers, None, form_body(payload));
+        match self.mode {
+            ReplayMode::Passthrough => self.inner.post_form(url, headers, payload).await,
+            ReplayMode::Replay => self.play(&request),
+            ReplayMode::Record => {
+                let result = self.inner.post_form(url, headers, payload).await;
+                self.store(request, &result)?;
+                result
+            }
+        }
+    }
+
+    #[inline]
+    async fn post_form_timeout(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+        timeout: Duration,
+    ) -> ClientResult<String> {
+        let request = recorded_request("POST", url, headers, None, form_body(payload));
+        match self.mode {
+            ReplayMode::Passthrough => {
+                self.inner
+                    .post_form_timeout(url, headers, payload, timeout)
+                    .await
+            }
+            ReplayMode::Replay => self.play(&request),
+            ReplayMode::Record => {
+                let result = self
+                    .inner
+                    .post_form_timeout(url, headers, payload, timeout)
+                    .await;
+                self.store(request, &result)?;
+                result
+            }
+        }
+    }
+
+    #[inline]
+    async fn put(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        let request = recorded_request("PUT", url, headers, None, payload.clone());
+        match self.mode {
+            ReplayMode::Passthrough => self.inner.put(url, headers, payload).await,
+            ReplayMode::Replay => self.play(&request),
+            ReplayMode::Record => {
+                let result = self.inner.put(url, headers, payload).await;
+                self.store(request, &result)?;
+                result
+            }
+        }
+    }
+
+    #[inline]
+    async fn delete(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> ClientResult<String> {
+        let request = recorded_request("DELETE", url, headers, None, payload.clone());
+        match self.mode {
+            ReplayMode::Passthrough => self.inner.delete(url, headers, payload).await,
+            ReplayMode::Replay => self.play(&request),
+            ReplayMode::Record => {
+                let result = self.inner.delete(url, headers, payload).await;
+                self.store(request, &result)?;
+                result
+            }
+        }
+    }
+
+    /// The `ETag`s aren't recorded, so the object is always requested when
+    /// recording, and returned when replaying.
+    async fn get_conditional(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<String>> {
+        match self.mode {
+            ReplayMode::Passthrough => {
+                self.inner
+                    .get_conditional(url, headers, payload, etag)
+                    .await
+            }
+            _ => {
+                let body = self.get(url, headers, payload).await?;
+                Ok(Conditional::Fetched(body, None))
+            }
+        }
+    }
+
+    #[inline]
+    async fn get_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        limit: usize,
+    ) -> ClientResult<Vec<u8>> {
+        // The bodies are saved in cassettes encoded in base64.
+        let request = recorded_request("GET", url, headers, None, Value::Null);
+        let encoded = match self.mode {
+            ReplayMode::Passthrough => return self.inner.get_bytes(url, headers, limit).await,
+            ReplayMode::Replay => self.play(&request)?,
+            ReplayMode::Record => {
+                let result = self
+                    .inner
+                    .get_bytes(url, headers, limit)
+                    .await
+                    .map(base64::encode);
+                self.store(request, &result)?;
+                result?
+            }
+        };
+
+        let body = base64::decode(encoded)
+            .map_err(|err| ClientError::Replay(format!("invalid recorded body: {}", err)))?;
+        if body.len() > limit {
+            return Err(ClientError::BodyTooLarge(limit));
+        }
+        Ok(body)
+    }
+
+    #[inline]
+    fn body_metrics(&self) -> BodyMetrics {
+        self.inner.body_metrics()
+    }
+
+    /// The limits are only enforced on the requests that are sent, and not
+    /// on the replayed ones.
+    fn with_body_limits(self, limits: BodyLimits) -> Self {
+        ReplayClient {
+            inner: self.inner.with_body_limits(limits),
+            ..self
+        }
+    }
+
+    fn with_response_hook(self, hook: ResponseHook) -> Self {
+        ReplayClient {
+            inner: self.inner.with_response_hook(hook.clone()),
+            response_hook: Some(hook),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_request() {
+        let mut headers = Headers::new();
+        headers.insert("authorization".to_owned(), "Bearer secret".to_owned());
+        let mut form = Form::new();
+        form.insert("grant_type".to_owned(), "refresh_token".to_owned());
+        form.insert("refresh_token".to_owned(), "secret".to_owned());
+
+        let request = recorded_request("POST", "token", Some(&headers), None, form_body(&form));
+        assert_eq!(request.headers["authorization"], REDACTED);
+        assert_eq!(request.body["grant_type"], "refresh_token");
+        assert_eq!(request.body["refresh_token"], REDACTED);
+    }
+
+    #[test]
+    fn test_redact_body() {
+        let body = r#"{"access_token":"secret","token_type":"Bearer"}"#;
+        let redacted: Value = serde_json::from_str(&redact_body(body)).unwrap();
+        assert_eq!(redacted["access_token"], REDACTED);
+        assert_eq!(redacted["token_type"], "Bearer");
+
+        let body = r#"{"id":"4iV5W9uYEdYUVa79Axb7Rh"}"#;
+        assert_eq!(redact_body(body), body);
+        assert_eq!(redact_body("not json"), "not json");
+    }
+
+    #[test]
+    fn test_play_in_order() {
+        let request = recorded_request("GET", "me", None, None, Value::Null);
+        let cassette = Cassette {
+            interactions: vec![
+                Interaction {
+                    request: request.clone(),
+                    response: RecordedResponse::Body("first".to_owned()),
+                },
+                Interaction {
+                    request: request.clone(),
+                    response: RecordedResponse::Unauthorized,
+                },
+            ],
+        };
+
+        let client = ReplayClient::<HTTPBackend>::from_cassette(cassette);
+        assert_eq!(client.remaining(), 2);
+        assert_eq!(client.play(&request).unwrap(), "first");
+        assert!(matches!(
+            client.play(&request),
+            Err(ClientError::Unauthorized)
+        ));
+        assert!(matches!(client.play(&request), Err(ClientError::Replay(_))));
+        assert_eq!(client.remaining(), 0);
+    }
+}
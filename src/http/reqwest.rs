@@ -2,12 +2,20 @@
 //! default.
 
 use maybe_async::async_impl;
-use reqwest::{Method, RequestBuilder, StatusCode};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use std::convert::TryInto;
+use std::sync::Arc;
+use std::time::Duration;
 
-use super::{BaseHTTPClient, Form, Headers, Query};
+use super::hook::{IncomingResponse, ResponseHook};
+use super::redact::log_request;
+use super::{
+    decode_body, headers, BaseHTTPClient, BodyLimits, BodyMetrics, BodyMetricsRecorder,
+    BodyTracker, Conditional, ConnectionConfig, Form, Headers, Query,
+};
 use crate::client::{APIError, ClientError, ClientResult};
 
 impl ClientError {
@@ -21,11 +29,14 @@ impl ClientError {
                     .and_then(|header| header.to_str().ok())
                     .and_then(|duration| duration.parse().ok()),
             ),
-            status @ StatusCode::FORBIDDEN | status @ StatusCode::NOT_FOUND => response
-                .json::<APIError>()
-                .await
-                .map(Into::into)
-                .unwrap_or_else(|_| status.into()),
+            status @ StatusCode::FORBIDDEN | status @ StatusCode::NOT_FOUND => {
+                let url = response.url().to_string();
+                response
+                    .json::<APIError>()
+                    .await
+                    .map(|err| err.with_endpoint(url).into())
+                    .unwrap_or_else(|_| status.into())
+            }
             status => status.into(),
         }
     }
@@ -33,7 +44,11 @@ impl ClientError {
 
 impl From<reqwest::Error> for ClientError {
     fn from(err: reqwest::Error) -> Self {
-        Self::Request(err.to_string())
+        if err.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::request(err.url().map(ToString::to_string), err)
+        }
     }
 }
 
@@ -46,13 +61,80 @@ impl From<reqwest::StatusCode> for ClientError {
     }
 }
 
+/// The status and headers of the response, for the response hook.
+fn incoming_response(response: &Response) -> IncomingResponse {
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, val)| Some((name.as_str().to_owned(), val.to_str().ok()?.to_owned())))
+        .collect();
+    IncomingResponse {
+        url: response.url().to_string(),
+        status: response.status().as_u16(),
+        headers,
+    }
+}
+
+/// Converts the headers into a `reqwest::HeaderMap`, which fails if a name or
+/// value is invalid instead of panicking.
+fn header_map(headers: &Headers) -> ClientResult<HeaderMap> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (name, val) in headers {
+        let invalid = |reason| ClientError::InvalidHeader {
+            name: name.clone(),
+            reason,
+        };
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| invalid("the name isn't a token"))?;
+        let val = HeaderValue::from_str(val)
+            .map_err(|_| invalid("the value contains control characters"))?;
+        map.insert(name, val);
+    }
+
+    Ok(map)
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct ReqwestClient {
     /// reqwest needs an instance of its client to perform requests.
     client: reqwest::Client,
+    /// Shared between clones so that the statistics cover every request.
+    metrics: Arc<BodyMetricsRecorder>,
+    limits: BodyLimits,
+    response_hook: Option<ResponseHook>,
 }
 
 impl ReqwestClient {
+    /// Uses a custom reqwest client to perform the requests, for example one
+    /// with a specific TLS backend, proxy or DNS resolver.
+    pub fn with_client(client: reqwest::Client) -> Self {
+        ReqwestClient {
+            client,
+            metrics: Arc::default(),
+            limits: BodyLimits::default(),
+            response_hook: None,
+        }
+    }
+
+    /// Reads the body of the response chunk by chunk, so that the limits are
+    /// enforced before it's all in memory. It can't be longer than `limit`
+    /// either.
+    async fn read_body(
+        &self,
+        mut response: Response,
+        limit: Option<usize>,
+    ) -> ClientResult<Vec<u8>> {
+        let url = response.url().to_string();
+        let mut tracker = BodyTracker::new(&url, &self.limits, response.content_length(), limit)?;
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            tracker.advance(chunk.len())?;
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
+
     async fn request<D>(
         &self,
         method: Method,
@@ -60,6 +142,45 @@ impl ReqwestClient {
         headers: Option<&Headers>,
         add_data: D,
     ) -> ClientResult<String>
+    where
+        D: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        let response = self.send(method, url, headers, add_data).await?;
+        let text = decode_body(self.read_body(response, None).await?);
+        self.metrics.record_buffered(text.len());
+
+        Ok(text)
+    }
+
+    /// The same as `request`, but the body is deserialized from the raw bytes
+    /// instead of being decoded into a `String` first.
+    async fn request_json<T, D>(
+        &self,
+        method: Method,
+        url: &str,
+        headers: Option<&Headers>,
+        add_data: D,
+    ) -> ClientResult<T>
+    where
+        T: DeserializeOwned,
+        D: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        let response = self.send(method, url, headers, add_data).await?;
+        // The async body can't be handed to `serde_json` as a reader, so it's
+        // still buffered, just without being decoded into a `String`.
+        let body = self.read_body(response, None).await?;
+        self.metrics.record_buffered(body.len());
+
+        serde_json::from_slice(&body).map_err(Into::into)
+    }
+
+    async fn send<D>(
+        &self,
+        method: Method,
+        url: &str,
+        headers: Option<&Headers>,
+        add_data: D,
+    ) -> ClientResult<Response>
     where
         D: Fn(RequestBuilder) -> RequestBuilder,
     {
@@ -67,26 +188,22 @@ impl ReqwestClient {
 
         // Setting the headers, if any
         if let Some(headers) = headers {
-            // The headers need to be converted into a `reqwest::HeaderMap`,
-            // which won't fail as long as its contents are ASCII. This is an
-            // internal function, so the condition cannot be broken by the user
-            // and will always be true.
-            //
             // The content-type header will be set automatically.
-            let headers = headers.try_into().unwrap();
-
-            request = request.headers(headers);
+            request = request.headers(header_map(headers)?);
         }
 
         // Configuring the request for the specific type (get/post/put/delete)
         request = add_data(request);
 
         // Finally performing the request and handling the response
-        log::info!("Making request {:?}", request);
+        log_request(method.as_str(), url, headers);
         let response = request.send().await?;
+        if let Some(hook) = &self.response_hook {
+            hook.run(&incoming_response(&response));
+        }
 
         if response.status().is_success() {
-            response.text().await.map_err(Into::into)
+            Ok(response)
         } else {
             Err(ClientError::from_response(response).await)
         }
@@ -95,6 +212,31 @@ impl ReqwestClient {
 
 #[async_impl]
 impl BaseHTTPClient for ReqwestClient {
+    type Native = reqwest::Client;
+
+    #[inline]
+    fn from_native(native: reqwest::Client) -> Self {
+        Self::with_client(native)
+    }
+
+    fn from_config(config: &ConnectionConfig) -> ClientResult<Self> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = config.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = config.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(interval) = config.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        if !config.http2 {
+            builder = builder.http1_only();
+        }
+
+        Ok(Self::with_client(builder.build()?))
+    }
+
     #[inline]
     async fn get(
         &self,
@@ -106,6 +248,17 @@ impl BaseHTTPClient for ReqwestClient {
             .await
     }
 
+    #[inline]
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        self.request_json(Method::GET, url, headers, |req| req.query(payload))
+            .await
+    }
+
     #[inline]
     async fn post(
         &self,
@@ -128,6 +281,20 @@ impl BaseHTTPClient for ReqwestClient {
             .await
     }
 
+    #[inline]
+    async fn post_form_timeout(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+        timeout: Duration,
+    ) -> ClientResult<String> {
+        self.request(Method::POST, url, headers, |req| {
+            req.form(payload).timeout(timeout)
+        })
+        .await
+    }
+
     #[inline]
     async fn put(
         &self,
@@ -149,4 +316,65 @@ impl BaseHTTPClient for ReqwestClient {
         self.request(Method::DELETE, url, headers, |req| req.json(payload))
             .await
     }
+
+    async fn get_conditional(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<String>> {
+        let mut headers = headers.cloned().unwrap_or_default();
+        if let Some(etag) = etag {
+            headers.insert(headers::IF_NONE_MATCH.to_owned(), etag.to_owned());
+        }
+
+        let send = self.send(Method::GET, url, Some(&headers), |req| req.query(payload));
+        let response = match send.await {
+            Ok(response) => response,
+            Err(ClientError::StatusCode(304, _)) => return Ok(Conditional::NotModified),
+            Err(err) => return Err(err),
+        };
+        let etag = response
+            .headers()
+            .get(headers::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(ToOwned::to_owned);
+        let text = decode_body(self.read_body(response, None).await?);
+        self.metrics.record_buffered(text.len());
+
+        Ok(Conditional::Fetched(text, etag))
+    }
+
+    #[inline]
+    async fn get_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        limit: usize,
+    ) -> ClientResult<Vec<u8>> {
+        let response = self.send(Method::GET, url, headers, |req| req).await?;
+        let body = self.read_body(response, Some(limit)).await?;
+        self.metrics.record_buffered(body.len());
+
+        Ok(body)
+    }
+
+    #[inline]
+    fn body_metrics(&self) -> BodyMetrics {
+        self.metrics.snapshot()
+    }
+
+    #[inline]
+    fn with_body_limits(self, limits: BodyLimits) -> Self {
+        ReqwestClient { limits, ..self }
+    }
+
+    #[inline]
+    fn with_response_hook(self, hook: ResponseHook) -> Self {
+        ReqwestClient {
+            response_hook: Some(hook),
+            ..self
+        }
+    }
 }
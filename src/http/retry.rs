@@ -0,0 +1,393 @@
+//! Retries of the requests that failed because of the rate limit or an error
+//! in the server, bounded by an optional deadline.
+
+use super::hook::{RateLimitHook, RateLimitInfo};
+use super::scheduler::SchedulerSession;
+use crate::client::{ClientError, ClientResult};
+
+use chrono::Utc;
+use maybe_async::maybe_async;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "__async")]
+use futures::future::{self, Either};
+#[cfg(feature = "__async")]
+use std::future::Future;
+
+/// How the requests that fail with `429 Too Many Requests` or a `5xx` server
+/// error are retried. Retries are disabled by default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times each request is retried at most.
+    pub max_retries: u32,
+    /// The time to wait before the first retry when Spotify doesn't say how
+    /// long to wait with `Retry-After`, doubled for each of the next ones.
+    pub backoff: Duration,
+    /// The longest time to wait before a retry. Requests that would have to
+    /// wait longer fail right away instead.
+    pub max_wait: Duration,
+    /// Whether only the idempotent requests are retried: `GET`, `PUT` and
+    /// `DELETE`. A `POST`, like adding an item to the queue, may have been
+    /// applied even if it failed, so retrying it could repeat its effect.
+    /// Enabled by default, see [`RetryPolicy::retry_post`].
+    pub idempotent_only: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            backoff: Duration::from_secs(1),
+            max_wait: Duration::from_secs(60),
+            idempotent_only: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Retries each request up to `max_retries` times, with the default
+    /// backoff.
+    pub fn new(max_retries: u32) -> Self {
+        RetryPolicy {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// The same policy, but only retrying the idempotent requests, so that
+    /// the ones made with `POST` are never repeated. This is the default.
+    pub fn idempotent_only(self) -> Self {
+        RetryPolicy {
+            idempotent_only: true,
+            ..self
+        }
+    }
+
+    /// The same policy, but also retrying the requests made with `POST`,
+    /// for the apps that prefer repeating their effect, like queueing the
+    /// same item twice, over failing.
+    pub fn retry_post(self) -> Self {
+        RetryPolicy {
+            idempotent_only: false,
+            ..self
+        }
+    }
+
+    /// The policy for the requests made with `method`.
+    pub(crate) fn for_method(&self, method: &str) -> RetryPolicy {
+        let idempotent = matches!(method, "GET" | "PUT" | "DELETE");
+        if self.idempotent_only && !idempotent {
+            RetryPolicy {
+                max_retries: 0,
+                ..self.clone()
+            }
+        } else {
+            self.clone()
+        }
+    }
+
+    /// The time to wait before retrying a request that failed with `err`
+    /// after `retries` retries, or `None` if it can't be retried.
+    pub(crate) fn wait(&self, retries: u32, err: &ClientError) -> Option<Duration> {
+        if retries >= self.max_retries {
+            return None;
+        }
+
+        let wait = match err {
+            ClientError::RateLimited(Some(secs)) => Duration::from_secs(*secs as u64),
+            err if matches!(err.status_code(), Some(429) | Some(500..=599)) => {
+                self.backoff * 2u32.saturating_pow(retries)
+            }
+            _ => return None,
+        };
+        Some(wait).filter(|wait| *wait <= self.max_wait)
+    }
+}
+
+/// The point in time by which a request must be finished, including its
+/// retries and the waits between them. Requests that would have to wait past
+/// it fail with their last error, and the ones still running once it's
+/// reached fail with [`ClientError::Timeout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    /// The deadline at the given instant.
+    pub fn at(instant: Instant) -> Self {
+        Deadline(instant)
+    }
+
+    /// The deadline once `budget` has passed from now.
+    pub fn after(budget: Duration) -> Self {
+        Deadline(Instant::now() + budget)
+    }
+
+    /// The instant of the deadline.
+    pub fn instant(&self) -> Instant {
+        self.0
+    }
+
+    /// The time left until the deadline, which is zero once it's reached.
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the deadline has been reached.
+    pub fn is_expired(&self) -> bool {
+        self.remaining() == Duration::from_secs(0)
+    }
+}
+
+impl From<Duration> for Deadline {
+    fn from(budget: Duration) -> Self {
+        Deadline::after(budget)
+    }
+}
+
+impl From<Instant> for Deadline {
+    fn from(instant: Instant) -> Self {
+        Deadline::at(instant)
+    }
+}
+
+/// Waits before the retries of the requests, bounds their attempts by the
+/// deadline in async mode, and spaces out the requests of the endpoints and
+/// utilities that poll or wait, so that they work with any runtime.
+///
+/// The default one depends on the client feature: [`TokioSleeper`] with
+/// `client-reqwest` and [`ThreadSleeper`] with `client-ureq`. Custom async
+/// HTTP clients that run under another runtime, like async-std, can
+/// implement it with that runtime's timer and configure it in
+/// [`Spotify::sleeper`](crate::client::Spotify::sleeper).
+#[maybe_async]
+pub trait Sleeper: fmt::Debug + Send + Sync {
+    /// Waits for the given duration.
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Waits with the timer of tokio, the runtime already required by reqwest.
+#[cfg(feature = "client-reqwest")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokioSleeper;
+
+#[cfg(feature = "client-reqwest")]
+#[maybe_async]
+impl Sleeper for TokioSleeper {
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+}
+
+/// Waits by blocking the current thread.
+#[cfg(feature = "client-ureq")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ThreadSleeper;
+
+#[cfg(feature = "client-ureq")]
+#[maybe_async]
+impl Sleeper for ThreadSleeper {
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// The sleeper of the client feature, for [`Spotify::sleeper`
+/// ](crate::client::Spotify::sleeper).
+#[cfg(feature = "client-reqwest")]
+pub(crate) fn default_sleeper() -> Arc<dyn Sleeper> {
+    Arc::new(TokioSleeper)
+}
+
+/// The sleeper of the client feature, for [`Spotify::sleeper`
+/// ](crate::client::Spotify::sleeper).
+#[cfg(feature = "client-ureq")]
+pub(crate) fn default_sleeper() -> Arc<dyn Sleeper> {
+    Arc::new(ThreadSleeper)
+}
+
+/// Whether there's time to wait before retrying.
+fn fits(wait: Duration, deadline: Option<Deadline>) -> bool {
+    match deadline {
+        Some(deadline) => wait < deadline.remaining(),
+        None => true,
+    }
+}
+
+/// Tells the hook about the error if it's because of the rate limit.
+fn notify_rate_limit(
+    hook: Option<&RateLimitHook>,
+    url: &str,
+    err: &ClientError,
+    wait: Option<Duration>,
+) {
+    if let (Some(hook), ClientError::RateLimited(after)) = (hook, err) {
+        hook.run(&RateLimitInfo {
+            retry_after: after.map(|secs| Duration::from_secs(secs as u64)),
+            endpoint: url.split('?').next().unwrap_or(url).to_owned(),
+            occurred_at: Utc::now(),
+            retried: wait.is_some(),
+        });
+    }
+}
+
+/// Holds the requests of the other sessions of the scheduler back for as long
+/// as Spotify asked in case the request was rate limited.
+fn pause_scheduler(scheduler: Option<&SchedulerSession>, err: &ClientError) {
+    if let (Some(scheduler), ClientError::RateLimited(Some(secs))) = (scheduler, err) {
+        scheduler.pause(Duration::from_secs(*secs as u64));
+    }
+}
+
+/// Makes the request to `url` with `request`, retrying it as specified by
+/// `policy` after waiting with `sleeper`, and telling `hook` about the ones
+/// limited by the rate limit. Each attempt waits for its turn in the
+/// `scheduler` first, and is cancelled once the deadline is reached.
+#[cfg(feature = "__async")]
+pub(crate) async fn retrying<T, F, Fut>(
+    policy: &RetryPolicy,
+    sleeper: &dyn Sleeper,
+    hook: Option<&RateLimitHook>,
+    scheduler: Option<&SchedulerSession>,
+    url: &str,
+    deadline: Option<Deadline>,
+    request: F,
+) -> ClientResult<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ClientResult<T>>,
+{
+    let attempt = || async {
+        if let Some(scheduler) = scheduler {
+            scheduler.acquire(sleeper).await;
+        }
+        request().await
+    };
+    let mut retries = 0;
+    loop {
+        // The result isn't kept in a variable so that `T` doesn't have to be
+        // `Send` for the future to be.
+        let err = match deadline {
+            Some(deadline) if deadline.is_expired() => ClientError::Timeout,
+            Some(deadline) => {
+                let attempt = attempt();
+                futures::pin_mut!(attempt);
+                match future::select(attempt, sleeper.sleep(deadline.remaining())).await {
+                    Either::Left((Ok(val), _)) => return Ok(val),
+                    Either::Left((Err(err), _)) => err,
+                    Either::Right(_) => ClientError::Timeout,
+                }
+            }
+            None => match attempt().await {
+                Ok(val) => return Ok(val),
+                Err(err) => err,
+            },
+        };
+        pause_scheduler(scheduler, &err);
+        let wait = policy
+            .wait(retries, &err)
+            .filter(|wait| fits(*wait, deadline));
+        notify_rate_limit(hook, url, &err, wait);
+        match wait {
+            Some(wait) => sleeper.sleep(wait).await,
+            None => return Err(err),
+        }
+        retries += 1;
+    }
+}
+
+/// Makes the request to `url` with `request`, retrying it as specified by
+/// `policy` after waiting with `sleeper`, and telling `hook` about the ones
+/// limited by the rate limit. Each attempt waits for its turn in the
+/// `scheduler` first. Since blocking requests can't be cancelled, the
+/// deadline is only checked before each attempt is sent.
+#[cfg(feature = "__sync")]
+pub(crate) fn retrying<T, F>(
+    policy: &RetryPolicy,
+    sleeper: &dyn Sleeper,
+    hook: Option<&RateLimitHook>,
+    scheduler: Option<&SchedulerSession>,
+    url: &str,
+    deadline: Option<Deadline>,
+    request: F,
+) -> ClientResult<T>
+where
+    F: Fn() -> ClientResult<T>,
+{
+    let mut retries = 0;
+    loop {
+        if let Some(scheduler) = scheduler {
+            scheduler.acquire(sleeper);
+        }
+        let result = match deadline {
+            Some(deadline) if deadline.is_expired() => Err(ClientError::Timeout),
+            _ => request(),
+        };
+
+        let err = match result {
+            Ok(val) => return Ok(val),
+            Err(err) => err,
+        };
+        pause_scheduler(scheduler, &err);
+        let wait = policy
+            .wait(retries, &err)
+            .filter(|wait| fits(*wait, deadline));
+        notify_rate_limit(hook, url, &err, wait);
+        match wait {
+            Some(wait) => sleeper.sleep(wait),
+            None => return Err(err),
+        }
+        retries += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait() {
+        let policy = RetryPolicy::new(3);
+        let status = ClientError::StatusCode(503, "Service Unavailable".to_owned());
+        assert_eq!(policy.wait(0, &status), Some(Duration::from_secs(1)));
+        assert_eq!(policy.wait(2, &status), Some(Duration::from_secs(4)));
+        assert_eq!(policy.wait(3, &status), None);
+
+        let limited = ClientError::RateLimited(Some(5));
+        assert_eq!(policy.wait(0, &limited), Some(Duration::from_secs(5)));
+        assert_eq!(policy.wait(0, &ClientError::RateLimited(Some(3600))), None);
+        assert_eq!(policy.wait(0, &ClientError::Unauthorized), None);
+        assert_eq!(RetryPolicy::default().wait(0, &limited), None);
+    }
+
+    #[test]
+    fn test_idempotent_only() {
+        let status = ClientError::StatusCode(503, "Service Unavailable".to_owned());
+        let policy = RetryPolicy::new(3);
+        assert!(policy.for_method("GET").wait(0, &status).is_some());
+        assert!(policy.for_method("PUT").wait(0, &status).is_some());
+        assert!(policy.for_method("DELETE").wait(0, &status).is_some());
+        assert_eq!(policy.for_method("POST").wait(0, &status), None);
+
+        let policy = policy.retry_post();
+        assert_eq!(policy.max_retries, 3);
+        assert!(policy.for_method("POST").wait(0, &status).is_some());
+        assert_eq!(
+            policy.idempotent_only().for_method("POST").wait(0, &status),
+            None
+        );
+    }
+
+    #[test]
+    fn test_deadline() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert!(!deadline.is_expired());
+        assert!(deadline.remaining() > Duration::from_secs(59));
+        assert!(Deadline::at(Instant::now()).is_expired());
+        assert!(fits(Duration::from_secs(1), Some(deadline)));
+        assert!(!fits(Duration::from_secs(61), Some(deadline)));
+        assert!(fits(Duration::from_secs(61), None));
+    }
+}
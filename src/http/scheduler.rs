@@ -0,0 +1,256 @@
+//! Scheduling of the requests to the API of multiple clients that share the
+//! same app credentials, and thus the same rate limit, so that the requests
+//! of each client are interleaved fairly and a heavy one doesn't starve the
+//! rest.
+
+use super::retry::Sleeper;
+
+use maybe_async::maybe_async;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The requests waiting for their turn.
+struct Queue {
+    /// When the next request may be sent.
+    next_free: Instant,
+    /// The sessions with requests waiting, in the order of their turns, each
+    /// one with its requests in the order they were made.
+    sessions: VecDeque<(String, VecDeque<u64>)>,
+    /// The number of the next request.
+    next_ticket: u64,
+}
+
+impl Queue {
+    fn enqueue(&mut self, session: &str) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket += 1;
+        match self.sessions.iter_mut().find(|(name, _)| name == session) {
+            Some((_, tickets)) => tickets.push_back(ticket),
+            None => self
+                .sessions
+                .push_back((session.to_owned(), vec![ticket].into())),
+        }
+        ticket
+    }
+
+    /// Sends the request if it's its turn and the rate limit allows it, or
+    /// returns how long to wait before trying again.
+    fn dispatch(&mut self, ticket: u64, interval: Duration, now: Instant) -> Option<Duration> {
+        let wait = self.next_free.saturating_duration_since(now);
+        let is_next = matches!(
+            self.sessions.front(),
+            Some((_, tickets)) if tickets.front() == Some(&ticket)
+        );
+        if !is_next {
+            return Some(wait.max(interval));
+        }
+        if wait > Duration::from_secs(0) {
+            return Some(wait);
+        }
+
+        // The session goes back to the end of the line if it has more
+        // requests waiting.
+        let (session, mut tickets) = self.sessions.pop_front().unwrap();
+        tickets.pop_front();
+        if !tickets.is_empty() {
+            self.sessions.push_back((session, tickets));
+        }
+        self.next_free = now + interval;
+        None
+    }
+
+    fn cancel(&mut self, ticket: u64) {
+        for (_, tickets) in self.sessions.iter_mut() {
+            tickets.retain(|queued| *queued != ticket);
+        }
+        self.sessions.retain(|(_, tickets)| !tickets.is_empty());
+    }
+}
+
+/// Sends the requests to the API of multiple clients, as many per period of
+/// time as the global rate limit allows, taking turns between the sessions
+/// they belong to. It's meant for the services where many users share the
+/// same app, so that syncing the library of one of them doesn't delay the
+/// requests of the rest until it's finished.
+///
+/// Each client is configured in [`Spotify::scheduler`
+/// ](crate::client::Spotify::scheduler) with the session of its user, and
+/// the clones of the scheduler share the same queue:
+///
+/// ```
+/// use rspotify::client::{Scheduler, SpotifyBuilder};
+/// use std::time::Duration;
+///
+/// let scheduler = Scheduler::new(10, Duration::from_secs(1));
+/// let alice = SpotifyBuilder::default()
+///     .scheduler(scheduler.session("alice"))
+///     .build()
+///     .unwrap();
+/// let bob = SpotifyBuilder::default()
+///     .scheduler(scheduler.session("bob"))
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// The requests are spaced evenly, so there are no bursts. When one of them
+/// is rate limited anyway and retried, the rest of the requests wait too.
+/// The token requests to the accounts service aren't scheduled.
+#[derive(Clone)]
+pub struct Scheduler {
+    queue: Arc<Mutex<Queue>>,
+    interval: Duration,
+}
+
+impl Scheduler {
+    /// A scheduler that sends up to `max_requests` requests every `period`.
+    ///
+    /// # Panics
+    ///
+    /// If `max_requests` is zero.
+    pub fn new(max_requests: u32, period: Duration) -> Self {
+        assert!(max_requests > 0, "the scheduler must allow some requests");
+        Scheduler {
+            queue: Arc::new(Mutex::new(Queue {
+                next_free: Instant::now(),
+                sessions: VecDeque::new(),
+                next_ticket: 0,
+            })),
+            interval: period / max_requests,
+        }
+    }
+
+    /// The session with the given name, usually the ID of its user, to
+    /// configure in the clients of that user.
+    pub fn session<S: Into<String>>(&self, name: S) -> SchedulerSession {
+        SchedulerSession {
+            scheduler: self.clone(),
+            name: name.into(),
+        }
+    }
+
+    /// The time between two requests.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// How many requests are waiting for their turn.
+    pub fn queued(&self) -> usize {
+        let queue = self.queue.lock().unwrap();
+        queue
+            .sessions
+            .iter()
+            .map(|(_, tickets)| tickets.len())
+            .sum()
+    }
+}
+
+impl fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("interval", &self.interval)
+            .field("queued", &self.queued())
+            .finish()
+    }
+}
+
+/// The session of a [`Scheduler`] that the requests of a client belong to.
+#[derive(Clone, Debug)]
+pub struct SchedulerSession {
+    scheduler: Scheduler,
+    name: String,
+}
+
+/// A request waiting in the queue, which leaves it when dropped, like when
+/// its future is cancelled.
+struct Ticket<'a> {
+    queue: &'a Mutex<Queue>,
+    number: u64,
+    dispatched: bool,
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if !self.dispatched {
+            self.queue.lock().unwrap().cancel(self.number);
+        }
+    }
+}
+
+impl SchedulerSession {
+    /// The name of the session.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The scheduler of the session.
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    /// Waits with `sleeper` until it's the turn of a new request of this
+    /// session.
+    #[maybe_async]
+    pub(crate) async fn acquire(&self, sleeper: &dyn Sleeper) {
+        let queue = &self.scheduler.queue;
+        let mut ticket = Ticket {
+            queue,
+            number: queue.lock().unwrap().enqueue(&self.name),
+            dispatched: false,
+        };
+        loop {
+            let wait = queue.lock().unwrap().dispatch(
+                ticket.number,
+                self.scheduler.interval,
+                Instant::now(),
+            );
+            match wait {
+                Some(wait) => sleeper.sleep(wait).await,
+                None => break,
+            }
+        }
+        ticket.dispatched = true;
+    }
+
+    /// Holds the requests of all the sessions back for `wait`, after one of
+    /// them was rate limited.
+    pub(crate) fn pause(&self, wait: Duration) {
+        let mut queue = self.scheduler.queue.lock().unwrap();
+        queue.next_free = queue.next_free.max(Instant::now() + wait);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_round_robin() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let mut queue = Queue {
+            next_free: start,
+            sessions: VecDeque::new(),
+            next_ticket: 0,
+        };
+        let heavy = (0..3).map(|_| queue.enqueue("heavy")).collect::<Vec<_>>();
+        let light = queue.enqueue("light");
+
+        // Only the first request of the heavy session can be sent, and then
+        // the light one takes its turn.
+        assert_eq!(queue.dispatch(light, interval, start), Some(interval));
+        assert_eq!(queue.dispatch(heavy[0], interval, start), None);
+        assert_eq!(queue.dispatch(heavy[1], interval, start), Some(interval));
+        assert_eq!(queue.dispatch(light, interval, start), Some(interval));
+        let later = start + interval;
+        assert_eq!(queue.dispatch(heavy[1], interval, later), Some(interval));
+        assert_eq!(queue.dispatch(light, interval, later), None);
+        assert_eq!(queue.dispatch(heavy[1], interval, later + interval), None);
+
+        // Cancelled requests leave their turn to the next one.
+        queue.cancel(heavy[2]);
+        assert!(queue.sessions.is_empty());
+    }
+}
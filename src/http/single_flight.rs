@@ -0,0 +1,152 @@
+//! Deduplication of identical requests made at the same time, so that only
+//! one of them reaches Spotify and the rest share its response.
+
+use super::{Headers, Query};
+
+#[cfg(feature = "__async")]
+use futures::channel::oneshot::{channel, Receiver, Sender};
+#[cfg(feature = "__sync")]
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The requests in flight, with the channels of the ones waiting for them.
+type Calls = Arc<Mutex<HashMap<String, Vec<Sender<String>>>>>;
+
+/// Keeps track of the requests in flight, shared between the clones of the
+/// client.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SingleFlight {
+    calls: Calls,
+}
+
+/// The role of a request after joining the ones in flight.
+pub(crate) enum Flight {
+    /// No identical request is in flight, so this one has to be made.
+    Leader(Leader),
+    /// An identical request is in flight, whose response will be received.
+    Follower(Receiver<String>),
+}
+
+/// The request that is actually made. Its waiters are released when it's
+/// dropped, so that they make their own requests if it failed.
+pub(crate) struct Leader {
+    calls: Calls,
+    key: String,
+    finished: bool,
+}
+
+impl SingleFlight {
+    pub(crate) fn join(&self, key: String) -> Flight {
+        let mut calls = self.calls.lock().unwrap();
+        match calls.get_mut(&key) {
+            Some(waiters) => {
+                let (sender, receiver) = channel();
+                waiters.push(sender);
+                Flight::Follower(receiver)
+            }
+            None => {
+                calls.insert(key.clone(), Vec::new());
+                Flight::Leader(Leader {
+                    calls: Arc::clone(&self.calls),
+                    key,
+                    finished: false,
+                })
+            }
+        }
+    }
+}
+
+impl Leader {
+    /// Sends the response body to the requests waiting for it.
+    pub(crate) fn finish(mut self, body: &str) {
+        self.finished = true;
+        for waiter in self.take_waiters() {
+            let _ = waiter.send(body.to_owned());
+        }
+    }
+
+    fn take_waiters(&self) -> Vec<Sender<String>> {
+        self.calls
+            .lock()
+            .unwrap()
+            .remove(&self.key)
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) {
+        if !self.finished {
+            // The waiters are dropped without a response.
+            self.take_waiters();
+        }
+    }
+}
+
+/// Waits for the response of the leader, or `None` if it failed.
+#[cfg(feature = "__async")]
+pub(crate) async fn wait(receiver: Receiver<String>) -> Option<String> {
+    receiver.await.ok()
+}
+
+/// Waits for the response of the leader, or `None` if it failed.
+#[cfg(feature = "__sync")]
+pub(crate) fn wait(receiver: Receiver<String>) -> Option<String> {
+    receiver.recv().ok()
+}
+
+/// Identifies a request by its URL, query and headers, so that requests made
+/// with different tokens aren't mixed.
+pub(crate) fn request_key(url: &str, headers: Option<&Headers>, query: &Query) -> String {
+    let mut query = query.iter().collect::<Vec<_>>();
+    query.sort();
+    let mut headers = headers
+        .map(|headers| headers.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    headers.sort();
+    format!("GET {} {:?} {:?}", url, query, headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "__async")]
+    fn receive(receiver: Receiver<String>) -> Option<String> {
+        futures::executor::block_on(wait(receiver))
+    }
+
+    #[cfg(feature = "__sync")]
+    fn receive(receiver: Receiver<String>) -> Option<String> {
+        wait(receiver)
+    }
+
+    #[test]
+    fn test_single_flight() {
+        let flights = SingleFlight::default();
+        let key = request_key("https://api.spotify.com/v1/me", None, &Query::new());
+
+        let leader = match flights.join(key.clone()) {
+            Flight::Leader(leader) => leader,
+            Flight::Follower(_) => panic!("no request was in flight"),
+        };
+        let follower = match flights.join(key.clone()) {
+            Flight::Follower(follower) => follower,
+            Flight::Leader(_) => panic!("the request was in flight"),
+        };
+        leader.finish("{}");
+        assert_eq!(receive(follower).as_deref(), Some("{}"));
+
+        // Once finished, the next request is made again, and its followers
+        // are released if it fails.
+        let leader = flights.join(key.clone());
+        let follower = match flights.join(key) {
+            Flight::Follower(follower) => follower,
+            Flight::Leader(_) => panic!("the request was in flight"),
+        };
+        drop(leader);
+        assert_eq!(receive(follower), None);
+    }
+}
@@ -1,11 +1,21 @@
 //! The client implementation for the ureq HTTP client, which is blocking.
 
-use super::{BaseHTTPClient, Form, Headers, Query};
+use super::hook::{IncomingResponse, ResponseHook};
+use super::redact::log_request;
+use super::{
+    decode_body, headers, BaseHTTPClient, BodyLimits, BodyMetrics, BodyMetricsRecorder,
+    BodyTracker, Conditional, ConnectionConfig, Form, Headers, Query,
+};
 use crate::client::{ClientError, ClientResult};
 
 use maybe_async::sync_impl;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use ureq::{Request, Response};
+use ureq::{Agent, AgentBuilder, Request, Response};
+
+use std::io::{self, BufReader, Read};
+use std::sync::Arc;
+use std::time::Duration;
 
 impl ClientError {
     pub fn from_response(r: ureq::Response) -> Self {
@@ -13,10 +23,113 @@ impl ClientError {
     }
 }
 
-#[derive(Default, Debug, Clone)]
-pub struct UreqClient {}
+/// Whether the transport error was caused by a timeout, which ureq reports as
+/// an IO error.
+fn is_timeout(err: &ureq::Transport) -> bool {
+    use std::error::Error;
+
+    let source = err
+        .source()
+        .and_then(|source| source.downcast_ref::<io::Error>())
+        .map(io::Error::kind);
+    err.kind() == ureq::ErrorKind::Io
+        && matches!(
+            source,
+            Some(io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock)
+        )
+}
+
+/// Wrapper over the reader of a response body that follows the bytes read
+/// through it, failing once it's longer than allowed.
+struct BodyReader<'a, R> {
+    inner: R,
+    tracker: BodyTracker<'a>,
+    /// The error that made the reader fail, which is lost in the `io::Error`.
+    err: Option<ClientError>,
+}
+
+impl<R: Read> Read for BodyReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if let Err(err) = self.tracker.advance(read) {
+            let io_err = io::Error::new(io::ErrorKind::InvalidData, err.to_string());
+            self.err = Some(err);
+            return Err(io_err);
+        }
+        Ok(read)
+    }
+}
+
+/// The status and headers of the response, for the response hook.
+fn incoming_response(response: &Response) -> IncomingResponse {
+    let headers = response
+        .headers_names()
+        .into_iter()
+        .filter_map(|name| {
+            let val = response.header(&name)?.to_owned();
+            Some((name.to_ascii_lowercase(), val))
+        })
+        .collect();
+    IncomingResponse {
+        url: response.get_url().to_owned(),
+        status: response.status(),
+        headers,
+    }
+}
+
+/// The `Content-Length` of the response, if any.
+fn content_length(response: &Response) -> Option<u64> {
+    response
+        .header("content-length")
+        .and_then(|len| len.parse().ok())
+}
+
+#[derive(Debug, Clone)]
+pub struct UreqClient {
+    /// The agent used to perform the requests, which holds the connection
+    /// pool and the TLS and DNS configuration.
+    agent: Agent,
+    /// Shared between clones so that the statistics cover every request.
+    metrics: Arc<BodyMetricsRecorder>,
+    limits: BodyLimits,
+    response_hook: Option<ResponseHook>,
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        Self::with_agent(Agent::new())
+    }
+}
 
 impl UreqClient {
+    /// Uses a custom ureq agent to perform the requests, for example one with
+    /// a specific TLS connector or DNS resolver.
+    pub fn with_agent(agent: Agent) -> Self {
+        UreqClient {
+            agent,
+            metrics: Arc::default(),
+            limits: BodyLimits::default(),
+            response_hook: None,
+        }
+    }
+
+    /// Reads the body of the response, which can't be longer than `limit`
+    /// either.
+    fn read_body(&self, response: Response, limit: Option<usize>) -> ClientResult<Vec<u8>> {
+        let url = response.get_url().to_owned();
+        let tracker = BodyTracker::new(&url, &self.limits, content_length(&response), limit)?;
+        let mut reader = BodyReader {
+            inner: response.into_reader(),
+            tracker,
+            err: None,
+        };
+        let mut body = Vec::new();
+        match reader.read_to_end(&mut body) {
+            Ok(_) => Ok(body),
+            Err(err) => Err(reader.err.take().unwrap_or_else(|| err.into())),
+        }
+    }
+
     /// The request handling in ureq is split in three parts:
     ///
     /// * The initial request (POST, GET, ...) is given as the `request`
@@ -27,10 +140,66 @@ impl UreqClient {
     ///   (JSON, a form...).
     fn request<D>(
         &self,
-        mut request: Request,
+        request: Request,
         headers: Option<&Headers>,
         send_request: D,
     ) -> ClientResult<String>
+    where
+        D: Fn(Request) -> Result<Response, ureq::Error>,
+    {
+        let response = self.send(request, headers, send_request)?;
+        let text = decode_body(self.read_body(response, None)?);
+        self.metrics.record_buffered(text.len());
+
+        Ok(text)
+    }
+
+    /// The same as `request`, but the body is deserialized straight from the
+    /// response reader, so that it's never buffered into a `String`.
+    fn request_json<T, D>(
+        &self,
+        request: Request,
+        headers: Option<&Headers>,
+        send_request: D,
+    ) -> ClientResult<T>
+    where
+        T: DeserializeOwned,
+        D: Fn(Request) -> Result<Response, ureq::Error>,
+    {
+        let response = self.send(request, headers, send_request)?;
+        let url = response.get_url().to_owned();
+        let tracker = BodyTracker::new(&url, &self.limits, content_length(&response), None)?;
+        let mut reader = BodyReader {
+            inner: BufReader::new(response.into_reader()),
+            tracker,
+            err: None,
+        };
+        let parsed = serde_json::from_reader(&mut reader);
+        self.metrics.record_streamed(reader.tracker.received);
+
+        match reader.err {
+            Some(err) => Err(err),
+            None => parsed.map_err(Into::into),
+        }
+    }
+
+    fn request_form(
+        &self,
+        request: Request,
+        headers: Option<&Headers>,
+        payload: &Form,
+    ) -> ClientResult<String> {
+        let sender = |req: Request| req.send_form(&payload.iter().collect::<Vec<_>>());
+
+        self.request(request, headers, sender)
+    }
+
+    fn send<D>(
+        &self,
+        mut request: Request,
+        headers: Option<&Headers>,
+        send_request: D,
+    ) -> ClientResult<Response>
     where
         D: Fn(Request) -> Result<Response, ureq::Error>,
     {
@@ -41,23 +210,50 @@ impl UreqClient {
             }
         }
 
-        log::info!("Making request {:?}", request);
-        match send_request(request) {
+        log_request(request.method(), request.url(), headers);
+        let result = send_request(request);
+        if let (Some(hook), Ok(response) | Err(ureq::Error::Status(_, response))) =
+            (&self.response_hook, &result)
+        {
+            hook.run(&incoming_response(response));
+        }
+        match result {
             // Successful request
-            Ok(response) => response.into_string().map_err(Into::into),
+            Ok(response) => Ok(response),
             // HTTP status error
             Err(ureq::Error::Status(_, response)) => Err(ClientError::from_response(response)),
             // Some kind of IO/transport error
-            Err(err) => Err(ClientError::Request(err.to_string())),
+            Err(ureq::Error::Transport(err)) if is_timeout(&err) => Err(ClientError::Timeout),
+            Err(ureq::Error::Transport(err)) => Err(ClientError::request(
+                err.url().map(ToString::to_string),
+                err,
+            )),
         }
     }
 }
 
 #[sync_impl]
 impl BaseHTTPClient for UreqClient {
+    type Native = Agent;
+
+    #[inline]
+    fn from_native(native: Agent) -> Self {
+        Self::with_agent(native)
+    }
+
+    /// Only the idle connections per host can be configured.
+    fn from_config(config: &ConnectionConfig) -> ClientResult<Self> {
+        let mut builder = AgentBuilder::new();
+        if let Some(max) = config.pool_max_idle_per_host {
+            builder = builder.max_idle_connections_per_host(max);
+        }
+
+        Ok(Self::with_agent(builder.build()))
+    }
+
     #[inline]
     fn get(&self, url: &str, headers: Option<&Headers>, payload: &Query) -> ClientResult<String> {
-        let request = ureq::get(url);
+        let request = self.agent.get(url);
         let sender = |mut req: Request| {
             for (key, val) in payload.iter() {
                 req = req.query(&key, &val)
@@ -67,9 +263,26 @@ impl BaseHTTPClient for UreqClient {
         self.request(request, headers, sender)
     }
 
+    #[inline]
+    fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> ClientResult<T> {
+        let request = self.agent.get(url);
+        let sender = |mut req: Request| {
+            for (key, val) in payload.iter() {
+                req = req.query(key, val)
+            }
+            req.call()
+        };
+        self.request_json(request, headers, sender)
+    }
+
     #[inline]
     fn post(&self, url: &str, headers: Option<&Headers>, payload: &Value) -> ClientResult<String> {
-        let request = ureq::post(url);
+        let request = self.agent.post(url);
         let sender = |req: Request| req.send_json(payload.clone());
         self.request(request, headers, sender)
     }
@@ -81,22 +294,24 @@ impl BaseHTTPClient for UreqClient {
         headers: Option<&Headers>,
         payload: &Form,
     ) -> ClientResult<String> {
-        let request = ureq::post(url);
-        let sender = |req: Request| {
-            let payload = payload
-                .iter()
-                .map(|(key, val)| (key.as_str(), val.as_str()))
-                .collect::<Vec<_>>();
-
-            req.send_form(&payload)
-        };
+        self.request_form(self.agent.post(url), headers, payload)
+    }
 
-        self.request(request, headers, sender)
+    #[inline]
+    fn post_form_timeout(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+        timeout: Duration,
+    ) -> ClientResult<String> {
+        let request = self.agent.post(url).timeout(timeout);
+        self.request_form(request, headers, payload)
     }
 
     #[inline]
     fn put(&self, url: &str, headers: Option<&Headers>, payload: &Value) -> ClientResult<String> {
-        let request = ureq::put(url);
+        let request = self.agent.put(url);
         let sender = |req: Request| req.send_json(payload.clone());
         self.request(request, headers, sender)
     }
@@ -108,8 +323,68 @@ impl BaseHTTPClient for UreqClient {
         headers: Option<&Headers>,
         payload: &Value,
     ) -> ClientResult<String> {
-        let request = ureq::delete(url);
+        let request = self.agent.delete(url);
         let sender = |req: Request| req.send_json(payload.clone());
         self.request(request, headers, sender)
     }
+
+    fn get_conditional(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        etag: Option<&str>,
+    ) -> ClientResult<Conditional<String>> {
+        let mut request = self.agent.get(url);
+        if let Some(etag) = etag {
+            request = request.set(headers::IF_NONE_MATCH, etag);
+        }
+        for (key, val) in payload.iter() {
+            request = request.query(key, val)
+        }
+
+        // Only errors are returned for statuses from 400 on, so 304 is a
+        // regular response.
+        let response = self.send(request, headers, Request::call)?;
+        if response.status() == 304 {
+            return Ok(Conditional::NotModified);
+        }
+        let etag = response.header(headers::ETAG).map(ToOwned::to_owned);
+        let text = decode_body(self.read_body(response, None)?);
+        self.metrics.record_buffered(text.len());
+
+        Ok(Conditional::Fetched(text, etag))
+    }
+
+    #[inline]
+    fn get_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        limit: usize,
+    ) -> ClientResult<Vec<u8>> {
+        let response = self.send(self.agent.get(url), headers, Request::call)?;
+        let body = self.read_body(response, Some(limit))?;
+        self.metrics.record_buffered(body.len());
+
+        Ok(body)
+    }
+
+    #[inline]
+    fn body_metrics(&self) -> BodyMetrics {
+        self.metrics.snapshot()
+    }
+
+    #[inline]
+    fn with_body_limits(self, limits: BodyLimits) -> Self {
+        UreqClient { limits, ..self }
+    }
+
+    #[inline]
+    fn with_response_hook(self, hook: ResponseHook) -> Self {
+        UreqClient {
+            response_hook: Some(hook),
+            ..self
+        }
+    }
 }
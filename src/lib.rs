@@ -54,6 +54,28 @@
 //! default. It reads the environment variables `HTTP_PROXY` and `HTTPS_PROXY`
 //! environmental variables to set HTTP and HTTPS proxies, respectively.
 //!
+//! For more advanced settings, like a custom TLS configuration or DNS
+//! resolver, the HTTP client can also be built from an already configured
+//! `reqwest::Client` or `ureq::Agent` with [`BaseHTTPClient::from_native`
+//! ](crate::client::BaseHTTPClient::from_native):
+//!
+//! ```
+//! # #[cfg(all(feature = "client-reqwest", not(feature = "dyn-http")))]
+//! # {
+//! use rspotify::client::{BaseHTTPClient, HTTPClient, SpotifyBuilder};
+//!
+//! let native = reqwest::Client::builder().https_only(true).build().unwrap();
+//! let spotify = SpotifyBuilder::default()
+//!     .http(HTTPClient::from_native(native))
+//!     .build()
+//!     .unwrap();
+//! # }
+//! ```
+//!
+//! The reuse of the connections, which matters when making lots of requests,
+//! can also be tuned without a native client with a [`ConnectionConfig`
+//! ](crate::client::ConnectionConfig).
+//!
 //! Rspotify supports the [`dotenv` crate](dotenv), which allows you to save
 //! credentials in a `.env` file. These will then be available as environmental
 //! values when using methods like [`CredentialsBuilder::from_env`](crate::oauth2::CredentialsBuilder::from_env):
@@ -63,6 +85,33 @@
 //! rspotify = { version = "...", features = ["env-file"] }
 //! ```
 //!
+//! If you're writing unit tests for code that uses Rspotify's models, the
+//! `fixtures` feature exposes realistic sample payloads and constructors like
+//! `FullTrack::fixture()` in the [`fixtures`](crate::fixtures) module:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! rspotify = { version = "...", features = ["fixtures"] }
+//! ```
+//!
+//! Integration tests can also run offline with the `replay` feature: a
+//! [`ReplayClient`](crate::client::ReplayClient) records the requests and
+//! responses into a JSON cassette once, with the tokens redacted, and then
+//! replays them deterministically with [`Spotify::with_replay`
+//! ](crate::client::Spotify::with_replay).
+//!
+//! For end-to-end tests of the requests themselves, like of the pagination
+//! or the retries, the `fake-server` feature adds a [`FakeServer`
+//! ](crate::fake_server::FakeServer) on localhost that serves canned
+//! responses to a client configured for it, without any other dependencies.
+//!
+//! The models ignore the fields they don't know about, so that new ones added
+//! by Spotify don't break existing apps. During development, the
+//! `strict-models` feature instead fails to parse the responses with unknown
+//! fields, or with null or missing values that the models otherwise replace
+//! with a default, like the genres of an artist, to catch changes in the API
+//! early. It shouldn't be enabled in production.
+//!
 //! Rspotify includes support for CLI apps to obtain access tokens by prompting
 //! the user, after enabling the `cli` feature. See the [Authorization
 //! ](#authorization) section for more information.
@@ -145,6 +194,18 @@
 //! ](https://github.com/ramsayleung/rspotify/blob/master/.env) for more
 //! details.
 //!
+//! ### Endpoints
+//!
+//! The endpoints are grouped in two traits implemented by the client:
+//! [`BaseClient`](crate::endpoints::BaseClient), with the ones that can be
+//! used with any token, and [`OAuthClient`](crate::endpoints::OAuthClient),
+//! with the ones that need the user's authorization. They have to be in scope
+//! to call the endpoints, which is easiest with the prelude:
+//!
+//! ```
+//! use rspotify::prelude::*;
+//! ```
+//!
 //! ### Examples
 //!
 //! There are some [available examples
@@ -159,11 +220,39 @@ use getrandom::getrandom;
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 pub mod client;
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub mod endpoints;
+#[cfg(all(
+    feature = "fake-server",
+    not(all(feature = "client-reqwest", feature = "client-ureq"))
+))]
+pub mod fake_server;
+#[cfg(all(
+    feature = "fixtures",
+    not(all(feature = "client-reqwest", feature = "client-ureq"))
+))]
+pub mod fixtures;
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 mod http;
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub mod matcher;
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 pub mod model;
 #[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
 pub mod oauth2;
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub mod pagination;
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub mod playback;
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub mod scopes;
+
+/// The traits with the endpoints of the clients and the methods of the IDs,
+/// which have to be in scope to call them: `use rspotify::prelude::*`.
+#[cfg(not(all(feature = "client-reqwest", feature = "client-ureq")))]
+pub mod prelude {
+    pub use crate::endpoints::{BaseClient, OAuthClient};
+    pub use crate::model::idtypes::{AnyId, PlayContextId, PlayableId};
+}
 
 #[cfg(all(feature = "client-reqwest", feature = "client-ureq"))]
 compile_error!(
@@ -190,7 +279,13 @@ pub(in crate) fn generate_random_string(length: usize) -> String {
     let alphanum: &[u8] =
         "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789".as_bytes();
     let mut buf = vec![0u8; length];
-    getrandom(&mut buf).unwrap();
+    #[cfg(feature = "fixtures")]
+    let seeded = fixtures::fill_seeded(&mut buf);
+    #[cfg(not(feature = "fixtures"))]
+    let seeded = false;
+    if !seeded {
+        getrandom(&mut buf).unwrap();
+    }
     let range = alphanum.len();
 
     buf.iter()
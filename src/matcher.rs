@@ -0,0 +1,252 @@
+//! Utilities to find the track on Spotify that corresponds to one from
+//! elsewhere, like when importing playlists from other services.
+//!
+//! The candidates, usually the results of a search, are scored by how
+//! similar their title, artists, album and duration are to the ones of the
+//! target, ignoring the case, the punctuation and the parts in brackets like
+//! `(feat. Someone)` or `[Remastered]`:
+//!
+//! ```no_run
+//! # #[cfg(feature = "client-reqwest")]
+//! # async fn run(spotify: rspotify::client::Spotify) {
+//! use rspotify::matcher::{best_match, TrackMetadata};
+//! use rspotify::model::{SearchResult, SearchType};
+//! use rspotify::prelude::*;
+//!
+//! let target = TrackMetadata::new("Cut to the Feeling", "Carly Rae Jepsen");
+//! let query = format!("track:{} artist:{}", target.title, target.artists[0]);
+//! let result = spotify
+//!     .search(&query, SearchType::Track, 10, None, None, None)
+//!     .await
+//!     .unwrap();
+//! if let SearchResult::Tracks(page) = result {
+//!     if let Some(found) = best_match(&page.items, &target) {
+//!         println!("{} ({:.2})", found.track.uri, found.score);
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::model::FullTrack;
+
+use std::time::Duration;
+
+/// The lowest score of the candidates returned by [`best_match`], below which
+/// they're most likely a different track.
+pub const MIN_SCORE: f64 = 0.6;
+
+/// The difference in duration up to which it's considered the same, since
+/// the same recording may be encoded with different padding.
+const DURATION_TOLERANCE: Duration = Duration::from_secs(2);
+
+/// The difference in duration from which it's considered a different
+/// recording.
+const DURATION_LIMIT: Duration = Duration::from_secs(15);
+
+/// The known metadata of the track to find.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrackMetadata {
+    pub title: String,
+    /// The artists, with the main one first. Only one is usually enough.
+    pub artists: Vec<String>,
+    pub album: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl TrackMetadata {
+    /// The metadata of a track with only its title and main artist.
+    pub fn new<T: Into<String>, A: Into<String>>(title: T, artist: A) -> Self {
+        TrackMetadata {
+            title: title.into(),
+            artists: vec![artist.into()],
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&FullTrack> for TrackMetadata {
+    fn from(track: &FullTrack) -> Self {
+        TrackMetadata {
+            title: track.name.clone(),
+            artists: track
+                .artists
+                .iter()
+                .map(|artist| artist.name.clone())
+                .collect(),
+            album: Some(track.album.name.clone()),
+            duration: Some(track.duration),
+        }
+    }
+}
+
+/// A candidate of [`best_match`], with its score.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrackMatch<'a> {
+    pub track: &'a FullTrack,
+    /// How similar it is to the target, from 0 to 1.
+    pub score: f64,
+}
+
+/// The candidate most similar to the target, or `None` if there are no
+/// candidates with at least [`MIN_SCORE`]. The first one wins the ties, so
+/// that the order of relevance of the search results is kept.
+pub fn best_match<'a, I>(search_results: I, target: &TrackMetadata) -> Option<TrackMatch<'a>>
+where
+    I: IntoIterator<Item = &'a FullTrack>,
+{
+    let mut best: Option<TrackMatch> = None;
+    for track in search_results {
+        let score = score(track, target);
+        match best {
+            Some(ref best) if best.score >= score => {}
+            _ if score < MIN_SCORE => {}
+            _ => best = Some(TrackMatch { track, score }),
+        }
+    }
+    best
+}
+
+/// How similar the candidate is to the target, from 0 to 1. The title
+/// weights the most, and the album and duration are only compared if the
+/// target has them.
+pub fn score(candidate: &FullTrack, target: &TrackMetadata) -> f64 {
+    let mut scores = vec![(0.45, similarity(&candidate.name, &target.title))];
+    if !target.artists.is_empty() {
+        // Each of the artists of the target is compared with the most
+        // similar of the candidate, since they may be in a different order.
+        let artists = target
+            .artists
+            .iter()
+            .map(|artist| {
+                candidate
+                    .artists
+                    .iter()
+                    .map(|candidate| similarity(&candidate.name, artist))
+                    .fold(0.0, f64::max)
+            })
+            .sum::<f64>()
+            / target.artists.len() as f64;
+        scores.push((0.3, artists));
+    }
+    if let Some(album) = &target.album {
+        scores.push((0.1, similarity(&candidate.album.name, album)));
+    }
+    if let Some(duration) = target.duration {
+        scores.push((0.15, duration_similarity(candidate.duration, duration)));
+    }
+
+    let weights = scores.iter().map(|(weight, _)| weight).sum::<f64>();
+    scores
+        .iter()
+        .map(|(weight, score)| weight * score)
+        .sum::<f64>()
+        / weights
+}
+
+/// The name in lowercase, without the parts in brackets, the punctuation and
+/// the extra whitespace.
+pub fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut depth = 0usize;
+    for c in name.chars() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() => normalized.extend(c.to_lowercase()),
+            _ => normalized.push(' '),
+        }
+    }
+    normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The Sørensen–Dice coefficient of the character bigrams of both names once
+/// normalized, which tolerates typos and small differences in the wording.
+fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize(a), normalize(b));
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |name: &str| {
+        let chars = name.chars().collect::<Vec<_>>();
+        chars
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>()
+    };
+    let (a, mut b) = (bigrams(&a), bigrams(&b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for bigram in a {
+        if let Some(pos) = b.iter().position(|other| *other == bigram) {
+            b.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    (2 * shared) as f64 / total as f64
+}
+
+/// 1 within [`DURATION_TOLERANCE`], decreasing down to 0 at
+/// [`DURATION_LIMIT`].
+fn duration_similarity(a: Duration, b: Duration) -> f64 {
+    let delta = a.max(b) - a.min(b);
+    if delta <= DURATION_TOLERANCE {
+        return 1.0;
+    }
+    let excess = (delta - DURATION_TOLERANCE).as_secs_f64();
+    let range = (DURATION_LIMIT - DURATION_TOLERANCE).as_secs_f64();
+    (1.0 - excess / range).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(name: &str, artist: &str, duration: u64) -> FullTrack {
+        let mut track: FullTrack =
+            serde_json::from_str(include_str!("fixtures/full_track.json")).unwrap();
+        track.name = name.to_owned();
+        track.artists[0].name = artist.to_owned();
+        track.duration = Duration::from_secs(duration);
+        track
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Cut To The Feeling"), "cut to the feeling");
+        assert_eq!(
+            normalize("Don't Stop Me Now - Remastered (2011) [Live]"),
+            "don t stop me now remastered"
+        );
+        assert_eq!(normalize("Señorita (feat. Someone)"), "señorita");
+        assert_eq!(normalize("(Intro)"), "");
+    }
+
+    #[test]
+    fn test_best_match() {
+        let candidates = vec![
+            track("Cut To The Feeling - Karaoke", "Sing Along", 208),
+            track("Cut To The Feeling", "Carly Rae Jepsen", 208),
+            track("Cut To The Feeling (Remix)", "Carly Rae Jepsen", 250),
+            track("Run Away With Me", "Carly Rae Jepsen", 251),
+        ];
+        let target = TrackMetadata {
+            duration: Some(Duration::from_secs(207)),
+            ..TrackMetadata::new("cut to the feeling", "Carly Rae Jepsen")
+        };
+        let found = best_match(&candidates, &target).unwrap();
+        assert!(std::ptr::eq(found.track, &candidates[1]));
+        assert!((found.score - 1.0).abs() < f64::EPSILON);
+
+        // The remix has the same title once normalized, but not the duration.
+        let score = score(&candidates[2], &target);
+        assert!(score < found.score && score > MIN_SCORE);
+
+        let target = TrackMetadata::new("Call Me Maybe", "Carly Rae Jepsen");
+        assert_eq!(best_match(&candidates, &target), None);
+    }
+}
@@ -2,14 +2,16 @@
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
-
 use super::artist::SimplifiedArtist;
+use super::date::{option_release_date_fields, release_date_fields};
 use super::image::Image;
 use super::page::Page;
 use super::track::SimplifiedTrack;
-use super::Restriction;
-use crate::model::{AlbumType, Copyright, DatePrecision, Type};
+use super::Restrictions;
+use crate::model::{
+    AlbumGroup, AlbumId, AlbumType, AnyId, Copyright, ExternalIds, ExternalUrls, Popularity,
+    ReleaseDate, Type,
+};
 
 /// Simplified Album Object
 ///
@@ -17,27 +19,33 @@ use crate::model::{AlbumType, Copyright, DatePrecision, Type};
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SimplifiedAlbum {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub album_group: Option<String>,
-    pub album_type: Option<String>,
+    pub album_group: Option<AlbumGroup>,
+    pub album_type: Option<AlbumType>,
     pub artists: Vec<SimplifiedArtist>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub available_markets: Vec<String>,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub href: Option<String>,
     pub id: Option<String>,
     pub images: Vec<Image>,
     pub name: String,
+    #[serde(flatten, with = "option_release_date_fields")]
+    pub release_date: Option<ReleaseDate>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub release_date: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub release_date_precision: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub restrictions: Option<Restriction>,
+    pub restrictions: Option<Restrictions>,
     #[serde(rename = "type")]
     pub _type: Type,
     pub uri: Option<String>,
 }
 
+impl SimplifiedAlbum {
+    /// The ID of the album, to build its URI or links to share it, or `None`
+    /// for the albums of local tracks.
+    pub fn album_id(&self) -> Option<AlbumId> {
+        self.id.as_deref().map(AlbumId::from_model)
+    }
+}
+
 /// Full Album Object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-albumobject)
@@ -47,26 +55,103 @@ pub struct FullAlbum {
     pub album_type: AlbumType,
     pub available_markets: Vec<String>,
     pub copyrights: Vec<Copyright>,
-    pub external_ids: HashMap<String, String>,
-    pub external_urls: HashMap<String, String>,
+    pub external_ids: ExternalIds,
+    pub external_urls: ExternalUrls,
     pub genres: Vec<String>,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
     pub name: String,
-    pub popularity: u32,
-    pub release_date: String,
-    pub release_date_precision: DatePrecision,
+    pub popularity: Popularity,
+    #[serde(flatten, with = "release_date_fields")]
+    pub release_date: ReleaseDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restrictions: Option<Restrictions>,
     pub tracks: Page<SimplifiedTrack>,
     #[serde(rename = "type")]
     pub _type: Type,
     pub uri: String,
 }
 
+impl FullAlbum {
+    /// The ID of the album, to build its URI or links to share it.
+    pub fn album_id(&self) -> AlbumId {
+        AlbumId::from_model(&self.id)
+    }
+
+    /// The same as [`FullTrack::to_uri`].
+    ///
+    /// [`FullTrack::to_uri`]: crate::model::FullTrack::to_uri
+    pub fn to_uri(&self) -> String {
+        self.album_id().to_uri()
+    }
+
+    /// The same as [`FullTrack::to_open_url`].
+    ///
+    /// [`FullTrack::to_open_url`]: crate::model::FullTrack::to_open_url
+    pub fn to_open_url(&self) -> String {
+        self.album_id().to_open_url()
+    }
+
+    /// The same as [`FullTrack::to_embed_url`].
+    ///
+    /// [`FullTrack::to_embed_url`]: crate::model::FullTrack::to_embed_url
+    pub fn to_embed_url(&self) -> String {
+        self.album_id().to_embed_url()
+    }
+}
+
+/// The album without its tracks and the fields only in the full object, like
+/// to keep it along with the simplified ones of other endpoints.
+impl From<FullAlbum> for SimplifiedAlbum {
+    fn from(album: FullAlbum) -> Self {
+        SimplifiedAlbum {
+            album_group: None,
+            album_type: Some(album.album_type),
+            artists: album.artists,
+            available_markets: album.available_markets,
+            external_urls: album.external_urls,
+            href: Some(album.href),
+            id: Some(album.id),
+            images: album.images,
+            name: album.name,
+            release_date: Some(album.release_date),
+            restrictions: album.restrictions,
+            _type: album._type,
+            uri: Some(album.uri),
+        }
+    }
+}
+
+/// The full and simplified versions of an album are the same one if they
+/// have the same ID.
+impl PartialEq<SimplifiedAlbum> for FullAlbum {
+    fn eq(&self, other: &SimplifiedAlbum) -> bool {
+        other.id.as_deref() == Some(self.id.as_str())
+    }
+}
+
+impl PartialEq<FullAlbum> for SimplifiedAlbum {
+    fn eq(&self, other: &FullAlbum) -> bool {
+        other == self
+    }
+}
+
+/// An album of an artist's discography, as returned by
+/// [`artist_discography`](crate::client::BaseClient::artist_discography), along
+/// with all of its tracks if they were requested.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct DiscographyAlbum {
+    pub album: SimplifiedAlbum,
+    pub tracks: Option<Vec<SimplifiedTrack>>,
+}
+
 /// Full Albums wrapped by Vec object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-albums)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct FullAlbums {
     pub albums: Vec<FullAlbum>,
 }
@@ -75,6 +160,7 @@ pub(in crate) struct FullAlbums {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-new-releases)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct PageSimpliedAlbums {
     pub albums: Page<SimplifiedAlbum>,
 }
@@ -83,6 +169,7 @@ pub(in crate) struct PageSimpliedAlbums {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-savedalbumobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SavedAlbum {
     pub added_at: DateTime<Utc>,
     pub album: FullAlbum,
@@ -3,14 +3,14 @@ use serde::{Deserialize, Serialize};
 
 use super::image::Image;
 use super::page::CursorBasedPage;
-use crate::model::{Followers, Type};
-use std::collections::HashMap;
+use crate::model::{AnyId, ArtistId, ExternalUrls, Followers, Popularity, Type};
 /// Simplified Artist Object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedartistobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SimplifiedArtist {
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub href: Option<String>,
     pub id: Option<String>,
     pub name: String,
@@ -19,28 +19,97 @@ pub struct SimplifiedArtist {
     pub uri: Option<String>,
 }
 
+impl SimplifiedArtist {
+    /// The ID of the artist, to build its URI or links to share it, or `None`
+    /// for the artists of local tracks.
+    pub fn artist_id(&self) -> Option<ArtistId> {
+        self.id.as_deref().map(ArtistId::from_model)
+    }
+}
+
 /// Full Artist Object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-artistobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct FullArtist {
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
+    /// Zero when Spotify omits it.
+    #[cfg_attr(not(feature = "strict-models"), serde(default))]
     pub followers: Followers,
+    /// Empty for the artists without genres, for which Spotify may omit it.
+    #[cfg_attr(not(feature = "strict-models"), serde(default))]
     pub genres: Vec<String>,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
     pub name: String,
-    pub popularity: u32,
+    pub popularity: Popularity,
     #[serde(rename = "type")]
     pub _type: Type,
     pub uri: String,
 }
 
+impl FullArtist {
+    /// The ID of the artist, to build its URI or links to share it.
+    pub fn artist_id(&self) -> ArtistId {
+        ArtistId::from_model(&self.id)
+    }
+
+    /// The same as [`FullTrack::to_uri`].
+    ///
+    /// [`FullTrack::to_uri`]: crate::model::FullTrack::to_uri
+    pub fn to_uri(&self) -> String {
+        self.artist_id().to_uri()
+    }
+
+    /// The same as [`FullTrack::to_open_url`].
+    ///
+    /// [`FullTrack::to_open_url`]: crate::model::FullTrack::to_open_url
+    pub fn to_open_url(&self) -> String {
+        self.artist_id().to_open_url()
+    }
+
+    /// The same as [`FullTrack::to_embed_url`].
+    ///
+    /// [`FullTrack::to_embed_url`]: crate::model::FullTrack::to_embed_url
+    pub fn to_embed_url(&self) -> String {
+        self.artist_id().to_embed_url()
+    }
+}
+
+impl From<FullArtist> for SimplifiedArtist {
+    fn from(artist: FullArtist) -> Self {
+        SimplifiedArtist {
+            external_urls: artist.external_urls,
+            href: Some(artist.href),
+            id: Some(artist.id),
+            name: artist.name,
+            _type: artist._type,
+            uri: Some(artist.uri),
+        }
+    }
+}
+
+/// The full and simplified versions of an artist are the same one if they
+/// have the same ID.
+impl PartialEq<SimplifiedArtist> for FullArtist {
+    fn eq(&self, other: &SimplifiedArtist) -> bool {
+        other.id.as_deref() == Some(self.id.as_str())
+    }
+}
+
+impl PartialEq<FullArtist> for SimplifiedArtist {
+    fn eq(&self, other: &FullArtist) -> bool {
+        other == self
+    }
+}
+
 /// Full artist object wrapped by `Vec`
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-artists)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct FullArtists {
     pub artists: Vec<FullArtist>,
 }
@@ -49,6 +118,7 @@ pub(in crate) struct FullArtists {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-followed)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct CursorPageFullArtists {
     pub artists: CursorBasedPage<FullArtist>,
 }
@@ -1,12 +1,62 @@
 //! All objects related to artist defined by Spotify API
-use crate::model::{duration_ms, enums::Modality, modality};
+use crate::model::{
+    duration_ms,
+    enums::{Key, Modality},
+    modality, pitch_class,
+};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{convert::TryFrom, fmt, time::Duration};
+
+/// The estimated time signature of a track, as the number of beats in each
+/// bar, like 4 for 4/4. It ranges from 3 to 7 according to Spotify, but 0 and
+/// 1 are returned too for the tracks without a clear meter.
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-audiofeaturesobject)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct TimeSignature(u8);
+
+impl TimeSignature {
+    /// The largest number of beats per bar estimated by Spotify.
+    pub const MAX_BEATS: u8 = 7;
+
+    /// The time signature with the given beats per bar, or `None` if there
+    /// are more than [`TimeSignature::MAX_BEATS`].
+    pub fn new(beats: u8) -> Option<Self> {
+        Some(TimeSignature(beats)).filter(|_| beats <= Self::MAX_BEATS)
+    }
+
+    /// The number of beats in each bar.
+    pub fn beats(&self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for TimeSignature {
+    type Error = String;
+
+    fn try_from(beats: u8) -> Result<Self, Self::Error> {
+        TimeSignature::new(beats).ok_or_else(|| format!("invalid time signature: {}", beats))
+    }
+}
+
+impl From<TimeSignature> for u8 {
+    fn from(time_signature: TimeSignature) -> Self {
+        time_signature.0
+    }
+}
+
+impl fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/4", self.0)
+    }
+}
 
 /// Audio Feature Object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-audiofeaturesobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct AudioFeatures {
     pub acousticness: f32,
     pub analysis_url: String,
@@ -16,14 +66,15 @@ pub struct AudioFeatures {
     pub energy: f32,
     pub id: String,
     pub instrumentalness: f32,
-    pub key: i32,
+    #[serde(with = "pitch_class")]
+    pub key: Key,
     pub liveness: f32,
     pub loudness: f32,
     #[serde(with = "modality")]
     pub mode: Modality,
     pub speechiness: f32,
     pub tempo: f32,
-    pub time_signature: i32,
+    pub time_signature: TimeSignature,
     pub track_href: String,
     #[serde(rename = "type")]
     pub _type: String,
@@ -35,6 +86,7 @@ pub struct AudioFeatures {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-several-audio-features)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct AudioFeaturesPayload {
     pub audio_features: Vec<AudioFeatures>,
 }
@@ -43,6 +95,7 @@ pub(in crate) struct AudioFeaturesPayload {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct AudioAnalysis {
     pub bars: Vec<TimeInterval>,
     pub beats: Vec<TimeInterval>,
@@ -72,12 +125,13 @@ pub struct AudioAnalysisSection {
     pub loudness: f32,
     pub tempo: f32,
     pub tempo_confidence: f32,
-    pub key: i32,
+    #[serde(with = "pitch_class")]
+    pub key: Key,
     pub key_confidence: f32,
     #[serde(with = "modality")]
     pub mode: Modality,
     pub mode_confidence: f32,
-    pub time_signature: i32,
+    pub time_signature: TimeSignature,
     pub time_signature_confidence: f32,
 }
 
@@ -85,6 +139,7 @@ pub struct AudioAnalysisSection {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct AudioAnalysisMeta {
     pub analyzer_version: String,
     pub platform: String,
@@ -113,6 +168,7 @@ pub struct AudioAnalysisSegment {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct AudioAnalysisTrack {
     pub num_samples: u32,
     pub duration: f32,
@@ -126,9 +182,10 @@ pub struct AudioAnalysisTrack {
     pub loudness: f32,
     pub tempo: f32,
     pub tempo_confidence: f32,
-    pub time_signature: i32,
+    pub time_signature: TimeSignature,
     pub time_signature_confidence: f32,
-    pub key: u32,
+    #[serde(with = "pitch_class")]
+    pub key: Key,
     pub key_confidence: f32,
     #[serde(with = "modality")]
     pub mode: Modality,
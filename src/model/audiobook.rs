@@ -0,0 +1,64 @@
+//! All kinds of audiobook object
+use serde::{Deserialize, Serialize};
+
+use super::image::Image;
+use super::show::Copyright;
+use crate::model::{empty_as_none, AudiobookId, ExternalUrls, Type};
+
+/// Author object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-authorobject)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Author {
+    pub name: String,
+}
+
+/// Narrator object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-narratorobject)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Narrator {
+    pub name: String,
+}
+
+/// Simplified audiobook object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedaudiobookobject)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct SimplifiedAudiobook {
+    pub authors: Vec<Author>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub available_markets: Vec<String>,
+    pub copyrights: Vec<Copyright>,
+    pub description: String,
+    /// The description with its HTML tags.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub html_description: Option<String>,
+    /// The edition of the audiobook, like `Unabridged`.
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
+    pub edition: Option<String>,
+    pub explicit: bool,
+    pub external_urls: ExternalUrls,
+    pub href: String,
+    pub id: String,
+    pub images: Vec<Image>,
+    pub languages: Vec<String>,
+    pub media_type: String,
+    pub name: String,
+    pub narrators: Vec<Narrator>,
+    pub publisher: String,
+    pub total_chapters: Option<u32>,
+    #[serde(rename = "type")]
+    pub _type: Type,
+    pub uri: String,
+}
+
+impl SimplifiedAudiobook {
+    /// The ID of the audiobook, to build its URI or links to share it.
+    pub fn audiobook_id(&self) -> AudiobookId {
+        AudiobookId::from_model(&self.id)
+    }
+}
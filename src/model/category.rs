@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-categories)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Category {
     pub href: String,
     pub icons: Vec<Image>,
@@ -17,6 +18,7 @@ pub struct Category {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-categories)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct PageCategory {
     pub categories: Page<Category>,
 }
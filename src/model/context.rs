@@ -2,28 +2,56 @@
 use super::device::Device;
 use super::PlayingItem;
 use crate::model::{
-    millisecond_timestamp, option_duration_ms, CurrentlyPlayingType, DisallowKey, RepeatState, Type,
+    millisecond_timestamp, option_duration_ms, parse_uri, ContextType, CurrentlyPlayingType,
+    DisallowKey, ExternalUrls, RepeatState, SpotifyId,
 };
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Deserializer, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
-/// Context object
+/// Context object, with what the item is played from, like an album or a
+/// playlist.
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Context {
     pub uri: String,
     pub href: String,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     #[serde(rename = "type")]
-    pub _type: Type,
+    pub _type: ContextType,
+}
+
+impl Context {
+    /// The ID of the album, artist, playlist or show played, which can be
+    /// given to its endpoint to show where the playback comes from, like
+    /// "Playing from <playlist name>". It's `None` for the user's saved
+    /// tracks, which have no ID.
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "client-reqwest")]
+    /// # async fn run(spotify: rspotify::client::Spotify) {
+    /// use rspotify::model::SpotifyId;
+    /// use rspotify::prelude::*;
+    ///
+    /// let playing = spotify.current_playing(None, None).await.unwrap();
+    /// let context = playing.and_then(|playing| playing.context);
+    /// if let Some(SpotifyId::Playlist(id)) = context.and_then(|context| context.id()) {
+    ///     let playlist = spotify.playlist(&id, None, None).await.unwrap();
+    ///     println!("Playing from {}", playlist.name);
+    /// }
+    /// # }
+    /// ```
+    pub fn id(&self) -> Option<SpotifyId> {
+        parse_uri(&self.uri, Some(self._type.object_type()?)).ok()
+    }
 }
 
 /// Currently playing object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct CurrentlyPlayingContext {
     pub context: Option<Context>,
     #[serde(with = "millisecond_timestamp")]
@@ -34,10 +62,12 @@ pub struct CurrentlyPlayingContext {
     pub is_playing: bool,
     pub item: Option<PlayingItem>,
     pub currently_playing_type: CurrentlyPlayingType,
+    #[serde(default)]
     pub actions: Actions,
 }
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-information-about-the-users-current-playback)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct CurrentPlaybackContext {
     pub device: Device,
     pub repeat_state: RepeatState,
@@ -51,34 +81,105 @@ pub struct CurrentPlaybackContext {
     pub is_playing: bool,
     pub item: Option<PlayingItem>,
     pub currently_playing_type: CurrentlyPlayingType,
+    #[serde(default)]
     pub actions: Actions,
 }
 
+/// The user's queue, after the currently playing item.
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-queue)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct CurrentUserQueue {
+    /// `None` if nothing is playing.
+    pub currently_playing: Option<PlayingItem>,
+    #[cfg_attr(not(feature = "strict-models"), serde(default))]
+    pub queue: Vec<PlayingItem>,
+}
+
+impl CurrentlyPlayingContext {
+    /// The time left until the current item finishes, if known.
+    pub fn remaining(&self) -> Option<Duration> {
+        remaining(self.item.as_ref(), self.progress)
+    }
+}
+
+impl CurrentPlaybackContext {
+    /// The time left until the current item finishes, if known.
+    pub fn remaining(&self) -> Option<Duration> {
+        remaining(self.item.as_ref(), self.progress)
+    }
+}
+
+fn remaining(item: Option<&PlayingItem>, progress: Option<Duration>) -> Option<Duration> {
+    Some(item?.duration().saturating_sub(progress?))
+}
+
 /// Actions object
 ///
-/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
-#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-currentlyplayingcontextobject)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Actions {
-    pub disallows: Vec<DisallowKey>,
+    #[serde(default)]
+    pub disallows: Disallows,
+}
+
+/// Disallows object, with the playback actions that aren't allowed in the
+/// current context, so that the controls can be disabled beforehand. The
+/// actions missing in the response are allowed.
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-disallowsobject)
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+#[serde(default)]
+pub struct Disallows {
+    pub interrupting_playback: bool,
+    pub pausing: bool,
+    pub resuming: bool,
+    pub seeking: bool,
+    pub skipping_next: bool,
+    pub skipping_prev: bool,
+    pub toggling_repeat_context: bool,
+    pub toggling_shuffle: bool,
+    pub toggling_repeat_track: bool,
+    pub transferring_playback: bool,
 }
 
-impl<'de> Deserialize<'de> for Actions {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        #[derive(Deserialize)]
-        struct OriginalActions {
-            pub disallows: HashMap<DisallowKey, bool>,
+impl Disallows {
+    /// Whether the given action is disallowed.
+    pub fn contains(&self, key: DisallowKey) -> bool {
+        match key {
+            DisallowKey::InterruptingPlayback => self.interrupting_playback,
+            DisallowKey::Pausing => self.pausing,
+            DisallowKey::Resuming => self.resuming,
+            DisallowKey::Seeking => self.seeking,
+            DisallowKey::SkippingNext => self.skipping_next,
+            DisallowKey::SkippingPrev => self.skipping_prev,
+            DisallowKey::TogglingRepeatContext => self.toggling_repeat_context,
+            DisallowKey::TogglingShuffle => self.toggling_shuffle,
+            DisallowKey::TogglingRepeatTrack => self.toggling_repeat_track,
+            DisallowKey::TransferringPlayback => self.transferring_playback,
         }
-        let orignal_actions = OriginalActions::deserialize(deserializer)?;
-        Ok(Actions {
-            disallows: orignal_actions
-                .disallows
-                .into_iter()
-                .filter(|(_, value)| *value)
-                .map(|(key, _)| key)
-                .collect(),
-        })
+    }
+
+    /// The list of disallowed actions.
+    pub fn keys(&self) -> Vec<DisallowKey> {
+        [
+            DisallowKey::InterruptingPlayback,
+            DisallowKey::Pausing,
+            DisallowKey::Resuming,
+            DisallowKey::Seeking,
+            DisallowKey::SkippingNext,
+            DisallowKey::SkippingPrev,
+            DisallowKey::TogglingRepeatContext,
+            DisallowKey::TogglingShuffle,
+            DisallowKey::TogglingRepeatTrack,
+            DisallowKey::TransferringPlayback,
+        ]
+        .iter()
+        .copied()
+        .filter(|key| self.contains(*key))
+        .collect()
     }
 }
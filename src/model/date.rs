@@ -0,0 +1,203 @@
+//! Release date object
+use chrono::{Datelike, NaiveDate};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use crate::model::DatePrecision;
+
+/// The date in which an album or episode was released, which may only be
+/// known up to the year or month.
+///
+/// It's read from the `release_date` and `release_date_precision` fields of
+/// the object, like `"1981-12"` and `"month"`, or from a string like
+/// `"1981-12"` on its own. An invalid date fails the whole object, instead of
+/// being silently ignored. Two dates can only be
+/// ordered when they differ at their coarsest common precision; `1981` and
+/// `1981-12` can't be compared, while `1980` comes before `1981-12`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReleaseDate {
+    date: NaiveDate,
+    precision: DatePrecision,
+}
+
+impl ReleaseDate {
+    /// Returns the release date, or `None` if the day or month are not valid.
+    /// The parts of the date beyond its precision are ignored.
+    pub fn new(year: i32, month: u32, day: u32, precision: DatePrecision) -> Option<Self> {
+        let (month, day) = match precision {
+            DatePrecision::Year => (1, 1),
+            DatePrecision::Month => (month, 1),
+            DatePrecision::Day => (month, day),
+        };
+        NaiveDate::from_ymd_opt(year, month, day).map(|date| ReleaseDate { date, precision })
+    }
+
+    /// Parses a date like `2020`, `2020-10` or `2020-10-20` with the given
+    /// precision.
+    pub fn parse(date: &str, precision: DatePrecision) -> Option<Self> {
+        let mut parts = date.splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let mut next = |needed: bool| match parts.next() {
+            Some(part) => part.parse().ok(),
+            None if !needed => Some(1),
+            None => None,
+        };
+        let month = next(precision != DatePrecision::Year)?;
+        let day = next(precision == DatePrecision::Day)?;
+        ReleaseDate::new(year, month, day, precision)
+    }
+
+    /// How precise the date is.
+    pub fn precision(&self) -> DatePrecision {
+        self.precision
+    }
+
+    /// The date, with the first month or day in place of the unknown ones.
+    pub fn approx_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// The year, month and day known at the given precision.
+    fn truncate(&self, precision: DatePrecision) -> (i32, u32, u32) {
+        match precision {
+            DatePrecision::Year => (self.date.year(), 0, 0),
+            DatePrecision::Month => (self.date.year(), self.date.month(), 0),
+            DatePrecision::Day => (self.date.year(), self.date.month(), self.date.day()),
+        }
+    }
+}
+
+impl PartialOrd for ReleaseDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let precision = self.precision.min(other.precision);
+        match self.truncate(precision).cmp(&other.truncate(precision)) {
+            Ordering::Equal if self.precision != other.precision => None,
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl fmt::Display for ReleaseDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.precision {
+            DatePrecision::Year => write!(f, "{:04}", self.date.year()),
+            DatePrecision::Month => write!(f, "{}", self.date.format("%Y-%m")),
+            DatePrecision::Day => write!(f, "{}", self.date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+impl FromStr for ReleaseDate {
+    type Err = String;
+
+    /// Parses a date like `2020`, `2020-10` or `2020-10-20`, with the
+    /// precision of its format.
+    fn from_str(date: &str) -> Result<Self, Self::Err> {
+        let precision = match date.matches('-').count() {
+            0 => DatePrecision::Year,
+            1 => DatePrecision::Month,
+            _ => DatePrecision::Day,
+        };
+        ReleaseDate::parse(date, precision).ok_or_else(|| format!("invalid release date {}", date))
+    }
+}
+
+impl Serialize for ReleaseDate {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReleaseDate {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let date = String::deserialize(d)?;
+        date.parse().map_err(de::Error::custom)
+    }
+}
+
+/// The `release_date` and `release_date_precision` fields of the objects,
+/// which are read together into a [`ReleaseDate`] with the precision given by
+/// Spotify.
+#[derive(Serialize, Deserialize)]
+struct RawReleaseDate {
+    release_date: Option<String>,
+    release_date_precision: Option<DatePrecision>,
+}
+
+impl RawReleaseDate {
+    fn into_date<E: de::Error>(self) -> Result<Option<ReleaseDate>, E> {
+        match (self.release_date, self.release_date_precision) {
+            (None, None) => Ok(None),
+            (Some(date), Some(precision)) => ReleaseDate::parse(&date, precision)
+                .map(Some)
+                .ok_or_else(|| {
+                    E::custom(format!(
+                        "invalid release date {} with precision {}",
+                        date,
+                        precision.to_string()
+                    ))
+                }),
+            _ => Err(E::custom("release date without its precision")),
+        }
+    }
+}
+
+impl From<Option<&ReleaseDate>> for RawReleaseDate {
+    fn from(date: Option<&ReleaseDate>) -> Self {
+        RawReleaseDate {
+            release_date: date.map(ToString::to_string),
+            release_date_precision: date.map(ReleaseDate::precision),
+        }
+    }
+}
+
+/// (De)serialize the flattened `release_date` and `release_date_precision`
+/// fields of an object as a [`ReleaseDate`].
+pub(in crate) mod release_date_fields {
+    use super::{RawReleaseDate, ReleaseDate};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn deserialize<'de, D>(d: D) -> Result<ReleaseDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        RawReleaseDate::deserialize(d)?
+            .into_date()?
+            .ok_or_else(|| de::Error::missing_field("release_date"))
+    }
+
+    pub fn serialize<S>(date: &ReleaseDate, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        RawReleaseDate::from(Some(date)).serialize(s)
+    }
+}
+
+/// The same as [`release_date_fields`], but with null fields, like in the
+/// albums of local tracks.
+pub(in crate) mod option_release_date_fields {
+    use super::{RawReleaseDate, ReleaseDate};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<ReleaseDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        RawReleaseDate::deserialize(d)?.into_date()
+    }
+
+    pub fn serialize<S>(date: &Option<ReleaseDate>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        RawReleaseDate::from(date.as_ref()).serialize(s)
+    }
+}
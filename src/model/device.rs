@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-users-available-devices)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Device {
     pub id: Option<String>,
     pub is_active: bool,
@@ -16,10 +17,33 @@ pub struct Device {
     pub volume_percent: Option<u32>,
 }
 
+/// How the default device of the player endpoints is chosen, configured in
+/// [`Spotify::default_device`](crate::client::Spotify::default_device).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceSelector {
+    /// The device with the given ID.
+    Id(String),
+    /// The first device with the given name, like `Office`, which is easier
+    /// to configure since it's what the user sees in the app.
+    Name(String),
+}
+
+impl DeviceSelector {
+    /// Whether the device is the selected one.
+    pub fn matches(&self, device: &Device) -> bool {
+        match self {
+            DeviceSelector::Id(id) => device.id.as_deref() == Some(id.as_str()),
+            DeviceSelector::Name(name) => device.name == *name,
+        }
+    }
+}
+
 /// Device payload object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-users-available-devices)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct DevicePayload {
     pub devices: Vec<Device>,
 }
@@ -61,7 +61,9 @@ pub enum IncludeExternal {
 /// Date precision: `year`, `month`, `day`.
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/object-model/):
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString)]
+#[derive(
+    Clone, Serialize, Deserialize, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, ToString,
+)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum DatePrecision {
@@ -70,16 +72,23 @@ pub enum DatePrecision {
     Day,
 }
 
-/// The reason for the restriction: `market`, `product`, `explicit`
+/// The reason for the restriction: `market`, `product`, `explicit`,
+/// `payment_required`
 ///
-/// [Reference](https://developer.spotify.com/documentation/web-api/reference/object-model/#track-restriction-object)
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-trackrestrictionobject)
 #[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum RestrictionReason {
+    /// The content isn't available in the given market.
     Market,
+    /// The content isn't available for the user's subscription type.
     Product,
-    Explict,
+    /// The content is explicit and the user's account is set to not play
+    /// explicit content.
+    Explicit,
+    /// Payment is required to play the content item.
+    PaymentRequired,
 }
 
 /// Indicates the modality (major or minor) of a track
@@ -94,6 +103,76 @@ pub enum Modality {
     NoResult = -1,
 }
 
+/// The key of a track, as a pitch class in the standard notation. This field
+/// will contain a number from 0 for `C` to 11 for `B`, or a -1 for `no
+/// result`
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-audiofeaturesobject)
+#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, Hash, ToString)]
+pub enum Key {
+    #[strum(serialize = "C")]
+    C = 0,
+    #[strum(serialize = "C♯/D♭")]
+    CSharp = 1,
+    #[strum(serialize = "D")]
+    D = 2,
+    #[strum(serialize = "D♯/E♭")]
+    DSharp = 3,
+    #[strum(serialize = "E")]
+    E = 4,
+    #[strum(serialize = "F")]
+    F = 5,
+    #[strum(serialize = "F♯/G♭")]
+    FSharp = 6,
+    #[strum(serialize = "G")]
+    G = 7,
+    #[strum(serialize = "G♯/A♭")]
+    GSharp = 8,
+    #[strum(serialize = "A")]
+    A = 9,
+    #[strum(serialize = "A♯/B♭")]
+    ASharp = 10,
+    #[strum(serialize = "B")]
+    B = 11,
+    #[strum(serialize = "no result")]
+    NoResult = -1,
+}
+
+impl Key {
+    const PITCH_CLASSES: [Key; 12] = [
+        Key::C,
+        Key::CSharp,
+        Key::D,
+        Key::DSharp,
+        Key::E,
+        Key::F,
+        Key::FSharp,
+        Key::G,
+        Key::GSharp,
+        Key::A,
+        Key::ASharp,
+        Key::B,
+    ];
+
+    /// The key of the given pitch class, from 0 for `C` to 11 for `B`, or
+    /// `NoResult` for -1.
+    pub fn from_pitch_class(pitch_class: i8) -> Option<Key> {
+        match pitch_class {
+            -1 => Some(Key::NoResult),
+            _ => Key::PITCH_CLASSES.get(pitch_class as usize).copied(),
+        }
+    }
+
+    /// The pitch class, from 0 for `C` to 11 for `B`, or `None` if the key
+    /// wasn't detected.
+    pub fn pitch_class(&self) -> Option<u8> {
+        match self {
+            Key::NoResult => None,
+            key => Some(*key as u8),
+        }
+    }
+}
+
 /// Limit the response to a particular market
 ///
 /// FromToken is the same thing as setting the market parameter to the user's country.
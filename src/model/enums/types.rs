@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use strum::ToString;
+use strum::{EnumString, ToString};
+
+use std::convert::TryFrom;
 
 /// Copyright type: `C` = the copyright, `P` = the sound recording (performance)
 /// copyright.
@@ -15,21 +17,58 @@ pub enum CopyrightType {
     Copyright,
 }
 
-/// Album type: `album`, `single`, `appears_on`, `compilation`
+/// Album type: `album`, `single`, `compilation`
+///
+/// It's deserialized regardless of its case, since the API sometimes returns
+/// `ALBUM` instead of `album`.
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/object-model/#album-object-full)
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString)]
-#[serde(rename_all = "snake_case")]
+#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString, EnumString)]
+#[serde(rename_all = "snake_case", try_from = "String")]
 #[strum(serialize_all = "snake_case")]
 pub enum AlbumType {
     Album,
     Single,
-    AppearsOn,
     Compilation,
 }
 
-/// Type: `artist`, `album`, `track`, `playlist`, `show` or `episode`
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString)]
+impl TryFrom<String> for AlbumType {
+    type Error = strum::ParseError;
+
+    fn try_from(album_type: String) -> Result<Self, Self::Error> {
+        album_type.to_ascii_lowercase().parse()
+    }
+}
+
+/// Album group: `album`, `single`, `compilation`, `appears_on`
+///
+/// The relationship between an artist and the albums returned by
+/// [`BaseClient::artist_albums`](crate::endpoints::BaseClient::artist_albums),
+/// which differs from the [`AlbumType`] for the albums the artist only
+/// appears on. It's deserialized regardless of its case too.
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-albums)
+#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString, EnumString)]
+#[serde(rename_all = "snake_case", try_from = "String")]
+#[strum(serialize_all = "snake_case")]
+pub enum AlbumGroup {
+    Album,
+    Single,
+    Compilation,
+    AppearsOn,
+}
+
+impl TryFrom<String> for AlbumGroup {
+    type Error = strum::ParseError;
+
+    fn try_from(album_group: String) -> Result<Self, Self::Error> {
+        album_group.to_ascii_lowercase().parse()
+    }
+}
+
+/// Type: `artist`, `album`, `track`, `playlist`, `user`, `show`, `episode`
+/// or `audiobook`
+#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString, EnumString)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Type {
@@ -40,6 +79,36 @@ pub enum Type {
     User,
     Show,
     Episode,
+    Audiobook,
+}
+
+/// Context type: `album`, `artist`, `playlist`, `show`, or `collection` for
+/// the user's saved tracks
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-contextobject)
+#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, ToString, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ContextType {
+    Album,
+    Artist,
+    Playlist,
+    Show,
+    Collection,
+}
+
+impl ContextType {
+    /// The type of the object played, or `None` for the collection, which
+    /// has no ID.
+    pub fn object_type(&self) -> Option<Type> {
+        match self {
+            ContextType::Album => Some(Type::Album),
+            ContextType::Artist => Some(Type::Artist),
+            ContextType::Playlist => Some(Type::Playlist),
+            ContextType::Show => Some(Type::Show),
+            ContextType::Collection => None,
+        }
+    }
 }
 
 /// Additional typs: `track`, `episode`
@@ -120,11 +189,16 @@ pub enum DeviceType {
 
 /// Recommendations seed type
 ///
+/// Spotify sends it in uppercase, like `ARTIST`, which is also accepted.
+///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/object-model/#recommendations-seed-object)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum RecommendationsSeedType {
+    #[serde(alias = "ARTIST")]
     Artist,
+    #[serde(alias = "TRACK")]
     Track,
+    #[serde(alias = "GENRE")]
     Genre,
 }
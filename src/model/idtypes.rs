@@ -0,0 +1,586 @@
+//! Parsing and validation of Spotify IDs, URIs and URLs
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::model::Type;
+
+use std::fmt;
+use std::str::FromStr;
+
+use url::Url;
+
+/// The reasons an ID may be rejected before making a request.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum IdError {
+    /// The URI or URL is for a different kind of object, like an album URI
+    /// where a track was expected.
+    #[error("expected an id of type {expected:?}, but found {found}")]
+    InvalidType { expected: Type, found: String },
+
+    /// The ID has characters not allowed for its type, which for everything
+    /// but users means it's not base-62.
+    #[error("invalid id: {0:?}")]
+    InvalidId(String),
+
+    /// The URI or URL is for a kind of object that has no [`Type`], like an
+    /// artist's concerts.
+    #[error("unknown id type: {0:?}")]
+    UnknownType(String),
+
+    /// A bare ID was given without saying which type it is.
+    #[error("the type of the id {0:?} is unknown")]
+    MissingType(String),
+
+    /// The input isn't a Spotify URI, nor an `open.spotify.com` URL.
+    #[error("invalid uri: {0:?}")]
+    InvalidUri(String),
+
+    /// The URI is of a local file added to a playlist, like
+    /// `spotify:local:{artist}:{album}:{title}:{duration}`, which has no ID
+    /// and can't be added, removed or played through the API.
+    #[error("local files can't be used through the api: {0:?}")]
+    LocalFile(String),
+}
+
+/// The prefix of the URIs of the local files.
+const LOCAL_URI_PREFIX: &str = "spotify:local:";
+
+/// Any kind of ID, which knows the type of the object it identifies.
+///
+/// It's refined by [`PlayableId`] and [`PlayContextId`], so that the playback
+/// endpoints only accept the kinds of IDs they can play.
+pub trait AnyId: Send + Sync {
+    /// The type of the object.
+    fn _type(&self) -> Type;
+
+    /// The bare ID, which can be given to the endpoints.
+    fn id(&self) -> &str;
+
+    /// The URI, like `spotify:track:<id>`.
+    fn to_uri(&self) -> String {
+        format!("spotify:{}:{}", self._type().to_string(), self.id())
+    }
+
+    /// The URL to open it in the browser, like
+    /// `https://open.spotify.com/track/<id>`.
+    fn to_open_url(&self) -> String {
+        format!(
+            "https://open.spotify.com/{}/{}",
+            self._type().to_string(),
+            self.id()
+        )
+    }
+
+    /// The URL of the embedded player, to be used as the source of an
+    /// `<iframe>`, like `https://open.spotify.com/embed/track/<id>`.
+    fn to_embed_url(&self) -> String {
+        format!(
+            "https://open.spotify.com/embed/{}/{}",
+            self._type().to_string(),
+            self.id()
+        )
+    }
+}
+
+/// The IDs of the items that can be played and queued: tracks and episodes.
+pub trait PlayableId: AnyId {}
+
+/// The IDs of the contexts that can be played: albums, artists, playlists and
+/// shows.
+pub trait PlayContextId: AnyId {}
+
+/// Defines an ID of a single type, parsed with [`parse_id`].
+macro_rules! define_ids {
+    ($($(#[$attr:meta])* $name:ident => $type:ident: $($kind:ident),*;)+) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+            pub struct $name(String);
+
+            impl $name {
+                /// Parses the ID from a URI, a URL or a bare ID, checking
+                /// that it's of this type and valid.
+                pub fn from_id_or_uri(input: &str) -> Result<Self, IdError> {
+                    parse_id(Type::$type, input).map($name)
+                }
+
+                /// The ID of an object returned by Spotify, which is known
+                /// to be valid.
+                pub(in crate) fn from_model(id: &str) -> Self {
+                    $name(id.to_owned())
+                }
+            }
+
+            impl AnyId for $name {
+                fn _type(&self) -> Type {
+                    Type::$type
+                }
+
+                fn id(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            $(impl $kind for $name {})*
+
+            impl FromStr for $name {
+                type Err = IdError;
+
+                fn from_str(input: &str) -> Result<Self, Self::Err> {
+                    Self::from_id_or_uri(input)
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str(&self.to_uri())
+                }
+            }
+
+            impl From<$name> for SpotifyId {
+                fn from(id: $name) -> Self {
+                    SpotifyId::$type(id.0)
+                }
+            }
+        )+
+    };
+}
+
+define_ids! {
+    /// The ID of an artist.
+    ArtistId => Artist: PlayContextId;
+    /// The ID of an album.
+    AlbumId => Album: PlayContextId;
+    /// The ID of a track.
+    TrackId => Track: PlayableId;
+    /// The ID of a playlist.
+    PlaylistId => Playlist: PlayContextId;
+    /// The ID of a user.
+    UserId => User: ;
+    /// The ID of a show.
+    ShowId => Show: PlayContextId;
+    /// The ID of an episode.
+    EpisodeId => Episode: PlayableId;
+    /// The ID of an audiobook.
+    AudiobookId => Audiobook: ;
+}
+
+/// The ID of either a track or an episode, for when any playable item is
+/// stored, like the one the playback starts from in an
+/// [`Offset`](crate::model::Offset).
+///
+/// It's serialized as its URI.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PlayableItemId {
+    Track(TrackId),
+    Episode(EpisodeId),
+}
+
+impl AnyId for PlayableItemId {
+    fn _type(&self) -> Type {
+        match self {
+            PlayableItemId::Track(id) => id._type(),
+            PlayableItemId::Episode(id) => id._type(),
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            PlayableItemId::Track(id) => id.id(),
+            PlayableItemId::Episode(id) => id.id(),
+        }
+    }
+}
+
+impl PlayableId for PlayableItemId {}
+
+impl From<TrackId> for PlayableItemId {
+    fn from(id: TrackId) -> Self {
+        PlayableItemId::Track(id)
+    }
+}
+
+impl From<EpisodeId> for PlayableItemId {
+    fn from(id: EpisodeId) -> Self {
+        PlayableItemId::Episode(id)
+    }
+}
+
+impl FromStr for PlayableItemId {
+    type Err = IdError;
+
+    /// Parses the URI or URL of a track or an episode. Bare IDs are rejected,
+    /// since their type can't be known.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match parse_uri(input, None)? {
+            SpotifyId::Track(id) => Ok(PlayableItemId::Track(TrackId(id))),
+            SpotifyId::Episode(id) => Ok(PlayableItemId::Episode(EpisodeId(id))),
+            other => Err(IdError::InvalidType {
+                expected: Type::Track,
+                found: other._type().to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for PlayableItemId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_uri())
+    }
+}
+
+impl Serialize for PlayableItemId {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PlayableItemId {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let uri = String::deserialize(d)?;
+        uri.parse().map_err(de::Error::custom)
+    }
+}
+
+/// An ID along with the type of the object it identifies, as returned by
+/// [`parse_uri`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SpotifyId {
+    Artist(String),
+    Album(String),
+    Track(String),
+    Playlist(String),
+    User(String),
+    Show(String),
+    Episode(String),
+    Audiobook(String),
+}
+
+impl SpotifyId {
+    /// The ID of the given type, checking that it's valid.
+    pub fn new(_type: Type, id: &str) -> Result<Self, IdError> {
+        if !is_valid_id(_type, id) {
+            return Err(IdError::InvalidId(id.to_owned()));
+        }
+
+        let id = id.to_owned();
+        Ok(match _type {
+            Type::Artist => SpotifyId::Artist(id),
+            Type::Album => SpotifyId::Album(id),
+            Type::Track => SpotifyId::Track(id),
+            Type::Playlist => SpotifyId::Playlist(id),
+            Type::User => SpotifyId::User(id),
+            Type::Show => SpotifyId::Show(id),
+            Type::Episode => SpotifyId::Episode(id),
+            Type::Audiobook => SpotifyId::Audiobook(id),
+        })
+    }
+}
+
+impl AnyId for SpotifyId {
+    fn _type(&self) -> Type {
+        match self {
+            SpotifyId::Artist(_) => Type::Artist,
+            SpotifyId::Album(_) => Type::Album,
+            SpotifyId::Track(_) => Type::Track,
+            SpotifyId::Playlist(_) => Type::Playlist,
+            SpotifyId::User(_) => Type::User,
+            SpotifyId::Show(_) => Type::Show,
+            SpotifyId::Episode(_) => Type::Episode,
+            SpotifyId::Audiobook(_) => Type::Audiobook,
+        }
+    }
+
+    fn id(&self) -> &str {
+        match self {
+            SpotifyId::Artist(id)
+            | SpotifyId::Album(id)
+            | SpotifyId::Track(id)
+            | SpotifyId::Playlist(id)
+            | SpotifyId::User(id)
+            | SpotifyId::Show(id)
+            | SpotifyId::Episode(id)
+            | SpotifyId::Audiobook(id) => id,
+        }
+    }
+}
+
+impl fmt::Display for SpotifyId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_uri())
+    }
+}
+
+/// Parses a Spotify URI like `spotify:track:<id>`, a shared URL like
+/// `https://open.spotify.com/track/<id>?si=...`, or a bare ID, with its query
+/// and any surrounding whitespace ignored, as pasted by users.
+///
+/// The type of bare IDs must be given in `_type`. If it's also given for URIs
+/// or URLs, they're checked to be of that type.
+pub fn parse_uri(input: &str, _type: Option<Type>) -> Result<SpotifyId, IdError> {
+    let input = input.trim();
+    if input.starts_with(LOCAL_URI_PREFIX) {
+        return Err(IdError::LocalFile(input.to_owned()));
+    }
+    // The URIs may also be separated with slashes, like
+    // `spotify/track/<id>`.
+    let uri_sep = [':', '/']
+        .iter()
+        .copied()
+        .find(|sep| input.starts_with(&format!("spotify{}", sep)));
+    let (found, id) = if let Some(sep) = uri_sep {
+        // Old playlist URIs are prefixed with their owner, like
+        // `spotify:user:<user>:playlist:<id>`.
+        let fields = input.split(sep).collect::<Vec<_>>();
+        match fields.len() {
+            len if len >= 3 => (fields[len - 2].to_owned(), fields[len - 1].to_owned()),
+            _ => return Err(IdError::InvalidUri(input.to_owned())),
+        }
+    } else if input.contains('/') {
+        parse_url(input)?
+    } else {
+        match _type {
+            Some(_type) => return SpotifyId::new(_type, input),
+            None => return Err(IdError::MissingType(input.to_owned())),
+        }
+    };
+
+    let found_type = found
+        .parse::<Type>()
+        .map_err(|_| IdError::UnknownType(found.clone()))?;
+    if let Some(expected) = _type {
+        if expected != found_type {
+            return Err(IdError::InvalidType { expected, found });
+        }
+    }
+    SpotifyId::new(found_type, &id)
+}
+
+/// The type and ID in the path of a shared URL, which may also include the
+/// language, like `https://open.spotify.com/intl-es/track/<id>`, or be for
+/// the embedded player, like `https://open.spotify.com/embed/track/<id>`.
+fn parse_url(input: &str) -> Result<(String, String), IdError> {
+    let invalid = || IdError::InvalidUri(input.to_owned());
+    let url = if input.starts_with("http://") || input.starts_with("https://") {
+        Url::parse(input)
+    } else {
+        Url::parse(&format!("https://{}", input))
+    }
+    .map_err(|_| invalid())?;
+
+    if !matches!(
+        url.host_str(),
+        Some("open.spotify.com") | Some("play.spotify.com")
+    ) {
+        return Err(invalid());
+    }
+
+    let segments = url
+        .path_segments()
+        .ok_or_else(invalid)?
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    match segments.len() {
+        len if len >= 2 => {
+            let id = percent_encoding::percent_decode_str(segments[len - 1])
+                .decode_utf8()
+                .map_err(|_| invalid())?;
+            Ok((segments[len - 2].to_owned(), id.into_owned()))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Whether the ID is valid for the given type. All IDs are base-62, except
+/// for the user ones, which can be any non-empty string.
+pub fn is_valid_id(_type: Type, id: &str) -> bool {
+    match _type {
+        Type::User => !id.is_empty(),
+        _ => !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
+
+/// Returns the ID in a Spotify URI like `spotify:track:<id>`, a URL like
+/// `https://open.spotify.com/track/<id>`, or the ID itself, checking that it
+/// is of the given type and valid. It's parsed like in [`parse_uri`].
+pub fn parse_id(_type: Type, id: &str) -> Result<String, IdError> {
+    parse_uri(id, Some(_type)).map(|id| id.id().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_id() {
+        let id = "4iV5W9uYEdYUVa79Axb7Rh";
+        assert_eq!(parse_id(Type::Track, id), Ok(id.to_owned()));
+        assert_eq!(
+            parse_id(Type::Track, "spotify:track:4iV5W9uYEdYUVa79Axb7Rh"),
+            Ok(id.to_owned())
+        );
+        assert_eq!(
+            parse_id(
+                Type::Track,
+                "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh"
+            ),
+            Ok(id.to_owned())
+        );
+        assert_eq!(
+            parse_id(
+                Type::Track,
+                "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh?si=1"
+            ),
+            Ok(id.to_owned())
+        );
+        assert!(matches!(
+            parse_id(Type::Album, "spotify:track:4iV5W9uYEdYUVa79Axb7Rh"),
+            Err(IdError::InvalidType { .. })
+        ));
+        assert_eq!(
+            parse_id(Type::Track, "4iV5W9uY?market=ES"),
+            Err(IdError::InvalidId("4iV5W9uY?market=ES".to_owned()))
+        );
+        assert_eq!(parse_id(Type::User, "john.doe"), Ok("john.doe".to_owned()));
+
+        // The same links as in `parse_uri` are accepted.
+        for link in &[
+            " https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh/ ",
+            "https://open.spotify.com/intl-es/track/4iV5W9uYEdYUVa79Axb7Rh#t=1",
+            "open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh?si=1",
+        ] {
+            assert_eq!(parse_id(Type::Track, link), Ok(id.to_owned()));
+        }
+        assert_eq!(
+            parse_id(Type::User, "https://open.spotify.com/user/john%20doe"),
+            Ok("john doe".to_owned())
+        );
+        assert!(matches!(
+            parse_id(
+                Type::Track,
+                "https://example.com/track/4iV5W9uYEdYUVa79Axb7Rh"
+            ),
+            Err(IdError::InvalidUri(_))
+        ));
+        let local = "spotify:local:The+Band:Demos:First+Take:182";
+        assert_eq!(
+            parse_id(Type::Track, local),
+            Err(IdError::LocalFile(local.to_owned()))
+        );
+        assert_eq!(
+            parse_uri(local, None),
+            Err(IdError::LocalFile(local.to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_typed_ids() {
+        let track = TrackId::from_id_or_uri("spotify:track:4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+        assert_eq!(track.id(), "4iV5W9uYEdYUVa79Axb7Rh");
+        assert_eq!(track.to_string(), "spotify:track:4iV5W9uYEdYUVa79Axb7Rh");
+        assert_eq!(
+            "https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ?si=1".parse::<EpisodeId>(),
+            Ok(EpisodeId("512ojhOuo1ktJprKbVcKyQ".to_owned()))
+        );
+        assert_eq!(
+            "spotify:audiobook:7iHfbu1YPACw6oZPAFJtqe".parse::<AudiobookId>(),
+            Ok(AudiobookId("7iHfbu1YPACw6oZPAFJtqe".to_owned()))
+        );
+        assert!(matches!(
+            AlbumId::from_id_or_uri("spotify:track:4iV5W9uYEdYUVa79Axb7Rh"),
+            Err(IdError::InvalidType { .. })
+        ));
+
+        // Tracks and episodes can be mixed as playable items.
+        let episode = EpisodeId::from_id_or_uri("512ojhOuo1ktJprKbVcKyQ").unwrap();
+        let items: [&dyn PlayableId; 2] = [&track, &episode];
+        let uris = items.iter().map(|item| item.to_uri()).collect::<Vec<_>>();
+        assert_eq!(
+            uris,
+            [
+                "spotify:track:4iV5W9uYEdYUVa79Axb7Rh",
+                "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+            ]
+        );
+        assert_eq!(
+            "spotify:episode:512ojhOuo1ktJprKbVcKyQ".parse::<PlayableItemId>(),
+            Ok(PlayableItemId::Episode(episode))
+        );
+        assert!(matches!(
+            "spotify:album:4iV5W9uYEdYUVa79Axb7Rh".parse::<PlayableItemId>(),
+            Err(IdError::InvalidType { .. })
+        ));
+        assert_eq!(
+            SpotifyId::from(track),
+            SpotifyId::Track("4iV5W9uYEdYUVa79Axb7Rh".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_uri() {
+        let track = SpotifyId::Track("4iV5W9uYEdYUVa79Axb7Rh".to_owned());
+        for input in &[
+            "spotify:track:4iV5W9uYEdYUVa79Axb7Rh",
+            "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh?si=a1b2c3d4e5f6",
+            "  http://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh/\n",
+            "open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh#time",
+            "https://open.spotify.com/intl-es/track/4iV5W9uYEdYUVa79Axb7Rh?si=1&nd=1",
+            "https://open.spotify.com/embed/track/4iV5W9uYEdYUVa79Axb7Rh",
+        ] {
+            assert_eq!(parse_uri(input, None).as_ref(), Ok(&track), "{}", input);
+            assert_eq!(parse_uri(input, Some(Type::Track)).as_ref(), Ok(&track));
+        }
+        assert_eq!(
+            parse_uri("4iV5W9uYEdYUVa79Axb7Rh", Some(Type::Track)),
+            Ok(track.clone())
+        );
+        assert_eq!(track.to_uri(), "spotify:track:4iV5W9uYEdYUVa79Axb7Rh");
+        assert_eq!(
+            track.to_open_url(),
+            "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh"
+        );
+        assert_eq!(
+            track.to_embed_url(),
+            "https://open.spotify.com/embed/track/4iV5W9uYEdYUVa79Axb7Rh"
+        );
+
+        assert_eq!(
+            parse_uri("spotify:user:wizzler:playlist:3cEYpjA9oz9GiPac4AsH4n", None),
+            Ok(SpotifyId::Playlist("3cEYpjA9oz9GiPac4AsH4n".to_owned()))
+        );
+        assert_eq!(
+            parse_uri("https://open.spotify.com/user/john.doe%40mail", None),
+            Ok(SpotifyId::User("john.doe@mail".to_owned()))
+        );
+
+        assert_eq!(
+            parse_uri("4iV5W9uYEdYUVa79Axb7Rh", None),
+            Err(IdError::MissingType("4iV5W9uYEdYUVa79Axb7Rh".to_owned()))
+        );
+        assert!(matches!(
+            parse_uri("spotify:album:4iV5W9uYEdYUVa79Axb7Rh", Some(Type::Track)),
+            Err(IdError::InvalidType { .. })
+        ));
+        assert_eq!(
+            parse_uri(
+                "https://open.spotify.com/concert/4iV5W9uYEdYUVa79Axb7Rh",
+                None
+            ),
+            Err(IdError::UnknownType("concert".to_owned()))
+        );
+        assert!(matches!(
+            parse_uri("https://example.com/track/4iV5W9uYEdYUVa79Axb7Rh", None),
+            Err(IdError::InvalidUri(_))
+        ));
+        assert!(matches!(
+            parse_uri("spotify:track:4iV5W9uY-EdY", None),
+            Err(IdError::InvalidId(_))
+        ));
+    }
+}
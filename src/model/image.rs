@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-imageobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Image {
     pub height: Option<u32>,
     pub url: String,
@@ -0,0 +1,84 @@
+//! Compact versions of the catalog objects, with only their IDs, names and a
+//! few key fields, for the consumers that process millions of them.
+//!
+//! They're deserialized from the same responses as the full models, but the
+//! rest of the fields are skipped instead of allocated, so they're cheaper to
+//! deserialize and to keep in memory. They never reject unknown fields, even
+//! with the `strict-models` feature.
+//!
+//! Note: this module requires the `lite-models` feature.
+use serde::{Deserialize, Serialize};
+
+use std::time::Duration;
+
+use crate::model::duration_ms;
+use crate::model::{AlbumId, AlbumType, ArtistId, TrackId};
+
+/// Compact artist object, from the full and simplified ones.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LiteArtist {
+    pub id: Option<String>,
+    pub name: String,
+}
+
+impl LiteArtist {
+    /// The ID of the artist, to build its URI or links to share it, or `None`
+    /// for the artists of local tracks.
+    pub fn artist_id(&self) -> Option<ArtistId> {
+        self.id.as_deref().map(ArtistId::from_model)
+    }
+}
+
+/// Compact album object, from the full and simplified ones.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LiteAlbum {
+    pub album_type: Option<AlbumType>,
+    pub artists: Vec<LiteArtist>,
+    pub id: Option<String>,
+    pub name: String,
+}
+
+impl LiteAlbum {
+    /// The ID of the album, to build its URI or links to share it, or `None`
+    /// for the albums of local tracks.
+    pub fn album_id(&self) -> Option<AlbumId> {
+        self.id.as_deref().map(AlbumId::from_model)
+    }
+}
+
+/// Compact track object, from the full one.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LiteTrack {
+    pub album: LiteAlbum,
+    pub artists: Vec<LiteArtist>,
+    #[serde(with = "duration_ms", rename = "duration_ms")]
+    pub duration: Duration,
+    pub id: Option<String>,
+    pub name: String,
+}
+
+impl LiteTrack {
+    /// The ID of the track, to build its URI or links to share it, or `None`
+    /// for local tracks.
+    pub fn track_id(&self) -> Option<TrackId> {
+        self.id.as_deref().map(TrackId::from_model)
+    }
+}
+
+/// Compact tracks wrapped by `Vec`
+#[derive(Deserialize)]
+pub(in crate) struct LiteTracks {
+    pub tracks: Vec<LiteTrack>,
+}
+
+/// Compact artists wrapped by `Vec`
+#[derive(Deserialize)]
+pub(in crate) struct LiteArtists {
+    pub artists: Vec<LiteArtist>,
+}
+
+/// Compact albums wrapped by `Vec`
+#[derive(Deserialize)]
+pub(in crate) struct LiteAlbums {
+    pub albums: Vec<LiteAlbum>,
+}
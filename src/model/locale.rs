@@ -0,0 +1,172 @@
+//! The locales of the localized responses, like the ones of the browse
+//! endpoints.
+use serde::de::{self, IntoDeserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::model::Country;
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// The reason a [`Locale`] is rejected before making a request.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error(
+    "invalid locale {0:?}: expected a lowercase ISO 639-1 language code and an \
+     ISO 3166-1 alpha-2 country code, joined by an underscore like `es_MX`"
+)]
+pub struct LocaleError(pub String);
+
+/// A language and the country whose variant of it is used, like `es_MX` for
+/// the Spanish of Mexico, as expected by the `locale` parameter of the browse
+/// endpoints.
+///
+/// It's validated when parsed, so the endpoints never receive one that
+/// Spotify would ignore:
+///
+/// ```
+/// use rspotify::model::{Country, Locale};
+///
+/// let locale: Locale = "es_MX".parse().unwrap();
+/// assert_eq!(locale.language(), "es");
+/// assert_eq!(locale.country(), Country::Mexico);
+/// assert_eq!(locale.to_string(), "es_MX");
+/// assert_eq!(locale.language_tag(), "es-MX");
+/// assert!("es-MX".parse::<Locale>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Locale {
+    language: String,
+    country: Country,
+}
+
+impl Locale {
+    /// The locale of the language in the country, where `language` is an
+    /// ISO 639-1 code like `es`.
+    pub fn new(language: &str, country: Country) -> Result<Self, LocaleError> {
+        let valid = language.len() == 2 && language.bytes().all(|b| b.is_ascii_lowercase());
+        if !valid {
+            return Err(LocaleError(format!("{}_{}", language, country.to_string())));
+        }
+
+        Ok(Locale {
+            language: language.to_owned(),
+            country,
+        })
+    }
+
+    /// The locale of the user from the environment, for command-line apps.
+    /// It's read from `LC_ALL`, or from `LANG` if it isn't set, like
+    /// `es_MX.UTF-8`, ignoring the encoding and the modifier.
+    ///
+    /// `None` is returned if neither is set, or if the locale has no
+    /// country, like `C` or `POSIX`.
+    pub fn from_env() -> Option<Self> {
+        let value = ["LC_ALL", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())?;
+        Locale::from_posix(&value)
+    }
+
+    /// Parses a POSIX locale like `es_MX.UTF-8@euro`.
+    fn from_posix(value: &str) -> Option<Self> {
+        let end = value.find(&['.', '@'][..]).unwrap_or(value.len());
+        value[..end].parse().ok()
+    }
+
+    /// The ISO 639-1 code of the language, like `es`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The country whose variant of the language is used.
+    pub fn country(&self) -> Country {
+        self.country
+    }
+
+    /// The locale as a language tag, like `es-MX`, which is how
+    /// [`Spotify::language`](crate::client::Spotify::language) expects it.
+    pub fn language_tag(&self) -> String {
+        format!("{}-{}", self.language, self.country.to_string())
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}_{}", self.language, self.country.to_string())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = LocaleError;
+
+    fn from_str(locale: &str) -> Result<Self, Self::Err> {
+        let invalid = || LocaleError(locale.to_owned());
+        let mut parts = locale.splitn(2, '_');
+        let language = parts.next().ok_or_else(invalid)?;
+        let country = parts.next().ok_or_else(invalid)?;
+        let country: Result<Country, de::value::Error> =
+            Country::deserialize(country.into_deserializer());
+        let country = country.map_err(|_| invalid())?;
+        Locale::new(language, country).map_err(|_| invalid())
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let locale = String::deserialize(d)?;
+        locale.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale() {
+        let locale: Locale = "en_US".parse().unwrap();
+        assert_eq!(locale, Locale::new("en", Country::UnitedStates).unwrap());
+        for invalid in &[
+            "en",
+            "en_",
+            "EN_US",
+            "en_us",
+            "en-US",
+            "eng_US",
+            "en_US_POSIX",
+        ] {
+            assert_eq!(
+                invalid.parse::<Locale>(),
+                Err(LocaleError(invalid.to_string()))
+            );
+        }
+        assert!(Locale::new("e1", Country::Spain).is_err());
+    }
+
+    #[test]
+    fn test_posix_locale() {
+        let locale = Locale::from_posix("pt_BR.UTF-8").unwrap();
+        assert_eq!(locale.to_string(), "pt_BR");
+        assert_eq!(
+            Locale::from_posix("de_DE@euro").map(|locale| locale.to_string()),
+            Some("de_DE".to_owned())
+        );
+        assert_eq!(Locale::from_posix("C.UTF-8"), None);
+        assert_eq!(Locale::from_posix("POSIX"), None);
+    }
+}
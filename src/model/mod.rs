@@ -1,12 +1,54 @@
 //! All Spotify API endpoint response object
+//!
+//! The objects can be serialized too, and deserializing what was serialized
+//! gives back the same model, so that the fetched objects can be persisted,
+//! like to disk or a database, and reloaded later.
+//!
+//! The serde helpers used by the models, like [`duration_ms`] and
+//! [`modality`], are public too, so that custom models with only some of the
+//! fields can be deserialized the same way, for example to use with
+//! [`BaseClient::endpoint_get_as`](crate::endpoints::BaseClient::endpoint_get_as):
+//!
+//! ```
+//! use rspotify::model::{duration_ms, html_unescaped};
+//! use serde::Deserialize;
+//! use std::time::Duration;
+//!
+//! #[derive(Deserialize)]
+//! struct TrackLength {
+//!     name: String,
+//!     #[serde(with = "duration_ms", rename = "duration_ms")]
+//!     duration: Duration,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct PlaylistDescription {
+//!     #[serde(deserialize_with = "html_unescaped::deserialize")]
+//!     description: String,
+//! }
+//!
+//! let track: TrackLength =
+//!     serde_json::from_str(r#"{"name": "Never Gonna Give You Up", "duration_ms": 213573}"#)
+//!         .unwrap();
+//! assert_eq!(track.duration, Duration::from_millis(213573));
+//! let playlist: PlaylistDescription =
+//!     serde_json::from_str(r#"{"description": "Rock &amp; roll"}"#).unwrap();
+//! assert_eq!(playlist.description, "Rock & roll");
+//! ```
 pub mod album;
 pub mod artist;
 pub mod audio;
+pub mod audiobook;
 pub mod category;
 pub mod context;
+pub mod date;
 pub mod device;
 pub mod enums;
+pub mod idtypes;
 pub mod image;
+#[cfg(feature = "lite-models")]
+pub mod lite;
+pub mod locale;
 pub mod offset;
 pub mod page;
 pub mod playing;
@@ -16,9 +58,13 @@ pub mod search;
 pub mod show;
 pub mod track;
 pub mod user;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 
-pub(in crate) mod duration_ms {
+use std::collections::HashMap;
+
+/// Deserialize/Serialize `std::time::Duration` to milliseconds, like the
+/// `duration_ms` fields.
+pub mod duration_ms {
     use serde::{de, Serializer};
     use std::{fmt, time::Duration};
 
@@ -39,7 +85,7 @@ pub(in crate) mod duration_ms {
     }
 
     /// Deserialize `std::time::Duration` from milliseconds (represented as u64)
-    pub(in crate) fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
+    pub fn deserialize<'de, D>(d: D) -> Result<Duration, D::Error>
     where
         D: de::Deserializer<'de>,
     {
@@ -47,14 +93,16 @@ pub(in crate) mod duration_ms {
     }
 
     /// Serialize `std::time::Duration` to milliseconds (represented as u64)
-    pub(in crate) fn serialize<S>(x: &Duration, s: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<S>(x: &Duration, s: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         s.serialize_u64(x.as_millis() as u64)
     }
 }
-pub(in crate) mod millisecond_timestamp {
+/// Deserialize/Serialize `DateTime<Utc>` to Unix millisecond timestamps, like
+/// the `timestamp` of the playback.
+pub mod millisecond_timestamp {
     use chrono::{DateTime, NaiveDateTime, Utc};
     use serde::{de, Serializer};
     use std::fmt;
@@ -88,7 +136,7 @@ pub(in crate) mod millisecond_timestamp {
     }
 
     /// Deserialize Unix millisecond timestamp to `DateTime<Utc>`
-    pub(in crate) fn deserialize<'de, D>(d: D) -> Result<DateTime<Utc>, D::Error>
+    pub fn deserialize<'de, D>(d: D) -> Result<DateTime<Utc>, D::Error>
     where
         D: de::Deserializer<'de>,
     {
@@ -96,14 +144,16 @@ pub(in crate) mod millisecond_timestamp {
     }
 
     /// Serialize DateTime<Utc> to Unix millisecond timestamp
-    pub(in crate) fn serialize<S>(x: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<S>(x: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         s.serialize_i64(x.timestamp_millis())
     }
 }
-pub(in crate) mod option_duration_ms {
+/// Deserialize/Serialize `Option<std::time::Duration>` to milliseconds, like
+/// the `progress_ms` of the playback.
+pub mod option_duration_ms {
     use super::duration_ms;
     use serde::{de, Serializer};
     use std::{fmt, time::Duration};
@@ -139,7 +189,7 @@ pub(in crate) mod option_duration_ms {
 
     /// Deserialize `Option<std::time::Duration>` from milliseconds
     /// (represented as u64)
-    pub(in crate) fn deserialize<'de, D>(d: D) -> Result<Option<Duration>, D::Error>
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<Duration>, D::Error>
     where
         D: de::Deserializer<'de>,
     {
@@ -148,7 +198,7 @@ pub(in crate) mod option_duration_ms {
 
     /// Serialize `Option<std::time::Duration>` to milliseconds (represented as
     /// u64)
-    pub(in crate) fn serialize<S>(x: &Option<Duration>, s: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<S>(x: &Option<Duration>, s: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
@@ -158,8 +208,172 @@ pub(in crate) mod option_duration_ms {
         }
     }
 }
+/// Deserialize the optional text that Spotify may return empty, null or not
+/// at all indistinctly, like the `preview_url` of the tracks or the `email`
+/// of the users, as `None` in all the cases. It must be used along with
+/// `#[serde(default)]` for the missing fields.
+pub mod empty_as_none {
+    use serde::{Deserialize, Deserializer};
+
+    /// Deserialize the text, or `None` if it's empty or null.
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = Option::<String>::deserialize(d)?;
+        Ok(text.filter(|text| !text.is_empty()))
+    }
+}
+/// Deserialize the text obtained from HTML, like the descriptions of the
+/// playlists, with its character references unescaped, and serialize it
+/// escaped back so that it's deserialized the same.
+pub mod html_unescaped {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// Deserialize the text unescaped, or empty if it's null, which is only
+    /// rejected with the `strict-models` feature.
+    pub fn deserialize<'de, D>(d: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = Option::<String>::deserialize(d)?;
+        if cfg!(feature = "strict-models") && text.is_none() {
+            return Err(de::Error::invalid_type(de::Unexpected::Option, &"a string"));
+        }
+
+        Ok(text.map(|text| unescape(&text)).unwrap_or_default())
+    }
+
+    /// Deserialize the text unescaped, if it isn't null.
+    pub fn deserialize_option<'de, D>(d: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = Option::<String>::deserialize(d)?;
+        Ok(text.map(|text| unescape(&text)))
+    }
+
+    /// Serialize the text with its ampersands escaped, which is enough for
+    /// [`unescape`] to give it back unchanged.
+    pub fn serialize<S>(text: &str, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&escape(text))
+    }
+
+    /// The same as [`serialize`], if it isn't `None`.
+    pub fn serialize_option<S>(text: &Option<String>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match text {
+            Some(text) => s.serialize_some(&escape(text)),
+            None => s.serialize_none(),
+        }
+    }
+
+    /// Escapes the ampersands, so that the character references in the
+    /// text aren't unescaped.
+    pub fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+    }
+
+    /// Replaces the named character references that Spotify uses and the
+    /// numeric ones. Anything else, like a lone `&`, is kept as is.
+    pub fn unescape(text: &str) -> String {
+        let mut unescaped = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('&') {
+            unescaped.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let reference = rest[1..]
+                .find(';')
+                .map(|end| &rest[1..=end])
+                .and_then(|name| decode_reference(name).map(|c| (c, name.len() + 2)));
+            match reference {
+                Some((c, len)) => {
+                    unescaped.push(c);
+                    rest = &rest[len..];
+                }
+                None => {
+                    unescaped.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        unescaped.push_str(rest);
+        unescaped
+    }
+
+    fn decode_reference(name: &str) -> Option<char> {
+        match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = name.strip_prefix('#')?;
+                let code = match code.strip_prefix('x').or_else(|| code.strip_prefix('X')) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                std::char::from_u32(code)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::unescape;
+
+        #[test]
+        fn test_unescape() {
+            assert_eq!(
+                unescape("Rock &amp; roll &#x27;n&#39; &lt;b&gt;blues&lt;/b&gt;"),
+                "Rock & roll 'n' <b>blues</b>"
+            );
+            assert_eq!(
+                unescape("&quot;R&B&quot; &#X2F; &unknown;"),
+                "\"R&B\" / &unknown;"
+            );
+            assert_eq!(unescape("a & b &"), "a & b &");
+            assert_eq!(unescape("&#xZZ; &#1114112;"), "&#xZZ; &#1114112;");
+        }
+    }
+}
+
+/// Deserialize/Serialize `Key` to its pitch class, an integer from 0 to 11,
+/// or -1.
+pub mod pitch_class {
+    use super::enums::Key;
+    use serde::{de, Deserialize, Serializer};
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Key, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let v = i8::deserialize(d)?;
+        Key::from_pitch_class(v).ok_or_else(|| {
+            de::Error::invalid_value(
+                de::Unexpected::Signed(v.into()),
+                &"valid value: from -1 to 11",
+            )
+        })
+    }
+
+    pub fn serialize<S>(x: &Key, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_i8(*x as i8)
+    }
+}
+
 /// Deserialize/Serialize `Modality` to integer(0, 1, -1).
-pub(in crate) mod modality {
+pub mod modality {
     use super::enums::Modality;
     use serde::{de, Deserialize, Serializer};
 
@@ -191,37 +405,322 @@ pub(in crate) mod modality {
     }
 }
 
-/// Restriction object
+/// Restrictions object, present when the content can't be played, on albums,
+/// tracks and episodes
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-albumrestrictionobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Restriction {
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct Restrictions {
     pub reason: RestrictionReason,
 }
 
 /// Followers object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-followersobject)
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Followers {
     // This field will always set to null, as the Web API does not support it at the moment.
     // pub href: Option<String>,
-    pub total: u32,
+    pub total: u64,
+}
+
+/// External IDs object, with the identifiers of an album or track in other
+/// catalogs.
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-externalidobject)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct ExternalIds {
+    /// International Standard Recording Code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isrc: Option<String>,
+    /// International Article Number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ean: Option<String>,
+    /// Universal Product Code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upc: Option<String>,
+}
+
+/// External URLs object, with the links to an object outside of the API.
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-externalurlobject)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalUrls {
+    /// The Spotify URL of the object, missing in some of them like users'
+    /// local files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spotify: Option<String>,
+    /// Any other URL, by the name of the service.
+    #[serde(flatten)]
+    pub others: HashMap<String, String>,
+}
+
+/// The popularity of an album, artist or track, between 0 and 100, with 100
+/// being the most popular.
+///
+/// Greater values are clamped to 100 when deserialized, so that a single
+/// item doesn't fail the whole response, and only rejected with the
+/// `strict-models` feature.
+#[derive(Clone, Copy, Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(into = "u8")]
+pub struct Popularity(u8);
+
+impl Popularity {
+    /// The highest popularity.
+    pub const MAX: Popularity = Popularity(100);
+
+    /// Returns the popularity, or `None` if it's greater than 100.
+    pub fn new(popularity: u8) -> Option<Self> {
+        if popularity <= Self::MAX.0 {
+            Some(Popularity(popularity))
+        } else {
+            None
+        }
+    }
+
+    /// The popularity as a number between 0 and 100.
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl std::convert::TryFrom<u8> for Popularity {
+    type Error = String;
+
+    fn try_from(popularity: u8) -> Result<Self, Self::Error> {
+        Popularity::new(popularity)
+            .ok_or_else(|| format!("popularity {} is greater than 100", popularity))
+    }
 }
 
-/// A full track object or a full episode object
+impl<'de> Deserialize<'de> for Popularity {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let popularity = u64::deserialize(d)?;
+        if cfg!(feature = "strict-models") && popularity > u64::from(Self::MAX.0) {
+            return Err(de::Error::custom(format!(
+                "popularity {} is greater than 100",
+                popularity
+            )));
+        }
+
+        Ok(Popularity(popularity.min(u64::from(Self::MAX.0)) as u8))
+    }
+}
+
+impl From<Popularity> for u8 {
+    fn from(popularity: Popularity) -> u8 {
+        popularity.0
+    }
+}
+
+impl std::fmt::Display for Popularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A full track object, a full episode object or a local file, as found in
+/// the playback state and the queue.
 ///
 /// + [Reference to full track](https://developer.spotify.com/documentation/web-api/reference/#object-trackobject)
 /// + [Reference to full episode](https://developer.spotify.com/documentation/web-api/reference/#object-episodeobject)
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum PlayingItem {
     Track(track::FullTrack),
     Episode(show::FullEpisode),
+    Local(track::LocalTrack),
+}
+
+impl PlayingItem {
+    /// The duration of the track or episode.
+    pub fn duration(&self) -> std::time::Duration {
+        match self {
+            PlayingItem::Track(track) => track.duration,
+            PlayingItem::Episode(episode) => episode.duration,
+            PlayingItem::Local(local) => local.duration,
+        }
+    }
+
+    /// The URI of the item.
+    pub fn uri(&self) -> &str {
+        match self {
+            PlayingItem::Track(track) => &track.uri,
+            PlayingItem::Episode(episode) => &episode.uri,
+            PlayingItem::Local(local) => &local.uri,
+        }
+    }
+}
+
+/// The items that may have explicit content, so that they can be removed
+/// when the user's settings demand it, with [`BaseClient::filter_explicit`
+/// ](crate::endpoints::BaseClient::filter_explicit).
+pub trait Explicit {
+    /// Whether the item has explicit content, or it's unknown.
+    fn is_explicit(&self) -> bool;
+}
+
+macro_rules! impl_explicit {
+    ($($model:ty),+) => {
+        $(
+            impl Explicit for $model {
+                fn is_explicit(&self) -> bool {
+                    self.explicit
+                }
+            }
+        )+
+    };
+}
+
+impl_explicit!(
+    track::FullTrack,
+    track::SimplifiedTrack,
+    show::SimplifiedShow,
+    show::FullShow,
+    show::SimplifiedEpisode,
+    show::FullEpisode
+);
+
+impl Explicit for PlayingItem {
+    /// Local files aren't rated, so they're never explicit.
+    fn is_explicit(&self) -> bool {
+        match self {
+            PlayingItem::Track(track) => track.explicit,
+            PlayingItem::Episode(episode) => episode.explicit,
+            PlayingItem::Local(_) => false,
+        }
+    }
+}
+
+/// The items that may not be playable everywhere, like tracks, which are
+/// licensed per market, or episodes.
+///
+/// When the item was requested with a market, Spotify applies [track
+/// relinking](https://developer.spotify.com/documentation/general/guides/track-relinking-guide/)
+/// and says whether it's playable in that market, which is preferred, so
+/// that same market should be given here. Otherwise, the markets where it's
+/// available are checked instead, and it's assumed to be playable when they
+/// aren't known, like with [`Market::FromToken`].
+pub trait Playable {
+    /// Whether the item can be played in `market`.
+    fn is_playable_in(&self, market: &Market) -> bool;
+}
+
+/// Whether a track with the given fields is playable in `market`.
+fn track_playable_in(
+    is_local: bool,
+    is_playable: Option<bool>,
+    restrictions: Option<&Restrictions>,
+    available_markets: &[String],
+    market: &Market,
+) -> bool {
+    if is_local || restrictions.is_some() {
+        return false;
+    }
+    if let Some(is_playable) = is_playable {
+        return is_playable;
+    }
+
+    match market {
+        Market::Country(country) if !available_markets.is_empty() => {
+            available_markets.contains(&country.to_string())
+        }
+        _ => true,
+    }
+}
+
+impl Playable for track::FullTrack {
+    fn is_playable_in(&self, market: &Market) -> bool {
+        track_playable_in(
+            self.is_local,
+            self.is_playable,
+            self.restrictions.as_ref(),
+            &self.available_markets,
+            market,
+        )
+    }
+}
+
+impl Playable for track::SimplifiedTrack {
+    fn is_playable_in(&self, market: &Market) -> bool {
+        track_playable_in(
+            self.is_local,
+            self.is_playable,
+            self.restrictions.as_ref(),
+            self.available_markets.as_deref().unwrap_or_default(),
+            market,
+        )
+    }
+}
+
+/// The episodes are always given for the market of the request, or the one
+/// of the user, so only their restrictions are checked.
+macro_rules! impl_playable_episode {
+    ($($model:ty),+) => {
+        $(
+            impl Playable for $model {
+                fn is_playable_in(&self, _market: &Market) -> bool {
+                    self.is_playable && self.restrictions.is_none()
+                }
+            }
+        )+
+    };
+}
+
+impl_playable_episode!(show::SimplifiedEpisode, show::FullEpisode);
+
+impl Playable for PlayingItem {
+    /// Local files can only be played from the devices that have them, so
+    /// they're never playable through the API.
+    fn is_playable_in(&self, market: &Market) -> bool {
+        match self {
+            PlayingItem::Track(track) => track.is_playable_in(market),
+            PlayingItem::Episode(episode) => episode.is_playable_in(market),
+            PlayingItem::Local(_) => false,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PlayingItem {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde_json::Value;
+
+        // Dispatched by hand instead of with `untagged` so that the errors
+        // of the actual kind of item are kept. Local files are detected the
+        // same way as in `PlaylistTrack`.
+        let value = Value::deserialize(d)?;
+        let local = value.get("is_local").and_then(Value::as_bool) == Some(true)
+            || value
+                .get("uri")
+                .and_then(Value::as_str)
+                .map(|uri| uri.starts_with("spotify:local:"))
+                == Some(true);
+
+        let item = if local {
+            track::LocalTrack::deserialize(value).map(PlayingItem::Local)
+        } else if value.get("type").and_then(Value::as_str) == Some("episode") {
+            show::FullEpisode::deserialize(value).map(PlayingItem::Episode)
+        } else {
+            track::FullTrack::deserialize(value).map(PlayingItem::Track)
+        };
+        item.map_err(D::Error::custom)
+    }
 }
 
+#[cfg(feature = "lite-models")]
+pub use lite::*;
 pub use {
-    album::*, artist::*, audio::*, category::*, context::*, device::*, enums::*, image::*,
-    offset::*, page::*, playing::*, playlist::*, recommend::*, search::*, show::*, track::*,
-    user::*,
+    album::*, artist::*, audio::*, audiobook::*, category::*, context::*, date::*, device::*,
+    enums::*, idtypes::*, image::*, locale::*, offset::*, page::*, playing::*, playlist::*,
+    recommend::*, search::*, show::*, track::*, user::*,
 };
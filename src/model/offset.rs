@@ -1,29 +1,19 @@
 //! Offset object
-use crate::model::option_duration_ms;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
 
-/// Offset object
+use crate::model::PlayableItemId;
+
+/// Offset object, indicating from where in the context the playback should
+/// start.
+///
+/// It's serialized as `{"position": 5}` or `{"uri": "spotify:track:..."}`.
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-start-a-users-playback)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Offset {
-    #[serde(default)]
-    #[serde(with = "option_duration_ms")]
-    pub position: Option<Duration>,
-    pub uri: Option<String>,
-}
-
-pub fn for_position(position: u64) -> Option<Offset> {
-    Some(Offset {
-        position: Some(Duration::from_millis(position)),
-        uri: None,
-    })
-}
-
-pub fn for_uri(uri: String) -> Option<Offset> {
-    Some(Offset {
-        position: None,
-        uri: Some(uri),
-    })
+#[serde(rename_all = "snake_case")]
+pub enum Offset {
+    /// The zero-based index of the item in the context.
+    Position(u32),
+    /// The track or episode in the context, serialized as its URI.
+    Uri(PlayableItemId),
 }
@@ -1,10 +1,14 @@
 //! All kinds of page object
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
+use std::{slice, vec};
+
 /// Paging object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-pagingobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Page<T> {
     pub href: String,
     pub items: Vec<T>,
@@ -14,10 +18,80 @@ pub struct Page<T> {
     pub previous: Option<String>,
     pub total: u32,
 }
+
+impl<T> Page<T> {
+    /// Whether there's a page after this one.
+    pub fn has_next(&self) -> bool {
+        self.next.is_some()
+    }
+
+    /// The offset of the page after this one, or `None` if it's the last
+    /// one.
+    pub fn next_offset(&self) -> Option<u32> {
+        if !self.has_next() || self.items.is_empty() {
+            None
+        } else {
+            Some(self.offset + self.items.len() as u32)
+        }
+    }
+
+    /// How many pages of the same size are left after this one.
+    pub fn pages_remaining(&self) -> u32 {
+        match self.next_offset() {
+            Some(offset) if self.limit > 0 => {
+                // The last page may not be full.
+                let items = self.total.saturating_sub(offset);
+                let full = items / self.limit;
+                if items % self.limit == 0 {
+                    full
+                } else {
+                    full + 1
+                }
+            }
+            _ => 0,
+        }
+    }
+
+    /// Transforms the items of the page, keeping the paging information.
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            href: self.href,
+            items: self.items.into_iter().map(f).collect(),
+            limit: self.limit,
+            next: self.next,
+            offset: self.offset,
+            previous: self.previous,
+            total: self.total,
+        }
+    }
+}
+
+impl<T> IntoIterator for Page<T> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Page<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
 /// Cursor-based paging object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-cursorpagingobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct CursorBasedPage<T> {
     pub href: String,
     pub items: Vec<T>,
@@ -32,6 +106,30 @@ pub struct CursorBasedPage<T> {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-cursorobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Cursor {
     pub after: Option<String>,
+    /// Only given by the endpoints that can be paged backwards, like the
+    /// recently played tracks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+}
+
+impl Cursor {
+    /// The `after` cursor as a point in time, for the endpoints whose
+    /// cursors are Unix timestamps in milliseconds, like the recently played
+    /// tracks.
+    pub fn after_time(&self) -> Option<DateTime<Utc>> {
+        self.after.as_deref().and_then(parse_timestamp)
+    }
+
+    /// The `before` cursor as a point in time, like [`Cursor::after_time`].
+    pub fn before_time(&self) -> Option<DateTime<Utc>> {
+        self.before.as_deref().and_then(parse_timestamp)
+    }
+}
+
+fn parse_timestamp(millis: &str) -> Option<DateTime<Utc>> {
+    let millis = millis.parse::<i64>().ok()?;
+    Utc.timestamp_millis_opt(millis).single()
 }
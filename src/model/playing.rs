@@ -9,8 +9,30 @@ use super::track::FullTrack;
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-playhistoryobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct PlayHistory {
     pub track: FullTrack,
     pub played_at: DateTime<Utc>,
     pub context: Option<Context>,
 }
+
+/// Limits the plays returned by
+/// [`current_user_recently_played`](crate::client::OAuthClient::current_user_recently_played)
+/// to the ones before or after the given time.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeLimits {
+    Before(DateTime<Utc>),
+    After(DateTime<Utc>),
+}
+
+impl TimeLimits {
+    /// The query parameter and its value, as a Unix timestamp in
+    /// milliseconds.
+    pub fn to_param(&self) -> (&'static str, String) {
+        match self {
+            TimeLimits::Before(time) => ("before", time.timestamp_millis().to_string()),
+            TimeLimits::After(time) => ("after", time.timestamp_millis().to_string()),
+        }
+    }
+}
@@ -1,18 +1,20 @@
 //! All kinds of playlists objects
 use chrono::prelude::*;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 use super::image::Image;
 use super::page::Page;
-use super::track::FullTrack;
+use super::track::{FullTrack, LocalTrack};
 use super::user::PublicUser;
-use crate::model::{Followers, Type};
+use crate::client::{ClientError, PLAYLIST_TRACKS_PAGE_SIZE};
+use crate::model::{html_unescaped, AnyId, ExternalUrls, Followers, PlaylistId, Type};
 
 /// Playlist result object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-add-tracks-to-playlist)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct PlaylistResult {
     pub snapshot_id: String,
 }
@@ -21,6 +23,7 @@ pub struct PlaylistResult {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-playlisttracksrefobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct PlaylistTracksRef {
     pub href: String,
     pub total: u32,
@@ -30,14 +33,25 @@ pub struct PlaylistTracksRef {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedplaylistobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SimplifiedPlaylist {
     pub collaborative: bool,
-    pub external_urls: HashMap<String, String>,
+    /// Only given for modified and verified playlists. It's unescaped, since
+    /// Spotify returns it with HTML character references.
+    #[serde(
+        default,
+        deserialize_with = "html_unescaped::deserialize_option",
+        serialize_with = "html_unescaped::serialize_option"
+    )]
+    pub description: Option<String>,
+    pub external_urls: ExternalUrls,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
     pub name: String,
     pub owner: PublicUser,
+    /// Always `None` at the moment.
+    pub primary_color: Option<String>,
     pub public: Option<bool>,
     pub snapshot_id: String,
     pub tracks: PlaylistTracksRef,
@@ -46,20 +60,44 @@ pub struct SimplifiedPlaylist {
     pub uri: String,
 }
 
+impl SimplifiedPlaylist {
+    /// The ID of the playlist, to build its URI or links to share it.
+    pub fn playlist_id(&self) -> PlaylistId {
+        PlaylistId::from_model(&self.id)
+    }
+
+    /// How many requests it takes to get the whole playlist with
+    /// [`BaseClient::hydrate_playlist`](crate::client::BaseClient::hydrate_playlist),
+    /// as an estimate of its size, since items may be added in the meantime.
+    pub fn hydration_requests(&self) -> u32 {
+        // The first page of items comes with the playlist.
+        let remaining = self.tracks.total.saturating_sub(PLAYLIST_TRACKS_PAGE_SIZE);
+        // The last page may not be full.
+        let partial = (remaining % PLAYLIST_TRACKS_PAGE_SIZE).min(1);
+        1 + remaining / PLAYLIST_TRACKS_PAGE_SIZE + partial
+    }
+}
+
 /// Full playlist object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-playlistobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct FullPlaylist {
     pub collaborative: bool,
+    /// Empty if the playlist doesn't have one. It's unescaped, since Spotify
+    /// returns it with HTML character references.
+    #[serde(with = "html_unescaped")]
     pub description: String,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub followers: Followers,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
     pub name: String,
     pub owner: PublicUser,
+    /// Always `None` at the moment.
+    pub primary_color: Option<String>,
     pub public: Option<bool>,
     pub snapshot_id: String,
     pub tracks: Page<PlaylistItem>,
@@ -68,19 +106,131 @@ pub struct FullPlaylist {
     pub uri: String,
 }
 
+impl FullPlaylist {
+    /// The same as [`SimplifiedPlaylist::playlist_id`].
+    pub fn playlist_id(&self) -> PlaylistId {
+        PlaylistId::from_model(&self.id)
+    }
+
+    /// The same as [`FullTrack::to_uri`].
+    ///
+    /// [`FullTrack::to_uri`]: crate::model::FullTrack::to_uri
+    pub fn to_uri(&self) -> String {
+        self.playlist_id().to_uri()
+    }
+
+    /// The same as [`FullTrack::to_open_url`].
+    ///
+    /// [`FullTrack::to_open_url`]: crate::model::FullTrack::to_open_url
+    pub fn to_open_url(&self) -> String {
+        self.playlist_id().to_open_url()
+    }
+
+    /// The same as [`FullTrack::to_embed_url`].
+    ///
+    /// [`FullTrack::to_embed_url`]: crate::model::FullTrack::to_embed_url
+    pub fn to_embed_url(&self) -> String {
+        self.playlist_id().to_embed_url()
+    }
+}
+
 /// Playlist track object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-playlisttrackobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct PlaylistItem {
+    /// Not available for very old playlists.
     pub added_at: Option<DateTime<Utc>>,
+    /// Not available for very old playlists.
     pub added_by: Option<PublicUser>,
+    #[cfg_attr(not(feature = "strict-models"), serde(default))]
     pub is_local: bool,
-    pub track: Option<FullTrack>,
+    /// Always `None` at the moment.
+    pub primary_color: Option<String>,
+    /// `None` if the track is no longer available.
+    pub track: Option<PlaylistTrack>,
+    pub video_thumbnail: Option<VideoThumbnail>,
+}
+
+/// The thumbnail of a video in a playlist.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
+pub struct VideoThumbnail {
+    pub url: Option<String>,
+}
+
+/// The track inside a playlist item, which may be a local file added by the
+/// user from their computer. Local files have no ID, and most of their
+/// metadata is missing, so they're kept apart from regular tracks.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum PlaylistTrack {
+    Track(FullTrack),
+    Local(LocalTrack),
+}
+
+impl PlaylistTrack {
+    /// The track, unless it's a local file.
+    pub fn as_track(&self) -> Option<&FullTrack> {
+        match self {
+            PlaylistTrack::Track(track) => Some(track),
+            PlaylistTrack::Local(_) => None,
+        }
+    }
+
+    /// Whether it's a local file.
+    pub fn is_local(&self) -> bool {
+        matches!(self, PlaylistTrack::Local(_))
+    }
+
+    /// The URI of the track, which can be given to the endpoints that modify
+    /// the playlist, unless it's a local file. Those fail with
+    /// [`IdError::LocalFile`](crate::model::IdError::LocalFile) instead, so
+    /// they have to be skipped.
+    pub fn uri(&self) -> Option<&str> {
+        self.as_track().map(|track| track.uri.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PlaylistTrack {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Local files are flagged with `is_local`, and their URIs look like
+        // `spotify:local:{artist}:{album}:{title}:{duration}`. The latter is
+        // also checked because serialized local tracks don't include the flag.
+        let mut value = Value::deserialize(d)?;
+        let local = value.get("is_local").and_then(Value::as_bool) == Some(true)
+            || value
+                .get("uri")
+                .and_then(Value::as_str)
+                .map(|uri| uri.starts_with("spotify:local:"))
+                == Some(true);
+
+        if local {
+            LocalTrack::deserialize(value)
+                .map(PlaylistTrack::Local)
+                .map_err(de::Error::custom)
+        } else {
+            // Tracks in playlists are also flagged with `"track": true` and
+            // `"episode": false`, which aren't part of the track object.
+            if let Value::Object(fields) = &mut value {
+                fields.remove("track");
+                fields.remove("episode");
+            }
+            FullTrack::deserialize(value)
+                .map(PlaylistTrack::Track)
+                .map_err(de::Error::custom)
+        }
+    }
 }
 /// Featured playlists object
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-featured-playlists)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct FeaturedPlaylists {
     pub message: String,
     pub playlists: Page<SimplifiedPlaylist>,
@@ -90,6 +240,58 @@ pub struct FeaturedPlaylists {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-categories-playlists)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct CategoryPlaylists {
     pub playlists: Page<SimplifiedPlaylist>,
 }
+
+/// A playlist to create with [`OAuthClient::create_playlists`], like one of
+/// the folders exported from another service.
+///
+/// It also keeps the progress of the import, so that the definitions
+/// returned in the [`PlaylistImport`]s can be stored and given again to
+/// resume an interrupted import.
+///
+/// [`OAuthClient::create_playlists`]: crate::prelude::OAuthClient::create_playlists
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlaylistDef {
+    pub name: String,
+    pub description: Option<String>,
+    /// The URIs, URLs or IDs of the tracks, in order.
+    pub tracks: Vec<String>,
+    /// The ID of the playlist once it's created, so that it isn't created
+    /// again when resuming.
+    #[serde(default)]
+    pub playlist_id: Option<String>,
+    /// How many of the tracks were already added to the playlist.
+    #[serde(default)]
+    pub added: usize,
+}
+
+impl PlaylistDef {
+    /// A playlist without a description.
+    pub fn new<N: Into<String>>(name: N, tracks: Vec<String>) -> Self {
+        PlaylistDef {
+            name: name.into(),
+            tracks,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the playlist was created with all of its tracks.
+    pub fn is_complete(&self) -> bool {
+        self.playlist_id.is_some() && self.added >= self.tracks.len()
+    }
+}
+
+/// The outcome of importing one of the playlists with
+/// [`OAuthClient::create_playlists`].
+///
+/// [`OAuthClient::create_playlists`]: crate::prelude::OAuthClient::create_playlists
+#[derive(Debug)]
+pub struct PlaylistImport {
+    /// The definition with the progress of the import, to resume it.
+    pub def: PlaylistDef,
+    /// Why the import of this playlist stopped, if it's not complete.
+    pub error: Option<ClientError>,
+}
@@ -7,7 +7,10 @@ use serde::{Deserialize, Serialize};
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-recommendationsobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Recommendations {
+    /// How many tracks each of the seeds contributed, to find out why few
+    /// tracks were recommended.
     pub seeds: Vec<RecommendationsSeed>,
     pub tracks: Vec<SimplifiedTrack>,
 }
@@ -16,13 +19,22 @@ pub struct Recommendations {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-recommendationseedobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct RecommendationsSeed {
+    /// The number of tracks left for the seed after the tunable attributes,
+    /// like `min_energy`, and the market were applied.
     #[serde(rename = "afterFilteringSize")]
     pub after_filtering_size: u32,
+    /// The number of tracks left after relinking the ones unavailable in the
+    /// market.
     #[serde(rename = "afterRelinkingSize")]
     pub after_relinking_size: u32,
+    /// The link to the artist or track of the seed, which is `None` for
+    /// genres.
     pub href: Option<String>,
+    /// The ID of the artist or track, or the name of the genre.
     pub id: String,
+    /// The number of tracks available for the seed before any filtering.
     #[serde(rename = "initialPoolSize")]
     pub initial_pool_size: u32,
     #[serde(rename = "type")]
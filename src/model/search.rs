@@ -5,12 +5,14 @@ use super::page::Page;
 use super::playlist::SimplifiedPlaylist;
 use super::show::{SimplifiedEpisode, SimplifiedShow};
 use super::track::FullTrack;
+use super::Explicit;
 use serde::{Deserialize, Serialize};
 
 /// Search for playlists
 ///
 ///[Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search);
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SearchPlaylists {
     pub playlists: Page<SimplifiedPlaylist>,
 }
@@ -19,6 +21,7 @@ pub struct SearchPlaylists {
 ///
 ///[Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SearchAlbums {
     pub albums: Page<SimplifiedAlbum>,
 }
@@ -27,11 +30,13 @@ pub struct SearchAlbums {
 ///
 ///[Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SearchArtists {
     pub artists: Page<FullArtist>,
 }
 ///[Search item](https://developer.spotify.com/documentation/web-api/reference/#category-search)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SearchTracks {
     pub tracks: Page<FullTrack>,
 }
@@ -40,6 +45,7 @@ pub struct SearchTracks {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SearchShows {
     pub shows: Page<SimplifiedShow>,
 }
@@ -48,6 +54,7 @@ pub struct SearchShows {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SearchEpisodes {
     pub episodes: Page<SimplifiedEpisode>,
 }
@@ -70,3 +77,16 @@ pub enum SearchResult {
     #[serde(rename = "episodes")]
     Episodes(Page<SimplifiedEpisode>),
 }
+
+impl SearchResult {
+    /// Removes the explicit tracks, shows and episodes from the results. The
+    /// `total` of the page still counts them.
+    pub fn remove_explicit(&mut self) {
+        match self {
+            SearchResult::Tracks(page) => page.items.retain(|item| !item.is_explicit()),
+            SearchResult::Shows(page) => page.items.retain(|item| !item.is_explicit()),
+            SearchResult::Episodes(page) => page.items.retain(|item| !item.is_explicit()),
+            SearchResult::Playlists(_) | SearchResult::Albums(_) | SearchResult::Artists(_) => {}
+        }
+    }
+}
@@ -1,30 +1,40 @@
+use super::date::release_date_fields;
 use super::image::Image;
 use super::page::Page;
-use crate::model::{duration_ms, CopyrightType, DatePrecision};
+use crate::model::{
+    duration_ms, empty_as_none, AnyId, CopyrightType, EpisodeId, ExternalUrls, ReleaseDate,
+    Restrictions, ShowId,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::time::Duration;
 
 /// Copyright object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-copyrightobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Copyright {
     pub text: String,
     #[serde(rename = "type")]
-    pub _type: CopyrightType,
+    pub kind: CopyrightType,
 }
 
 /// Simplified show object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedshowobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SimplifiedShow {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub available_markets: Vec<String>,
     pub copyrights: Vec<Copyright>,
     pub description: String,
+    /// The description with its HTML tags, like the links to the episodes.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub html_description: Option<String>,
     pub explicit: bool,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
@@ -38,10 +48,18 @@ pub struct SimplifiedShow {
     pub uri: String,
 }
 
+impl SimplifiedShow {
+    /// The ID of the show, to build its URI or links to share it.
+    pub fn show_id(&self) -> ShowId {
+        ShowId::from_model(&self.id)
+    }
+}
+
 /// SimplifiedShows wrapped by `Vec`
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-shows)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct SeversalSimplifiedShows {
     pub shows: Vec<SimplifiedShow>,
 }
@@ -50,8 +68,9 @@ pub(in crate) struct SeversalSimplifiedShows {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-savedshowobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct Show {
-    pub added_at: String,
+    pub added_at: DateTime<Utc>,
     pub show: SimplifiedShow,
 }
 
@@ -59,13 +78,18 @@ pub struct Show {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-showobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct FullShow {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub available_markets: Vec<String>,
     pub copyrights: Vec<Copyright>,
     pub description: String,
+    /// The description with its HTML tags, like the links to the episodes.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub html_description: Option<String>,
     pub explicit: bool,
     pub episodes: Page<SimplifiedEpisode>,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
@@ -79,17 +103,50 @@ pub struct FullShow {
     pub uri: String,
 }
 
+impl FullShow {
+    /// The same as [`SimplifiedShow::show_id`].
+    pub fn show_id(&self) -> ShowId {
+        ShowId::from_model(&self.id)
+    }
+
+    /// The same as [`FullTrack::to_uri`].
+    ///
+    /// [`FullTrack::to_uri`]: crate::model::FullTrack::to_uri
+    pub fn to_uri(&self) -> String {
+        self.show_id().to_uri()
+    }
+
+    /// The same as [`FullTrack::to_open_url`].
+    ///
+    /// [`FullTrack::to_open_url`]: crate::model::FullTrack::to_open_url
+    pub fn to_open_url(&self) -> String {
+        self.show_id().to_open_url()
+    }
+
+    /// The same as [`FullTrack::to_embed_url`].
+    ///
+    /// [`FullTrack::to_embed_url`]: crate::model::FullTrack::to_embed_url
+    pub fn to_embed_url(&self) -> String {
+        self.show_id().to_embed_url()
+    }
+}
+
 /// Simplified episode object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedepisodeobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SimplifiedEpisode {
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
     pub audio_preview_url: Option<String>,
     pub description: String,
+    /// The description with its HTML tags, like the links to the episodes.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub html_description: Option<String>,
     #[serde(with = "duration_ms", rename = "duration_ms")]
     pub duration: Duration,
     pub explicit: bool,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
@@ -101,25 +158,39 @@ pub struct SimplifiedEpisode {
     pub language: String,
     pub languages: Vec<String>,
     pub name: String,
-    pub release_date: String,
-    pub release_date_precision: DatePrecision,
+    #[serde(flatten, with = "release_date_fields")]
+    pub release_date: ReleaseDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restrictions: Option<Restrictions>,
     pub resume_point: Option<ResumePoint>,
     #[serde(rename = "type")]
     pub _type: String,
     pub uri: String,
 }
 
+impl SimplifiedEpisode {
+    /// The ID of the episode, to build its URI or links to share it.
+    pub fn episode_id(&self) -> EpisodeId {
+        EpisodeId::from_model(&self.id)
+    }
+}
+
 /// Full episode object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-episodeobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct FullEpisode {
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
     pub audio_preview_url: Option<String>,
     pub description: String,
+    /// The description with its HTML tags, like the links to the episodes.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub html_description: Option<String>,
     #[serde(with = "duration_ms", rename = "duration_ms")]
     pub duration: Duration,
     pub explicit: bool,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub href: String,
     pub id: String,
     pub images: Vec<Image>,
@@ -130,15 +201,47 @@ pub struct FullEpisode {
     pub language: String,
     pub languages: Vec<String>,
     pub name: String,
-    pub release_date: String,
-    pub release_date_precision: DatePrecision,
+    #[serde(flatten, with = "release_date_fields")]
+    pub release_date: ReleaseDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restrictions: Option<Restrictions>,
     pub resume_point: Option<ResumePoint>,
     pub show: SimplifiedShow,
     #[serde(rename = "type")]
     pub _type: String,
     pub uri: String,
 }
+
+impl FullEpisode {
+    /// The same as [`SimplifiedEpisode::episode_id`].
+    pub fn episode_id(&self) -> EpisodeId {
+        EpisodeId::from_model(&self.id)
+    }
+
+    /// The same as [`FullTrack::to_uri`].
+    ///
+    /// [`FullTrack::to_uri`]: crate::model::FullTrack::to_uri
+    pub fn to_uri(&self) -> String {
+        self.episode_id().to_uri()
+    }
+
+    /// The same as [`FullTrack::to_open_url`].
+    ///
+    /// [`FullTrack::to_open_url`]: crate::model::FullTrack::to_open_url
+    pub fn to_open_url(&self) -> String {
+        self.episode_id().to_open_url()
+    }
+
+    /// The same as [`FullTrack::to_embed_url`].
+    ///
+    /// [`FullTrack::to_embed_url`]: crate::model::FullTrack::to_embed_url
+    pub fn to_embed_url(&self) -> String {
+        self.episode_id().to_embed_url()
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SeveralEpisodes {
     pub episodes: Vec<FullEpisode>,
 }
@@ -147,6 +250,7 @@ pub struct SeveralEpisodes {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-resumepointobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct ResumePoint {
     pub fully_played: bool,
     #[serde(with = "duration_ms", rename = "resume_position_ms")]
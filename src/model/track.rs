@@ -2,18 +2,21 @@
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use std::{collections::HashMap, time::Duration};
+use std::time::Duration;
 
 use super::album::SimplifiedAlbum;
 use super::artist::SimplifiedArtist;
-use super::Restriction;
-use crate::model::duration_ms;
-use crate::model::Type;
+use super::Restrictions;
+use crate::model::{duration_ms, empty_as_none};
+use crate::model::{
+    AnyId, ExternalIds, ExternalUrls, Popularity, RestrictionReason, TrackId, Type,
+};
 
 /// Full track object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-trackobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct FullTrack {
     pub album: SimplifiedAlbum,
     pub artists: Vec<SimplifiedArtist>,
@@ -23,8 +26,8 @@ pub struct FullTrack {
     #[serde(with = "duration_ms", rename = "duration_ms")]
     pub duration: Duration,
     pub explicit: bool,
-    pub external_ids: HashMap<String, String>,
-    pub external_urls: HashMap<String, String>,
+    pub external_ids: ExternalIds,
+    pub external_urls: ExternalUrls,
     pub href: Option<String>,
     pub id: Option<String>,
     pub is_local: bool,
@@ -33,9 +36,10 @@ pub struct FullTrack {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub linked_from: Option<TrackLink>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub restrictions: Option<Restriction>,
+    pub restrictions: Option<Restrictions>,
     pub name: String,
-    pub popularity: u32,
+    pub popularity: Popularity,
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
     pub preview_url: Option<String>,
     pub track_number: u32,
     #[serde(rename = "type")]
@@ -43,12 +47,84 @@ pub struct FullTrack {
     pub uri: String,
 }
 
+impl FullTrack {
+    /// The ID of the track, to build its URI or links to share it, or `None`
+    /// for local tracks.
+    pub fn track_id(&self) -> Option<TrackId> {
+        self.id.as_deref().map(TrackId::from_model)
+    }
+
+    /// The URI of the track, like `spotify:track:<id>`, or `None` for local
+    /// tracks. See [`AnyId::to_uri`].
+    pub fn to_uri(&self) -> Option<String> {
+        self.track_id().map(|id| id.to_uri())
+    }
+
+    /// The link to share the track, like
+    /// `https://open.spotify.com/track/<id>`, or `None` for local tracks.
+    pub fn to_open_url(&self) -> Option<String> {
+        self.track_id().map(|id| id.to_open_url())
+    }
+
+    /// The URL of the embedded player of the track, to be used as the source
+    /// of an `<iframe>`, or `None` for local tracks.
+    pub fn to_embed_url(&self) -> Option<String> {
+        self.track_id().map(|id| id.to_embed_url())
+    }
+
+    /// Why the track can't be played, if it can't. This is only known when a
+    /// market was given in the request, so that [track relinking
+    /// ](https://developer.spotify.com/documentation/general/guides/track-relinking-guide/)
+    /// was applied; unplayable tracks without an explicit reason are
+    /// unavailable in that market.
+    pub fn restriction_reason(&self) -> Option<RestrictionReason> {
+        restriction_reason(self.is_playable, self.restrictions.as_ref())
+    }
+
+    /// Whether the track was replaced by another one available in the given
+    /// market, in which case `linked_from` is the track that was requested.
+    pub fn is_relinked(&self) -> bool {
+        self.linked_from.is_some()
+    }
+}
+
+/// Local file object, for the tracks that users add to their playlists from
+/// their own computer. Their artists and album only contain the name.
+///
+/// [Reference](https://developer.spotify.com/documentation/general/guides/local-files-spotify-playlists/)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalTrack {
+    pub album: SimplifiedAlbum,
+    pub artists: Vec<SimplifiedArtist>,
+    #[serde(with = "duration_ms", rename = "duration_ms")]
+    pub duration: Duration,
+    pub name: String,
+    /// With the format `spotify:local:{artist}:{album}:{title}:{duration}`.
+    pub uri: String,
+}
+
+impl LocalTrack {
+    /// The name of the artist, if the file had one.
+    pub fn artist_name(&self) -> Option<&str> {
+        self.artists
+            .first()
+            .map(|artist| artist.name.as_str())
+            .filter(|name| !name.is_empty())
+    }
+
+    /// The name of the album, if the file had one.
+    pub fn album_name(&self) -> Option<&str> {
+        Some(self.album.name.as_str()).filter(|name| !name.is_empty())
+    }
+}
+
 /// Track link object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-linkedtrackobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct TrackLink {
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub href: String,
     pub id: String,
     #[serde(rename = "type")]
@@ -60,6 +136,7 @@ pub struct TrackLink {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-several-tracks)
 #[derive(Deserialize)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub(in crate) struct FullTracks {
     pub tracks: Vec<FullTrack>,
 }
@@ -71,6 +148,7 @@ pub(in crate) struct FullTracks {
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedtrackobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SimplifiedTrack {
     pub artists: Vec<SimplifiedArtist>,
     pub available_markets: Option<Vec<String>>,
@@ -78,15 +156,22 @@ pub struct SimplifiedTrack {
     #[serde(with = "duration_ms", rename = "duration_ms")]
     pub duration: Duration,
     pub explicit: bool,
-    pub external_urls: HashMap<String, String>,
+    /// Only given in some responses, like the tracks of the albums.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_ids: Option<ExternalIds>,
+    pub external_urls: ExternalUrls,
     #[serde(default)]
     pub href: Option<String>,
     pub id: Option<String>,
     pub is_local: bool,
     pub is_playable: Option<bool>,
     pub linked_from: Option<TrackLink>,
-    pub restrictions: Option<Restriction>,
+    pub restrictions: Option<Restrictions>,
     pub name: String,
+    /// The same as [`SimplifiedTrack::external_ids`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub popularity: Option<Popularity>,
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
     pub preview_url: Option<String>,
     pub track_number: u32,
     #[serde(rename = "type")]
@@ -94,11 +179,111 @@ pub struct SimplifiedTrack {
     pub uri: String,
 }
 
+impl SimplifiedTrack {
+    /// The same as [`FullTrack::track_id`].
+    pub fn track_id(&self) -> Option<TrackId> {
+        self.id.as_deref().map(TrackId::from_model)
+    }
+
+    /// The same as [`FullTrack::restriction_reason`].
+    pub fn restriction_reason(&self) -> Option<RestrictionReason> {
+        restriction_reason(self.is_playable, self.restrictions.as_ref())
+    }
+
+    /// The same as [`FullTrack::is_relinked`].
+    pub fn is_relinked(&self) -> bool {
+        self.linked_from.is_some()
+    }
+}
+
+/// The track without its album, like to keep it along with the simplified
+/// ones of other endpoints.
+impl From<FullTrack> for SimplifiedTrack {
+    fn from(track: FullTrack) -> Self {
+        SimplifiedTrack {
+            artists: track.artists,
+            available_markets: Some(track.available_markets).filter(|markets| !markets.is_empty()),
+            disc_number: track.disc_number,
+            duration: track.duration,
+            explicit: track.explicit,
+            external_ids: Some(track.external_ids),
+            external_urls: track.external_urls,
+            href: track.href,
+            id: track.id,
+            is_local: track.is_local,
+            is_playable: track.is_playable,
+            linked_from: track.linked_from,
+            restrictions: track.restrictions,
+            name: track.name,
+            popularity: Some(track.popularity),
+            preview_url: track.preview_url,
+            track_number: track.track_number,
+            _type: track._type,
+            uri: track.uri,
+        }
+    }
+}
+
+/// The full and simplified versions of a track are the same one if they have
+/// the same ID, so local tracks are never the same.
+impl PartialEq<SimplifiedTrack> for FullTrack {
+    fn eq(&self, other: &SimplifiedTrack) -> bool {
+        self.id.is_some() && self.id == other.id
+    }
+}
+
+impl PartialEq<FullTrack> for SimplifiedTrack {
+    fn eq(&self, other: &FullTrack) -> bool {
+        other == self
+    }
+}
+
+fn restriction_reason(
+    is_playable: Option<bool>,
+    restrictions: Option<&Restrictions>,
+) -> Option<RestrictionReason> {
+    match (restrictions, is_playable) {
+        (Some(restrictions), _) => Some(restrictions.reason),
+        (None, Some(false)) => Some(RestrictionReason::Market),
+        (None, _) => None,
+    }
+}
+
 /// Saved track object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-savedtrackobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct SavedTrack {
     pub added_at: DateTime<Utc>,
     pub track: FullTrack,
 }
+
+/// The saved tracks of the user as of a sync with
+/// [`OAuthClient::sync_saved_tracks`], stored to find what changed in the
+/// next one.
+///
+/// [`OAuthClient::sync_saved_tracks`]: crate::prelude::OAuthClient::sync_saved_tracks
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SavedTracksSnapshot {
+    /// The IDs of the saved tracks, the most recently saved first.
+    pub ids: Vec<String>,
+    /// When the most recent of them was saved.
+    pub latest_added_at: Option<DateTime<Utc>>,
+}
+
+/// The changes in the saved tracks of the user since a previous sync.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SavedTracksSync {
+    /// The tracks saved since the previous sync, the most recent first.
+    pub added: Vec<SavedTrack>,
+    /// The IDs of the tracks removed since the previous sync. They're only
+    /// known after a full pass.
+    pub removed: Vec<String>,
+    /// Whether tracks may have been removed without being listed in
+    /// `removed`, because the number of saved tracks doesn't add up. A full
+    /// pass finds them.
+    pub incomplete: bool,
+    /// The saved tracks after this sync, for the next one.
+    pub snapshot: SavedTracksSnapshot,
+}
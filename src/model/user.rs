@@ -1,18 +1,20 @@
 //! All kinds of user object
 use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
-
 use super::image::Image;
-use crate::model::{Country, Followers, SubscriptionLevel, Type};
+use crate::model::{
+    empty_as_none, Country, ExternalUrls, Followers, SubscriptionLevel, Type, UserId,
+};
 
 /// Public user object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-publicuserobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct PublicUser {
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
     pub display_name: Option<String>,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub followers: Option<Followers>,
     pub href: String,
     pub id: String,
@@ -23,15 +25,25 @@ pub struct PublicUser {
     pub uri: String,
 }
 
+impl PublicUser {
+    /// The ID of the user, to build their URI or links to share their profile.
+    pub fn user_id(&self) -> UserId {
+        UserId::from_model(&self.id)
+    }
+}
+
 /// Private user object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-privateuserobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct PrivateUser {
     pub country: Option<Country>,
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
     pub display_name: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none::deserialize")]
     pub email: Option<String>,
-    pub external_urls: HashMap<String, String>,
+    pub external_urls: ExternalUrls,
     pub explicit_content: Option<ExplicitContent>,
     pub followers: Option<Followers>,
     pub href: String,
@@ -43,11 +55,29 @@ pub struct PrivateUser {
     pub uri: String,
 }
 
+impl PrivateUser {
+    /// The same as [`PublicUser::user_id`].
+    pub fn user_id(&self) -> UserId {
+        UserId::from_model(&self.id)
+    }
+
+    /// Whether the user's explicit content filter is enabled, so that the
+    /// explicit tracks and episodes shouldn't be played for them. It's only
+    /// known with the `user-read-private` scope.
+    pub fn filters_explicit(&self) -> bool {
+        matches!(&self.explicit_content, Some(settings) if settings.filter_enabled)
+    }
+}
+
 /// Explicit content setting object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-explicitcontentsettingsobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "strict-models", serde(deny_unknown_fields))]
 pub struct ExplicitContent {
+    /// Whether the explicit content shouldn't be played.
     pub filter_enabled: bool,
+    /// Whether the setting can't be changed by the user, like in family
+    /// accounts managed by a parent.
     pub filter_locked: bool,
 }
@@ -4,23 +4,51 @@ use chrono::prelude::*;
 use derive_builder::Builder;
 use maybe_async::maybe_async;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "cli")]
+use url::Host;
 use url::Url;
 
 use chrono::Duration;
 use std::collections::{HashMap, HashSet};
+#[cfg(feature = "cli")]
+use std::io::{BufRead, BufReader};
+#[cfg(feature = "cli")]
+use std::net::{IpAddr, TcpListener, TcpStream};
+#[cfg(any(feature = "cli", feature = "__sync"))]
+use std::sync::PoisonError;
 use std::{
-    env, fs,
+    env, fmt, fs,
     io::{Read, Write},
     path::Path,
+    sync::{Arc, Mutex},
 };
 
-use super::client::{ClientResult, Spotify};
+use super::client::{ClientError, ClientResult, Spotify};
+use super::http::redact::Secret;
 use super::http::{headers, Form, Headers};
 use crate::generate_random_string;
 
-mod auth_urls {
-    pub const AUTHORIZE: &str = "https://accounts.spotify.com/authorize";
-    pub const TOKEN: &str = "https://accounts.spotify.com/api/token";
+/// How long before its expiration a client token is renewed automatically.
+const RENEWAL_MARGIN_SECS: i64 = 60;
+
+/// Held while a client token is being renewed automatically, so that the
+/// concurrent requests wait for the new token instead of requesting their own.
+#[cfg(feature = "__async")]
+pub(crate) type RenewalLock = futures::lock::Mutex<()>;
+/// Held while a client token is being renewed automatically, so that the
+/// concurrent requests wait for the new token instead of requesting their own.
+#[cfg(feature = "__sync")]
+pub(crate) type RenewalLock = Mutex<()>;
+
+#[cfg(feature = "__async")]
+async fn lock_renewal(lock: &RenewalLock) -> futures::lock::MutexGuard<'_, ()> {
+    lock.lock().await
+}
+
+#[cfg(feature = "__sync")]
+fn lock_renewal(lock: &RenewalLock) -> std::sync::MutexGuard<'_, ()> {
+    lock.lock().unwrap_or_else(PoisonError::into_inner)
 }
 
 mod duration_second {
@@ -68,7 +96,7 @@ mod space_separated_scope {
 
 /// Spotify access token information
 /// [Reference](https://developer.spotify.com/documentation/general/guides/authorization-guide/)
-#[derive(Builder, Clone, Debug, Serialize, Deserialize)]
+#[derive(Builder, Clone, Serialize, Deserialize)]
 pub struct Token {
     /// An access token that can be provided in subsequent calls
     #[builder(setter(into))]
@@ -133,8 +161,91 @@ impl Token {
     }
 }
 
+/// Where the tokens are stored so that they can be reused, configured in
+/// [`Spotify::token_cache`], with one entry per key, like the ID of each user
+/// of a web service. By default, [`Spotify::cache_path`] is used instead.
+pub trait TokenCache: fmt::Debug + Send + Sync {
+    /// Returns the token stored under `key`, if any.
+    fn read(&self, key: &str) -> ClientResult<Option<Token>>;
+
+    /// Stores the token under `key`, replacing the previous one.
+    fn write(&self, key: &str, token: &Token) -> ClientResult<()>;
+
+    /// Removes the token stored under `key`, if any.
+    fn remove(&self, key: &str) -> ClientResult<()>;
+}
+
+/// A [`TokenCache`] kept in memory, for the services that don't want to
+/// write files, shared between its clones. The expired tokens are dropped
+/// unless they have a refresh token, since they're useless otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryTokenCache {
+    tokens: Arc<Mutex<HashMap<String, Token>>>,
+}
+
+impl MemoryTokenCache {
+    /// How many tokens are stored, including the expired ones that haven't
+    /// been dropped yet.
+    pub fn len(&self) -> usize {
+        self.tokens.lock().unwrap().len()
+    }
+
+    /// Whether no tokens are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops the expired tokens that can't be refreshed.
+    pub fn prune(&self) {
+        self.tokens
+            .lock()
+            .unwrap()
+            .retain(|_, tok| !is_useless(tok));
+    }
+}
+
+/// Whether a token can't be used anymore, not even to refresh it.
+fn is_useless(tok: &Token) -> bool {
+    tok.is_expired() && tok.refresh_token.is_none()
+}
+
+impl TokenCache for MemoryTokenCache {
+    fn read(&self, key: &str) -> ClientResult<Option<Token>> {
+        let mut tokens = self.tokens.lock().unwrap();
+        if matches!(tokens.get(key), Some(tok) if is_useless(tok)) {
+            tokens.remove(key);
+        }
+        Ok(tokens.get(key).cloned())
+    }
+
+    fn write(&self, key: &str, token: &Token) -> ClientResult<()> {
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.retain(|_, tok| !is_useless(tok));
+        tokens.insert(key.to_owned(), token.clone());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> ClientResult<()> {
+        self.tokens.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+// The tokens are redacted so that they aren't leaked in the logs.
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("access_token", &Secret(&self.access_token))
+            .field("expires_in", &self.expires_in)
+            .field("expires_at", &self.expires_at)
+            .field("refresh_token", &self.refresh_token.as_deref().map(Secret))
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
 /// Simple client credentials object for Spotify.
-#[derive(Builder, Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Builder, Default, Clone, Serialize, Deserialize)]
 pub struct Credentials {
     #[builder(setter(into))]
     pub id: String,
@@ -142,6 +253,16 @@ pub struct Credentials {
     pub secret: String,
 }
 
+// The secret is redacted so that it isn't leaked in the logs.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("id", &self.id)
+            .field("secret", &Secret(&self.secret))
+            .finish()
+    }
+}
+
 impl CredentialsBuilder {
     /// Parses the credentials from the environment variables
     /// `RSPOTIFY_CLIENT_ID` and `RSPOTIFY_CLIENT_SECRET`. You can optionally
@@ -161,7 +282,7 @@ impl CredentialsBuilder {
 }
 
 /// Structure that holds the required information for requests with OAuth.
-#[derive(Builder, Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Builder, Default, Clone, Serialize, Deserialize)]
 pub struct OAuth {
     #[builder(setter(into))]
     pub redirect_uri: String,
@@ -173,67 +294,699 @@ pub struct OAuth {
     pub scope: HashSet<String>,
     #[builder(setter(into, strip_option), default)]
     pub proxies: Option<String>,
+    /// The code verifier of the [PKCE extension
+    /// ](https://datatracker.ietf.org/doc/html/rfc7636), for clients that
+    /// can't keep their secret, like desktop apps. When set, its challenge is
+    /// included in the authorization URL, and the token requests send the
+    /// verifier and the client ID instead of the secret, which can be left
+    /// empty. See [`generate_code_verifier`].
+    #[builder(setter(into, strip_option), default)]
+    pub code_verifier: Option<String>,
+}
+
+// The code verifier is redacted so that it isn't leaked in the logs.
+impl fmt::Debug for OAuth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OAuth")
+            .field("redirect_uri", &self.redirect_uri)
+            .field("state", &self.state)
+            .field("scope", &self.scope)
+            .field("proxies", &self.proxies)
+            .field("code_verifier", &self.code_verifier.as_deref().map(Secret))
+            .finish()
+    }
+}
+
+/// The options of the authorization URL, for [`Spotify::get_authorize_url`].
+///
+/// ```
+/// use rspotify::oauth2::AuthorizeUrlOptions;
+///
+/// // Forces the consent dialog, so that the user can switch accounts.
+/// let options = AuthorizeUrlOptions {
+///     show_dialog: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuthorizeUrlOptions {
+    /// Whether the user is asked to approve the app again even if they
+    /// already did, which lets them log in with a different account.
+    pub show_dialog: bool,
+    /// The state sent instead of [`OAuth::state`], like one generated for
+    /// each login. It's up to the caller to check that the callback receives
+    /// the same one.
+    pub state: Option<String>,
+    /// Additional query parameters, appended after the standard ones in the
+    /// given order, for the integrations that need them.
+    pub extra_params: Vec<(String, String)>,
+}
+
+/// Generates a random code verifier for [`OAuth::code_verifier`], of the
+/// maximum length allowed.
+pub fn generate_code_verifier() -> String {
+    generate_random_string(128)
+}
+
+/// The challenge sent in the authorization URL for the code verifier, with
+/// the `S256` method.
+fn code_challenge(verifier: &str) -> String {
+    let hash = Sha256::digest(verifier.as_bytes());
+    base64::encode_config(hash, base64::URL_SAFE_NO_PAD)
 }
 
 impl OAuthBuilder {
     /// Parses the credentials from the environment variable
-    /// `RSPOTIFY_REDIRECT_URI`. You can optionally activate the `env-file`
-    /// feature in order to read these variables from a `.env` file.
+    /// `RSPOTIFY_REDIRECT_URI`, and the scopes from the optional
+    /// `RSPOTIFY_SCOPE`, separated by spaces. You can optionally activate the
+    /// `env-file` feature in order to read these variables from a `.env` file.
     pub fn from_env() -> Self {
         #[cfg(feature = "env-file")]
         {
             dotenv::dotenv().ok();
         }
 
+        let scope = env::var("RSPOTIFY_SCOPE")
+            .ok()
+            .map(|scope| scope.split_whitespace().map(|x| x.to_owned()).collect());
+
         OAuthBuilder {
             redirect_uri: env::var("RSPOTIFY_REDIRECT_URI").ok(),
+            scope,
             ..Default::default()
         }
     }
 }
 
+/// Obtains the user's authorization in the [Authorization Code Flow
+/// ](https://developer.spotify.com/documentation/general/guides/authorization-guide/#authorization-code-flow),
+/// so that the flow isn't tied to a terminal. For example, a GUI may open the
+/// URL in an embedded browser, and a web server may wait for the callback.
+///
+/// It's implemented for closures as well, which is useful for tests:
+///
+/// ```
+/// use rspotify::oauth2::AuthorizationPrompt;
+///
+/// fn takes_prompt<P: AuthorizationPrompt>(_prompt: &P) {}
+///
+/// let prompt = |_url: &str| Ok("http://localhost:8888/callback?code=AQD0yXvF".to_owned());
+/// takes_prompt(&prompt);
+/// ```
+///
+/// With an async client the prompt is async too, so that waiting for the user
+/// doesn't block the executor.
+#[maybe_async]
+pub trait AuthorizationPrompt {
+    /// Shows the authorization URL to the user, and returns the URL they were
+    /// redirected to afterwards, which contains the code.
+    async fn prompt(&self, authorize_url: &str) -> ClientResult<String>;
+
+    /// The same as `prompt`, but failing with [`ClientError::Timeout`] once
+    /// `timeout` is exceeded, for the [`Spotify::prompt_timeout`] of the
+    /// blocking client. The prompts in this module wait for the user in a
+    /// separate thread, which is left behind after the timeout. A blocking
+    /// prompt can't be interrupted otherwise, so by default it fails with
+    /// [`ClientError::InvalidAuth`] instead of waiting longer than allowed.
+    #[cfg(feature = "__sync")]
+    fn prompt_with_timeout(
+        &self,
+        authorize_url: &str,
+        timeout: std::time::Duration,
+    ) -> ClientResult<String> {
+        let _ = (authorize_url, timeout);
+        Err(ClientError::InvalidAuth(
+            "this prompt doesn't support prompt_timeout".to_string(),
+        ))
+    }
+}
+
+#[maybe_async]
+impl<F> AuthorizationPrompt for F
+where
+    F: Fn(&str) -> ClientResult<String> + Sync,
+{
+    async fn prompt(&self, authorize_url: &str) -> ClientResult<String> {
+        self(authorize_url)
+    }
+}
+
+/// Prompt that opens the authorization URL in the user's browser, and reads
+/// the redirect URL from the standard input.
+///
+/// An error is returned if the standard input isn't a terminal, like in CI,
+/// instead of waiting for an input that will never come. [`StreamPrompt`]
+/// may be used to read from somewhere else.
+///
+/// Note: this requires the `cli` feature.
+#[cfg(feature = "cli")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CliPrompt;
+
+#[cfg(feature = "cli")]
+impl CliPrompt {
+    /// Opens the authorization URL, failing if the standard input isn't a
+    /// terminal.
+    fn start(authorize_url: &str) -> ClientResult<()> {
+        use std::io::IsTerminal;
+
+        if !std::io::stdin().is_terminal() {
+            return Err(ClientError::CLI(
+                "the standard input isn't a terminal, so the redirect URL can't be entered"
+                    .to_string(),
+            ));
+        }
+
+        open_in_browser(authorize_url);
+        println!("Please enter the URL you were redirected to: ");
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cli")]
+#[maybe_async]
+impl AuthorizationPrompt for CliPrompt {
+    async fn prompt(&self, authorize_url: &str) -> ClientResult<String> {
+        Self::start(authorize_url)?;
+        read_stdin_redirect().await
+    }
+
+    #[cfg(feature = "__sync")]
+    fn prompt_with_timeout(
+        &self,
+        authorize_url: &str,
+        timeout: std::time::Duration,
+    ) -> ClientResult<String> {
+        Self::start(authorize_url)?;
+        wait_on_thread(timeout, || read_redirect(&mut std::io::stdin().lock()))
+    }
+}
+
+/// Opens the authorization URL in the user's browser, or asks them to do it
+/// if it can't be opened.
+#[cfg(feature = "cli")]
+fn open_in_browser(authorize_url: &str) {
+    match webbrowser::open(authorize_url) {
+        Ok(_) => println!("Opened {} in your browser.", authorize_url),
+        Err(why) => eprintln!(
+            "Error when trying to open an URL in your browser: {:?}. \
+             Please navigate here manually: {}",
+            why, authorize_url
+        ),
+    }
+}
+
+/// Reads the redirect URL from the standard input, in a separate thread so
+/// that the executor isn't blocked.
+#[cfg(all(feature = "cli", feature = "__async"))]
+async fn read_stdin_redirect() -> ClientResult<String> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(read_redirect(&mut std::io::stdin().lock()));
+    });
+
+    receiver
+        .await
+        .map_err(|_| ClientError::CLI("the standard input couldn't be read".to_string()))?
+}
+
+/// Reads the redirect URL from the standard input.
+#[cfg(all(feature = "cli", feature = "__sync"))]
+fn read_stdin_redirect() -> ClientResult<String> {
+    read_redirect(&mut std::io::stdin().lock())
+}
+
+/// Runs the blocking `prompt` in a separate thread, failing with
+/// [`ClientError::Timeout`] once `timeout` is exceeded. The thread can't be
+/// interrupted, so it's left behind until the prompt returns, if ever.
+#[cfg(all(feature = "cli", feature = "__sync"))]
+fn wait_on_thread<F>(timeout: std::time::Duration, prompt: F) -> ClientResult<String>
+where
+    F: FnOnce() -> ClientResult<String> + Send + 'static,
+{
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(prompt());
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(ClientError::Timeout),
+        Err(RecvTimeoutError::Disconnected) => Err(ClientError::CLI(
+            "the prompt stopped unexpectedly".to_string(),
+        )),
+    }
+}
+
+/// Reads a line with the redirect URL, which can't be empty.
+#[cfg(feature = "cli")]
+fn read_redirect<R: BufRead>(input: &mut R) -> ClientResult<String> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    if line.trim().is_empty() {
+        return Err(ClientError::CLI("no redirect URL was entered".to_string()));
+    }
+
+    Ok(line)
+}
+
+/// Prompt that writes the authorization URL to the given output, and reads
+/// the redirect URL from the given input, so that the terminal flow can be
+/// embedded in a TUI, or run without a terminal at all. The browser isn't
+/// opened.
+///
+/// The streams are used synchronously, even with an async client. With the
+/// `prompt_timeout` of the blocking client they're used from a separate
+/// thread, so they must be `Send` and `'static`.
+///
+/// ```
+/// use rspotify::oauth2::StreamPrompt;
+/// use std::io::Cursor;
+///
+/// let input = Cursor::new("http://localhost:8888/callback?code=AQD0yXvF\n");
+/// let prompt = StreamPrompt::new(input, Vec::new());
+/// ```
+///
+/// Note: this requires the `cli` feature.
+#[cfg(feature = "cli")]
+#[derive(Debug)]
+pub struct StreamPrompt<R, W> {
+    /// Shared with the thread reading the input after a timeout.
+    streams: Arc<Mutex<(R, W)>>,
+}
+
+#[cfg(feature = "cli")]
+impl<R: BufRead, W: Write> StreamPrompt<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        StreamPrompt {
+            streams: Arc::new(Mutex::new((input, output))),
+        }
+    }
+
+    /// Returns the input and output streams, or `None` if a prompt that timed
+    /// out is still waiting for the input.
+    pub fn into_inner(self) -> Option<(R, W)> {
+        let streams = Arc::try_unwrap(self.streams).ok()?;
+        Some(streams.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Writes the authorization URL to the output, and reads the redirect
+    /// URL from the input.
+    fn ask(streams: &Mutex<(R, W)>, authorize_url: &str) -> ClientResult<String> {
+        let mut streams = streams.lock().unwrap_or_else(PoisonError::into_inner);
+        let (input, output) = &mut *streams;
+
+        writeln!(output, "Please navigate here: {}", authorize_url)?;
+        writeln!(output, "Please enter the URL you were redirected to: ")?;
+        output.flush()?;
+        read_redirect(input)
+    }
+}
+
+#[cfg(feature = "cli")]
+#[maybe_async]
+impl<R, W> AuthorizationPrompt for StreamPrompt<R, W>
+where
+    R: BufRead + Send + 'static,
+    W: Write + Send + 'static,
+{
+    async fn prompt(&self, authorize_url: &str) -> ClientResult<String> {
+        Self::ask(&self.streams, authorize_url)
+    }
+
+    #[cfg(feature = "__sync")]
+    fn prompt_with_timeout(
+        &self,
+        authorize_url: &str,
+        timeout: std::time::Duration,
+    ) -> ClientResult<String> {
+        let streams = Arc::clone(&self.streams);
+        let authorize_url = authorize_url.to_owned();
+        wait_on_thread(timeout, move || Self::ask(&streams, &authorize_url))
+    }
+}
+
+/// Prompt that opens the authorization URL in the user's browser, and
+/// receives the redirect in a local server instead of asking the user to
+/// copy it, for CLI and desktop applications. It follows the [loopback
+/// redirect](https://datatracker.ietf.org/doc/html/rfc8252#section-7.3) of RFC
+/// 8252: the redirect URI is an `http` one to a loopback IP, and the server
+/// listens on the port given in it, or on an ephemeral one if there's none.
+/// The actual redirect URI must then be used in the authorization URL, which
+/// [`Spotify::prompt_for_user_token_loopback`] takes care of.
+///
+/// The redirects whose `state` doesn't match the one in the authorization
+/// URL are rejected, since they weren't initiated by it.
+///
+/// Note: this requires the `cli` feature.
+#[cfg(feature = "cli")]
+#[derive(Debug)]
+pub struct LoopbackPrompt {
+    listener: TcpListener,
+    redirect_uri: Url,
+}
+
+#[cfg(feature = "cli")]
+impl LoopbackPrompt {
+    /// Starts listening for the redirect to `redirect_uri`, like
+    /// `http://127.0.0.1:8888/callback` or `http://[::1]/callback`. Its host
+    /// must be a loopback IP: `localhost` isn't accepted, since it may
+    /// resolve to something else.
+    pub fn bind(redirect_uri: &str) -> ClientResult<Self> {
+        let invalid = |reason: &str| {
+            ClientError::InvalidAuth(format!(
+                "invalid loopback redirect URI {}: {}",
+                redirect_uri, reason
+            ))
+        };
+        let mut url = Url::parse(redirect_uri)?;
+        if url.scheme() != "http" {
+            return Err(invalid("the scheme must be http"));
+        }
+        let ip = match url.host() {
+            Some(Host::Ipv4(ip)) if ip.is_loopback() => IpAddr::V4(ip),
+            Some(Host::Ipv6(ip)) if ip.is_loopback() => IpAddr::V6(ip),
+            _ => return Err(invalid("the host must be a loopback IP, like 127.0.0.1")),
+        };
+
+        let listener = TcpListener::bind((ip, url.port().unwrap_or(0)))?;
+        let port = listener.local_addr()?.port();
+        url.set_port(Some(port))
+            .map_err(|_| invalid("the port can't be set"))?;
+        Ok(LoopbackPrompt {
+            listener,
+            redirect_uri: url,
+        })
+    }
+
+    /// The redirect URI with the port the server is listening on, which has
+    /// to be the one in the authorization URL.
+    pub fn redirect_uri(&self) -> &str {
+        self.redirect_uri.as_str()
+    }
+}
+
+#[cfg(feature = "cli")]
+impl LoopbackPrompt {
+    /// Opens the authorization URL, returning its state.
+    fn start(authorize_url: &str) -> ClientResult<Option<String>> {
+        let state = Url::parse(authorize_url)?
+            .query_pairs()
+            .find(|(key, _)| key == headers::STATE)
+            .map(|(_, state)| state.into_owned());
+
+        open_in_browser(authorize_url);
+        println!("Waiting for the authorization in your browser...");
+        Ok(state)
+    }
+}
+
+#[cfg(feature = "cli")]
+#[maybe_async]
+impl AuthorizationPrompt for LoopbackPrompt {
+    async fn prompt(&self, authorize_url: &str) -> ClientResult<String> {
+        let state = Self::start(authorize_url)?;
+        receive_redirect(self.listener.try_clone()?, self.redirect_uri.clone(), state).await
+    }
+
+    #[cfg(feature = "__sync")]
+    fn prompt_with_timeout(
+        &self,
+        authorize_url: &str,
+        timeout: std::time::Duration,
+    ) -> ClientResult<String> {
+        let state = Self::start(authorize_url)?;
+        let listener = self.listener.try_clone()?;
+        let redirect_uri = self.redirect_uri.clone();
+        wait_on_thread(timeout, move || {
+            accept_redirect(&listener, &redirect_uri, state.as_deref())
+        })
+    }
+}
+
+/// Waits for the redirect in a separate thread, so that the executor isn't
+/// blocked.
+#[cfg(all(feature = "cli", feature = "__async"))]
+async fn receive_redirect(
+    listener: TcpListener,
+    redirect_uri: Url,
+    state: Option<String>,
+) -> ClientResult<String> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(accept_redirect(&listener, &redirect_uri, state.as_deref()));
+    });
+
+    receiver
+        .await
+        .map_err(|_| ClientError::CLI("the redirect couldn't be received".to_string()))?
+}
+
+/// Waits for the redirect.
+#[cfg(all(feature = "cli", feature = "__sync"))]
+fn receive_redirect(
+    listener: TcpListener,
+    redirect_uri: Url,
+    state: Option<String>,
+) -> ClientResult<String> {
+    accept_redirect(&listener, &redirect_uri, state.as_deref())
+}
+
+/// Accepts connections until one is the redirect to `redirect_uri` with the
+/// given state, answering the rest with an error, like the browser asking
+/// for a favicon. Returns the full URL of the redirect.
+#[cfg(feature = "cli")]
+fn accept_redirect(
+    listener: &TcpListener,
+    redirect_uri: &Url,
+    state: Option<&str>,
+) -> ClientResult<String> {
+    // Connections opened in advance by browsers may never send a request.
+    let read_timeout = std::time::Duration::from_secs(5);
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        stream.set_read_timeout(Some(read_timeout))?;
+
+        let mut reader = BufReader::new(&stream);
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() {
+            continue;
+        }
+        // The headers are skipped, but read so that the connection isn't
+        // reset when closed.
+        let mut header = String::new();
+        while matches!(reader.read_line(&mut header), Ok(len) if len > 2) {
+            header.clear();
+        }
+        let redirect = match line
+            .split(' ')
+            .nth(1)
+            .map(|target| redirect_uri.join(target))
+        {
+            Some(Ok(url)) if url.path() == redirect_uri.path() => url,
+            _ => {
+                respond(&mut stream, "404 Not Found", "Not found.");
+                continue;
+            }
+        };
+
+        let param = |name: &str| {
+            redirect
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, val)| val.into_owned())
+        };
+        if state.is_some() && param(headers::STATE).as_deref() != state {
+            log::warn!("Ignored a redirect with an unexpected state");
+            respond(&mut stream, "400 Bad Request", "Unexpected state.");
+            continue;
+        }
+        if let Some(error) = param("error") {
+            respond(&mut stream, "200 OK", "The authorization was denied.");
+            return Err(ClientError::InvalidAuth(format!(
+                "the authorization failed: {}",
+                error
+            )));
+        }
+
+        respond(
+            &mut stream,
+            "200 OK",
+            "The application was authorized, you may close this window now.",
+        );
+        return Ok(redirect.into());
+    }
+
+    Err(ClientError::CLI("the local server stopped".to_string()))
+}
+
+/// Answers the browser with a plain text message.
+#[cfg(feature = "cli")]
+fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\ncontent-type: text/plain; charset=utf-8\r\n\
+         content-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        message.len(),
+        message
+    );
+    // The browser may have closed the connection already.
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// The form of a request to the token URL, whose `grant_type` depends on the
+/// flow.
+pub(crate) trait TokenRequest: Serialize {
+    const GRANT_TYPE: &'static str;
+
+    /// The form sent in the request, without the fields that aren't set.
+    fn to_form(&self) -> ClientResult<Form> {
+        let mut form = Form::new();
+        form.insert(headers::GRANT_TYPE.to_owned(), Self::GRANT_TYPE.to_owned());
+        if let serde_json::Value::Object(fields) = serde_json::to_value(self)? {
+            for (key, val) in fields {
+                match val {
+                    serde_json::Value::Null => {}
+                    serde_json::Value::String(val) => {
+                        form.insert(key, val);
+                    }
+                    val => {
+                        form.insert(key, val.to_string());
+                    }
+                }
+            }
+        }
+        Ok(form)
+    }
+}
+
+/// Exchanges the code of the [Authorization Code
+/// Flow](https://developer.spotify.com/documentation/general/guides/authorization-guide/#authorization-code-flow)
+/// for a token.
+#[derive(Debug, Serialize)]
+pub(crate) struct AuthCodeTokenRequest<'a> {
+    pub code: &'a str,
+    pub redirect_uri: &'a str,
+    pub scope: String,
+    pub state: &'a str,
+    pub code_verifier: Option<&'a str>,
+}
+
+impl TokenRequest for AuthCodeTokenRequest<'_> {
+    const GRANT_TYPE: &'static str = headers::GRANT_AUTH_CODE;
+}
+
+/// Renews a token with its refresh token.
+#[derive(Debug, Serialize)]
+pub(crate) struct RefreshTokenRequest<'a> {
+    pub refresh_token: &'a str,
+}
+
+impl TokenRequest for RefreshTokenRequest<'_> {
+    const GRANT_TYPE: &'static str = headers::GRANT_REFRESH_TOKEN;
+}
+
+/// Requests a token with the [Client Credentials
+/// Flow](https://developer.spotify.com/documentation/general/guides/authorization-guide/#client-credentials-flow).
+#[derive(Debug, Serialize)]
+pub(crate) struct ClientCredsTokenRequest;
+
+impl TokenRequest for ClientCredsTokenRequest {
+    const GRANT_TYPE: &'static str = headers::GRANT_CLIENT_CREDS;
+}
+
+/// How the user token was obtained by [`Spotify::resume`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resumed {
+    /// The cached token was still valid.
+    Cached,
+    /// The cached token was expired, and it was refreshed.
+    Refreshed,
+    /// There was no cached token with the configured scopes, or it couldn't be
+    /// refreshed, so the user was asked for authorization again.
+    Authorized,
+}
+
 /// Authorization-related methods for the client.
 impl Spotify {
-    /// Updates the cache file at the internal cache path.
+    /// Updates the cache file at the internal cache path, or the entry of
+    /// `cache_key` in the `token_cache` if it's configured.
     pub fn write_token_cache(&self) -> ClientResult<()> {
         if let Some(tok) = self.token.as_ref() {
-            tok.write_cache(&self.cache_path)?;
+            self.write_cached_token(tok)?;
         }
 
         Ok(())
     }
 
+    /// Reads the token from the `token_cache` if it's configured, or from the
+    /// cache file otherwise.
+    fn read_cached_token(&self) -> Option<Token> {
+        match &self.token_cache {
+            Some(cache) => cache.read(&self.cache_key).unwrap_or_else(|err| {
+                log::warn!("Couldn't read the cached token: {}", err);
+                None
+            }),
+            None => TokenBuilder::from_cache(&self.cache_path).build().ok(),
+        }
+    }
+
+    /// Saves the token into the `token_cache` if it's configured, or into
+    /// the cache file otherwise.
+    fn write_cached_token(&self, tok: &Token) -> ClientResult<()> {
+        match &self.token_cache {
+            Some(cache) => cache.write(&self.cache_key, tok),
+            None => tok.write_cache(&self.cache_path),
+        }
+    }
+
     /// Gets the required URL to authorize the current client to start the
-    /// [Authorization Code Flow](https://developer.spotify.com/documentation/general/guides/authorization-guide/#authorization-code-flow).
-    pub fn get_authorize_url(&self, show_dialog: bool) -> ClientResult<String> {
+    /// [Authorization Code Flow](https://developer.spotify.com/documentation/general/guides/authorization-guide/#authorization-code-flow),
+    /// with the given options. All the parameters are percent-encoded.
+    pub fn get_authorize_url(&self, options: &AuthorizeUrlOptions) -> ClientResult<String> {
         let oauth = self.get_oauth()?;
-        let mut payload: HashMap<&str, &str> = HashMap::new();
-        let scope = oauth
-            .scope
-            .clone()
-            .into_iter()
-            .collect::<Vec<_>>()
-            .join(" ");
-        payload.insert(headers::CLIENT_ID, &self.get_creds()?.id);
-        payload.insert(headers::RESPONSE_TYPE, headers::RESPONSE_CODE);
-        payload.insert(headers::REDIRECT_URI, &oauth.redirect_uri);
-        payload.insert(headers::SCOPE, &scope);
-        payload.insert(headers::STATE, &oauth.state);
-
-        if show_dialog {
-            payload.insert(headers::SHOW_DIALOG, "true");
-        }
-
-        let parsed = Url::parse_with_params(auth_urls::AUTHORIZE, payload)?;
+        // The parameters are in a fixed order and the scopes are sorted, so
+        // that the URL is always the same for the same configuration.
+        let mut payload: Vec<(&str, &str)> = Vec::new();
+        let mut scope = oauth.scope.iter().map(String::as_str).collect::<Vec<_>>();
+        scope.sort_unstable();
+        let scope = scope.join(" ");
+        let state = options.state.as_deref().unwrap_or(&oauth.state);
+        payload.push((headers::CLIENT_ID, &self.get_creds()?.id));
+        payload.push((headers::RESPONSE_TYPE, headers::RESPONSE_CODE));
+        payload.push((headers::REDIRECT_URI, &oauth.redirect_uri));
+        payload.push((headers::SCOPE, &scope));
+        payload.push((headers::STATE, state));
+
+        if options.show_dialog {
+            payload.push((headers::SHOW_DIALOG, "true"));
+        }
+        let challenge = oauth.code_verifier.as_deref().map(code_challenge);
+        if let Some(challenge) = &challenge {
+            payload.push((headers::CODE_CHALLENGE_METHOD, "S256"));
+            payload.push((headers::CODE_CHALLENGE, challenge));
+        }
+        for (key, val) in &options.extra_params {
+            payload.push((key, val));
+        }
+
+        let parsed = Url::parse_with_params(&self.authorize_url, payload)?;
         Ok(parsed.into_string())
     }
 
-    /// Tries to read the cache file's token, which may not exist.
+    /// Tries to read the cached token, which may not exist, from the cache
+    /// file or the `token_cache`. The scopes are only checked when OAuth is
+    /// configured.
     #[maybe_async]
-    pub async fn read_token_cache(&mut self) -> Option<Token> {
-        let tok = TokenBuilder::from_cache(&self.cache_path).build().ok()?;
+    pub async fn read_token_cache(&self) -> Option<Token> {
+        let tok = self.read_cached_token()?;
+        let has_scopes = self
+            .get_oauth()
+            .map_or(true, |oauth| oauth.scope.is_subset(&tok.scope));
 
-        if !self.get_oauth().ok()?.scope.is_subset(&tok.scope) || tok.is_expired() {
+        if !has_scopes || tok.is_expired() {
             // Invalid token, since it doesn't have at least the currently
             // required scopes or it's expired.
             None
@@ -244,15 +997,23 @@ impl Spotify {
 
     /// Sends a request to Spotify for an access token.
     #[maybe_async]
-    async fn fetch_access_token(&self, payload: &Form) -> ClientResult<Token> {
+    async fn fetch_access_token<R: TokenRequest + Sync>(&self, request: &R) -> ClientResult<Token> {
         // This request uses a specific content type, and the client ID/secret
         // as the authentication, since the access token isn't available yet.
+        // Clients without a secret, with PKCE, send their ID in the form.
+        let creds = self.get_creds()?;
+        let mut payload = request.to_form()?;
         let mut head = Headers::new();
-        let (key, val) = headers::basic_auth(&self.get_creds()?.id, &self.get_creds()?.secret);
-        head.insert(key, val);
+        if creds.secret.is_empty() {
+            payload.insert(headers::CLIENT_ID.to_owned(), creds.id.clone());
+        } else {
+            let (key, val) = headers::basic_auth(&creds.id, &creds.secret);
+            head.insert(key, val);
+        }
+        let payload = &payload;
 
         let response = self
-            .post_form(auth_urls::TOKEN, Some(&head), payload)
+            .post_form(&self.token_url, Some(&head), payload)
             .await?;
         let mut tok = serde_json::from_str::<Token>(&response)?;
         tok.expires_at = Utc::now().checked_add_signed(tok.expires_in);
@@ -263,47 +1024,127 @@ impl Spotify {
     /// [Authorization Code Flow](https://developer.spotify.com/documentation/general/guides/authorization-guide/#authorization-code-flow),
     /// without saving it into the cache file.
     ///
-    /// The obtained token will be saved internally.
+    /// The obtained token will be saved internally. If it was granted fewer
+    /// scopes than requested, like when the user has revoked some of them,
+    /// [`ClientError::ScopesReduced`] is returned, even though the new token
+    /// is still saved and may be used for the remaining scopes.
+    ///
+    /// The new refresh token is kept in case Spotify returns one, since the
+    /// given one may have been revoked, so the next refresh must use the one
+    /// in the saved token.
     #[maybe_async]
     pub async fn refresh_user_token_without_cache(
         &mut self,
         refresh_token: &str,
     ) -> ClientResult<()> {
-        let mut data = Form::new();
-        data.insert(headers::REFRESH_TOKEN.to_owned(), refresh_token.to_owned());
-        data.insert(
-            headers::GRANT_TYPE.to_owned(),
-            headers::GRANT_REFRESH_TOKEN.to_owned(),
-        );
+        let request = RefreshTokenRequest { refresh_token };
+        let mut tok = self.fetch_access_token(&request).await?;
+        // Spotify may rotate the refresh token, like with PKCE, in which case
+        // the previous one is revoked. Otherwise, it's still valid.
+        if tok.refresh_token.is_none() {
+            tok.refresh_token = Some(refresh_token.to_owned());
+        }
 
-        let mut tok = self.fetch_access_token(&data).await?;
-        tok.refresh_token = Some(refresh_token.to_string());
+        // The scopes that should have been granted are the configured ones,
+        // or otherwise the ones of the previous token.
+        let previous = self.token.take().map(|tok| tok.scope).unwrap_or_default();
+        if tok.scope.is_empty() {
+            // Spotify may omit the scopes when they haven't changed.
+            tok.scope = previous.clone();
+        }
+        let requested = match &self.oauth {
+            Some(oauth) => &oauth.scope,
+            None => &previous,
+        };
+        let mut missing = requested
+            .difference(&tok.scope)
+            .cloned()
+            .collect::<Vec<_>>();
         self.token = Some(tok);
 
-        Ok(())
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            missing.sort();
+            Err(ClientError::ScopesReduced { missing })
+        }
     }
 
     /// The same as `refresh_user_token_without_cache`, but saves the token
     /// into the cache file if possible.
     #[maybe_async]
     pub async fn refresh_user_token(&mut self, refresh_token: &str) -> ClientResult<()> {
-        self.refresh_user_token_without_cache(refresh_token).await?;
+        let result = self.refresh_user_token_without_cache(refresh_token).await;
+        if let Ok(()) | Err(ClientError::ScopesReduced { .. }) = result {
+            self.write_token_cache()?;
+        }
+        result
+    }
 
-        Ok(())
+    /// Sends a request to Spotify for an access token with the [Client
+    /// Credentials Flow](https://developer.spotify.com/documentation/general/guides/authorization-guide/#client-credentials-flow).
+    #[maybe_async]
+    async fn fetch_client_token(&self) -> ClientResult<Token> {
+        self.fetch_access_token(&ClientCredsTokenRequest).await
     }
 
     /// Obtains the client access token for the app without saving it into the
     /// cache file. The resulting token is saved internally.
     #[maybe_async]
     pub async fn request_client_token_without_cache(&mut self) -> ClientResult<()> {
-        let mut data = Form::new();
-        data.insert(
-            headers::GRANT_TYPE.to_owned(),
-            headers::GRANT_CLIENT_CREDS.to_owned(),
-        );
+        self.token = Some(self.fetch_client_token().await?);
+
+        Ok(())
+    }
+
+    /// Obtains a new client access token in case the current one is missing
+    /// or about to expire, so that long-lived clients don't have to renew it
+    /// manually. This is only possible with the Client Credentials Flow, since
+    /// the other flows require the user's intervention, so nothing is done when
+    /// OAuth is configured.
+    ///
+    /// The new token is kept internally without modifying `Spotify.token`. It
+    /// will also be read from and saved into the cache file if
+    /// `cache_renewed_tokens` is enabled. The requests made while it's being
+    /// renewed, including the ones from clones, wait for it instead of
+    /// requesting another one.
+    #[maybe_async]
+    pub(crate) async fn request_token_if_needed(&self) -> ClientResult<()> {
+        if self.oauth.is_some() || self.credentials.is_none() {
+            return Ok(());
+        }
 
-        self.token = Some(self.fetch_access_token(&data).await?);
+        // Renewing it a bit earlier so that it doesn't expire mid-request.
+        let limit = Utc::now() + Duration::seconds(RENEWAL_MARGIN_SECS);
+        let valid = |tok: &Token| matches!(tok.expires_at, Some(at) if at > limit);
+        if matches!(self.get_token(), Ok(tok) if valid(&tok)) {
+            return Ok(());
+        }
+
+        // Only one request renews it at a time, and the rest use its token
+        // once it's done. If it fails, the next one tries again.
+        let _renewing = lock_renewal(&self.renewal_lock).await;
+        if matches!(self.get_token(), Ok(tok) if valid(&tok)) {
+            return Ok(());
+        }
+
+        let cached = if self.cache_renewed_tokens {
+            self.read_token_cache().await.filter(valid)
+        } else {
+            None
+        };
+        let tok = match cached {
+            Some(tok) => tok,
+            None => {
+                let tok = self.fetch_client_token().await?;
+                if self.cache_renewed_tokens {
+                    self.write_cached_token(&tok)?;
+                }
+                tok
+            }
+        };
 
+        *self.renewed_token.lock().unwrap() = Some(tok);
         Ok(())
     }
 
@@ -335,25 +1176,15 @@ impl Spotify {
     #[maybe_async]
     pub async fn request_user_token_without_cache(&mut self, code: &str) -> ClientResult<()> {
         let oauth = self.get_oauth()?;
-        let mut data = Form::new();
-        data.insert(
-            headers::GRANT_TYPE.to_owned(),
-            headers::GRANT_AUTH_CODE.to_owned(),
-        );
-        data.insert(headers::REDIRECT_URI.to_owned(), oauth.redirect_uri.clone());
-        data.insert(headers::CODE.to_owned(), code.to_owned());
-        data.insert(
-            headers::SCOPE.to_owned(),
-            oauth
-                .scope
-                .clone()
-                .into_iter()
-                .collect::<Vec<_>>()
-                .join(" "),
-        );
-        data.insert(headers::STATE.to_owned(), oauth.state.clone());
+        let request = AuthCodeTokenRequest {
+            code,
+            redirect_uri: &oauth.redirect_uri,
+            scope: oauth.scope.iter().cloned().collect::<Vec<_>>().join(" "),
+            state: &oauth.state,
+            code_verifier: oauth.code_verifier.as_deref(),
+        };
 
-        self.token = Some(self.fetch_access_token(&data).await?);
+        self.token = Some(self.fetch_access_token(&request).await?);
 
         Ok(())
     }
@@ -375,63 +1206,218 @@ impl Spotify {
     #[cfg(feature = "cli")]
     #[maybe_async]
     pub async fn prompt_for_user_token_without_cache(&mut self) -> ClientResult<()> {
-        let code = self.get_code_from_user()?;
+        let code = self.get_code_from_user(&CliPrompt).await?;
         self.request_user_token_without_cache(&code).await?;
 
         Ok(())
     }
 
     /// The same as the `prompt_for_user_token_without_cache` method, but it
-    /// will try to use the user token into the cache file, and save it in
-    /// case it didn't exist/was invalid.
+    /// will try to use the user token into the cache file, refreshing it if
+    /// it's expired, and save it in case it didn't exist/was invalid.
     ///
     /// Note: this method requires the `cli` feature.
     #[cfg(feature = "cli")]
     #[maybe_async]
     pub async fn prompt_for_user_token(&mut self) -> ClientResult<()> {
-        // TODO: shouldn't this also refresh the obtained token?
-        self.token = self.read_token_cache().await;
+        self.prompt_for_user_token_with(&CliPrompt).await
+    }
+
+    /// The same as [`Spotify::prompt_for_user_token`], but receiving the
+    /// redirect in a local server with [`LoopbackPrompt`], so the redirect URI
+    /// must be a loopback one like `http://127.0.0.1/callback`. Without a port
+    /// in it, an ephemeral one is used, as RFC 8252 allows for the loopback
+    /// redirect URIs registered without a port.
+    ///
+    /// Note: this method requires the `cli` feature.
+    #[cfg(feature = "cli")]
+    #[maybe_async]
+    pub async fn prompt_for_user_token_loopback(&mut self) -> ClientResult<()> {
+        let configured = self.get_oauth()?.redirect_uri.clone();
+        let prompt = LoopbackPrompt::bind(&configured)?;
 
-        // Otherwise following the usual procedure to get the token.
-        if self.token.is_none() {
-            let code = self.get_code_from_user()?;
-            // Will write to the cache file if successful
-            self.request_user_token(&code).await?;
+        // The same redirect URI must be sent when requesting the token.
+        if let Some(oauth) = &mut self.oauth {
+            oauth.redirect_uri = prompt.redirect_uri().to_owned();
         }
+        let result = self.prompt_for_user_token_with(&prompt).await;
+        if let Some(oauth) = &mut self.oauth {
+            oauth.redirect_uri = configured;
+        }
+        result
+    }
 
-        Ok(())
+    /// Obtains the user token with the cache file if possible, and with the
+    /// given prompt otherwise:
+    ///
+    /// 1. The cached token is used if it has at least the configured scopes.
+    /// 2. If it's expired, it's refreshed with its refresh token.
+    /// 3. Otherwise, the user is asked for authorization with `prompt`.
+    ///
+    /// The obtained token is saved into the cache file.
+    #[maybe_async]
+    pub async fn prompt_for_user_token_with<P: AuthorizationPrompt + Sync>(
+        &mut self,
+        prompt: &P,
+    ) -> ClientResult<()> {
+        self.obtain_user_token(prompt).await.map(|_| ())
     }
 
-    /// Tries to open the authorization URL in the user's browser, and returns
-    /// the obtained code.
+    /// Resumes the session of the user after the app is restarted, which is
+    /// the whole startup of a desktop app in one call. The token is obtained
+    /// from `cache` as in [`Spotify::prompt_for_user_token_with`], refreshing
+    /// it if it's expired and asking the user for authorization with `prompt`
+    /// only as a last resort. The cache is also configured as the
+    /// `token_cache`, so that the next tokens are saved into it.
     ///
-    /// Note: this method requires the `cli` feature.
-    #[cfg(feature = "cli")]
-    fn get_code_from_user(&self) -> ClientResult<String> {
-        use crate::client::ClientError;
+    /// ```no_run
+    /// # #[cfg(all(feature = "cli", feature = "client-reqwest"))]
+    /// # async fn run(mut spotify: rspotify::client::Spotify) {
+    /// use rspotify::oauth2::{CliPrompt, MemoryTokenCache, Resumed};
+    /// use std::sync::Arc;
+    ///
+    /// let cache = Arc::new(MemoryTokenCache::default());
+    /// if spotify.resume(cache, &CliPrompt).await.unwrap() == Resumed::Authorized {
+    ///     println!("Welcome!");
+    /// }
+    /// # }
+    /// ```
+    #[maybe_async]
+    pub async fn resume<P: AuthorizationPrompt + Sync>(
+        &mut self,
+        cache: Arc<dyn TokenCache>,
+        prompt: &P,
+    ) -> ClientResult<Resumed> {
+        self.token_cache = Some(cache);
+        self.obtain_user_token(prompt).await
+    }
 
-        let url = self.get_authorize_url(false)?;
+    /// The flow of [`Spotify::prompt_for_user_token_with`], returning how the
+    /// token was obtained.
+    #[maybe_async]
+    async fn obtain_user_token<P: AuthorizationPrompt + Sync>(
+        &mut self,
+        prompt: &P,
+    ) -> ClientResult<Resumed> {
+        let scope = &self.get_oauth()?.scope;
+        let cached = self
+            .read_cached_token()
+            .filter(|tok| scope.is_subset(&tok.scope));
+
+        if let Some(tok) = cached {
+            if !tok.is_expired() {
+                self.token = Some(tok);
+                return Ok(Resumed::Cached);
+            }
 
-        match webbrowser::open(&url) {
-            Ok(_) => println!("Opened {} in your browser.", url),
-            Err(why) => eprintln!(
-                "Error when trying to open an URL in your browser: {:?}. \
-                 Please navigate here manually: {}",
-                why, url
-            ),
+            if let Some(refresh_token) = &tok.refresh_token {
+                match self.refresh_user_token(refresh_token).await {
+                    Ok(()) => return Ok(Resumed::Refreshed),
+                    Err(err) => log::warn!("Couldn't refresh the cached token: {}", err),
+                }
+            }
         }
 
-        println!("Please enter the URL you were redirected to: ");
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        let code = self
-            .parse_response_code(&input)
-            .ok_or_else(|| ClientError::CLI("unable to parse the response code".to_string()))?;
+        let code = self.get_code_from_user(prompt).await?;
+        // Will write to the cache file if successful
+        self.request_user_token(&code).await?;
+        Ok(Resumed::Authorized)
+    }
+
+    /// Creates an authenticated client for the Authorization Code Flow from
+    /// the environment, which is the common setup for examples and
+    /// integration tests:
+    ///
+    /// * The credentials are read with [`CredentialsBuilder::from_env`].
+    /// * The OAuth information is read with [`OAuthBuilder::from_env`].
+    /// * The token is obtained with [`Spotify::prompt_for_user_token_with`].
+    #[maybe_async]
+    pub async fn from_env_with_prompt<P: AuthorizationPrompt + Sync>(
+        prompt: &P,
+    ) -> ClientResult<Self> {
+        use crate::client::SpotifyBuilder;
+
+        let creds = CredentialsBuilder::from_env()
+            .build()
+            .map_err(ClientError::InvalidAuth)?;
+        let oauth = OAuthBuilder::from_env()
+            .build()
+            .map_err(ClientError::InvalidAuth)?;
+        let mut spotify = SpotifyBuilder::default()
+            .credentials(creds)
+            .oauth(oauth)
+            .build()
+            .map_err(ClientError::InvalidAuth)?;
+
+        spotify.prompt_for_user_token_with(prompt).await?;
+        Ok(spotify)
+    }
+
+    /// The same as [`Spotify::from_env_with_prompt`], asking for the user's
+    /// authorization in the terminal with [`CliPrompt`].
+    ///
+    /// Note: this method requires the `cli` feature.
+    #[cfg(feature = "cli")]
+    #[maybe_async]
+    pub async fn from_env_interactive() -> ClientResult<Self> {
+        Self::from_env_with_prompt(&CliPrompt).await
+    }
+
+    /// Asks the user for authorization with the given prompt, and returns the
+    /// obtained code.
+    #[maybe_async]
+    async fn get_code_from_user<P: AuthorizationPrompt + Sync>(
+        &self,
+        prompt: &P,
+    ) -> ClientResult<String> {
+        let url = self.get_authorize_url(&AuthorizeUrlOptions::default())?;
+        let input = self.wait_for_prompt(prompt, &url).await?;
+        let code = self.parse_response_code(&input).ok_or_else(|| {
+            ClientError::InvalidAuth("unable to parse the response code".to_string())
+        })?;
 
         Ok(code)
     }
 }
 
+impl Spotify {
+    /// Asks the user for authorization with `prompt`, failing once
+    /// `prompt_timeout` is exceeded.
+    #[cfg(feature = "__async")]
+    async fn wait_for_prompt<P: AuthorizationPrompt + Sync>(
+        &self,
+        prompt: &P,
+        url: &str,
+    ) -> ClientResult<String> {
+        use futures::future::{self, Either};
+
+        let timeout = match self.prompt_timeout {
+            Some(timeout) => timeout,
+            None => return prompt.prompt(url).await,
+        };
+        let input = prompt.prompt(url);
+        futures::pin_mut!(input);
+        match future::select(input, self.sleeper.sleep(timeout)).await {
+            Either::Left((input, _)) => input,
+            Either::Right(_) => Err(ClientError::Timeout),
+        }
+    }
+
+    /// Asks the user for authorization with `prompt`, failing once
+    /// `prompt_timeout` is exceeded if the prompt supports it.
+    #[cfg(feature = "__sync")]
+    fn wait_for_prompt<P: AuthorizationPrompt + Sync>(
+        &self,
+        prompt: &P,
+        url: &str,
+    ) -> ClientResult<String> {
+        match self.prompt_timeout {
+            Some(timeout) => prompt.prompt_with_timeout(url, timeout),
+            None => prompt.prompt(url),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -439,11 +1425,83 @@ mod tests {
     use url::Url;
 
     use chrono::Duration;
-    use std::collections::HashSet;
-    use std::fs;
+    use std::collections::{HashMap, HashSet};
+    use std::{env, fs};
     use std::io::Read;
     use std::thread::sleep;
 
+    #[test]
+    fn test_token_request_form() {
+        let form = RefreshTokenRequest {
+            refresh_token: "refresh",
+        }
+        .to_form()
+        .unwrap();
+        assert_eq!(form.len(), 2);
+        assert_eq!(form["grant_type"], "refresh_token");
+        assert_eq!(form["refresh_token"], "refresh");
+
+        let form = ClientCredsTokenRequest.to_form().unwrap();
+        assert_eq!(form.len(), 1);
+        assert_eq!(form["grant_type"], "client_credentials");
+
+        let form = AuthCodeTokenRequest {
+            code: "code",
+            redirect_uri: "http://localhost:8888/callback",
+            scope: "user-read-private".to_owned(),
+            state: "state",
+            code_verifier: None,
+        }
+        .to_form()
+        .unwrap();
+        assert_eq!(form["grant_type"], "authorization_code");
+        assert_eq!(form["code"], "code");
+        assert_eq!(form["redirect_uri"], "http://localhost:8888/callback");
+        assert_eq!(form["scope"], "user-read-private");
+        assert_eq!(form["state"], "state");
+        assert!(!form.contains_key("code_verifier"));
+    }
+
+    #[test]
+    fn test_pkce_authorize_url() {
+        // The example of the appendix B of RFC 7636
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        assert_eq!(
+            code_challenge(verifier),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let oauth = OAuthBuilder::default()
+            .redirect_uri("localhost")
+            .code_verifier(verifier)
+            .build()
+            .unwrap();
+        let creds = CredentialsBuilder::default()
+            .id("this-is-my-client-id")
+            .secret("")
+            .build()
+            .unwrap();
+        let spotify = SpotifyBuilder::default()
+            .credentials(creds)
+            .oauth(oauth)
+            .build()
+            .unwrap();
+
+        let url = spotify.get_authorize_url(&Default::default()).unwrap();
+        let query: HashMap<_, _> = Url::parse(&url)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect();
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(
+            query["code_challenge"],
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert!(!format!("{:?}", spotify).contains(verifier));
+        assert_eq!(generate_code_verifier().len(), 128);
+    }
+
     #[test]
     fn test_get_authorize_url() {
         let scope = "playlist-read-private";
@@ -467,7 +1525,7 @@ mod tests {
             .build()
             .unwrap();
 
-        let authorize_url = spotify.get_authorize_url(false).unwrap();
+        let authorize_url = spotify.get_authorize_url(&Default::default()).unwrap();
         let hash_query: HashMap<_, _> = Url::parse(&authorize_url)
             .unwrap()
             .query_pairs()
@@ -480,6 +1538,76 @@ mod tests {
         assert_eq!(hash_query.get("scope").unwrap(), "playlist-read-private");
         assert_eq!(hash_query.get("state").unwrap(), "fdsafdsfa");
     }
+
+    #[test]
+    fn test_get_authorize_url_custom() {
+        let oauth = OAuthBuilder::default()
+            .redirect_uri("localhost")
+            .build()
+            .unwrap();
+        let creds = CredentialsBuilder::default()
+            .id("this-is-my-client-id")
+            .secret("this-is-my-client-secret")
+            .build()
+            .unwrap();
+
+        let spotify = SpotifyBuilder::default()
+            .credentials(creds)
+            .oauth(oauth)
+            .authorize_url("https://sso.example.com/spotify/authorize")
+            .build()
+            .unwrap();
+
+        let authorize_url =
+            Url::parse(&spotify.get_authorize_url(&Default::default()).unwrap()).unwrap();
+        assert_eq!(authorize_url.host_str(), Some("sso.example.com"));
+        assert_eq!(authorize_url.path(), "/spotify/authorize");
+    }
+    #[test]
+    fn test_get_authorize_url_options() {
+        let oauth = OAuthBuilder::default()
+            .state("configured")
+            .redirect_uri("localhost")
+            .build()
+            .unwrap();
+        let creds = CredentialsBuilder::default()
+            .id("this-is-my-client-id")
+            .secret("this-is-my-client-secret")
+            .build()
+            .unwrap();
+        let spotify = SpotifyBuilder::default()
+            .credentials(creds)
+            .oauth(oauth)
+            .build()
+            .unwrap();
+
+        let options = AuthorizeUrlOptions {
+            show_dialog: true,
+            state: Some("per login".to_owned()),
+            extra_params: vec![("login_hint".to_owned(), "user@example.com&a=b".to_owned())],
+        };
+        let authorize_url = spotify.get_authorize_url(&options).unwrap();
+        assert!(authorize_url.ends_with("&login_hint=user%40example.com%26a%3Db"));
+        let query: Vec<(String, String)> = Url::parse(&authorize_url)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect();
+        let get = |key: &str| {
+            query
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, val)| val.as_str())
+        };
+        assert_eq!(get("show_dialog"), Some("true"));
+        assert_eq!(get("state"), Some("per login"));
+        assert_eq!(get("login_hint"), Some("user@example.com&a=b"));
+
+        let authorize_url = spotify.get_authorize_url(&Default::default()).unwrap();
+        assert!(authorize_url.contains("&state=configured"));
+        assert!(!authorize_url.contains("show_dialog"));
+    }
+
     #[test]
     fn test_write_token() {
         let now: DateTime<Utc> = Utc::now();
@@ -506,6 +1634,7 @@ mod tests {
 
         let spotify = SpotifyBuilder::default()
             .token(tok.clone())
+            .cache_path(env::temp_dir().join("rspotify_test_write_token.json"))
             .build()
             .unwrap();
 
@@ -521,6 +1650,56 @@ mod tests {
         assert_eq!(tok_from_file.scope, scope);
         assert_eq!(tok_from_file.expires_in, Duration::seconds(3600));
         assert_eq!(tok_from_file.expires_at.unwrap(), now);
+
+        fs::remove_file(&spotify.cache_path).unwrap();
+    }
+
+    #[test]
+    fn test_memory_token_cache() {
+        let token = |access_token: &str, expires_at: DateTime<Utc>| {
+            TokenBuilder::default()
+                .access_token(access_token)
+                .expires_at(expires_at)
+                .build()
+                .unwrap()
+        };
+        let valid = token("alice-token", Utc::now() + Duration::seconds(3600));
+        let expired = token("bob-token", Utc::now() - Duration::seconds(10));
+        let mut refreshable = expired.clone();
+        refreshable.refresh_token = Some("carol-refresh".to_owned());
+
+        // The expired tokens are dropped once read, or when another one is
+        // written, unless they can be refreshed.
+        let access_token = |tok: Option<Token>| tok.map(|tok| tok.access_token);
+        let cache = MemoryTokenCache::default();
+        cache.write("bob", &expired).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(access_token(cache.read("bob").unwrap()), None);
+        assert!(cache.is_empty());
+        cache.write("bob", &expired).unwrap();
+        cache.write("carol", &refreshable).unwrap();
+        assert_eq!(cache.len(), 1);
+        let carol = cache.read("carol").unwrap();
+        assert_eq!(access_token(carol), Some(refreshable.access_token));
+
+        // The clients of each user share the same cache with their own key.
+        let client = |key: &str| {
+            SpotifyBuilder::default()
+                .token_cache(Arc::new(cache.clone()))
+                .cache_key(key)
+                .build()
+                .unwrap()
+        };
+        let mut alice = client("alice");
+        alice.token = Some(valid);
+        alice.write_token_cache().unwrap();
+        let alice = client("alice").read_cached_token();
+        assert_eq!(access_token(alice), Some("alice-token".to_owned()));
+        assert_eq!(access_token(client("dave").read_cached_token()), None);
+
+        cache.remove("alice").unwrap();
+        cache.remove("carol").unwrap();
+        assert!(cache.is_empty());
     }
 
     #[test]
@@ -547,6 +1726,31 @@ mod tests {
         assert!(tok.is_expired());
     }
 
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let tok = TokenBuilder::default()
+            .access_token("test-access-token")
+            .refresh_token("test-refresh-token")
+            .build()
+            .unwrap();
+        let creds = CredentialsBuilder::default()
+            .id("test-id")
+            .secret("test-secret")
+            .build()
+            .unwrap();
+        let spotify = SpotifyBuilder::default()
+            .token(tok)
+            .credentials(creds)
+            .build()
+            .unwrap();
+
+        let debug = format!("{:?}", spotify);
+        assert!(debug.contains("test-id"));
+        assert!(!debug.contains("test-access-token"));
+        assert!(!debug.contains("test-refresh-token"));
+        assert!(!debug.contains("test-secret"));
+    }
+
     #[test]
     fn test_parse_response_code() {
         let spotify = SpotifyBuilder::default().build().unwrap();
@@ -567,4 +1771,67 @@ mod tests {
         let code = spotify.parse_response_code(url);
         assert_eq!(code, Some("AQD0yXvFEOvw".to_string()));
     }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_loopback_prompt_bind() {
+        let prompt = LoopbackPrompt::bind("http://127.0.0.1/callback").unwrap();
+        let port = prompt.listener.local_addr().unwrap().port();
+        assert_ne!(port, 0);
+        assert_eq!(
+            prompt.redirect_uri(),
+            format!("http://127.0.0.1:{}/callback", port)
+        );
+
+        for uri in &[
+            "http://localhost:8888/callback",
+            "https://127.0.0.1/callback",
+            "http://192.168.1.2/callback",
+        ] {
+            assert!(matches!(
+                LoopbackPrompt::bind(uri),
+                Err(ClientError::InvalidAuth(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_accept_redirect() {
+        use std::net::TcpStream;
+
+        let prompt = LoopbackPrompt::bind("http://127.0.0.1:0/callback").unwrap();
+        let redirect_uri = Url::parse(prompt.redirect_uri()).unwrap();
+        let addr = prompt.listener.local_addr().unwrap();
+        let browser = std::thread::spawn(move || {
+            let mut statuses = Vec::new();
+            for target in &[
+                "/favicon.ico",
+                "/callback?code=forged&state=other",
+                "/callback?code=AQD0yXvF&state=sN",
+            ] {
+                let mut stream = TcpStream::connect(addr).unwrap();
+                let request = format!("GET {} HTTP/1.1\r\nhost: {}\r\n\r\n", target, addr);
+                stream.write_all(request.as_bytes()).unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                statuses.push(response.lines().next().unwrap().to_owned());
+            }
+            statuses
+        });
+
+        let redirect = accept_redirect(&prompt.listener, &redirect_uri, Some("sN")).unwrap();
+        assert_eq!(
+            redirect,
+            format!("http://{}/callback?code=AQD0yXvF&state=sN", addr)
+        );
+        assert_eq!(
+            browser.join().unwrap(),
+            [
+                "HTTP/1.1 404 Not Found",
+                "HTTP/1.1 400 Bad Request",
+                "HTTP/1.1 200 OK"
+            ]
+        );
+    }
 }
@@ -0,0 +1,330 @@
+//! Synchronous pagination with iterators.
+
+use super::{CursorPage, PageCursor};
+use crate::client::ClientResult;
+use crate::model::Page;
+
+use std::iter;
+use std::vec::IntoIter;
+
+/// Returns an iterator over all the items of a paginated endpoint, requested
+/// in pages of `page_size` items. `request` is called with the limit and the
+/// offset of each page.
+///
+/// The iteration stops after the first error.
+pub fn paginate<T, F>(request: F, page_size: u32) -> PageIter<T, F>
+where
+    F: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    PageIter {
+        request,
+        page_size,
+        offset: Some(0),
+        items: Vec::new().into_iter(),
+    }
+}
+
+/// Iterator returned by [`paginate`].
+pub struct PageIter<T, F> {
+    request: F,
+    page_size: u32,
+    /// The offset of the next page to request, `None` if there are no more.
+    offset: Option<u32>,
+    /// The remaining items of the current page.
+    items: IntoIter<T>,
+}
+
+impl<T, F> Iterator for PageIter<T, F>
+where
+    F: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    type Item = ClientResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            let offset = self.offset?;
+            match (self.request)(self.page_size, offset) {
+                Ok(page) => {
+                    self.offset = page.next_offset();
+                    self.items = page.items.into_iter();
+                }
+                Err(err) => {
+                    self.offset = None;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+/// The same as [`paginate`], but the pages are yielded whole instead of their
+/// items, to process them one at a time, like to insert them in bulk into a
+/// database.
+///
+/// The iteration stops after the first error.
+pub fn paginate_whole_pages<T, F>(
+    request: F,
+    page_size: u32,
+) -> impl Iterator<Item = ClientResult<Page<T>>>
+where
+    F: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    let mut offset = Some(0);
+    iter::from_fn(move || {
+        let page = request(page_size, offset.take()?);
+        if let Ok(page) = &page {
+            offset = page.next_offset();
+        }
+        Some(page)
+    })
+}
+
+/// Returns an iterator over all the items of a cursor-based endpoint.
+/// `request` is called with the cursor of each page, starting with `start`,
+/// and returns its items along with the cursor of the next page, or `None` if
+/// it's the last one.
+///
+/// The iteration stops after the first error.
+pub fn paginate_cursor<T, C, F>(request: F, start: C) -> CursorPageIter<T, C, F>
+where
+    F: Fn(C) -> ClientResult<CursorPage<T, C>>,
+{
+    CursorPageIter {
+        request,
+        cursor: Some(start),
+        items: Vec::new().into_iter(),
+    }
+}
+
+/// Iterator returned by [`paginate_cursor`].
+pub struct CursorPageIter<T, C, F> {
+    request: F,
+    /// The cursor of the next page to request, `None` if there are no more.
+    cursor: Option<C>,
+    /// The remaining items of the current page.
+    items: IntoIter<T>,
+}
+
+impl<T, C, F> Iterator for CursorPageIter<T, C, F>
+where
+    F: Fn(C) -> ClientResult<CursorPage<T, C>>,
+{
+    type Item = ClientResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            let cursor = self.cursor.take()?;
+            match (self.request)(cursor) {
+                Ok((items, cursor)) => {
+                    self.cursor = cursor;
+                    self.items = items.into_iter();
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Returns an iterator over the pages requested with `request`, starting at
+/// `start`. The iteration stops after the first error.
+fn paginate_checkpoints<T, F>(
+    request: F,
+    start: PageCursor,
+) -> impl Iterator<Item = ClientResult<CursorPage<T, PageCursor>>>
+where
+    F: Fn(PageCursor) -> ClientResult<CursorPage<T, PageCursor>>,
+{
+    let mut cursor = Some(start);
+    iter::from_fn(move || {
+        let page = request(cursor.take()?);
+        if let Ok((_, next)) = &page {
+            cursor = next.clone();
+        }
+        Some(page)
+    })
+}
+
+/// Returns an iterator over the pages of a paginated endpoint, starting at
+/// the offset in `start`. Each page is yielded with the cursor of the next
+/// one, which can be saved to resume later with [`paginate_pages`], or `None`
+/// if it's the last one. Otherwise, the same as [`paginate`].
+///
+/// The iteration stops after the first error, including when `start` isn't an
+/// offset.
+pub fn paginate_pages<T, F>(
+    request: F,
+    page_size: u32,
+    start: PageCursor,
+) -> impl Iterator<Item = ClientResult<CursorPage<T, PageCursor>>>
+where
+    F: Fn(u32, u32) -> ClientResult<Page<T>>,
+{
+    let request = move |cursor: PageCursor| {
+        let page = request(page_size, cursor.into_offset()?)?;
+        let next = page.next_offset().map(PageCursor::Offset);
+        Ok((page.items, next))
+    };
+    paginate_checkpoints(request, start)
+}
+
+/// Returns an iterator over the pages of a cursor-based endpoint, starting at
+/// the cursor in `start`. Each page is yielded with the cursor of the next
+/// one, which can be saved to resume later with [`paginate_cursor_pages`], or
+/// `None` if it's the last one. Otherwise, the same as [`paginate_cursor`].
+///
+/// The iteration stops after the first error, including when `start` isn't a
+/// cursor.
+pub fn paginate_cursor_pages<T, F>(
+    request: F,
+    start: PageCursor,
+) -> impl Iterator<Item = ClientResult<CursorPage<T, PageCursor>>>
+where
+    F: Fn(String) -> ClientResult<CursorPage<T, String>>,
+{
+    let request = move |cursor: PageCursor| {
+        let (items, next) = request(cursor.into_cursor()?)?;
+        Ok((items, next.map(PageCursor::Cursor)))
+    };
+    paginate_checkpoints(request, start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientError;
+
+    #[test]
+    fn test_paginate() {
+        // Pages over the numbers `0..7`, failing after the second page.
+        let request = |limit: u32, offset: u32| {
+            if offset >= 6 {
+                return Err(ClientError::Unauthorized);
+            }
+            Ok(Page {
+                href: String::new(),
+                items: (offset..offset + limit).collect(),
+                limit,
+                next: Some(String::new()),
+                offset,
+                previous: None,
+                total: 7,
+            })
+        };
+
+        let items = paginate(request, 3).collect::<Vec<_>>();
+        assert_eq!(items.len(), 7);
+        assert_eq!(items[5].as_ref().unwrap(), &5);
+        assert!(matches!(items[6], Err(ClientError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_paginate_whole_pages() {
+        // Pages over the numbers `0..7`, failing after the second page.
+        let request = |limit: u32, offset: u32| {
+            if offset >= 6 {
+                return Err(ClientError::Unauthorized);
+            }
+            Ok(Page {
+                href: String::new(),
+                items: (offset..offset + limit).collect::<Vec<_>>(),
+                limit,
+                next: Some(String::new()),
+                offset,
+                previous: None,
+                total: 7,
+            })
+        };
+
+        let pages = paginate_whole_pages(request, 3).collect::<Vec<_>>();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[1].as_ref().unwrap().items, vec![3, 4, 5]);
+        assert!(matches!(pages[2], Err(ClientError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_paginate_cursor() {
+        // Pages backwards from 10 in steps of 4.
+        let request = |before: u32| {
+            let items = (before.saturating_sub(4)..before).rev().collect::<Vec<_>>();
+            let next = items.last().copied().filter(|&last| last > 0);
+            Ok((items, next))
+        };
+        let items = paginate_cursor(request, 10).collect::<ClientResult<Vec<_>>>();
+        assert_eq!(items.unwrap(), (0..10).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_paginate_pages() {
+        // Pages over the numbers `0..7`.
+        let request = |limit: u32, offset: u32| {
+            let end = (offset + limit).min(7);
+            Ok(Page {
+                href: String::new(),
+                items: (offset..end).collect::<Vec<_>>(),
+                limit,
+                next: if end < 7 { Some(String::new()) } else { None },
+                offset,
+                previous: None,
+                total: 7,
+            })
+        };
+
+        let mut pages = paginate_pages(request, 3, PageCursor::Offset(0));
+        let (items, next) = pages.next().unwrap().unwrap();
+        assert_eq!(items, vec![0, 1, 2]);
+        assert_eq!(next, Some(PageCursor::Offset(3)));
+
+        // Resuming from the checkpoint of the first page.
+        let resumed = paginate_pages(request, 3, next.unwrap())
+            .collect::<ClientResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            resumed,
+            vec![
+                (vec![3, 4, 5], Some(PageCursor::Offset(6))),
+                (vec![6], None)
+            ]
+        );
+
+        let mut pages = paginate_pages(request, 3, PageCursor::Cursor("abc".to_owned()));
+        assert!(matches!(
+            pages.next(),
+            Some(Err(ClientError::InvalidCursor(_)))
+        ));
+        assert!(pages.next().is_none());
+    }
+
+    #[test]
+    fn test_paginate_cursor_pages() {
+        // Two pages, linked by the cursor "next".
+        let request = |after: String| match after.as_str() {
+            "first" => Ok((vec![1, 2], Some("next".to_owned()))),
+            "next" => Ok((vec![3], None)),
+            _ => Err(ClientError::Unauthorized),
+        };
+        let pages = paginate_cursor_pages(request, PageCursor::Cursor("first".to_owned()))
+            .collect::<ClientResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            pages,
+            vec![
+                (vec![1, 2], Some(PageCursor::Cursor("next".to_owned()))),
+                (vec![3], None),
+            ]
+        );
+
+        let mut pages = paginate_cursor_pages(request, PageCursor::Offset(0));
+        assert!(matches!(
+            pages.next(),
+            Some(Err(ClientError::InvalidCursor(_)))
+        ));
+    }
+}
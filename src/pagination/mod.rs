@@ -0,0 +1,192 @@
+//! Utilities to iterate over all the items of a paginated endpoint, instead
+//! of requesting each page manually.
+//!
+//! The endpoints are passed as a closure that requests the page with the
+//! given limit and offset, so that any paginated endpoint can be used:
+//!
+//! ```no_run
+//! # #[cfg(feature = "client-reqwest")]
+//! # async fn run(spotify: rspotify::client::Spotify) {
+//! use futures::stream::TryStreamExt;
+//! use rspotify::pagination::paginate;
+//! use rspotify::prelude::*;
+//!
+//! let saved = paginate(
+//!     |limit, offset| spotify.current_user_saved_tracks(limit, offset),
+//!     50,
+//! );
+//! let saved = saved.try_collect::<Vec<_>>().await.unwrap();
+//! # }
+//! ```
+//!
+//! In the async version, the items are yielded as a [`Stream`
+//! ](futures::stream::Stream), and [`paginate_concurrent`] may also be used to
+//! request multiple pages at the same time. The blocking version returns an
+//! [`Iterator`] instead.
+//!
+//! To process the items a page at a time, like to insert them in bulk into a
+//! database, [`paginate_whole_pages`] yields the [`Page`](crate::model::Page)s
+//! themselves instead, with their `total` and `offset`.
+//!
+//! Cursor-based endpoints, which are paged by the cursor given in each
+//! response instead of an offset, can be iterated with [`paginate_cursor`].
+//!
+//! Long jobs that must survive a restart can instead iterate over whole pages
+//! with [`paginate_pages`] or [`paginate_cursor_pages`]. Each page comes with
+//! the [`PageCursor`] of the next one, which can be saved once the page has been
+//! processed and passed back later to resume from there:
+//!
+//! ```no_run
+//! # #[cfg(feature = "client-reqwest")]
+//! # async fn run(spotify: rspotify::client::Spotify, saved: Option<String>) {
+//! use futures::stream::TryStreamExt;
+//! use rspotify::pagination::{paginate_pages, PageCursor};
+//! use rspotify::prelude::*;
+//!
+//! let start = match saved {
+//!     Some(saved) => serde_json::from_str(&saved).unwrap(),
+//!     None => PageCursor::Offset(0),
+//! };
+//! let pages = paginate_pages(
+//!     |limit, offset| spotify.current_user_saved_tracks(limit, offset),
+//!     50,
+//!     start,
+//! );
+//! futures::pin_mut!(pages);
+//! while let Some((tracks, next)) = pages.try_next().await.unwrap() {
+//!     // Process the tracks, then save `next` to resume after them.
+//!     let checkpoint = serde_json::to_string(&next).unwrap();
+//! }
+//! # }
+//! ```
+
+#[cfg(feature = "__sync")]
+mod iter;
+#[cfg(feature = "__async")]
+mod stream;
+
+#[cfg(feature = "__sync")]
+pub use iter::{
+    paginate, paginate_cursor, paginate_cursor_pages, paginate_pages, paginate_whole_pages,
+    CursorPageIter, PageIter,
+};
+#[cfg(feature = "__async")]
+pub use stream::{
+    paginate, paginate_concurrent, paginate_cursor, paginate_cursor_pages, paginate_pages,
+    paginate_whole_pages,
+};
+
+use crate::client::{ClientError, ClientResult};
+
+use serde::{Deserialize, Serialize};
+
+/// A page of a cursor-based endpoint: its items and the cursor of the next
+/// page, or `None` if it's the last one.
+pub type CursorPage<T, C> = (Vec<T>, Option<C>);
+
+/// The position of a page within a paginated endpoint, which can be
+/// serialized to checkpoint a long iteration and resume it later.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageCursor {
+    /// The offset of the page, for the endpoints paginated by offset.
+    Offset(u32),
+    /// The cursor given by Spotify, for the cursor-based endpoints.
+    Cursor(String),
+}
+
+impl PageCursor {
+    /// The offset of the page, if it's an offset.
+    pub fn offset(&self) -> Option<u32> {
+        match self {
+            PageCursor::Offset(offset) => Some(*offset),
+            PageCursor::Cursor(_) => None,
+        }
+    }
+
+    /// The cursor of the page, if it's a cursor.
+    pub fn cursor(&self) -> Option<&str> {
+        match self {
+            PageCursor::Offset(_) => None,
+            PageCursor::Cursor(cursor) => Some(cursor),
+        }
+    }
+
+    /// The offset of the page, or an error if a cursor was given to an
+    /// endpoint paginated by offset.
+    pub(crate) fn into_offset(self) -> ClientResult<u32> {
+        match self {
+            PageCursor::Offset(offset) => Ok(offset),
+            PageCursor::Cursor(cursor) => Err(ClientError::InvalidCursor(format!(
+                "expected an offset, got the cursor {:?}",
+                cursor
+            ))),
+        }
+    }
+
+    /// The cursor of the page, or an error if an offset was given to a
+    /// cursor-based endpoint.
+    pub(crate) fn into_cursor(self) -> ClientResult<String> {
+        match self {
+            PageCursor::Cursor(cursor) => Ok(cursor),
+            PageCursor::Offset(offset) => Err(ClientError::InvalidCursor(format!(
+                "expected a cursor, got the offset {}",
+                offset
+            ))),
+        }
+    }
+}
+
+impl From<u32> for PageCursor {
+    fn from(offset: u32) -> Self {
+        PageCursor::Offset(offset)
+    }
+}
+
+impl From<String> for PageCursor {
+    fn from(cursor: String) -> Self {
+        PageCursor::Cursor(cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Page;
+
+    fn page(offset: u32, items: Vec<u32>, next: bool) -> Page<u32> {
+        Page {
+            href: String::new(),
+            limit: 2,
+            next: if next { Some(String::new()) } else { None },
+            offset,
+            previous: None,
+            total: 5,
+            items,
+        }
+    }
+
+    #[test]
+    fn test_next_offset() {
+        assert_eq!(page(0, vec![1, 2], true).next_offset(), Some(2));
+        assert_eq!(page(4, vec![5], false).next_offset(), None);
+        assert_eq!(page(2, vec![], true).next_offset(), None);
+    }
+
+    #[test]
+    fn test_page_cursor() {
+        let offset = PageCursor::from(20);
+        assert_eq!(serde_json::to_string(&offset).unwrap(), r#"{"offset":20}"#);
+        assert_eq!(offset.offset(), Some(20));
+        assert_eq!(offset.clone().into_offset().unwrap(), 20);
+        assert!(matches!(
+            offset.into_cursor(),
+            Err(ClientError::InvalidCursor(_))
+        ));
+
+        let cursor = serde_json::from_str::<PageCursor>(r#"{"cursor":"abc"}"#).unwrap();
+        assert_eq!(cursor, PageCursor::Cursor("abc".to_owned()));
+        assert_eq!(cursor.cursor(), Some("abc"));
+        assert!(cursor.into_offset().is_err());
+    }
+}
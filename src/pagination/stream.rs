@@ -0,0 +1,340 @@
+//! Asynchronous pagination with streams.
+
+use super::{CursorPage, PageCursor};
+use crate::client::ClientResult;
+use crate::model::Page;
+
+use futures::future::{self, Future};
+use futures::stream::{self, Stream, StreamExt};
+
+/// Flattens a stream of pages into a stream of their items, keeping the
+/// errors.
+fn flatten_pages<'a, T: 'a>(
+    pages: impl Stream<Item = ClientResult<Vec<T>>> + 'a,
+) -> impl Stream<Item = ClientResult<T>> + 'a {
+    pages
+        .map(|page| match page {
+            Ok(items) => stream::iter(items.into_iter().map(Ok)).left_stream(),
+            Err(err) => stream::once(future::ready(Err(err))).right_stream(),
+        })
+        .flatten()
+}
+
+/// Returns a stream over all the items of a paginated endpoint, requested in
+/// pages of `page_size` items. `request` is called with the limit and the
+/// offset of each page, one at a time.
+///
+/// The stream ends after the first error.
+pub fn paginate<'a, T, F, Fut>(
+    request: F,
+    page_size: u32,
+) -> impl Stream<Item = ClientResult<T>> + 'a
+where
+    T: 'a,
+    F: Fn(u32, u32) -> Fut + 'a,
+    Fut: Future<Output = ClientResult<Page<T>>> + 'a,
+{
+    let pages = paginate_whole_pages(request, page_size).map(|page| page.map(|page| page.items));
+    flatten_pages(pages)
+}
+
+/// The same as [`paginate`], but the pages are yielded whole instead of their
+/// items, to process them one at a time, like to insert them in bulk into a
+/// database.
+///
+/// The stream ends after the first error.
+pub fn paginate_whole_pages<'a, T, F, Fut>(
+    request: F,
+    page_size: u32,
+) -> impl Stream<Item = ClientResult<Page<T>>> + 'a
+where
+    T: 'a,
+    F: Fn(u32, u32) -> Fut + 'a,
+    Fut: Future<Output = ClientResult<Page<T>>> + 'a,
+{
+    stream::unfold(Some(0), move |offset| {
+        let page = offset.map(|offset| request(page_size, offset));
+        async move {
+            match page?.await {
+                Ok(page) => {
+                    let offset = page.next_offset();
+                    Some((Ok(page), offset))
+                }
+                Err(err) => Some((Err(err), None)),
+            }
+        }
+    })
+}
+
+/// The same as [`paginate`], but after the first page is received, the
+/// remaining ones are requested with up to `concurrency` requests at the same
+/// time. The items are still yielded in order.
+///
+/// Since the offsets of the remaining pages are calculated from the `total`
+/// of the first one, items added or removed in the meantime may be skipped or
+/// repeated. Errors in any page are yielded in its place, without stopping the
+/// stream.
+pub fn paginate_concurrent<'a, T, F, Fut>(
+    request: F,
+    page_size: u32,
+    concurrency: usize,
+) -> impl Stream<Item = ClientResult<T>> + 'a
+where
+    T: 'a,
+    F: Fn(u32, u32) -> Fut + 'a,
+    Fut: Future<Output = ClientResult<Page<T>>> + 'a,
+{
+    let pages = stream::once(async move {
+        let first = match request(page_size, 0).await {
+            Ok(first) => first,
+            Err(err) => return stream::once(future::ready(Err(err))).left_stream(),
+        };
+
+        // Spotify may return fewer items per page than requested.
+        let limit = first.limit.max(1);
+        let offsets = match first.next_offset() {
+            Some(start) => (start..first.total).step_by(limit as usize),
+            None => (0..0).step_by(1),
+        };
+        let rest = stream::iter(offsets)
+            .map(move |offset| request(limit, offset))
+            .buffered(concurrency.max(1))
+            .map(|page| page.map(|page| page.items));
+
+        stream::once(future::ready(Ok(first.items)))
+            .chain(rest)
+            .right_stream()
+    })
+    .flatten();
+
+    flatten_pages(pages)
+}
+
+/// Returns a stream over all the items of a cursor-based endpoint. `request`
+/// is called with the cursor of each page, starting with `start`, and returns
+/// its items along with the cursor of the next page, or `None` if it's the
+/// last one.
+///
+/// The stream ends after the first error.
+pub fn paginate_cursor<'a, T, C, F, Fut>(
+    request: F,
+    start: C,
+) -> impl Stream<Item = ClientResult<T>> + 'a
+where
+    T: 'a,
+    C: 'a,
+    F: Fn(C) -> Fut + 'a,
+    Fut: Future<Output = ClientResult<CursorPage<T, C>>> + 'a,
+{
+    let pages = stream::unfold(Some(start), move |cursor| {
+        let page = cursor.map(&request);
+        async move {
+            match page?.await {
+                Ok((items, cursor)) => Some((Ok(items), cursor)),
+                Err(err) => Some((Err(err), None)),
+            }
+        }
+    });
+
+    flatten_pages(pages)
+}
+
+/// Returns a stream over the pages of a paginated endpoint, starting at the
+/// offset in `start`. Each page is yielded with the cursor of the next one,
+/// which can be saved to resume later with [`paginate_pages`], or `None` if
+/// it's the last one. Otherwise, the same as [`paginate`].
+///
+/// The stream ends after the first error, including when `start` isn't an
+/// offset.
+pub fn paginate_pages<'a, T, F, Fut>(
+    request: F,
+    page_size: u32,
+    start: PageCursor,
+) -> impl Stream<Item = ClientResult<CursorPage<T, PageCursor>>> + 'a
+where
+    T: 'a,
+    F: Fn(u32, u32) -> Fut + 'a,
+    Fut: Future<Output = ClientResult<Page<T>>> + 'a,
+{
+    stream::unfold(Some(start), move |cursor| {
+        let page = cursor.map(|cursor| {
+            cursor
+                .into_offset()
+                .map(|offset| request(page_size, offset))
+        });
+        async move {
+            let page = match page? {
+                Ok(page) => page.await,
+                Err(err) => Err(err),
+            };
+            match page {
+                Ok(page) => {
+                    let next = page.next_offset().map(PageCursor::Offset);
+                    Some((Ok((page.items, next.clone())), next))
+                }
+                Err(err) => Some((Err(err), None)),
+            }
+        }
+    })
+}
+
+/// Returns a stream over the pages of a cursor-based endpoint, starting at
+/// the cursor in `start`. Each page is yielded with the cursor of the next
+/// one, which can be saved to resume later with [`paginate_cursor_pages`], or
+/// `None` if it's the last one. Otherwise, the same as [`paginate_cursor`].
+///
+/// The stream ends after the first error, including when `start` isn't a
+/// cursor.
+pub fn paginate_cursor_pages<'a, T, F, Fut>(
+    request: F,
+    start: PageCursor,
+) -> impl Stream<Item = ClientResult<CursorPage<T, PageCursor>>> + 'a
+where
+    T: 'a,
+    F: Fn(String) -> Fut + 'a,
+    Fut: Future<Output = ClientResult<CursorPage<T, String>>> + 'a,
+{
+    stream::unfold(Some(start), move |cursor| {
+        let page = cursor.map(|cursor| cursor.into_cursor().map(&request));
+        async move {
+            let page = match page? {
+                Ok(page) => page.await,
+                Err(err) => Err(err),
+            };
+            match page {
+                Ok((items, next)) => {
+                    let next = next.map(PageCursor::Cursor);
+                    Some((Ok((items, next.clone())), next))
+                }
+                Err(err) => Some((Err(err), None)),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientError;
+
+    use futures::executor::block_on;
+    use futures::stream::TryStreamExt;
+
+    use std::cell::Cell;
+
+    /// Pages over the numbers `0..total`.
+    async fn numbers(total: u32, limit: u32, offset: u32) -> ClientResult<Page<u32>> {
+        let end = (offset + limit).min(total);
+        Ok(Page {
+            href: String::new(),
+            items: (offset..end).collect(),
+            limit,
+            next: if end < total {
+                Some(String::new())
+            } else {
+                None
+            },
+            offset,
+            previous: None,
+            total,
+        })
+    }
+
+    #[test]
+    fn test_paginate() {
+        let items = block_on(
+            paginate(|limit, offset| numbers(7, limit, offset), 3).try_collect::<Vec<_>>(),
+        );
+        assert_eq!(items.unwrap(), (0..7).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_paginate_whole_pages() {
+        let pages = paginate_whole_pages(|limit, offset| numbers(7, limit, offset), 3);
+        let pages = block_on(pages.try_collect::<Vec<_>>()).unwrap();
+        let offsets = pages.iter().map(|page| page.offset).collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0, 3, 6]);
+        assert_eq!(pages[2].items, vec![6]);
+
+        let request = |_, _| future::ready(Err::<Page<u32>, _>(ClientError::Unauthorized));
+        let pages = block_on(paginate_whole_pages(request, 3).collect::<Vec<_>>());
+        assert_eq!(pages.len(), 1);
+        assert!(matches!(pages[0], Err(ClientError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_paginate_concurrent() {
+        let requests = Cell::new(0);
+        let request = |limit, offset| {
+            requests.set(requests.get() + 1);
+            numbers(10, limit, offset)
+        };
+        let items = block_on(paginate_concurrent(request, 3, 4).try_collect::<Vec<_>>());
+        assert_eq!(items.unwrap(), (0..10).collect::<Vec<_>>());
+        assert_eq!(requests.get(), 4);
+
+        let request = |_, _| future::ready(Err::<Page<u32>, _>(ClientError::Unauthorized));
+        let items = block_on(paginate_concurrent(request, 3, 4).collect::<Vec<_>>());
+        assert_eq!(items.len(), 1);
+        assert!(matches!(items[0], Err(ClientError::Unauthorized)));
+    }
+
+    #[test]
+    fn test_paginate_cursor() {
+        // Pages backwards from 10 in steps of 4.
+        let request = |before: u32| {
+            let items = (before.saturating_sub(4)..before).rev().collect::<Vec<_>>();
+            let next = items.last().copied().filter(|&last| last > 0);
+            future::ready(Ok((items, next)))
+        };
+        let items = block_on(paginate_cursor(request, 10).try_collect::<Vec<_>>());
+        assert_eq!(items.unwrap(), (0..10).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_paginate_pages() {
+        let request = |limit, offset| numbers(7, limit, offset);
+        let pages =
+            block_on(paginate_pages(request, 3, PageCursor::Offset(0)).try_collect::<Vec<_>>());
+        let pages = pages.unwrap();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0], (vec![0, 1, 2], Some(PageCursor::Offset(3))));
+        assert_eq!(pages[2], (vec![6], None));
+
+        // Resuming from the checkpoint of the first page.
+        let resumed = paginate_pages(request, 3, pages[0].1.clone().unwrap());
+        let resumed = block_on(resumed.try_collect::<Vec<_>>()).unwrap();
+        assert_eq!(resumed, pages[1..]);
+
+        let cursor = PageCursor::Cursor("abc".to_owned());
+        let pages = block_on(paginate_pages(request, 3, cursor).collect::<Vec<_>>());
+        assert_eq!(pages.len(), 1);
+        assert!(matches!(pages[0], Err(ClientError::InvalidCursor(_))));
+    }
+
+    #[test]
+    fn test_paginate_cursor_pages() {
+        // Pages over the letters of the alphabet, two at a time.
+        let request = |after: String| {
+            let start = after.bytes().next().unwrap_or(b'a');
+            let items = (start..(start + 2).min(b'z' + 1)).collect::<Vec<_>>();
+            let next = Some(start + 2)
+                .filter(|&next| next <= b'z')
+                .map(|next| (next as char).to_string());
+            future::ready(Ok((items, next)))
+        };
+        let start = PageCursor::Cursor("w".to_owned());
+        let pages = block_on(paginate_cursor_pages(request, start).try_collect::<Vec<_>>());
+        assert_eq!(
+            pages.unwrap(),
+            vec![
+                (b"wx".to_vec(), Some(PageCursor::Cursor("y".to_owned()))),
+                (b"yz".to_vec(), None),
+            ]
+        );
+
+        let pages =
+            block_on(paginate_cursor_pages(request, PageCursor::Offset(0)).collect::<Vec<_>>());
+        assert!(matches!(pages[0], Err(ClientError::InvalidCursor(_))));
+    }
+}
@@ -0,0 +1,476 @@
+//! Utilities to watch the user's playback, like for now-playing displays.
+//!
+//! Instead of polling at fixed intervals, the next poll is scheduled for when
+//! the current item is expected to end, which is when the playback usually
+//! changes. Changes that can't be predicted, like skipping a track, are still
+//! noticed within the maximum interval of the [`PollSchedule`]:
+//!
+//! ```no_run
+//! # #[cfg(feature = "client-reqwest")]
+//! # async fn run(spotify: rspotify::client::Spotify) {
+//! use futures::stream::StreamExt;
+//! use rspotify::playback::{watch_playback, PollSchedule};
+//! use rspotify::prelude::*;
+//!
+//! let playing = watch_playback(
+//!     || spotify.current_playing(None, None),
+//!     PollSchedule::default(),
+//!     spotify.sleeper.clone(),
+//! );
+//! futures::pin_mut!(playing);
+//! while let Some(playing) = playing.next().await {
+//!     if let Ok(Some(playing)) = playing {
+//!         println!("Playing {:?}", playing.item.map(|item| item.uri().to_owned()));
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! In the async version, the playback is yielded as a [`Stream`
+//! ](futures::stream::Stream). The blocking version returns an [`Iterator`]
+//! instead.
+//!
+//! The playback can also be paused or resumed at a given time, like with a
+//! sleep timer that pauses it after a while:
+//!
+//! ```no_run
+//! # #[cfg(feature = "client-reqwest")]
+//! # async fn run(mut spotify: rspotify::client::Spotify) {
+//! use rspotify::playback::sleep_timer;
+//!
+//! sleep_timer(&mut spotify, std::time::Duration::from_secs(30 * 60), None)
+//!     .await
+//!     .unwrap();
+//! # }
+//! ```
+
+use crate::client::{ClientError, ClientResult, Sleeper, Spotify};
+use crate::endpoints::OAuthClient;
+use crate::model::{CurrentPlaybackContext, CurrentlyPlayingContext};
+
+use chrono::{DateTime, Utc};
+use maybe_async::maybe_async;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "__async")]
+use futures::{
+    future::Future,
+    stream::{self, Stream},
+};
+
+/// The playback information needed to know when it's expected to change,
+/// from [`OAuthClient::current_playback`
+/// ](crate::endpoints::OAuthClient::current_playback) or
+/// [`OAuthClient::current_playing`
+/// ](crate::endpoints::OAuthClient::current_playing).
+pub trait PlaybackProgress {
+    /// Whether something is playing, rather than paused.
+    fn is_playing(&self) -> bool;
+
+    /// The time left until the current item finishes, if known.
+    fn remaining(&self) -> Option<Duration>;
+}
+
+impl PlaybackProgress for CurrentlyPlayingContext {
+    fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    fn remaining(&self) -> Option<Duration> {
+        CurrentlyPlayingContext::remaining(self)
+    }
+}
+
+impl PlaybackProgress for CurrentPlaybackContext {
+    fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    fn remaining(&self) -> Option<Duration> {
+        CurrentPlaybackContext::remaining(self)
+    }
+}
+
+/// When the playback is polled by [`watch_playback`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PollSchedule {
+    /// The shortest time between two polls, 1 second by default.
+    pub min_interval: Duration,
+    /// The longest time between two polls, which is how late the changes
+    /// that can't be predicted may be noticed. It's also used while nothing
+    /// is playing and after errors. 30 seconds by default.
+    pub max_interval: Duration,
+    /// How long after the current item is expected to end it's polled, so
+    /// that the next one has already started. 1 second by default.
+    pub margin: Duration,
+}
+
+impl Default for PollSchedule {
+    fn default() -> Self {
+        PollSchedule {
+            min_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(30),
+            margin: Duration::from_secs(1),
+        }
+    }
+}
+
+impl PollSchedule {
+    /// The time to wait before polling again, given the last playback
+    /// received, which is `None` if nothing is playing.
+    pub fn next_poll<P: PlaybackProgress>(&self, playback: Option<&P>) -> Duration {
+        let wait = match playback.filter(|playback| playback.is_playing()) {
+            Some(playback) => playback
+                .remaining()
+                .map_or(self.max_interval, |remaining| remaining + self.margin),
+            None => self.max_interval,
+        };
+        wait.max(self.min_interval).min(self.max_interval)
+    }
+
+    /// The time to wait after the given result of a poll.
+    fn after<P: PlaybackProgress>(&self, polled: &ClientResult<Option<P>>) -> Duration {
+        match polled {
+            Ok(playback) => self.next_poll(playback.as_ref()),
+            Err(_) => self.max_interval,
+        }
+    }
+}
+
+/// Returns a stream that polls the playback with `request` as specified by
+/// `schedule`, starting right away. It never ends: the errors are yielded
+/// and followed by another poll after the maximum interval. The polls are
+/// waited for with `sleeper`, like the [`Spotify::sleeper`] of the client.
+#[cfg(feature = "__async")]
+pub fn watch_playback<'a, P, F, Fut>(
+    request: F,
+    schedule: PollSchedule,
+    sleeper: Arc<dyn Sleeper>,
+) -> impl Stream<Item = ClientResult<Option<P>>> + 'a
+where
+    P: PlaybackProgress + 'a,
+    F: Fn() -> Fut + 'a,
+    Fut: Future<Output = ClientResult<Option<P>>> + 'a,
+{
+    stream::unfold(None, move |wait| {
+        let polled = request();
+        let sleeper = Arc::clone(&sleeper);
+        async move {
+            if let Some(wait) = wait {
+                sleeper.sleep(wait).await;
+            }
+            let polled = polled.await;
+            let wait = schedule.after(&polled);
+            Some((polled, Some(wait)))
+        }
+    })
+}
+
+/// Returns an iterator that polls the playback with `request` as specified
+/// by `schedule`, starting right away. It never ends: the errors are yielded
+/// and followed by another poll after the maximum interval. The polls are
+/// waited for with `sleeper`, like the [`Spotify::sleeper`] of the client.
+#[cfg(feature = "__sync")]
+pub fn watch_playback<P, F>(
+    request: F,
+    schedule: PollSchedule,
+    sleeper: Arc<dyn Sleeper>,
+) -> PlaybackWatcher<F>
+where
+    P: PlaybackProgress,
+    F: Fn() -> ClientResult<Option<P>>,
+{
+    PlaybackWatcher {
+        request,
+        schedule,
+        sleeper,
+        wait: None,
+    }
+}
+
+/// Iterator returned by [`watch_playback`].
+#[cfg(feature = "__sync")]
+pub struct PlaybackWatcher<F> {
+    request: F,
+    schedule: PollSchedule,
+    sleeper: Arc<dyn Sleeper>,
+    /// The time to wait before the next poll, `None` for the first one.
+    wait: Option<Duration>,
+}
+
+#[cfg(feature = "__sync")]
+impl<P, F> Iterator for PlaybackWatcher<F>
+where
+    P: PlaybackProgress,
+    F: Fn() -> ClientResult<Option<P>>,
+{
+    type Item = ClientResult<Option<P>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(wait) = self.wait {
+            self.sleeper.sleep(wait);
+        }
+        let polled = (self.request)();
+        self.wait = Some(self.schedule.after(&polled));
+        Some(polled)
+    }
+}
+
+/// The longest time [`wait_until`] sleeps at once, so that it doesn't
+/// oversleep much if the clock changes or the system is suspended meanwhile.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Waits with `sleeper` until `at`, or returns right away if it's already
+/// past.
+#[maybe_async]
+async fn wait_until(sleeper: &dyn Sleeper, at: DateTime<Utc>) {
+    while let Ok(left) = (at - Utc::now()).to_std() {
+        if left == Duration::default() {
+            break;
+        }
+        sleeper.sleep(left.min(MAX_SLEEP)).await;
+    }
+}
+
+/// Refreshes the user's token if it expired, which is likely after a long
+/// wait, and it has a refresh token. The refreshed token is kept even if it
+/// was granted fewer scopes, since the request fails anyway if it needed
+/// them.
+#[maybe_async]
+async fn refresh_token(spotify: &mut Spotify, expired_only: bool) -> ClientResult<bool> {
+    let refresh_token = match &spotify.token {
+        Some(tok) if !expired_only || tok.is_expired() => tok.refresh_token.clone(),
+        _ => None,
+    };
+    let refresh_token = match refresh_token {
+        Some(refresh_token) => refresh_token,
+        None => return Ok(false),
+    };
+    match spotify.refresh_user_token(&refresh_token).await {
+        Ok(()) | Err(ClientError::ScopesReduced { .. }) => Ok(true),
+        Err(err) => Err(err),
+    }
+}
+
+/// Pauses the playback of the device, or the active one, at the given time,
+/// waiting with the [`Spotify::sleeper`] of the client.
+///
+/// The client is only needed for the request made at that time, but it's
+/// borrowed mutably during the wait so that the user's token can be
+/// refreshed if it expired meanwhile, or if it's rejected as such.
+///
+/// Parameters:
+/// - at - when to pause the playback
+/// - device_id - the device to pause, or the active one if `None`
+#[maybe_async]
+pub async fn schedule_pause_at(
+    spotify: &mut Spotify,
+    at: DateTime<Utc>,
+    device_id: Option<String>,
+) -> ClientResult<()> {
+    wait_until(spotify.sleeper.as_ref(), at).await;
+    refresh_token(spotify, true).await?;
+    match spotify.pause_playback(device_id.clone()).await {
+        Err(err) if err.status_code() == Some(401) && refresh_token(spotify, false).await? => {
+            spotify.pause_playback(device_id).await
+        }
+        result => result,
+    }
+}
+
+/// Resumes the playback of the device, or the active one, at the given
+/// time. See [`schedule_pause_at`].
+///
+/// Parameters:
+/// - at - when to resume the playback
+/// - device_id - the device to resume, or the active one if `None`
+#[maybe_async]
+pub async fn schedule_resume_at(
+    spotify: &mut Spotify,
+    at: DateTime<Utc>,
+    device_id: Option<String>,
+) -> ClientResult<()> {
+    wait_until(spotify.sleeper.as_ref(), at).await;
+    refresh_token(spotify, true).await?;
+    let resumed = spotify
+        .start_playback(device_id.clone(), None, None, None, None)
+        .await;
+    match resumed {
+        Err(err) if err.status_code() == Some(401) && refresh_token(spotify, false).await? => {
+            spotify
+                .start_playback(device_id, None, None, None, None)
+                .await
+        }
+        result => result,
+    }
+}
+
+/// Pauses the playback of the device, or the active one, after `duration`.
+/// See [`schedule_pause_at`].
+///
+/// # Panics
+///
+/// If the time after `duration` can't be represented, which is hundreds of
+/// thousands of years from now.
+#[maybe_async]
+pub async fn sleep_timer(
+    spotify: &mut Spotify,
+    duration: Duration,
+    device_id: Option<String>,
+) -> ClientResult<()> {
+    let at = chrono::Duration::from_std(duration)
+        .ok()
+        .and_then(|duration| Utc::now().checked_add_signed(duration))
+        .expect("the sleep timer ends too far in the future");
+    schedule_pause_at(spotify, at, device_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientError;
+
+    use std::cell::Cell;
+    use std::sync::Mutex;
+
+    struct Progress {
+        playing: bool,
+        remaining: Option<Duration>,
+    }
+
+    impl PlaybackProgress for Progress {
+        fn is_playing(&self) -> bool {
+            self.playing
+        }
+
+        fn remaining(&self) -> Option<Duration> {
+            self.remaining
+        }
+    }
+
+    fn progress(playing: bool, remaining: Option<u64>) -> Progress {
+        Progress {
+            playing,
+            remaining: remaining.map(Duration::from_secs),
+        }
+    }
+
+    #[test]
+    fn test_next_poll() {
+        let schedule = PollSchedule::default();
+        let secs = Duration::from_secs;
+        assert_eq!(
+            schedule.next_poll(Some(&progress(true, Some(10)))),
+            secs(11)
+        );
+        assert_eq!(schedule.next_poll(Some(&progress(true, Some(0)))), secs(1));
+        assert_eq!(
+            schedule.next_poll(Some(&progress(true, Some(200)))),
+            secs(30)
+        );
+        assert_eq!(schedule.next_poll(Some(&progress(true, None))), secs(30));
+        assert_eq!(
+            schedule.next_poll(Some(&progress(false, Some(10)))),
+            secs(30)
+        );
+        assert_eq!(schedule.next_poll::<Progress>(None), secs(30));
+        assert_eq!(
+            schedule.after::<Progress>(&Err(ClientError::Timeout)),
+            secs(30)
+        );
+    }
+
+    /// The schedule of the tests.
+    const FAST: PollSchedule = PollSchedule {
+        min_interval: Duration::from_millis(1),
+        max_interval: Duration::from_millis(5),
+        margin: Duration::from_millis(1),
+    };
+
+    /// Records the waits, which are short enough in the tests to block the
+    /// thread for them even in async mode.
+    #[derive(Debug, Default)]
+    struct RecordingSleeper(Mutex<Vec<Duration>>);
+
+    #[maybe_async]
+    impl Sleeper for RecordingSleeper {
+        async fn sleep(&self, duration: Duration) {
+            self.0.lock().unwrap().push(duration);
+            std::thread::sleep(duration);
+        }
+    }
+
+    /// The result of each poll: playing, failed, and then stopped.
+    fn poll(polls: &Cell<u32>) -> ClientResult<Option<Progress>> {
+        polls.set(polls.get() + 1);
+        match polls.get() {
+            1 => Ok(Some(progress(true, Some(0)))),
+            2 => Err(ClientError::Timeout),
+            _ => Ok(None),
+        }
+    }
+
+    /// The next poll is scheduled after the margin while playing, and after
+    /// the maximum interval once it failed.
+    fn check_watched(watched: &[ClientResult<Option<Progress>>], sleeper: &RecordingSleeper) {
+        assert!(matches!(watched[0], Ok(Some(_))));
+        assert!(matches!(watched[1], Err(ClientError::Timeout)));
+        assert!(matches!(watched[2], Ok(None)));
+        assert_eq!(
+            *sleeper.0.lock().unwrap(),
+            [Duration::from_millis(1), Duration::from_millis(5)]
+        );
+    }
+
+    #[maybe_async]
+    async fn check_wait_until() {
+        let sleeper = RecordingSleeper::default();
+        wait_until(&sleeper, Utc::now() - chrono::Duration::seconds(1)).await;
+        assert!(sleeper.0.lock().unwrap().is_empty());
+
+        let at = Utc::now() + chrono::Duration::milliseconds(20);
+        wait_until(&sleeper, at).await;
+        assert!(Utc::now() >= at);
+        let waits = sleeper.0.lock().unwrap();
+        assert!(!waits.is_empty());
+        assert!(waits[0] <= Duration::from_millis(20));
+    }
+
+    #[cfg(feature = "__async")]
+    #[tokio::test]
+    async fn test_wait_until() {
+        check_wait_until().await
+    }
+
+    #[cfg(feature = "__sync")]
+    #[test]
+    fn test_wait_until() {
+        check_wait_until()
+    }
+
+    #[cfg(feature = "__async")]
+    #[tokio::test]
+    async fn test_watch_playback() {
+        use futures::stream::StreamExt;
+
+        let polls = Cell::new(0);
+        let sleeper = Arc::new(RecordingSleeper::default());
+        let watched = watch_playback(|| async { poll(&polls) }, FAST, sleeper.clone());
+        let watched = watched.take(3).collect::<Vec<_>>().await;
+        check_watched(&watched, &sleeper);
+        assert_eq!(polls.get(), 3);
+    }
+
+    #[cfg(feature = "__sync")]
+    #[test]
+    fn test_watch_playback() {
+        let polls = Cell::new(0);
+        let sleeper = Arc::new(RecordingSleeper::default());
+        let watched = watch_playback(|| poll(&polls), FAST, sleeper.clone());
+        let watched = watched.take(3).collect::<Vec<_>>();
+        check_watched(&watched, &sleeper);
+        assert_eq!(polls.get(), 3);
+    }
+}
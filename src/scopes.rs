@@ -0,0 +1,457 @@
+//! The scopes required by each endpoint of the client.
+//!
+//! See the [Authorization Scopes
+//! ](https://developer.spotify.com/documentation/general/guides/scopes/)
+//! guide for more information. Only the scopes without which the request
+//! fails are listed; some endpoints return more information with additional
+//! scopes, like the email in [`OAuthClient::me`](crate::client::OAuthClient::me).
+
+use std::collections::HashSet;
+
+/// The scopes an endpoint requires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequiredScopes {
+    /// The endpoint can be used with any token.
+    None,
+    /// The token must hold all of the scopes.
+    All(&'static [&'static str]),
+    /// The token must hold at least one of the scopes, like when modifying
+    /// either a public or a private playlist.
+    Any(&'static [&'static str]),
+}
+
+impl RequiredScopes {
+    /// The scopes listed in the requirement.
+    pub fn scopes(&self) -> &'static [&'static str] {
+        match self {
+            RequiredScopes::None => &[],
+            RequiredScopes::All(scopes) | RequiredScopes::Any(scopes) => scopes,
+        }
+    }
+
+    /// Whether the given scopes satisfy the requirement.
+    pub fn is_satisfied_by(&self, held: &HashSet<String>) -> bool {
+        match self {
+            RequiredScopes::None => true,
+            RequiredScopes::All(scopes) => scopes.iter().all(|scope| held.contains(*scope)),
+            RequiredScopes::Any(scopes) => scopes.iter().any(|scope| held.contains(*scope)),
+        }
+    }
+}
+
+/// The scopes required by each endpoint, by the name of the method in
+/// [`Spotify`](crate::client::Spotify).
+const ENDPOINT_SCOPES: &[(&str, RequiredScopes)] = &[
+    ("track", RequiredScopes::None),
+    ("tracks", RequiredScopes::None),
+    ("artist", RequiredScopes::None),
+    ("artists", RequiredScopes::None),
+    ("artist_albums", RequiredScopes::None),
+    ("artist_discography", RequiredScopes::None),
+    ("artist_top_tracks", RequiredScopes::None),
+    ("artist_related_artists", RequiredScopes::None),
+    ("album", RequiredScopes::None),
+    ("albums", RequiredScopes::None),
+    ("playable_markets", RequiredScopes::None),
+    ("track_lite", RequiredScopes::None),
+    ("tracks_lite", RequiredScopes::None),
+    ("artist_lite", RequiredScopes::None),
+    ("artists_lite", RequiredScopes::None),
+    ("album_lite", RequiredScopes::None),
+    ("albums_lite", RequiredScopes::None),
+    ("search", RequiredScopes::None),
+    ("album_track", RequiredScopes::None),
+    ("user", RequiredScopes::None),
+    ("playlist", RequiredScopes::None),
+    ("playlists", RequiredScopes::None),
+    ("playlist_cover_image", RequiredScopes::None),
+    ("current_user_playlists", RequiredScopes::None),
+    ("current_user_playlists_all", RequiredScopes::None),
+    ("current_user_owned_playlists", RequiredScopes::None),
+    ("current_user_followed_playlists", RequiredScopes::None),
+    ("user_playlists", RequiredScopes::None),
+    ("user_playlists_all", RequiredScopes::None),
+    ("user_playlist", RequiredScopes::None),
+    ("playlist_tracks", RequiredScopes::None),
+    ("playlist_tracks_all", RequiredScopes::None),
+    ("hydrate_playlist", RequiredScopes::None),
+    (
+        "user_playlist_create",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "create_playlists",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_change_detail",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_unfollow",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "unfollow_all_playlists_matching",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_add_tracks",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_replace_tracks",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_reorder_tracks",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_remove_all_occurrences_of_tracks",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_remove_specific_occurrences_of_tracks",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    (
+        "playlist_follow",
+        RequiredScopes::Any(&["playlist-modify-public", "playlist-modify-private"]),
+    ),
+    ("playlist_check_follow", RequiredScopes::None),
+    ("me", RequiredScopes::None),
+    ("current_user", RequiredScopes::None),
+    ("current_user_id", RequiredScopes::None),
+    ("is_following_playlist", RequiredScopes::None),
+    (
+        "current_user_playing_track",
+        RequiredScopes::Any(&["user-read-currently-playing", "user-read-playback-state"]),
+    ),
+    (
+        "current_user_saved_albums",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_saved_albums_all",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_saved_tracks",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "sync_saved_tracks",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_followed_artists",
+        RequiredScopes::All(&["user-follow-read"]),
+    ),
+    (
+        "current_user_saved_tracks_delete",
+        RequiredScopes::All(&["user-library-modify"]),
+    ),
+    (
+        "remove_saved_tracks_matching",
+        RequiredScopes::All(&["user-library-read", "user-library-modify"]),
+    ),
+    (
+        "current_user_saved_tracks_contains",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_saved_tracks_contains_map",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_saved_tracks_add",
+        RequiredScopes::All(&["user-library-modify"]),
+    ),
+    (
+        "current_user_top_artists",
+        RequiredScopes::All(&["user-top-read"]),
+    ),
+    (
+        "current_user_top_tracks",
+        RequiredScopes::All(&["user-top-read"]),
+    ),
+    (
+        "current_user_recently_played",
+        RequiredScopes::All(&["user-read-recently-played"]),
+    ),
+    (
+        "current_user_recently_played_all",
+        RequiredScopes::All(&["user-read-recently-played"]),
+    ),
+    (
+        "current_user_recently_played_between",
+        RequiredScopes::All(&["user-read-recently-played"]),
+    ),
+    (
+        "current_user_saved_albums_add",
+        RequiredScopes::All(&["user-library-modify"]),
+    ),
+    (
+        "current_user_saved_albums_delete",
+        RequiredScopes::All(&["user-library-modify"]),
+    ),
+    (
+        "current_user_saved_albums_contains",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_saved_albums_contains_map",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "user_follow_artists",
+        RequiredScopes::All(&["user-follow-modify"]),
+    ),
+    (
+        "user_unfollow_artists",
+        RequiredScopes::All(&["user-follow-modify"]),
+    ),
+    (
+        "user_artist_check_follow",
+        RequiredScopes::All(&["user-follow-read"]),
+    ),
+    (
+        "user_artist_check_follow_map",
+        RequiredScopes::All(&["user-follow-read"]),
+    ),
+    (
+        "user_follow_users",
+        RequiredScopes::All(&["user-follow-modify"]),
+    ),
+    (
+        "user_unfollow_users",
+        RequiredScopes::All(&["user-follow-modify"]),
+    ),
+    ("featured_playlists", RequiredScopes::None),
+    ("new_releases", RequiredScopes::None),
+    ("categories", RequiredScopes::None),
+    ("category_playlists", RequiredScopes::None),
+    ("recommendations", RequiredScopes::None),
+    ("track_features", RequiredScopes::None),
+    ("tracks_features", RequiredScopes::None),
+    ("track_analysis", RequiredScopes::None),
+    ("device", RequiredScopes::All(&["user-read-playback-state"])),
+    (
+        "current_playback",
+        RequiredScopes::All(&["user-read-playback-state"]),
+    ),
+    (
+        "current_playing",
+        RequiredScopes::Any(&["user-read-currently-playing", "user-read-playback-state"]),
+    ),
+    (
+        "current_user_queue",
+        RequiredScopes::Any(&["user-read-currently-playing", "user-read-playback-state"]),
+    ),
+    (
+        "transfer_playback",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "transfer_playback_to",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "start_playback",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "start_context_playback",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "start_uris_playback",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "start_playback_queued",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "pause_playback",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "next_track",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "previous_track",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "seek_track",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "repeat",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "volume",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "fade_volume",
+        RequiredScopes::All(&["user-read-playback-state", "user-modify-playback-state"]),
+    ),
+    (
+        "shuffle",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "add_item_to_queue",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    (
+        "add_to_queue",
+        RequiredScopes::All(&["user-modify-playback-state"]),
+    ),
+    ("save_shows", RequiredScopes::All(&["user-library-modify"])),
+    (
+        "current_user_saved_shows",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_saved_shows_all",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    ("get_a_show", RequiredScopes::None),
+    ("get_several_shows", RequiredScopes::None),
+    ("get_shows_episodes", RequiredScopes::None),
+    ("get_an_episode", RequiredScopes::None),
+    ("get_several_episodes", RequiredScopes::None),
+    (
+        "check_users_saved_shows",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "check_users_saved_shows_map",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "remove_users_saved_shows",
+        RequiredScopes::All(&["user-library-modify"]),
+    ),
+    (
+        "current_user_saved_audiobooks",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "current_user_saved_audiobooks_all",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+    (
+        "save_audiobooks",
+        RequiredScopes::All(&["user-library-modify"]),
+    ),
+    (
+        "remove_saved_audiobooks",
+        RequiredScopes::All(&["user-library-modify"]),
+    ),
+    (
+        "check_saved_audiobooks",
+        RequiredScopes::All(&["user-library-read"]),
+    ),
+];
+
+/// Returns the table of the scopes required by each endpoint, as pairs of
+/// the name of the method in [`Spotify`](crate::client::Spotify) and its
+/// requirement.
+pub fn endpoint_scopes() -> &'static [(&'static str, RequiredScopes)] {
+    ENDPOINT_SCOPES
+}
+
+/// Returns the scopes required by the endpoint with the given method name,
+/// or `None` if it's not an endpoint.
+pub fn scopes_for(endpoint: &str) -> Option<RequiredScopes> {
+    ENDPOINT_SCOPES
+        .iter()
+        .find(|(name, _)| *name == endpoint)
+        .map(|(_, scopes)| *scopes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_scopes_unique() {
+        let names = endpoint_scopes()
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<HashSet<_>>();
+        assert_eq!(names.len(), endpoint_scopes().len());
+    }
+
+    /// The sources of the traits with the endpoints.
+    const ENDPOINT_SOURCES: &[&str] = &[
+        include_str!("endpoints/base.rs"),
+        include_str!("endpoints/oauth.rs"),
+    ];
+
+    #[test]
+    fn test_checked_endpoints_listed() {
+        let call = "check_scopes(\"";
+        for source in ENDPOINT_SOURCES {
+            for (start, _) in source.match_indices(call) {
+                let rest = &source[start + call.len()..];
+                let name = &rest[..rest.find('"').unwrap()];
+                assert!(scopes_for(name).is_some(), "{} isn't listed", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trait_endpoints_listed() {
+        // The methods of the traits that aren't endpoints themselves.
+        let helpers = [
+            "check_scopes",
+            "default_device",
+            "default_market",
+            "endpoint_get",
+            "endpoint_get_as",
+            "endpoint_post",
+            "endpoint_put",
+            "endpoint_delete",
+            "filter_explicit",
+            "filters_explicit",
+            "sleep",
+        ];
+        for source in ENDPOINT_SOURCES {
+            for line in source.lines() {
+                let line = line.strip_prefix("    ").unwrap_or("");
+                let line = line.strip_prefix("async ").unwrap_or(line);
+                let name = match line.strip_prefix("fn ") {
+                    Some(rest) => rest.split(&['(', '<'][..]).next().unwrap(),
+                    None => continue,
+                };
+                if !helpers.contains(&name) {
+                    assert!(scopes_for(name).is_some(), "{} isn't listed", name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_required_scopes() {
+        let held = ["playlist-modify-private".to_owned()]
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+        assert_eq!(scopes_for("track"), Some(RequiredScopes::None));
+        assert!(scopes_for("playlist_add_tracks")
+            .unwrap()
+            .is_satisfied_by(&held));
+        assert!(!scopes_for("current_user_saved_tracks")
+            .unwrap()
+            .is_satisfied_by(&held));
+        assert_eq!(scopes_for("with_replay"), None);
+    }
+}
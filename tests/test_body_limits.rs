@@ -0,0 +1,111 @@
+//! Tests for the limits of the response bodies, against a local server that
+//! answers with a fixed body, with or without its length.
+
+mod common;
+
+use common::maybe_async_test;
+use rspotify::client::{
+    BaseHTTPClient, BodyLimits, ClientError, HTTPClient, ProgressHook, Spotify, SpotifyBuilder,
+};
+use rspotify::oauth2::TokenBuilder;
+
+use chrono::{Duration, Utc};
+use maybe_async::maybe_async;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+const BODY: &str = r#"{"name":"Rick Astley","genres":["dance pop"]}"#;
+
+/// Answers a single request with `BODY`, saying its length only if
+/// `content_length` is set.
+fn serve(listener: TcpListener, content_length: bool) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim_end().is_empty() {
+                break;
+            }
+        }
+
+        let length = if content_length {
+            format!("Content-Length: {}\r\n", BODY.len())
+        } else {
+            String::new()
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}Connection: close\r\n\r\n{}",
+            length, BODY
+        );
+        // The client may hang up as soon as it knows the body is too long.
+        let _ = stream.write_all(response.as_bytes());
+    })
+}
+
+fn client(content_length: bool, limits: BodyLimits) -> (Spotify, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let prefix = format!("http://{}/", listener.local_addr().unwrap());
+    let server = serve(listener, content_length);
+
+    let tok = TokenBuilder::default()
+        .access_token("dummy-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let spotify = SpotifyBuilder::default()
+        .token(tok)
+        .prefix(prefix)
+        .http(HTTPClient::default().with_body_limits(limits))
+        .build()
+        .unwrap();
+    (spotify, server)
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_body_too_large() {
+    for &content_length in &[true, false] {
+        let limits = BodyLimits {
+            max_size: Some(10),
+            ..Default::default()
+        };
+        let (spotify, server) = client(content_length, limits);
+        let fetched = spotify.get_if_none_match::<Value>("artists/id", None).await;
+        assert!(matches!(fetched, Err(ClientError::BodyTooLarge(10))));
+        server.join().unwrap();
+    }
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_download_progress() {
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let progress = {
+        let reported = Arc::clone(&reported);
+        ProgressHook::new(move |progress| {
+            assert!(progress.url.ends_with("/artists/id"));
+            reported
+                .lock()
+                .unwrap()
+                .push((progress.received, progress.total));
+        })
+    };
+    let limits = BodyLimits {
+        max_size: Some(BODY.len()),
+        progress: Some(progress),
+    };
+    let (spotify, server) = client(true, limits);
+    let fetched = spotify.get_if_none_match::<Value>("artists/id", None).await;
+    assert!(fetched.is_ok());
+    server.join().unwrap();
+
+    let reported = reported.lock().unwrap();
+    assert!(!reported.is_empty());
+    assert!(reported.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    assert_eq!(reported.last(), Some(&(BODY.len(), Some(BODY.len()))));
+}
@@ -0,0 +1,146 @@
+//! Tests for the conditional requests with `ETag`s, against a local server
+//! that answers with `304 Not Modified` when the `ETag` matches.
+
+mod common;
+
+use common::maybe_async_test;
+use rspotify::client::{Conditional, RetryPolicy, SpotifyBuilder};
+use rspotify::oauth2::TokenBuilder;
+
+use chrono::{Duration, Utc};
+use maybe_async::maybe_async;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+const ETAG: &str = "\"abc\"";
+
+/// Answers the given number of requests, the first `failures` of them with
+/// `503 Service Unavailable`, returning the `If-None-Match` header of each of
+/// them once the server is done.
+fn serve(
+    listener: TcpListener,
+    failures: usize,
+    requests: usize,
+) -> thread::JoinHandle<Vec<Option<String>>> {
+    thread::spawn(move || {
+        let mut received = Vec::new();
+        for i in 0..requests {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut if_none_match = None;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((key, val)) = line.split_once(':') {
+                    if key.eq_ignore_ascii_case("if-none-match") {
+                        if_none_match = Some(val.trim().to_owned());
+                    }
+                }
+            }
+
+            let response = if i < failures {
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\
+                 Connection: close\r\n\r\n"
+                    .to_owned()
+            } else if if_none_match.as_deref() == Some(ETAG) {
+                "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_owned()
+            } else {
+                let body = r#"{"name":"Rick Astley"}"#;
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nETag: {}\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    ETAG,
+                    body.len(),
+                    body
+                )
+            };
+            stream.write_all(response.as_bytes()).unwrap();
+            received.push(if_none_match);
+        }
+        received
+    })
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_get_if_none_match() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let prefix = format!("http://{}/", listener.local_addr().unwrap());
+    let server = serve(listener, 0, 3);
+
+    let tok = TokenBuilder::default()
+        .access_token("dummy-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let spotify = SpotifyBuilder::default()
+        .token(tok)
+        .prefix(prefix)
+        .build()
+        .unwrap();
+
+    let fetched = spotify.get_if_none_match::<Value>("artists/id", None).await;
+    let etag = match fetched.unwrap() {
+        Conditional::Fetched(artist, etag) => {
+            assert_eq!(artist["name"], "Rick Astley");
+            etag.unwrap()
+        }
+        Conditional::NotModified => panic!("no ETag was sent"),
+    };
+    assert_eq!(etag, ETAG);
+
+    let cached = spotify
+        .get_if_none_match::<Value>("artists/id", Some(&etag))
+        .await;
+    assert_eq!(cached.unwrap(), Conditional::NotModified);
+
+    let stale = spotify
+        .get_if_none_match::<Value>("artists/id", Some("\"old\""))
+        .await;
+    assert!(matches!(stale.unwrap(), Conditional::Fetched(_, Some(_))));
+
+    let received = server.join().unwrap();
+    assert_eq!(
+        received,
+        vec![None, Some(ETAG.to_owned()), Some("\"old\"".to_owned())]
+    );
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_get_if_none_match_retries() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let prefix = format!("http://{}/", listener.local_addr().unwrap());
+    let server = serve(listener, 1, 2);
+
+    let tok = TokenBuilder::default()
+        .access_token("dummy-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let spotify = SpotifyBuilder::default()
+        .token(tok)
+        .prefix(prefix)
+        .retry_policy(RetryPolicy {
+            backoff: std::time::Duration::from_millis(10),
+            ..RetryPolicy::new(1)
+        })
+        .build()
+        .unwrap();
+
+    // The server error is retried like in the rest of the `GET` requests,
+    // sending the same `ETag` again.
+    let stale = spotify
+        .get_if_none_match::<Value>("artists/id", Some("\"old\""))
+        .await;
+    assert!(matches!(stale.unwrap(), Conditional::Fetched(_, Some(_))));
+
+    let received = server.join().unwrap();
+    assert_eq!(received, vec![Some("\"old\"".to_owned()); 2]);
+}
@@ -0,0 +1,155 @@
+//! Tests for custom clients, which inherit all the endpoints by implementing
+//! the HTTP wrappers of `BaseClient` and `OAuthClient`.
+
+mod common;
+
+use common::maybe_async_test;
+use rspotify::client::{ClientError, ClientResult, Query};
+use rspotify::prelude::*;
+
+use maybe_async::maybe_async;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+/// A client that answers the GET requests with the recorded responses of a
+/// cassette, and saves the rest of requests.
+#[derive(Default)]
+struct FixtureClient {
+    responses: HashMap<String, String>,
+    sent: Mutex<Vec<(String, String)>>,
+}
+
+impl FixtureClient {
+    fn from_cassette(cassette: &str) -> Self {
+        let path = format!("tests/cassettes/{}.json", cassette);
+        let cassette: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        let responses = cassette["interactions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|interaction| interaction["request"]["method"] == "GET")
+            .filter_map(|interaction| {
+                let url = interaction["request"]["url"].as_str()?;
+                let url = url.trim_start_matches("https://api.spotify.com/v1/");
+                // The failed requests aren't answered, like unknown URLs.
+                let body = interaction["response"]["body"].as_str()?;
+                Some((url.to_owned(), body.to_owned()))
+            })
+            .collect();
+
+        FixtureClient {
+            responses,
+            ..Default::default()
+        }
+    }
+
+    fn send(&self, method: &str, url: &str) -> ClientResult<String> {
+        let request = (method.to_owned(), url.to_owned());
+        self.sent.lock().unwrap().push(request);
+        Ok(String::new())
+    }
+}
+
+#[maybe_async]
+impl BaseClient for FixtureClient {
+    async fn endpoint_get(&self, url: &str, _payload: &Query) -> ClientResult<String> {
+        self.responses
+            .get(url)
+            .cloned()
+            .ok_or_else(|| ClientError::StatusCode(404, format!("no response for {}", url)))
+    }
+}
+
+#[maybe_async]
+impl OAuthClient for FixtureClient {
+    async fn endpoint_post(&self, url: &str, _payload: &Value) -> ClientResult<String> {
+        self.send("POST", url)
+    }
+
+    async fn endpoint_put(&self, url: &str, _payload: &Value) -> ClientResult<String> {
+        self.send("PUT", url)
+    }
+
+    async fn endpoint_delete(&self, url: &str, _payload: &Value) -> ClientResult<String> {
+        self.send("DELETE", url)
+    }
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_custom_client_endpoints() {
+    let client = FixtureClient::from_cassette("track");
+
+    let track = client.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+    let missing = client.track("0000000000000000000000").await;
+    assert!(matches!(missing, Err(ClientError::StatusCode(404, _))));
+
+    client.volume(50, Some("device".to_owned())).await.unwrap();
+    client.pause_playback(None).await.unwrap();
+    let sent = client.sent.lock().unwrap();
+    assert_eq!(
+        *sent,
+        vec![
+            (
+                "PUT".to_owned(),
+                "me/player/volume?volume_percent=50&device_id=device".to_owned()
+            ),
+            ("PUT".to_owned(), "me/player/pause".to_owned()),
+        ]
+    );
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_remove_saved_tracks_matching() {
+    let client = FixtureClient::from_cassette("saved_tracks_sync");
+    let not_first = |saved: &rspotify::model::SavedTrack| saved.track.name != "Track 0";
+
+    // Nothing is removed in a dry run.
+    let matching = client
+        .remove_saved_tracks_matching(not_first, true)
+        .await
+        .unwrap();
+    let names = matching
+        .iter()
+        .map(|saved| saved.track.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Track 4", "Track 2"]);
+    assert!(client.sent.lock().unwrap().is_empty());
+
+    let removed = client
+        .remove_saved_tracks_matching(not_first, false)
+        .await
+        .unwrap();
+    assert_eq!(removed, matching);
+    let sent = client.sent.lock().unwrap();
+    assert_eq!(
+        *sent,
+        vec![(
+            "DELETE".to_owned(),
+            "me/tracks/?ids=0000000000000000000004,0000000000000000000002".to_owned()
+        )]
+    );
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_unfollow_all_playlists_matching() {
+    let client = FixtureClient::from_cassette("current_user_playlists");
+    let unfollowed = client
+        .unfollow_all_playlists_matching(|playlist| playlist.owner.id == "spotify", false)
+        .await
+        .unwrap();
+    assert_eq!(unfollowed.len(), 1);
+    let sent = client.sent.lock().unwrap();
+    assert_eq!(
+        *sent,
+        vec![(
+            "DELETE".to_owned(),
+            format!("playlists/{}/followers", unfollowed[0].id)
+        )]
+    );
+}
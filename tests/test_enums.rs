@@ -30,8 +30,14 @@ fn test_date_precision() {
 
 #[test]
 fn test_album_type_convert_from_str() {
-    let appears_on = AlbumType::AppearsOn;
+    let appears_on = AlbumGroup::AppearsOn;
     assert_eq!("appears_on".to_string(), appears_on.to_string());
+    let compilation: AlbumType = serde_json::from_str(r#""COMPILATION""#).unwrap();
+    assert_eq!(compilation, AlbumType::Compilation);
+    let single: AlbumGroup = serde_json::from_str(r#""single""#).unwrap();
+    assert_eq!(single, AlbumGroup::Single);
+    assert_eq!(serde_json::to_string(&single).unwrap(), r#""single""#);
+    assert!(serde_json::from_str::<AlbumType>(r#""appears_on""#).is_err());
 }
 #[test]
 fn test_convert_search_type_from_str() {
@@ -65,3 +71,14 @@ fn test_convert_country_from_str() {
     let zimbabwe = Country::Zimbabwe;
     assert_eq!(zimbabwe.to_string(), "ZW".to_string());
 }
+
+#[test]
+fn test_key() {
+    assert_eq!(Key::CSharp.to_string(), "C♯/D♭");
+    assert_eq!(Key::from_pitch_class(11), Some(Key::B));
+    assert_eq!(Key::from_pitch_class(-1), Some(Key::NoResult));
+    assert_eq!(Key::from_pitch_class(12), None);
+    assert_eq!(Key::ASharp.pitch_class(), Some(10));
+    assert_eq!(Key::NoResult.pitch_class(), None);
+    assert_eq!(TimeSignature::new(8), None);
+}
@@ -0,0 +1,339 @@
+//! End-to-end tests of the clients against the fake server, which go through
+//! the actual HTTP backend unlike the replayed ones.
+
+mod common;
+
+use common::maybe_async_test;
+use rspotify::client::{ClientError, RetryPolicy};
+use rspotify::fake_server::{FakeResponse, FakeServer};
+use rspotify::model::{PlaylistId, SearchType};
+use rspotify::oauth2::{CredentialsBuilder, MemoryTokenCache, OAuthBuilder, TokenBuilder};
+use rspotify::playback::{schedule_resume_at, sleep_timer};
+use rspotify::prelude::*;
+
+use chrono::{Duration, Utc};
+use maybe_async::maybe_async;
+use serde::Deserialize;
+use std::sync::Arc;
+
+const USER: &str = r#"{"display_name":"waq5aexykhm6nlv0cnwdieng0","external_urls":{},"href":"https://api.spotify.com/v1/users/waq5aexykhm6nlv0cnwdieng0","id":"waq5aexykhm6nlv0cnwdieng0","type":"user","uri":"spotify:user:waq5aexykhm6nlv0cnwdieng0","followers":{"href":null,"total":0},"images":[]}"#;
+
+/// A page of playlists with the given IDs, all of them followed and owned
+/// by the user but the first one.
+fn playlists_page(ids: &[&str], offset: u32, total: u32) -> FakeResponse {
+    let items = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let owner = if i == 0 && offset == 0 {
+                "spotify"
+            } else {
+                "waq5aexykhm6nlv0cnwdieng0"
+            };
+            format!(
+                r#"{{"collaborative":false,"external_urls":{{}},"href":"https://api.spotify.com/v1/playlists/{id}","id":"{id}","images":[],"name":"{id}","owner":{{"display_name":"{owner}","external_urls":{{}},"href":"https://api.spotify.com/v1/users/{owner}","id":"{owner}","type":"user","uri":"spotify:user:{owner}"}},"public":true,"snapshot_id":"MSwxNjM","tracks":{{"href":"https://api.spotify.com/v1/playlists/{id}/tracks","total":1}},"type":"playlist","uri":"spotify:playlist:{id}"}}"#,
+                id = id,
+                owner = owner
+            )
+        })
+        .collect::<Vec<_>>();
+    let next = offset + ids.len() as u32;
+    let next = if next < total {
+        format!(
+            r#""https://api.spotify.com/v1/me/playlists?offset={}&limit=50""#,
+            next
+        )
+    } else {
+        "null".to_owned()
+    };
+    FakeResponse::json(format!(
+        r#"{{"href":"https://api.spotify.com/v1/me/playlists","items":[{}],"limit":50,"next":{},"offset":{},"previous":null,"total":{}}}"#,
+        items.join(","),
+        next,
+        offset,
+        total
+    ))
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_fake_server_pagination() {
+    let server = FakeServer::start().unwrap();
+    let first = (0..50).map(|i| format!("{:022}", i)).collect::<Vec<_>>();
+    let first = first.iter().map(String::as_str).collect::<Vec<_>>();
+    server
+        .respond("GET", "/v1/me/", FakeResponse::json(USER))
+        .respond("GET", "/v1/me/playlists", playlists_page(&first, 0, 52))
+        .respond(
+            "GET",
+            "/v1/me/playlists",
+            playlists_page(
+                &["3cEYpjA9oz9GiPac4AsH4n", "37i9dQZF1DXcBWIGoYBM5M"],
+                50,
+                52,
+            ),
+        );
+
+    let tok = TokenBuilder::default()
+        .access_token("user-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let spotify = server.builder().token(tok).build().unwrap();
+    let owned = spotify.current_user_owned_playlists().await.unwrap();
+    assert_eq!(owned.len(), 51);
+    assert_eq!(owned.last().unwrap().id, "37i9dQZF1DXcBWIGoYBM5M");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[1].query.get("offset"), Some("0"));
+    assert_eq!(requests[2].query.get("offset"), Some("50"));
+    assert_eq!(requests[2].query.get("limit"), Some("50"));
+    assert_eq!(server.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_fake_server_retries() {
+    let server = FakeServer::start().unwrap();
+    let artist = include_str!("../src/fixtures/full_artist.json");
+    let path = "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF";
+    server
+        .respond("GET", path, FakeResponse::rate_limited(0))
+        .respond("GET", path, FakeResponse::status(503))
+        .respond("GET", path, FakeResponse::json(artist));
+
+    let tok = TokenBuilder::default()
+        .access_token("client-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let spotify = server
+        .builder()
+        .token(tok.clone())
+        .retry_policy(RetryPolicy {
+            backoff: std::time::Duration::from_millis(10),
+            ..RetryPolicy::new(2)
+        })
+        .build()
+        .unwrap();
+    let fetched = spotify.artist("0OdUWJ0sBjDrqHygGUXeCF").await.unwrap();
+    assert_eq!(fetched.id, "0OdUWJ0sBjDrqHygGUXeCF");
+    assert_eq!(server.requests().len(), 3);
+
+    // Without retries the error is returned right away.
+    server.respond("GET", path, FakeResponse::status(503));
+    let spotify = server.builder().token(tok).build().unwrap();
+    let result = spotify.artist("0OdUWJ0sBjDrqHygGUXeCF").await;
+    assert!(matches!(result, Err(ClientError::StatusCode(503, _))));
+    assert_eq!(server.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_fake_server_client_credentials() {
+    let server = FakeServer::start().unwrap();
+    server
+        .respond("POST", "/api/token", FakeResponse::token("client-token"))
+        .respond(
+            "GET",
+            "/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+            FakeResponse::json(include_str!("../src/fixtures/full_artist.json")),
+        );
+
+    let creds = CredentialsBuilder::default()
+        .id("client-id")
+        .secret("client-secret")
+        .build()
+        .unwrap();
+    let spotify = server.builder().credentials(creds).build().unwrap();
+    spotify.artist("0OdUWJ0sBjDrqHygGUXeCF").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].form().get("grant_type"),
+        Some("client_credentials")
+    );
+    assert!(requests[0].headers["authorization"].starts_with("Basic "));
+    assert_eq!(requests[1].headers["authorization"], "Bearer client-token");
+
+    // The requests without a response left fail as not found.
+    let result = spotify.artist("0OdUWJ0sBjDrqHygGUXeCF").await;
+    assert!(result.is_err());
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_fake_server_invalid_parameters() {
+    let server = FakeServer::start().unwrap();
+    let tok = TokenBuilder::default()
+        .access_token("client-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let spotify = server.builder().token(tok).build().unwrap();
+
+    // The parameters out of range fail without making the requests.
+    let result = spotify
+        .search("abba", SearchType::Track, 51, None, None, None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ClientError::InvalidParameter {
+            name: "limit",
+            value: 51,
+            min: 1,
+            max: 50
+        })
+    ));
+    let result = spotify
+        .search("abba", SearchType::Track, 10, 100_000, None, None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ClientError::InvalidParameter { name: "offset", .. })
+    ));
+    let result = spotify
+        .playlist_tracks("37i9dQZF1DXcBWIGoYBM5M", None, 101, None, None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ClientError::InvalidParameter { max: 100, .. })
+    ));
+    assert!(server.requests().is_empty());
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_fake_server_playlists() {
+    #[derive(Deserialize)]
+    struct NamedPlaylist {
+        id: String,
+        name: String,
+    }
+
+    let server = FakeServer::start().unwrap();
+    let ids = [
+        "3cEYpjA9oz9GiPac4AsH4n",
+        "37i9dQZF1DXcBWIGoYBM5M",
+        "59ZbFPES4DQwEjBpWHzrtC",
+    ];
+    for id in &ids {
+        server.respond(
+            "GET",
+            &format!("/v1/playlists/{}", id),
+            FakeResponse::json(format!(
+                r#"{{"id":"{id}","name":"Playlist {id}"}}"#,
+                id = id
+            )),
+        );
+    }
+
+    let tok = TokenBuilder::default()
+        .access_token("client-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let spotify = server.builder().token(tok).build().unwrap();
+    let playlist_ids = ids
+        .iter()
+        .map(|id| PlaylistId::from_id_or_uri(id).unwrap())
+        .collect::<Vec<_>>();
+    let playlists: Vec<NamedPlaylist> = spotify
+        .playlists(&playlist_ids, Some("id,name"), None, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        playlists.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(),
+        ids
+    );
+    assert_eq!(playlists[1].name, "Playlist 37i9dQZF1DXcBWIGoYBM5M");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests
+        .iter()
+        .all(|request| request.query.get("fields") == Some("id,name")));
+
+    // The ones that can't be requested fail the whole request.
+    let result = spotify
+        .playlists::<NamedPlaylist>(&playlist_ids, None, None, 2)
+        .await;
+    assert_eq!(result.err().unwrap().status_code(), Some(404));
+
+    // At least one request has to be made at a time.
+    let made = server.requests().len();
+    let result = spotify
+        .playlists::<NamedPlaylist>(&playlist_ids, None, None, 0)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ClientError::InvalidParameter {
+            name: "concurrency",
+            ..
+        })
+    ));
+    assert_eq!(server.requests().len(), made);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_fake_server_sleep_timer() {
+    let server = FakeServer::start().unwrap();
+    server
+        .respond("POST", "/api/token", FakeResponse::token("refreshed-token"))
+        .respond("PUT", "/v1/me/player/pause", FakeResponse::empty())
+        .respond("PUT", "/v1/me/player/play", FakeResponse::status(401))
+        .respond("POST", "/api/token", FakeResponse::token("renewed-token"))
+        .respond("PUT", "/v1/me/player/play", FakeResponse::empty());
+
+    // The token expired while waiting, so it's refreshed before pausing.
+    let tok = TokenBuilder::default()
+        .access_token("user-token")
+        .refresh_token("refresh-token")
+        .expires_at(Utc::now() - Duration::seconds(10))
+        .build()
+        .unwrap();
+    let creds = CredentialsBuilder::default()
+        .id("client-id")
+        .secret("client-secret")
+        .build()
+        .unwrap();
+    let oauth = OAuthBuilder::default()
+        .redirect_uri("http://localhost:8888/callback")
+        .build()
+        .unwrap();
+    let mut spotify = server
+        .builder()
+        .token(tok)
+        .credentials(creds)
+        .oauth(oauth)
+        .token_cache(Arc::new(MemoryTokenCache::default()))
+        .build()
+        .unwrap();
+    sleep_timer(&mut spotify, std::time::Duration::from_millis(10), None)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].form().get("refresh_token"),
+        Some("refresh-token")
+    );
+    assert_eq!(requests[1].path, "/v1/me/player/pause");
+    assert_eq!(
+        requests[1].headers["authorization"],
+        "Bearer refreshed-token"
+    );
+
+    // The token rejected as expired is refreshed and the request retried.
+    schedule_resume_at(&mut spotify, Utc::now(), Some("device".to_owned()))
+        .await
+        .unwrap();
+    let requests = server.requests();
+    assert_eq!(requests.len(), 5);
+    assert_eq!(requests[4].query.get("device_id"), Some("device"));
+    assert_eq!(requests[4].headers["authorization"], "Bearer renewed-token");
+    assert_eq!(server.remaining(), 0);
+}
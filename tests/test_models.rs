@@ -1,6 +1,20 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use rspotify::model::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
 use std::time::Duration;
+
+/// Serializes the model and deserializes it back, which has to give the same
+/// model, so that the fetched objects can be persisted and reloaded.
+fn assert_round_trip<T>(model: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(model).unwrap();
+    let reloaded: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(&reloaded, model, "{}", json);
+}
 #[test]
 fn test_simplified_track() {
     let json_str = r#"
@@ -34,6 +48,7 @@ fn test_simplified_track() {
 
 "#;
     let track: SimplifiedTrack = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&track);
     let duration = Duration::from_millis(276773);
     assert_eq!(track.duration, duration);
 }
@@ -64,7 +79,9 @@ fn test_public_user() {
         }
         "#;
     let user: PublicUser = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&user);
     assert_eq!(user.id, "wizzler".to_string());
+    assert_eq!(user.followers.unwrap().total, 4050);
 }
 #[test]
 fn test_private_user() {
@@ -93,9 +110,73 @@ fn test_private_user() {
           } 
         "#;
     let private_user: PrivateUser = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&private_user);
     assert_eq!(private_user.country.unwrap(), Country::UnitedStates);
 }
 
+#[test]
+fn test_empty_fields_as_none() {
+    // Spotify returns the missing text fields as empty, null or not at all,
+    // depending on the endpoint and the user.
+    let user = |fields: &str| {
+        format!(
+            r#"{{
+                {}
+                "external_urls": {{}},
+                "href": "https://api.spotify.com/v1/users/waq5aexykhm6nlv0cnwdieng0",
+                "id": "waq5aexykhm6nlv0cnwdieng0",
+                "type": "user",
+                "uri": "spotify:user:waq5aexykhm6nlv0cnwdieng0"
+            }}"#,
+            fields
+        )
+    };
+    for (display_name, email) in &[
+        (r#""display_name": "","#, r#""email": "","#),
+        (r#""display_name": null,"#, r#""email": null,"#),
+        ("", ""),
+    ] {
+        let fields = format!("{} {}", display_name, email);
+        let private_user: PrivateUser = serde_json::from_str(&user(&fields)).unwrap();
+        assert_round_trip(&private_user);
+        assert_eq!(private_user.display_name, None);
+        assert_eq!(private_user.email, None);
+        let public_user: PublicUser = serde_json::from_str(&user(display_name)).unwrap();
+        assert_eq!(public_user.display_name, None);
+    }
+    let private_user: PrivateUser =
+        serde_json::from_str(&user(r#""email": "vixatew967@top-email.org","#)).unwrap();
+    assert_eq!(
+        private_user.email.as_deref(),
+        Some("vixatew967@top-email.org")
+    );
+
+    let track = |preview_url: &str| {
+        format!(
+            r#"{{
+                "artists": [],
+                "disc_number": 1,
+                "duration_ms": 276773,
+                "explicit": false,
+                "external_urls": {{}},
+                "id": "2TpxZ7JUBn3uw46aR7qd6V",
+                "is_local": false,
+                "name": "All I Want",
+                {}
+                "track_number": 1,
+                "type": "track",
+                "uri": "spotify:track:2TpxZ7JUBn3uw46aR7qd6V"
+            }}"#,
+            preview_url
+        )
+    };
+    for preview_url in &[r#""preview_url": "","#, r#""preview_url": null,"#, ""] {
+        let simplified: SimplifiedTrack = serde_json::from_str(&track(preview_url)).unwrap();
+        assert_round_trip(&simplified);
+        assert_eq!(simplified.preview_url, None);
+    }
+}
+
 #[test]
 fn test_full_artist() {
     let json_str = r#"
@@ -126,8 +207,123 @@ fn test_full_artist() {
         }
         "#;
     let full_artist: FullArtist = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&full_artist);
     assert_eq!(full_artist.name, "Band of Horses");
     assert_eq!(full_artist.followers.total, 833247);
+    assert_eq!(full_artist.genres, ["indie folk"]);
+    assert_eq!(full_artist.popularity, Popularity::new(65).unwrap());
+    assert_eq!(full_artist.to_uri(), full_artist.uri);
+    assert_eq!(
+        Some(full_artist.to_open_url()),
+        full_artist.external_urls.spotify
+    );
+
+    let simplified = SimplifiedArtist::from(full_artist);
+    assert_eq!(simplified.name, "Band of Horses");
+    assert_eq!(simplified.id.as_deref(), Some("0OdUWJ0sBjDrqHygGUXeCF"));
+    assert_eq!(
+        simplified.uri.as_deref(),
+        Some("spotify:artist:0OdUWJ0sBjDrqHygGUXeCF")
+    );
+}
+
+#[test]
+fn test_full_artist_without_genres() {
+    let json_str = r#"
+        {
+            "external_urls": {},
+            "href": "https://api.spotify.com/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+            "id": "0OdUWJ0sBjDrqHygGUXeCF",
+            "images": [],
+            "name": "Band of Horses",
+            "popularity": 65,
+            "type": "artist",
+            "uri": "spotify:artist:0OdUWJ0sBjDrqHygGUXeCF"
+        }
+        "#;
+    // The missing fields are empty, unless the models are strict.
+    let full_artist = serde_json::from_str::<FullArtist>(json_str);
+    if cfg!(feature = "strict-models") {
+        assert!(full_artist.is_err());
+        return;
+    }
+    let full_artist = full_artist.unwrap();
+    assert_round_trip(&full_artist);
+    assert!(full_artist.genres.is_empty());
+    assert_eq!(full_artist.followers.total, 0);
+}
+
+#[test]
+fn test_release_date() {
+    let day = ReleaseDate::parse("1981-12-15", DatePrecision::Day).unwrap();
+    let month = ReleaseDate::parse("1981-12", DatePrecision::Month).unwrap();
+    let year = ReleaseDate::parse("1981", DatePrecision::Year).unwrap();
+    let earlier = ReleaseDate::parse("1980", DatePrecision::Year).unwrap();
+
+    assert_eq!(
+        month.approx_date(),
+        NaiveDate::from_ymd_opt(1981, 12, 1).unwrap()
+    );
+    assert!(earlier < month);
+    assert!(earlier < day);
+    assert!(month.partial_cmp(&day).is_none());
+    assert!(year.partial_cmp(&month).is_none());
+    assert!(ReleaseDate::parse("1981", DatePrecision::Day).is_none());
+
+    let date: ReleaseDate = serde_json::from_str(r#""1981-12""#).unwrap();
+    assert_round_trip(&date);
+    assert_eq!(date, month);
+    assert_eq!(serde_json::to_string(&date).unwrap(), r#""1981-12""#);
+    assert_eq!("1981".parse(), Ok(year));
+    assert!("1981-13".parse::<ReleaseDate>().is_err());
+
+    // An invalid date fails the object instead of being ignored.
+    let mut album = serde_json::json!({
+        "album_type": null,
+        "artists": [],
+        "external_urls": {},
+        "href": null,
+        "id": null,
+        "images": [],
+        "name": "Local album",
+        "release_date": null,
+        "release_date_precision": null,
+        "type": "album",
+        "uri": null
+    });
+    let local: SimplifiedAlbum = serde_json::from_value(album.clone()).unwrap();
+    assert_round_trip(&local);
+    assert_eq!(local.release_date, None);
+    album["release_date"] = "1981-13".into();
+    album["release_date_precision"] = "month".into();
+    assert!(serde_json::from_value::<SimplifiedAlbum>(album.clone()).is_err());
+
+    // The precision is the one given by Spotify, rather than guessed from
+    // the date.
+    album["release_date"] = "1981-12-15".into();
+    let released: SimplifiedAlbum = serde_json::from_value(album.clone()).unwrap();
+    assert_round_trip(&released);
+    assert_eq!(released.release_date, Some(month));
+    album["release_date_precision"] = serde_json::Value::Null;
+    assert!(serde_json::from_value::<SimplifiedAlbum>(album).is_err());
+}
+
+#[test]
+fn test_popularity() {
+    let popularity: Popularity = serde_json::from_str("42").unwrap();
+    assert_round_trip(&popularity);
+    assert_eq!(popularity.get(), 42);
+    assert!(popularity < Popularity::MAX);
+    assert_eq!(serde_json::to_string(&popularity).unwrap(), "42");
+
+    // Out of range values are clamped, unless the models are strict.
+    let popularity = serde_json::from_str::<Popularity>("101");
+    if cfg!(feature = "strict-models") {
+        assert!(popularity.is_err());
+    } else {
+        assert_eq!(popularity.unwrap(), Popularity::MAX);
+    }
+    assert!(Popularity::new(101).is_none());
 }
 
 #[test]
@@ -168,8 +364,9 @@ fn test_simplified_episode() {
         }
         "#;
     let simplified_episode: SimplifiedEpisode = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&simplified_episode);
     assert_eq!(
-        simplified_episode.release_date_precision,
+        simplified_episode.release_date.precision(),
         DatePrecision::Day
     );
     let duration = Duration::from_millis(2685023);
@@ -239,9 +436,94 @@ fn test_full_episode() {
     }
         "#;
     let full_episode: FullEpisode = serde_json::from_str(&json_str).unwrap();
-    assert_eq!(full_episode.release_date_precision, DatePrecision::Day);
+    assert_round_trip(&full_episode);
+    assert_eq!(full_episode.release_date.precision(), DatePrecision::Day);
+    assert_eq!(
+        full_episode.release_date.approx_date(),
+        NaiveDate::from_ymd_opt(2015, 10, 1).unwrap()
+    );
     let duration = Duration::from_millis(1502795);
     assert_eq!(full_episode.duration, duration);
+    assert_eq!(
+        full_episode.to_embed_url(),
+        "https://open.spotify.com/embed/episode/512ojhOuo1ktJprKbVcKyQ"
+    );
+}
+
+#[test]
+fn test_region_limited_show() {
+    // Without a market the responses have no `available_markets`, and the
+    // descriptions come both with and without HTML.
+    let json_str = r#"
+    {
+        "copyrights": [],
+        "description": "Vi är där historien är.",
+        "html_description": "<p>Vi är där <a href=\"https://sverigesradio.se\">historien</a> är.</p>",
+        "explicit": false,
+        "external_urls": {
+            "spotify": "https://open.spotify.com/show/38bS44xjbVVZ3No3ByF1dJ"
+        },
+        "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+        "id": "38bS44xjbVVZ3No3ByF1dJ",
+        "images": [],
+        "is_externally_hosted": false,
+        "languages": [
+            "sv"
+        ],
+        "media_type": "audio",
+        "name": "Vetenskapsradion Historia",
+        "publisher": "Sveriges Radio",
+        "type": "show",
+        "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+    }
+    "#;
+    let show: SimplifiedShow = serde_json::from_str(json_str).unwrap();
+    assert_round_trip(&show);
+    assert!(show.available_markets.is_empty());
+    assert_eq!(show.description, "Vi är där historien är.");
+    assert!(show
+        .html_description
+        .as_deref()
+        .unwrap()
+        .starts_with("<p>Vi är där <a"));
+
+    let value = serde_json::to_value(&show).unwrap();
+    assert!(value.get("available_markets").is_none());
+    let show: SimplifiedShow = serde_json::from_value(value).unwrap();
+    assert!(show.html_description.is_some());
+}
+
+#[test]
+fn test_external_ids_urls() {
+    let json_str = r#"
+    {
+        "isrc": "USUM71703861",
+        "upc": "00602557510966"
+    }
+    "#;
+    let ids: ExternalIds = serde_json::from_str(json_str).unwrap();
+    assert_round_trip(&ids);
+    assert_eq!(ids.isrc.as_deref(), Some("USUM71703861"));
+    assert_eq!(ids.ean, None);
+    assert_eq!(ids.upc.as_deref(), Some("00602557510966"));
+
+    let json_str = r#"
+    {
+        "spotify": "https://open.spotify.com/artist/0TnOYISbd1XYRBk9myaseg",
+        "other": "https://example.com/artist"
+    }
+    "#;
+    let urls: ExternalUrls = serde_json::from_str(json_str).unwrap();
+    assert_round_trip(&urls);
+    assert_eq!(
+        urls.spotify.as_deref(),
+        Some("https://open.spotify.com/artist/0TnOYISbd1XYRBk9myaseg")
+    );
+    assert_eq!(urls.others["other"], "https://example.com/artist");
+
+    let urls: ExternalUrls = serde_json::from_str("{}").unwrap();
+    assert_round_trip(&urls);
+    assert_eq!(urls, ExternalUrls::default());
 }
 
 #[test]
@@ -254,7 +536,8 @@ fn test_copyright() {
 
 "#;
     let copyrights: Vec<Copyright> = serde_json::from_str(&json_str).unwrap();
-    assert_eq!(copyrights[0]._type, CopyrightType::Performance);
+    assert_round_trip(&copyrights);
+    assert_eq!(copyrights[0].kind, CopyrightType::Performance);
 }
 
 #[test]
@@ -276,6 +559,7 @@ fn test_audio_analysis_section() {
         }
         "#;
     let session: AudioAnalysisSection = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&session);
     assert_eq!(session.time_interval.duration, 18.32542);
 }
 #[test]
@@ -298,7 +582,8 @@ fn test_audio_analysis_segments() {
             }
             "#;
     let segment: AudioAnalysisSegment = serde_json::from_str(&json_str).unwrap();
-    assert_eq!(segment.time_interval.start, 252.15601);
+    assert_round_trip(&segment);
+    assert_eq!(segment.time_interval.start, 252.156);
 }
 
 #[test]
@@ -311,7 +596,31 @@ fn test_actions() {
         }
         "#;
     let actions: Actions = serde_json::from_str(&json_str).unwrap();
-    assert_eq!(actions.disallows[0], DisallowKey::Resuming);
+    assert_round_trip(&actions);
+    assert!(actions.disallows.resuming);
+    assert!(!actions.disallows.pausing);
+    assert!(actions.disallows.contains(DisallowKey::Resuming));
+    assert_eq!(actions.disallows.keys(), vec![DisallowKey::Resuming]);
+
+    let json_str = r#"
+        {
+            "disallows": {
+                "pausing": false,
+                "seeking": true,
+                "toggling_shuffle": true
+            }
+        }
+        "#;
+    let actions: Actions = serde_json::from_str(json_str).unwrap();
+    assert_round_trip(&actions);
+    assert_eq!(
+        actions.disallows.keys(),
+        vec![DisallowKey::Seeking, DisallowKey::TogglingShuffle]
+    );
+
+    let actions: Actions = serde_json::from_str("{}").unwrap();
+    assert_round_trip(&actions);
+    assert_eq!(actions, Actions::default());
 }
 
 #[test]
@@ -327,9 +636,46 @@ fn test_recommendations_seed() {
         }        
         "#;
     let seed: RecommendationsSeed = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&seed);
     assert_eq!(seed._type, RecommendationsSeedType::Artist);
 }
 
+#[test]
+fn test_recommendations_seeds() {
+    let json_str = r#"
+    {
+        "seeds": [
+            {
+                "initialPoolSize": 500,
+                "afterFilteringSize": 380,
+                "afterRelinkingSize": 365,
+                "href": "https://api.spotify.com/v1/artists/4NHQUGzhtTLFvgF5SZesLK",
+                "id": "4NHQUGzhtTLFvgF5SZesLK",
+                "type": "ARTIST"
+            },
+            {
+                "initialPoolSize": 250,
+                "afterFilteringSize": 3,
+                "afterRelinkingSize": 3,
+                "href": null,
+                "id": "classical",
+                "type": "GENRE"
+            }
+        ],
+        "tracks": []
+    }
+    "#;
+    let recommendations = serde_json::from_str::<Recommendations>(json_str).unwrap();
+    let genre = &recommendations.seeds[1];
+    assert_eq!(
+        recommendations.seeds[0]._type,
+        RecommendationsSeedType::Artist
+    );
+    assert_eq!(genre._type, RecommendationsSeedType::Genre);
+    assert_eq!(genre.href, None);
+    assert_eq!(genre.after_filtering_size, 3);
+}
+
 #[test]
 fn test_full_playlist() {
     let json_str_images = r#"
@@ -460,11 +806,114 @@ fn test_full_playlist() {
         }
         "#.replace("json_str_images", json_str_images).replace("json_str_simplified_artists", json_str_simplified_artists);
     let full_playlist: FullPlaylist = serde_json::from_str(&json_str).unwrap();
+    assert_round_trip(&full_playlist);
     assert_eq!(
         full_playlist.uri,
         "spotify:playlist:3cEYpjA9oz9GiPac4AsH4n".to_string()
     );
+    assert_eq!(full_playlist.to_uri(), full_playlist.uri);
+    assert_eq!(
+        full_playlist.to_open_url(),
+        "https://open.spotify.com/playlist/3cEYpjA9oz9GiPac4AsH4n"
+    );
     assert_eq!(full_playlist.followers.total, 109);
+    assert_eq!(full_playlist.primary_color, None);
+    let item = &full_playlist.tracks.items[0];
+    assert_eq!(item.primary_color, None);
+    assert_eq!(item.video_thumbnail, Some(VideoThumbnail { url: None }));
+}
+
+#[test]
+fn test_playlist_item_local_track() {
+    let json_str = r#"
+    {
+        "added_at": "2020-12-05T12:44:57Z",
+        "added_by": {
+            "external_urls": {
+                "spotify": "https://open.spotify.com/user/wizzler"
+            },
+            "href": "https://api.spotify.com/v1/users/wizzler",
+            "id": "wizzler",
+            "type": "user",
+            "uri": "spotify:user:wizzler"
+        },
+        "is_local": true,
+        "primary_color": null,
+        "track": {
+            "album": {
+                "album_type": null,
+                "artists": [],
+                "available_markets": [],
+                "external_urls": {},
+                "href": null,
+                "id": null,
+                "images": [],
+                "name": "Demos",
+                "release_date": null,
+                "release_date_precision": null,
+                "type": "album",
+                "uri": null
+            },
+            "artists": [
+                {
+                    "external_urls": {},
+                    "href": null,
+                    "id": null,
+                    "name": "The Band",
+                    "type": "artist",
+                    "uri": null
+                }
+            ],
+            "available_markets": [],
+            "disc_number": 0,
+            "duration_ms": 182000,
+            "explicit": false,
+            "external_ids": {},
+            "external_urls": {},
+            "href": null,
+            "id": null,
+            "is_local": true,
+            "name": "First Take",
+            "popularity": 0,
+            "preview_url": null,
+            "track_number": 0,
+            "type": "track",
+            "uri": "spotify:local:The+Band:Demos:First+Take:182"
+        }
+    }
+    "#;
+    let item: PlaylistItem = serde_json::from_str(json_str).unwrap();
+    assert_round_trip(&item);
+    assert!(item.is_local);
+    assert_eq!(item.added_by.unwrap().id, "wizzler");
+    match item.track.unwrap() {
+        PlaylistTrack::Local(local) => {
+            assert_eq!(local.name, "First Take");
+            assert_eq!(local.artists[0].name, "The Band");
+            assert_eq!(local.album.name, "Demos");
+            assert_eq!(local.duration, Duration::from_secs(182));
+            assert_eq!(local.artist_name(), Some("The Band"));
+            assert_eq!(local.album_name(), Some("Demos"));
+            assert!(matches!(
+                parse_uri(&local.uri, Some(Type::Track)),
+                Err(IdError::LocalFile(_))
+            ));
+        }
+        PlaylistTrack::Track(_) => panic!("local file parsed as a regular track"),
+    }
+
+    let json_str = r#"
+    {
+        "added_at": null,
+        "added_by": null,
+        "is_local": false,
+        "track": null
+    }
+    "#;
+    let item: PlaylistItem = serde_json::from_str(json_str).unwrap();
+    assert_round_trip(&item);
+    assert!(item.added_at.is_none());
+    assert!(item.track.is_none());
 }
 
 #[test]
@@ -492,8 +941,14 @@ fn test_audio_features() {
     }
     "#;
     let audio_features: AudioFeatures = serde_json::from_str(json).unwrap();
+    assert_round_trip(&audio_features);
     let duration = Duration::from_millis(255349);
     assert_eq!(audio_features.duration, duration);
+    assert_eq!(audio_features.key, Key::F);
+    assert_eq!(audio_features.time_signature.beats(), 4);
+    let serialized = serde_json::to_value(&audio_features).unwrap();
+    assert_eq!(serialized["key"], 5);
+    assert_eq!(serialized["time_signature"], 4);
 }
 
 #[test]
@@ -571,8 +1026,33 @@ fn test_full_track() {
 }
     "#;
     let full_track: FullTrack = serde_json::from_str(&json).unwrap();
+    assert_round_trip(&full_track);
     let duration = Duration::from_millis(207959);
     assert_eq!(full_track.duration, duration);
+
+    let track_id = full_track.track_id().unwrap();
+    assert_eq!(track_id.to_uri(), full_track.uri);
+    assert_eq!(
+        track_id.to_open_url(),
+        "https://open.spotify.com/track/11dFghVXANMlKmJXsNCbNl"
+    );
+    assert_eq!(
+        track_id.to_embed_url(),
+        "https://open.spotify.com/embed/track/11dFghVXANMlKmJXsNCbNl"
+    );
+    let album_id = full_track.album.album_id().unwrap();
+    assert_eq!(album_id.to_uri(), "spotify:album:0tGPJ0bkWOUmH7MEOR77qc");
+    let artist_id = full_track.artists[0].artist_id().unwrap();
+    assert_eq!(
+        artist_id.to_embed_url(),
+        "https://open.spotify.com/embed/artist/6sFIWsNpZYqfjUpaCgueju"
+    );
+    assert_eq!(full_track.to_uri().as_ref(), Some(&full_track.uri));
+    assert_eq!(full_track.to_open_url(), full_track.external_urls.spotify);
+    assert_eq!(
+        full_track.to_embed_url().unwrap(),
+        "https://open.spotify.com/embed/track/11dFghVXANMlKmJXsNCbNl"
+    );
 }
 
 #[test]
@@ -584,6 +1064,7 @@ fn test_resume_point() {
     }   
     "#;
     let resume_point: ResumePoint = serde_json::from_str(&json).unwrap();
+    assert_round_trip(&resume_point);
     let duration = Duration::from_millis(423432);
     assert_eq!(resume_point.resume_position, duration);
 }
@@ -689,6 +1170,7 @@ fn test_currently_playing_context() {
 }
     "#;
     let currently_playing_context: CurrentlyPlayingContext = serde_json::from_str(&json).unwrap();
+    assert_round_trip(&currently_playing_context);
     let timestamp = 1607769168429;
     let second: i64 = (timestamp - timestamp % 1000) / 1000;
     let nanosecond = (timestamp % 1000) * 1000000;
@@ -700,6 +1182,34 @@ fn test_currently_playing_context() {
 
     let duration = Duration::from_millis(22270);
     assert_eq!(currently_playing_context.progress, Some(duration));
+    assert_eq!(
+        currently_playing_context.remaining(),
+        Some(Duration::from_millis(191680 - 22270))
+    );
+    let context = currently_playing_context.context.unwrap();
+    assert_eq!(context._type, ContextType::Album);
+    assert_eq!(
+        context.id(),
+        Some(SpotifyId::Album("2lgOc40hhHqjUGAKMWqGxO".to_owned()))
+    );
+}
+
+#[test]
+fn test_collection_context() {
+    let json = r#"
+{
+  "external_urls": {
+    "spotify": "https://open.spotify.com/collection/tracks"
+  },
+  "href": "https://api.spotify.com/v1/me/tracks",
+  "type": "collection",
+  "uri": "spotify:user:waq5aexykhm6nlv0cnwdieng0:collection"
+}
+    "#;
+    let context: Context = serde_json::from_str(json).unwrap();
+    assert_round_trip(&context);
+    assert_eq!(context._type, ContextType::Collection);
+    assert_eq!(context.id(), None);
 }
 
 #[test]
@@ -804,6 +1314,7 @@ fn test_current_playback_context() {
 }
     "#;
     let current_playback_context: CurrentPlaybackContext = serde_json::from_str(&json).unwrap();
+    assert_round_trip(&current_playback_context);
     let timestamp = 1607774342714;
     let second: i64 = (timestamp - timestamp % 1000) / 1000;
     let nanosecond = (timestamp % 1000) * 1000000;
@@ -819,21 +1330,23 @@ fn test_current_playback_context() {
 fn test_offset() {
     let json = r#"
   {
-    "position": 5,
-    "uri": "spotify:track:1301WleyT98MSxVHPZCA6M"
+    "position": 5
   }
   "#;
     let offset: Offset = serde_json::from_str(&json).unwrap();
-    let duration = Duration::from_millis(5);
-    assert_eq!(offset.position, Some(duration));
+    assert_round_trip(&offset);
+    assert_eq!(offset, Offset::Position(5));
 
-    let empty_json = r#"
-  {
+    let track = TrackId::from_id_or_uri("1301WleyT98MSxVHPZCA6M").unwrap();
+    let offset = Offset::Uri(track.into());
+    assert_round_trip(&offset);
+    assert_eq!(
+        serde_json::to_value(&offset).unwrap(),
+        serde_json::json!({ "uri": "spotify:track:1301WleyT98MSxVHPZCA6M" })
+    );
 
-  }
-  "#;
-    let empty_offset: Offset = serde_json::from_str(&empty_json).unwrap();
-    assert!(empty_offset.position.is_none());
+    let json = r#"{ "uri": "spotify:album:0sNOF9WDwhWunNAHPD3Baj" }"#;
+    assert!(serde_json::from_str::<Offset>(json).is_err());
 }
 
 #[test]
@@ -869,7 +1382,10 @@ fn test_audio_analysis_track() {
   }
   "#;
     let audio_analysis_track: AudioAnalysisTrack = serde_json::from_str(&json).unwrap();
+    assert_round_trip(&audio_analysis_track);
     assert_eq!(audio_analysis_track.mode, Modality::Minor);
+    assert_eq!(audio_analysis_track.key.to_string(), "F");
+    assert_eq!(audio_analysis_track.time_signature.to_string(), "4/4");
 }
 
 #[test]
@@ -913,9 +1429,318 @@ fn test_simplified_playlist() {
   } 
   "#;
     let simplified_playlist: SimplifiedPlaylist = serde_json::from_str(&json).unwrap();
+    assert_round_trip(&simplified_playlist);
     assert_eq!(
         simplified_playlist.tracks.href,
         "https://api.spotify.com/v1/playlists/37i9dQZF1DX8mBRYewE6or/tracks"
     );
     assert_eq!(simplified_playlist.tracks.total, 62);
+    assert_eq!(
+        simplified_playlist.description.as_deref(),
+        Some("Chegou o grande dia, aperte o play e partiu fim de semana!")
+    );
+    assert_eq!(simplified_playlist.primary_color, None);
+}
+
+#[test]
+fn test_playlist_description_unescaped() {
+    let json = r#"
+  {
+    "collaborative": true,
+    "description": "Rock &amp; roll, &#x27;90s &lt;3",
+    "external_urls": {},
+    "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DX8mBRYewE6or",
+    "id": "37i9dQZF1DX8mBRYewE6or",
+    "images": [],
+    "name": "Sexta",
+    "owner": {
+      "external_urls": {},
+      "href": "https://api.spotify.com/v1/users/spotify",
+      "id": "spotify",
+      "type": "user",
+      "uri": "spotify:user:spotify"
+    },
+    "primary_color": null,
+    "public": null,
+    "snapshot_id": "MTYxMzM5MzIyMyww",
+    "tracks": {
+      "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DX8mBRYewE6or/tracks",
+      "total": 62
+    },
+    "type": "playlist",
+    "uri": "spotify:playlist:37i9dQZF1DX8mBRYewE6or"
+  }
+  "#;
+    let playlist: SimplifiedPlaylist = serde_json::from_str(json).unwrap();
+    assert_round_trip(&playlist);
+    assert!(playlist.collaborative);
+    assert_eq!(playlist.public, None);
+    assert_eq!(
+        playlist.description.as_deref(),
+        Some("Rock & roll, '90s <3")
+    );
+
+    // The text is escaped back, so that references written literally are
+    // kept as well.
+    let mut literal = playlist.clone();
+    literal.description = Some("Use &amp; for &".to_owned());
+    let serialized = serde_json::to_value(&literal).unwrap();
+    assert_eq!(serialized["description"], "Use &amp;amp; for &amp;");
+    assert_round_trip(&literal);
+
+    let json = json.replace(r#""Rock &amp; roll, &#x27;90s &lt;3""#, "null");
+    let playlist: SimplifiedPlaylist = serde_json::from_str(&json).unwrap();
+    assert_round_trip(&playlist);
+    assert_eq!(playlist.description, None);
+
+    // The description of the full playlists is empty when it's null, unless
+    // the models are strict.
+    let description = html_unescaped::deserialize(serde_json::Value::Null);
+    if cfg!(feature = "strict-models") {
+        assert!(description.is_err());
+    } else {
+        assert_eq!(description.unwrap(), "");
+    }
+}
+
+#[test]
+fn test_restrictions() {
+    let json = r#"
+    {
+        "artists": [],
+        "available_markets": null,
+        "disc_number": 1,
+        "duration_ms": 207959,
+        "explicit": true,
+        "external_urls": {},
+        "href": null,
+        "id": "11dFghVXANMlKmJXsNCbNl",
+        "is_local": false,
+        "is_playable": false,
+        "restrictions": {
+            "reason": "explicit"
+        },
+        "name": "Cut To The Feeling",
+        "preview_url": null,
+        "track_number": 1,
+        "type": "track",
+        "uri": "spotify:track:11dFghVXANMlKmJXsNCbNl"
+    }
+    "#;
+    let mut track: SimplifiedTrack = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        track.restrictions,
+        Some(Restrictions {
+            reason: RestrictionReason::Explicit
+        })
+    );
+    assert_eq!(
+        track.restriction_reason(),
+        Some(RestrictionReason::Explicit)
+    );
+    assert!(!track.is_relinked());
+
+    track.restrictions = None;
+    assert_eq!(track.restriction_reason(), Some(RestrictionReason::Market));
+    track.is_playable = Some(true);
+    assert_eq!(track.restriction_reason(), None);
+
+    let reason: RestrictionReason = serde_json::from_str("\"payment_required\"").unwrap();
+    assert_round_trip(&reason);
+    assert_eq!(reason, RestrictionReason::PaymentRequired);
+    assert_eq!(reason.to_string(), "payment_required");
+}
+
+#[test]
+fn test_playable_in() {
+    let json = r#"
+    {
+        "artists": [],
+        "available_markets": ["ES", "US"],
+        "disc_number": 1,
+        "duration_ms": 207959,
+        "explicit": false,
+        "external_urls": {},
+        "href": null,
+        "id": "11dFghVXANMlKmJXsNCbNl",
+        "is_local": false,
+        "is_playable": null,
+        "linked_from": null,
+        "restrictions": null,
+        "name": "Cut To The Feeling",
+        "preview_url": null,
+        "track_number": 1,
+        "type": "track",
+        "uri": "spotify:track:11dFghVXANMlKmJXsNCbNl"
+    }
+    "#;
+    let spain = Market::Country(Country::Spain);
+    let france = Market::Country(Country::France);
+    let mut track: SimplifiedTrack = serde_json::from_str(json).unwrap();
+    assert!(track.is_playable_in(&spain));
+    assert!(!track.is_playable_in(&france));
+    assert!(track.is_playable_in(&Market::FromToken));
+
+    // Once relinked for a market, that's what counts.
+    track.is_playable = Some(true);
+    assert!(track.is_playable_in(&france));
+    track.is_playable = Some(false);
+    assert!(!track.is_playable_in(&spain));
+    track.is_playable = None;
+    track.restrictions = Some(Restrictions {
+        reason: RestrictionReason::Product,
+    });
+    assert!(!track.is_playable_in(&spain));
+    track.restrictions = None;
+    track.is_local = true;
+    assert!(!track.is_playable_in(&spain));
+}
+
+#[test]
+fn test_playing_item() {
+    // The errors come from the kind of item given in its type.
+    let json_str = r#"
+    {
+        "type": "episode",
+        "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+    }
+    "#;
+    let err = serde_json::from_str::<PlayingItem>(json_str).unwrap_err();
+    assert!(err.to_string().contains("description"), "{}", err);
+
+    let json_str = r#"
+    {
+        "album": {
+            "album_type": null,
+            "artists": [],
+            "external_urls": {},
+            "href": null,
+            "id": null,
+            "images": [],
+            "name": "Demos",
+            "type": "album",
+            "uri": null
+        },
+        "artists": [],
+        "duration_ms": 182000,
+        "is_local": true,
+        "name": "First Take",
+        "type": "track",
+        "uri": "spotify:local:The+Band:Demos:First+Take:182"
+    }
+    "#;
+    let item = serde_json::from_str::<PlayingItem>(json_str).unwrap();
+    assert!(matches!(item, PlayingItem::Local(_)));
+    assert_eq!(item.duration(), Duration::from_secs(182));
+    assert_eq!(item.uri(), "spotify:local:The+Band:Demos:First+Take:182");
+
+    // Serialized items can be read back.
+    assert_round_trip(&item);
+}
+
+#[test]
+fn test_unknown_fields() {
+    let json_str = r#"
+    {
+        "height": 64,
+        "url": "https://i.scdn.co/image/5a73a056d0af707b4119a883d87285feda543fbb",
+        "width": 64,
+        "color": "white"
+    }
+    "#;
+    let image = serde_json::from_str::<Image>(json_str);
+    assert_eq!(image.is_err(), cfg!(feature = "strict-models"));
+}
+
+#[test]
+fn test_page() {
+    let json_str = r#"
+    {
+        "href": "https://api.spotify.com/v1/me/albums?offset=20&limit=20",
+        "items": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20],
+        "limit": 20,
+        "next": "https://api.spotify.com/v1/me/albums?offset=40&limit=20",
+        "offset": 20,
+        "previous": "https://api.spotify.com/v1/me/albums?offset=0&limit=20",
+        "total": 81
+    }
+    "#;
+    let page = serde_json::from_str::<Page<u32>>(json_str).unwrap();
+    assert!(page.has_next());
+    assert_eq!(page.next_offset(), Some(40));
+    assert_eq!(page.pages_remaining(), 3);
+    assert_eq!((&page).into_iter().max(), Some(&20));
+
+    let page = page.map(|item| item.to_string());
+    assert_eq!(page.items[0], "1");
+    assert_eq!(page.total, 81);
+    assert_eq!(page.into_iter().count(), 20);
+
+    let json_str = r#"
+    {
+        "href": "https://api.spotify.com/v1/me/albums?offset=80&limit=20",
+        "items": [81],
+        "limit": 20,
+        "next": null,
+        "offset": 80,
+        "previous": "https://api.spotify.com/v1/me/albums?offset=60&limit=20",
+        "total": 81
+    }
+    "#;
+    let last = serde_json::from_str::<Page<u32>>(json_str).unwrap();
+    assert!(!last.has_next());
+    assert_eq!(last.next_offset(), None);
+    assert_eq!(last.pages_remaining(), 0);
+}
+
+#[cfg(feature = "fixtures")]
+#[test]
+fn test_full_to_simplified() {
+    let full_track = FullTrack::fixture();
+    let simplified = SimplifiedTrack::from(full_track.clone());
+    assert_eq!(simplified.name, full_track.name);
+    assert_eq!(simplified.available_markets, Some(vec!["ZA".to_owned()]));
+    assert_eq!(simplified, full_track);
+
+    // The tracks of the album are the same ones as the full tracks, even if
+    // their fields differ.
+    let full_album = FullAlbum::fixture();
+    assert_eq!(full_album.tracks.items[0], full_track);
+    assert_eq!(full_track.album, full_album);
+    let album = SimplifiedAlbum::from(full_album.clone());
+    assert_eq!(album.album_type, Some(full_album.album_type));
+    assert_eq!(album.release_date.as_ref(), Some(&full_album.release_date));
+    assert_eq!(full_album, album);
+
+    let mut local = simplified;
+    local.id = None;
+    assert_ne!(local, full_track);
+
+    let full_artist = FullArtist::fixture();
+    let artist = SimplifiedArtist::from(full_artist.clone());
+    assert_eq!(artist, full_artist);
+    assert_ne!(full_track.artists[0], full_artist);
+}
+
+#[test]
+fn test_parameters_round_trip() {
+    let selector = DeviceSelector::Name("Office".to_owned());
+    assert_eq!(
+        serde_json::to_string(&selector).unwrap(),
+        r#"{"name":"Office"}"#
+    );
+    assert_round_trip(&selector);
+    assert_round_trip(&TimeLimits::Before(Utc::now()));
+}
+
+#[cfg(feature = "fixtures")]
+#[test]
+fn test_fixtures_round_trip() {
+    assert_round_trip(&FullTrack::fixture());
+    assert_round_trip(&FullArtist::fixture());
+    assert_round_trip(&FullAlbum::fixture());
+    assert_round_trip(&PrivateUser::fixture());
+    assert_round_trip(&SimplifiedPlaylist::fixture());
+    assert_round_trip(&Page::<SavedTrack>::fixture());
+    assert_round_trip(&Page::<SimplifiedPlaylist>::fixture());
 }
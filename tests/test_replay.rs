@@ -0,0 +1,1227 @@
+//! Offline tests replaying the interactions recorded in `tests/cassettes`.
+
+mod common;
+
+use common::maybe_async_test;
+use rspotify::client::{
+    BaseHTTPClient, ClientError, ClientResult, DynHTTPClient, Form, Query, RateLimitHook,
+    ReplayClient, RequestHook, RetryPolicy, Sleeper, Spotify, SpotifyBuilder, TRANSFER_RETRY_DELAY,
+};
+use rspotify::model::{
+    AlbumId, Country, DeviceSelector, EpisodeId, FullTrack, Market, PlayingItem, PlaylistDef,
+    SavedTracksSnapshot, SearchResult, SearchType, SimplifiedPlaylist, TimeLimits, TrackId,
+};
+use rspotify::oauth2::{
+    CredentialsBuilder, MemoryTokenCache, OAuthBuilder, Resumed, Token, TokenBuilder, TokenCache,
+};
+use rspotify::prelude::*;
+
+use chrono::{DateTime, Duration, Utc};
+use maybe_async::maybe_async;
+use std::sync::{Arc, Mutex};
+use std::{env, fs};
+
+/// A client that replays the given cassette, with dummy credentials since
+/// they were redacted when recording.
+fn replay_client(cassette: &str) -> Spotify {
+    let creds = CredentialsBuilder::default()
+        .id("dummy-id")
+        .secret("dummy-secret")
+        .build()
+        .unwrap();
+    let http = ReplayClient::replay(format!("tests/cassettes/{}.json", cassette)).unwrap();
+
+    SpotifyBuilder::default()
+        .credentials(creds)
+        .build()
+        .unwrap()
+        .with_replay(http)
+}
+
+/// A client with a valid user token that replays the given cassette, along
+/// with the replayed HTTP client to check that all its interactions were made.
+fn user_replay_client(cassette: &str) -> (Spotify, ReplayClient) {
+    user_replay_client_with(cassette, &mut SpotifyBuilder::default())
+}
+
+/// The same as [`user_replay_client`], with the other options set in the
+/// given builder.
+fn user_replay_client_with(
+    cassette: &str,
+    builder: &mut SpotifyBuilder,
+) -> (Spotify, ReplayClient) {
+    let tok = TokenBuilder::default()
+        .access_token("user-token")
+        .expires_at(Utc::now() + Duration::seconds(3600))
+        .build()
+        .unwrap();
+    let replay = ReplayClient::replay(format!("tests/cassettes/{}.json", cassette)).unwrap();
+    let spotify = builder
+        .token(tok)
+        .build()
+        .unwrap()
+        .with_replay(replay.clone());
+    (spotify, replay)
+}
+
+/// Collects all the items of a paginated endpoint, panicking on errors.
+#[cfg(feature = "__async")]
+async fn collect<T>(items: impl futures::stream::Stream<Item = ClientResult<T>>) -> Vec<T> {
+    use futures::stream::TryStreamExt;
+    items.try_collect().await.unwrap()
+}
+
+/// Collects all the items of a paginated endpoint, panicking on errors.
+#[cfg(feature = "__sync")]
+fn collect<T>(items: impl Iterator<Item = ClientResult<T>>) -> Vec<T> {
+    items.collect::<ClientResult<_>>().unwrap()
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_track() {
+    let mut spotify = replay_client("track");
+    spotify.request_client_token_without_cache().await.unwrap();
+
+    let track = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+}
+
+/// The requests behind the trait object are the same ones, like the cassette
+/// was recorded without it.
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_dyn_http() {
+    let replay: ReplayClient = ReplayClient::replay("tests/cassettes/track.json").unwrap();
+    let http = DynHTTPClient::new(replay.clone());
+
+    let mut form = Form::new();
+    form.insert("grant_type", "client_credentials");
+    let token = http
+        .post_form("https://accounts.spotify.com/api/token", None, &form)
+        .await;
+    assert!(token.is_ok());
+    let url = "https://api.spotify.com/v1/tracks/11dFghVXANMlKmJXsNCbNl";
+    let track: FullTrack = http.get_json(url, None, &Query::new()).await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+    assert_eq!(replay.remaining(), 1);
+}
+
+#[cfg(feature = "lite-models")]
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_track_lite() {
+    let mut spotify = replay_client("track");
+    spotify.request_client_token_without_cache().await.unwrap();
+
+    let track = spotify.track_lite("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+    assert_eq!(track.id.as_deref(), Some("11dFghVXANMlKmJXsNCbNl"));
+    assert_eq!(track.artists[0].name, "Carly Rae Jepsen");
+    assert_eq!(track.album.name, track.name);
+}
+
+#[cfg(feature = "previews")]
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_download_preview() {
+    let mut spotify = replay_client("preview");
+    spotify.request_client_token_without_cache().await.unwrap();
+
+    let mut track = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    let preview = spotify.download_preview(&track).await.unwrap().unwrap();
+    assert!(preview.starts_with(b"ID3"));
+    assert_eq!(preview.len(), 14);
+
+    track.preview_url = None;
+    let preview = spotify.download_preview(&track).await.unwrap();
+    assert_eq!(preview, None);
+}
+
+/// Only the fields of a track that are used, for `endpoint_get_as`.
+#[derive(serde::Deserialize)]
+struct TrackName {
+    id: String,
+    name: String,
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_endpoint_get_as() {
+    let mut spotify = replay_client("track");
+    spotify.request_client_token_without_cache().await.unwrap();
+
+    let track: TrackName = spotify
+        .endpoint_get_as("tracks/11dFghVXANMlKmJXsNCbNl", &Query::new())
+        .await
+        .unwrap();
+    assert_eq!(track.id, "11dFghVXANMlKmJXsNCbNl");
+    assert_eq!(track.name, "Cut To The Feeling");
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_dedup_requests() {
+    let mut spotify = replay_client("track");
+    spotify.dedup_requests = true;
+    spotify.request_client_token_without_cache().await.unwrap();
+
+    // Requests that aren't in flight at the same time are made separately.
+    let track = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+    let err = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap_err();
+    assert!(matches!(err, ClientError::Replay(_)));
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_status_error() {
+    let mut spotify = replay_client("track");
+    spotify.request_client_token_without_cache().await.unwrap();
+
+    let err = spotify.track("0000000000000000000000").await.unwrap_err();
+    assert!(matches!(err, ClientError::StatusCode(404, _)));
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_missing_interaction() {
+    let mut spotify = replay_client("track");
+    spotify.request_client_token_without_cache().await.unwrap();
+
+    let err = spotify.artist("0OdUWJ0sBjDrqHygGUXeCF").await.unwrap_err();
+    assert!(matches!(err, ClientError::Replay(_)));
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_client_token_renewal() {
+    // No token was requested, so it's obtained automatically before the
+    // first request.
+    let spotify = replay_client("track");
+    let track = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+
+    // The expired token is renewed as well.
+    let expired = TokenBuilder::default()
+        .access_token("expired")
+        .expires_at(Utc::now() - Duration::seconds(10))
+        .build()
+        .unwrap();
+    let mut spotify = replay_client("track");
+    spotify.token = Some(expired);
+    spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_prompt_for_user_token() {
+    let oauth = OAuthBuilder::default()
+        .redirect_uri("http://localhost:8888/callback")
+        .state("abcdefgh")
+        .scope(vec!["user-read-private".to_owned()].into_iter().collect())
+        .build()
+        .unwrap();
+    let mut spotify = replay_client("user_token");
+    spotify.oauth = Some(oauth);
+    spotify.cache_path = env::temp_dir().join("rspotify_test_replay_prompt.json");
+    let _ = fs::remove_file(&spotify.cache_path);
+
+    // The prompt doesn't need a terminal, it just has to return the URL the
+    // user was redirected to.
+    let prompt = |url: &str| {
+        assert!(url.starts_with("https://accounts.spotify.com/authorize"));
+        Ok("http://localhost:8888/callback?code=AQD0yXvFEOvw&state=abcdefgh".to_owned())
+    };
+    spotify.prompt_for_user_token_with(&prompt).await.unwrap();
+    assert!(spotify.token.is_some());
+
+    // The second time the token is read from the cache, without prompting.
+    let never = |_: &str| -> ClientResult<String> { panic!("the user was prompted twice") };
+    spotify.token = None;
+    spotify.prompt_for_user_token_with(&never).await.unwrap();
+    assert!(spotify.token.is_some());
+
+    fs::remove_file(&spotify.cache_path).unwrap();
+}
+
+#[cfg(feature = "cli")]
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_stream_prompt() {
+    use rspotify::oauth2::StreamPrompt;
+    use std::io::Cursor;
+
+    let oauth = OAuthBuilder::default()
+        .redirect_uri("http://localhost:8888/callback")
+        .state("abcdefgh")
+        .scope(vec!["user-read-private".to_owned()].into_iter().collect())
+        .build()
+        .unwrap();
+    let mut spotify = replay_client("user_token");
+    spotify.oauth = Some(oauth);
+    spotify.cache_path = env::temp_dir().join("rspotify_test_replay_stream_prompt.json");
+    let _ = fs::remove_file(&spotify.cache_path);
+
+    let input = Cursor::new("http://localhost:8888/callback?code=AQD0yXvFEOvw&state=abcdefgh\n");
+    let prompt = StreamPrompt::new(input, Vec::new());
+    spotify.prompt_for_user_token_with(&prompt).await.unwrap();
+    assert!(spotify.token.is_some());
+
+    let (_, output) = prompt.into_inner().unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("https://accounts.spotify.com/authorize"));
+
+    // Nothing else can be read from the input.
+    let prompt = StreamPrompt::new(Cursor::new(""), Vec::new());
+    let url = spotify.get_authorize_url(&Default::default()).unwrap();
+    let result = rspotify::oauth2::AuthorizationPrompt::prompt(&prompt, &url).await;
+    assert!(matches!(result, Err(ClientError::CLI(_))));
+
+    fs::remove_file(&spotify.cache_path).unwrap();
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_market_from_profile() {
+    let (spotify, replay) = user_replay_client_with(
+        "market_from_profile",
+        SpotifyBuilder::default().market_from_profile(true),
+    );
+
+    // The profile is only requested once, and its country is used as the
+    // market in both requests.
+    for _ in 0..2 {
+        let tracks = spotify.tracks(vec!["11dFghVXANMlKmJXsNCbNl"], None).await;
+        assert_eq!(tracks.unwrap().len(), 1);
+    }
+    // The playback is requested with the same `market` key, and nothing is
+    // playing.
+    let playback = spotify.current_playback(None, None).await;
+    assert_eq!(playback.unwrap(), None);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_playable_markets() {
+    let (spotify, replay) = user_replay_client("playable_markets");
+
+    let markets = spotify
+        .playable_markets("spotify:album:0tGPJ0bkWOUmH7MEOR77qc")
+        .await
+        .unwrap();
+    assert_eq!(markets, ["ES", "US", "ZA"]);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_hydrate_playlist() {
+    let (spotify, replay) = user_replay_client("hydrate_playlist");
+
+    let mut playlist: SimplifiedPlaylist =
+        serde_json::from_str(include_str!("../src/fixtures/simplified_playlist.json")).unwrap();
+    assert_eq!(playlist.hydration_requests(), 1);
+    playlist.tracks.total = 250;
+    assert_eq!(playlist.hydration_requests(), 3);
+
+    // The items after the first page are requested too.
+    let full = spotify.hydrate_playlist(&playlist, None).await.unwrap();
+    assert_eq!(full.id, playlist.id);
+    assert_eq!(full.tracks.items.len(), 3);
+    assert_eq!(full.tracks.next, None);
+    let added = full.tracks.items[2].added_at.unwrap();
+    assert_eq!(added.to_rfc3339(), "2021-02-03T12:00:00+00:00");
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_is_following_playlist() {
+    let (spotify, replay) = user_replay_client("following_playlist");
+
+    // The ID of the current user is only requested once.
+    let following = spotify
+        .is_following_playlist("spotify:playlist:37i9dQZF1DXcBWIGoYBM5M")
+        .await;
+    assert!(following.unwrap());
+    let following = spotify
+        .is_following_playlist("59ZbFPES4DQwEjBpWHzrtC")
+        .await;
+    assert!(!following.unwrap());
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_explicit_from_profile() {
+    let (spotify, replay) = user_replay_client_with(
+        "explicit_from_profile",
+        SpotifyBuilder::default().explicit_from_profile(true),
+    );
+
+    // The user's filter is enabled, so the explicit track is removed.
+    let result = spotify
+        .search(
+            "cut to the feeling",
+            SearchType::Track,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await;
+    let tracks = match result.unwrap() {
+        SearchResult::Tracks(page) => page.items,
+        result => panic!("unexpected search result: {:?}", result),
+    };
+    assert_eq!(tracks.len(), 1);
+    assert!(!tracks[0].explicit);
+
+    // The profile is already cached.
+    let mut explicit = tracks[0].clone();
+    explicit.explicit = true;
+    let filtered = spotify.filter_explicit(vec![explicit]).await.unwrap();
+    assert!(filtered.is_empty());
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_current_user_queue() {
+    let (spotify, replay) = user_replay_client("user_queue");
+
+    let queue = spotify.current_user_queue().await.unwrap();
+    match queue.currently_playing.unwrap() {
+        PlayingItem::Track(track) => assert_eq!(track.name, "Cut To The Feeling"),
+        item => panic!("unexpected currently playing item {:?}", item),
+    }
+    assert_eq!(queue.queue.len(), 3);
+    assert!(matches!(queue.queue[0], PlayingItem::Episode(_)));
+    match &queue.queue[1] {
+        PlayingItem::Local(local) => assert_eq!(local.name, "First Take"),
+        item => panic!("local file parsed as {:?}", item),
+    }
+    assert_eq!(queue.queue[2].uri(), "spotify:track:11dFghVXANMlKmJXsNCbNl");
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_sync_saved_tracks() {
+    let (spotify, replay) = user_replay_client("saved_tracks_sync");
+    let id = |i: u32| format!("{:022}", i);
+
+    // Track 0 was saved and track 2 removed since the snapshot, which only
+    // the totals tell in an incremental sync.
+    let previous = SavedTracksSnapshot {
+        ids: vec![id(1), id(2), id(3)],
+        latest_added_at: None,
+    };
+    let sync = spotify
+        .sync_saved_tracks(Some(&previous), false)
+        .await
+        .unwrap();
+    let added: Vec<_> = sync
+        .added
+        .iter()
+        .map(|saved| saved.track.name.as_str())
+        .collect();
+    assert_eq!(added, ["Track 0"]);
+    assert!(sync.removed.is_empty());
+    assert!(sync.incomplete);
+    assert_eq!(sync.snapshot.ids, [id(0), id(1), id(2), id(3)]);
+    assert_eq!(
+        sync.snapshot.latest_added_at.unwrap().to_rfc3339(),
+        "2021-09-01T00:00:00+00:00"
+    );
+
+    let sync = spotify
+        .sync_saved_tracks(Some(&sync.snapshot), true)
+        .await
+        .unwrap();
+    let added: Vec<_> = sync
+        .added
+        .iter()
+        .map(|saved| saved.track.name.as_str())
+        .collect();
+    assert_eq!(added, ["Track 4"]);
+    assert_eq!(sync.removed, [id(1), id(3)]);
+    assert!(!sync.incomplete);
+    assert_eq!(sync.snapshot.ids, [id(4), id(0), id(2)]);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_request_hook() {
+    let signed = Arc::new(Mutex::new(Vec::new()));
+    let hook = {
+        let signed = Arc::clone(&signed);
+        RequestHook::new(move |request| {
+            assert_eq!(request.headers()["authorization"], "Bearer user-token");
+            let signature = format!("{} {}", request.method(), request.url());
+            request.insert_header("x-signature", signature.clone());
+            signed.lock().unwrap().push(signature);
+        })
+    };
+    let (spotify, replay) =
+        user_replay_client_with("request_hook", SpotifyBuilder::default().request_hook(hook));
+
+    // The query is part of the signed URL, sorted.
+    let page = spotify.current_user_saved_tracks(50, 0).await.unwrap();
+    assert_eq!(page.items.len(), 3);
+    assert_eq!(
+        *signed.lock().unwrap(),
+        ["GET https://api.spotify.com/v1/me/tracks?limit=50&offset=0"]
+    );
+    assert_eq!(replay.remaining(), 0);
+}
+
+/// An invalid header added by the hook fails the request before it's sent.
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_request_hook_invalid_header() {
+    let hook = RequestHook::new(|request| request.insert_header("x trace", "a\nb"));
+    let (spotify, replay) =
+        user_replay_client_with("request_hook", SpotifyBuilder::default().request_hook(hook));
+
+    let result = spotify.current_user_saved_tracks(50, 0).await;
+    assert!(matches!(
+        result,
+        Err(ClientError::InvalidHeader { name, .. }) if name == "x trace"
+    ));
+    assert_eq!(replay.remaining(), 1);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_categories_language() {
+    let languages = Arc::new(Mutex::new(Vec::new()));
+    let hook = {
+        let languages = Arc::clone(&languages);
+        RequestHook::new(move |request| {
+            let language = request.headers().get("accept-language").cloned();
+            languages.lock().unwrap().push(language);
+        })
+    };
+    let (spotify, replay) = user_replay_client_with(
+        "categories_language",
+        SpotifyBuilder::default()
+            .language("es-MX")
+            .request_hook(hook),
+    );
+
+    let categories = spotify.categories(None, None, None, None).await.unwrap();
+    assert_eq!(categories.items[0].name, "Éxitos");
+
+    // The locale overrides the language of the client.
+    let categories = spotify
+        .categories(Some("en_US".parse().unwrap()), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(categories.items[0].name, "Top Lists");
+    assert_eq!(
+        *languages.lock().unwrap(),
+        [Some("es-MX".to_owned()), Some("es-MX".to_owned())]
+    );
+    assert_eq!(replay.remaining(), 0);
+}
+
+/// A language that can't be sent as a header fails the request before it's
+/// sent, instead of injecting other headers.
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_invalid_language() {
+    let (spotify, replay) = user_replay_client_with(
+        "categories_language",
+        SpotifyBuilder::default().language("fr\r\nX: y"),
+    );
+
+    let result = spotify.categories(None, None, None, None).await;
+    assert!(matches!(
+        result,
+        Err(ClientError::InvalidHeader { name, .. }) if name == "accept-language"
+    ));
+    assert_eq!(replay.remaining(), 2);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_transfer_playback_to() {
+    let sleeper = Arc::new(RecordingSleeper::default());
+    let (spotify, replay) = user_replay_client_with(
+        "transfer_playback",
+        SpotifyBuilder::default().sleeper(sleeper.clone()),
+    );
+
+    // The device only shows up when listed again, and the first transfer
+    // races it.
+    let speaker = "5fbb3ba6aa454b5534c4ba43a8c7e8e45a63ad0e";
+    spotify
+        .transfer_playback_to(speaker, true, true)
+        .await
+        .unwrap();
+
+    let result = spotify.transfer_playback_to(speaker, true, true).await;
+    assert!(matches!(result, Err(ClientError::DeviceRestricted(id)) if id == speaker));
+    assert_eq!(replay.remaining(), 0);
+
+    // Both retries waited with the configured sleeper.
+    assert_eq!(
+        *sleeper.0.lock().unwrap(),
+        vec![TRANSFER_RETRY_DELAY, TRANSFER_RETRY_DELAY]
+    );
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_default_device() {
+    let (spotify, replay) = user_replay_client_with(
+        "default_device",
+        SpotifyBuilder::default().default_device(DeviceSelector::Name("Office".to_owned())),
+    );
+
+    // The devices are only listed the first time.
+    spotify.pause_playback(None).await.unwrap();
+    spotify.volume(50, None).await.unwrap();
+    let living_room = "74ASZWbe4lXaubB36ztrGX";
+    spotify
+        .next_track(Some(living_room.to_owned()))
+        .await
+        .unwrap();
+
+    let spotify = SpotifyBuilder::default()
+        .token(spotify.token.clone().unwrap())
+        .default_device(DeviceSelector::Name("Garage".to_owned()))
+        .build()
+        .unwrap()
+        .with_replay(replay.clone());
+    let result = spotify.pause_playback(None).await;
+    assert!(matches!(result, Err(ClientError::DeviceNotFound(name)) if name == "Garage"));
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_typed_playback() {
+    let (spotify, replay) = user_replay_client("typed_playback");
+
+    let album = AlbumId::from_id_or_uri("0tGPJ0bkWOUmH7MEOR77qc").unwrap();
+    spotify
+        .start_context_playback(&album, None, None, None)
+        .await
+        .unwrap();
+
+    let track = TrackId::from_id_or_uri("spotify:track:11dFghVXANMlKmJXsNCbNl").unwrap();
+    let episode = EpisodeId::from_id_or_uri("512ojhOuo1ktJprKbVcKyQ").unwrap();
+    spotify
+        .start_uris_playback(&[&track, &episode], None, None, None)
+        .await
+        .unwrap();
+    spotify.add_to_queue(&episode, None).await.unwrap();
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_retries_with_deadline() {
+    let limited = Arc::new(Mutex::new(Vec::new()));
+    let hook = {
+        let limited = Arc::clone(&limited);
+        RateLimitHook::new(move |info| limited.lock().unwrap().push(info.clone()))
+    };
+    let (spotify, replay) = user_replay_client_with(
+        "retries",
+        SpotifyBuilder::default()
+            .retry_policy(RetryPolicy {
+                max_retries: 2,
+                backoff: std::time::Duration::from_millis(10),
+                ..Default::default()
+            })
+            .rate_limit_hook(hook),
+    );
+
+    // The server error is retried after the backoff.
+    let track = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+
+    // Waiting out the `Retry-After` would exceed the deadline, so the error
+    // is returned right away.
+    let start = std::time::Instant::now();
+    let result = spotify
+        .with_deadline(std::time::Duration::from_secs(5))
+        .track("11dFghVXANMlKmJXsNCbNl")
+        .await;
+    assert!(matches!(result, Err(ClientError::RateLimited(Some(30)))));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    assert_eq!(replay.remaining(), 0);
+
+    // Only the rate limited request is reported, even if not retried.
+    let limited = limited.lock().unwrap();
+    assert_eq!(limited.len(), 1);
+    assert_eq!(
+        limited[0].endpoint,
+        "https://api.spotify.com/v1/tracks/11dFghVXANMlKmJXsNCbNl"
+    );
+    assert_eq!(
+        limited[0].retry_after,
+        Some(std::time::Duration::from_secs(30))
+    );
+    assert!(!limited[0].retried);
+}
+
+/// Records the waits instead of sleeping, like a custom runtime would.
+#[derive(Debug, Default)]
+struct RecordingSleeper(Mutex<Vec<std::time::Duration>>);
+
+#[maybe_async]
+impl Sleeper for RecordingSleeper {
+    async fn sleep(&self, duration: std::time::Duration) {
+        self.0.lock().unwrap().push(duration);
+    }
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_retries_custom_sleeper() {
+    let sleeper = Arc::new(RecordingSleeper::default());
+    let (spotify, replay) = user_replay_client_with(
+        "retries",
+        SpotifyBuilder::default()
+            .retry_policy(RetryPolicy {
+                max_retries: 1,
+                backoff: std::time::Duration::from_secs(10),
+                ..Default::default()
+            })
+            .sleeper(sleeper.clone()),
+    );
+
+    // The backoff is waited with the configured sleeper, so the test doesn't
+    // take 10 seconds.
+    let start = std::time::Instant::now();
+    let track = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    assert_eq!(
+        *sleeper.0.lock().unwrap(),
+        vec![std::time::Duration::from_secs(10)]
+    );
+    assert_eq!(replay.remaining(), 1);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_with_meta() {
+    let (spotify, replay) = user_replay_client_with(
+        "retries",
+        SpotifyBuilder::default().retry_policy(RetryPolicy {
+            max_retries: 1,
+            backoff: std::time::Duration::from_millis(10),
+            max_wait: std::time::Duration::from_secs(1),
+            ..Default::default()
+        }),
+    );
+
+    let spotify = spotify.with_meta();
+    assert_eq!(spotify.last_meta(), None);
+
+    // The server error is retried, and the metadata is the one of the
+    // response that succeeded.
+    let track = spotify.track("11dFghVXANMlKmJXsNCbNl").await.unwrap();
+    assert_eq!(track.name, "Cut To The Feeling");
+    let meta = spotify.last_meta().unwrap();
+    assert_eq!(
+        meta.url,
+        "https://api.spotify.com/v1/tracks/11dFghVXANMlKmJXsNCbNl"
+    );
+    assert_eq!(meta.status, 200);
+    assert_eq!(meta.retries, 1);
+    assert!(meta.duration >= std::time::Duration::from_millis(10));
+    assert_eq!(meta.request_id(), None);
+
+    // The metadata of the failed requests is kept too.
+    let result = spotify.track("11dFghVXANMlKmJXsNCbNl").await;
+    assert!(matches!(result, Err(ClientError::RateLimited(Some(30)))));
+    let meta = spotify.last_meta().unwrap();
+    assert_eq!(meta.status, 429);
+    assert_eq!(meta.retries, 0);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_start_playback_queued() {
+    let sleeper = Arc::new(RecordingSleeper::default());
+    let (spotify, replay) = user_replay_client_with(
+        "queued_playback",
+        SpotifyBuilder::default().sleeper(sleeper.clone()),
+    );
+
+    // The first 100 tracks start the playback and the last two are queued.
+    let uris = (0..102)
+        .map(|i| format!("spotify:track:{:022}", i))
+        .collect::<Vec<_>>();
+    let mut progress = Vec::new();
+    spotify
+        .start_playback_queued(
+            None,
+            uris,
+            std::time::Duration::from_secs(1),
+            |done, total| progress.push((done, total)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(progress, vec![(100, 102), (101, 102), (102, 102)]);
+    assert_eq!(
+        *sleeper.0.lock().unwrap(),
+        vec![std::time::Duration::from_secs(1); 2]
+    );
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_saved_tracks_contains_map() {
+    let (spotify, replay) = user_replay_client("saved_tracks_contains");
+
+    // The 51 tracks are checked in two requests.
+    let ids = (0..51).map(|i| format!("{:022}", i)).collect::<Vec<_>>();
+    let saved = spotify
+        .current_user_saved_tracks_contains_map(ids.iter().map(String::as_str))
+        .await
+        .unwrap();
+    let track = |i: usize| TrackId::from_id_or_uri(&ids[i]).unwrap();
+    assert_eq!(saved.len(), 51);
+    assert!(saved[&track(0)]);
+    assert!(!saved[&track(1)]);
+    assert!(saved[&track(50)]);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_playlist_cover_image() {
+    let (spotify, _) = user_replay_client("playlist_cover_image");
+
+    let images = spotify
+        .playlist_cover_image("spotify:playlist:3cEYpjA9oz9GiPac4AsH4n")
+        .await
+        .unwrap();
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0].width, Some(640));
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_current_user_playlists_by_owner() {
+    let (spotify, replay) = user_replay_client("current_user_playlists");
+
+    let owned = spotify.current_user_owned_playlists().await.unwrap();
+    assert_eq!(owned.len(), 1);
+    assert_eq!(owned[0].id, "3cEYpjA9oz9GiPac4AsH4n");
+
+    let followed = spotify.current_user_followed_playlists().await.unwrap();
+    assert_eq!(followed.len(), 1);
+    assert_eq!(followed[0].owner.id, "spotify");
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_refresh_scopes_reduced() {
+    let scope = vec!["user-read-private".to_owned(), "user-top-read".to_owned()];
+    let oauth = OAuthBuilder::default()
+        .redirect_uri("http://localhost:8888/callback")
+        .scope(scope.into_iter().collect())
+        .build()
+        .unwrap();
+    let mut spotify = replay_client("reduced_scopes");
+    spotify.oauth = Some(oauth);
+
+    // The user revoked `user-top-read`, but the new token is still kept.
+    let err = spotify
+        .refresh_user_token_without_cache("refresh-token")
+        .await
+        .unwrap_err();
+    match err {
+        ClientError::ScopesReduced { missing } => assert_eq!(missing, vec!["user-top-read"]),
+        err => panic!("unexpected error: {}", err),
+    }
+    let tok = spotify.token.unwrap();
+    assert!(tok.scope.contains("user-read-private"));
+    assert_eq!(tok.refresh_token.as_deref(), Some("refresh-token"));
+}
+
+/// The refresh token rotated by Spotify replaces the previous one, which is
+/// only kept when none is returned.
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_refresh_rotated() {
+    let mut spotify = replay_client("rotated_refresh_token");
+
+    spotify
+        .refresh_user_token_without_cache("refresh-token")
+        .await
+        .unwrap();
+    let tok = spotify.token.clone().unwrap();
+    assert_eq!(tok.access_token, "first-token");
+    assert_eq!(tok.refresh_token.as_deref(), Some("rotated-refresh-token"));
+
+    let refresh_token = tok.refresh_token.unwrap();
+    spotify
+        .refresh_user_token_without_cache(&refresh_token)
+        .await
+        .unwrap();
+    let tok = spotify.token.unwrap();
+    assert_eq!(tok.access_token, "second-token");
+    assert_eq!(tok.refresh_token.as_deref(), Some("rotated-refresh-token"));
+}
+
+/// A token in the cache of `test_replay_resume` with the given scope.
+fn cached_token(scope: &str, expired: bool) -> Token {
+    let expires_at = match expired {
+        true => Utc::now() - Duration::seconds(60),
+        false => Utc::now() + Duration::seconds(3600),
+    };
+    TokenBuilder::default()
+        .access_token("cached-token")
+        .expires_at(expires_at)
+        .refresh_token("refresh-token")
+        .scope(vec![scope.to_owned()].into_iter().collect())
+        .build()
+        .unwrap()
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_resume() {
+    let oauth = OAuthBuilder::default()
+        .redirect_uri("http://localhost:8888/callback")
+        .state("abcdefgh")
+        .scope(vec!["user-read-private".to_owned()].into_iter().collect())
+        .build()
+        .unwrap();
+    let replay: ReplayClient = ReplayClient::replay("tests/cassettes/resume_session.json").unwrap();
+    let mut spotify = replay_client("resume_session").with_replay(replay.clone());
+    spotify.oauth = Some(oauth);
+    let redirect =
+        |_: &str| Ok("http://localhost:8888/callback?code=AQD0yXvFEOvw&state=abcdefgh".to_owned());
+    let cache = Arc::new(MemoryTokenCache::default());
+
+    // The valid token is used as is.
+    let key = spotify.cache_key.clone();
+    cache
+        .write(&key, &cached_token("user-read-private", false))
+        .unwrap();
+    let resumed = spotify.resume(cache.clone(), &redirect).await.unwrap();
+    assert_eq!(resumed, Resumed::Cached);
+    assert_eq!(replay.remaining(), 2);
+
+    // The expired one is refreshed, and the new one is cached.
+    cache
+        .write(&key, &cached_token("user-read-private", true))
+        .unwrap();
+    let resumed = spotify.resume(cache.clone(), &redirect).await.unwrap();
+    assert_eq!(resumed, Resumed::Refreshed);
+    assert_eq!(replay.remaining(), 1);
+    let cached = cache.read(&key).unwrap().unwrap();
+    assert!(!cached.is_expired());
+    assert_ne!(cached.access_token, "cached-token");
+
+    // Without the configured scopes, the user has to authorize the app.
+    cache
+        .write(&key, &cached_token("user-top-read", false))
+        .unwrap();
+    let resumed = spotify.resume(cache.clone(), &redirect).await.unwrap();
+    assert_eq!(resumed, Resumed::Authorized);
+    assert_eq!(replay.remaining(), 0);
+    let cached = cache.read(&key).unwrap().unwrap();
+    assert!(cached.scope.contains("user-read-private"));
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_recently_played_all() {
+    let (spotify, replay) = user_replay_client("recently_played");
+
+    // Walks back until the history is exhausted, dropping the repeated plays
+    // of the same track, even across pages.
+    let plays = collect(spotify.current_user_recently_played_all(None, true)).await;
+    let ids = plays
+        .iter()
+        .map(|play| play.track.id.as_deref().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        [
+            "4uLU6hMCjMI75M1A2tKUQC",
+            "7ouMYWpwJ422jRcDASZB7P",
+            "2TpxZ7JUBn3uw46aR7qd6V"
+        ]
+    );
+
+    // Stops at the first play before the given time.
+    let after = "2021-02-15T11:59:00Z".parse::<DateTime<Utc>>().unwrap();
+    let plays = collect(spotify.current_user_recently_played_all(Some(after), false)).await;
+    assert_eq!(plays.len(), 2);
+    assert!(plays.iter().all(|play| play.played_at > after));
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_recently_played_between() {
+    let (spotify, replay) = user_replay_client("recently_played_window");
+
+    let page = spotify
+        .current_user_recently_played(
+            50,
+            Some(TimeLimits::Before(
+                "2021-02-15T12:01:00Z".parse::<DateTime<Utc>>().unwrap(),
+            )),
+        )
+        .await
+        .unwrap();
+    let before = page.cursors.before_time().unwrap();
+    assert_eq!(before, page.items.last().unwrap().played_at);
+    assert_eq!(page.cursors.after_time(), Some(page.items[0].played_at));
+
+    // Walks back from the end of the window until its start.
+    let after = "2021-02-15T11:52:00Z".parse::<DateTime<Utc>>().unwrap();
+    let plays = collect(spotify.current_user_recently_played_between(after, before)).await;
+    let played_at = plays
+        .iter()
+        .map(|play| play.played_at.to_rfc3339())
+        .collect::<Vec<_>>();
+    assert_eq!(played_at, ["2021-02-15T11:54:00+00:00"]);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_artists_chunked() {
+    let (spotify, replay) = user_replay_client("artists_chunked");
+
+    // The 60 artists are requested in two requests, keeping their order.
+    let ids = (0..60).map(|i| format!("{:022}", i)).collect::<Vec<_>>();
+    let artists = spotify
+        .artists(ids.iter().map(String::as_str))
+        .await
+        .unwrap();
+    let found = artists.iter().map(|artist| &artist.id).collect::<Vec<_>>();
+    assert_eq!(found, ids.iter().collect::<Vec<_>>());
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_saved_albums_chunked() {
+    let (spotify, replay) = user_replay_client("saved_albums");
+
+    // The 25 albums are saved and checked in two requests each.
+    let ids = (0..25).map(|i| format!("{:022}", i)).collect::<Vec<_>>();
+    spotify
+        .current_user_saved_albums_add(ids.iter().map(String::as_str))
+        .await
+        .unwrap();
+    let saved = spotify
+        .current_user_saved_albums_contains(ids.iter().map(String::as_str))
+        .await
+        .unwrap();
+    assert_eq!(saved.len(), 25);
+    assert!(saved[..24].iter().all(|&saved| saved));
+    assert!(!saved[24]);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_saved_shows() {
+    let (spotify, replay) = user_replay_client("saved_shows");
+
+    let saved = collect(spotify.current_user_saved_shows_all()).await;
+    let names = saved
+        .iter()
+        .map(|saved| saved.show.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Science", "History"]);
+    assert_eq!(
+        saved[1].added_at,
+        "2021-02-01T10:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    );
+
+    // The market is sent in the URL.
+    let market = Market::Country(Country::Spain);
+    spotify
+        .remove_users_saved_shows(vec![saved[1].show.id.as_str()], Some(market))
+        .await
+        .unwrap();
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_saved_audiobooks() {
+    let (spotify, replay) = user_replay_client("saved_audiobooks");
+
+    // The 52 audiobooks are saved and checked in two requests each.
+    let ids = (0..52).map(|i| format!("{:022}", i)).collect::<Vec<_>>();
+    spotify
+        .save_audiobooks(ids.iter().map(String::as_str))
+        .await
+        .unwrap();
+
+    let saved = collect(spotify.current_user_saved_audiobooks_all()).await;
+    let names = saved
+        .iter()
+        .map(|audiobook| audiobook.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Dune", "Emma"]);
+    assert_eq!(saved[0].authors[0].name, "Frank Herbert");
+    assert_eq!(saved[0].edition.as_deref(), Some("Unabridged"));
+    assert_eq!(saved[1].edition, None);
+
+    let contains = spotify
+        .check_saved_audiobooks(ids.iter().map(String::as_str))
+        .await
+        .unwrap();
+    assert_eq!(contains.len(), 52);
+    assert!(contains[..51].iter().all(|&saved| saved));
+    assert!(!contains[51]);
+
+    spotify
+        .remove_saved_audiobooks(vec![saved[1].id.as_str()])
+        .await
+        .unwrap();
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_artist_discography() {
+    let (spotify, replay) = user_replay_client("artist_discography");
+
+    // The second album is the same release as the first one, so it's skipped
+    // without requesting its tracks.
+    let albums = collect(spotify.artist_discography("0OdUWJ0sBjDrqHygGUXeCF", None, true)).await;
+    assert_eq!(albums.len(), 2);
+    assert_eq!(
+        albums[0].album.id.as_deref(),
+        Some("0000000000000000000000")
+    );
+    let tracks = albums[0].tracks.as_ref().unwrap();
+    assert_eq!(tracks.len(), 2);
+    assert_eq!(tracks[0].popularity.map(|p| p.get()), Some(63));
+    assert_eq!(
+        tracks[0].external_ids.as_ref().unwrap().isrc.as_deref(),
+        Some("USUM71703861")
+    );
+    assert_eq!(
+        albums[1].album.id.as_deref(),
+        Some("0000000000000000000002")
+    );
+    assert_eq!(albums[1].tracks.as_ref().unwrap().len(), 1);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_playlist_add_tracks_chunked() {
+    let (spotify, replay) = user_replay_client("playlist_add_tracks");
+
+    // The 150 tracks are added in two requests, the second one right after
+    // the tracks of the first.
+    let ids = (0..150).map(|i| format!("{:022}", i)).collect::<Vec<_>>();
+    let result = spotify
+        .playlist_add_tracks(
+            "3cEYpjA9oz9GiPac4AsH4n",
+            ids.iter().map(String::as_str),
+            Some(5),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.snapshot_id, "MiwxMDM");
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_create_playlists() {
+    let (spotify, replay) = user_replay_client("create_playlists");
+
+    let tracks = |range: std::ops::Range<usize>| range.map(|i| format!("{:022}", i)).collect();
+    let defs = vec![
+        PlaylistDef {
+            description: Some("Imported from another service".to_owned()),
+            ..PlaylistDef::new("Road Trip", tracks(0..150))
+        },
+        PlaylistDef::new("Chill", tracks(150..152)),
+    ];
+
+    // The second request of the first playlist fails, which doesn't stop the
+    // import of the next one.
+    let imports = spotify.create_playlists(defs).await.unwrap();
+    assert!(matches!(
+        imports[0].error,
+        Some(ClientError::StatusCode(502, _))
+    ));
+    assert_eq!(
+        imports[0].def.playlist_id.as_deref(),
+        Some("5C2IUrxHVdplrbyEckjAWb")
+    );
+    assert_eq!(imports[0].def.added, 100);
+    assert!(imports[1].error.is_none());
+    assert!(imports[1].def.is_complete());
+
+    // Resuming only adds the rest of the tracks of the first one.
+    let defs = imports.into_iter().map(|import| import.def).collect();
+    let imports = spotify.create_playlists(defs).await.unwrap();
+    assert!(imports
+        .iter()
+        .all(|import| import.error.is_none() && import.def.is_complete()));
+    assert_eq!(imports[0].def.added, 150);
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_playlist_change_detail() {
+    let (spotify, replay) = user_replay_client("playlist_change_detail");
+
+    // Only the given fields are sent, so that the rest are left unchanged.
+    let id = "3cEYpjA9oz9GiPac4AsH4n";
+    spotify
+        .playlist_change_detail(id, None, None, Some("Rock & roll"), None)
+        .await
+        .unwrap();
+    spotify
+        .playlist_change_detail(id, None, Some(false), None, Some(true))
+        .await
+        .unwrap();
+    assert_eq!(replay.remaining(), 0);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_replay_fade_volume() {
+    let sleeper = Arc::new(RecordingSleeper::default());
+    let (spotify, replay) = user_replay_client_with(
+        "fade_volume",
+        SpotifyBuilder::default().sleeper(sleeper.clone()),
+    );
+
+    // From 20 to 50 in a second, which only leaves time for two steps. They
+    // are waited with the configured sleeper, so the test doesn't take a
+    // second.
+    spotify
+        .fade_volume(50, std::time::Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!(replay.remaining(), 0);
+    assert_eq!(
+        *sleeper.0.lock().unwrap(),
+        vec![std::time::Duration::from_millis(500); 2]
+    );
+}
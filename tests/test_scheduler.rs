@@ -0,0 +1,136 @@
+//! Tests for the scheduling of the requests of multiple clients, against a
+//! local server that records the order in which they arrive.
+
+use rspotify::client::{Query, Scheduler, Spotify, SpotifyBuilder};
+use rspotify::oauth2::TokenBuilder;
+use rspotify::prelude::*;
+
+use chrono::{Duration as ChronoDuration, Utc};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How many requests the heavy client makes at the same time.
+const HEAVY_REQUESTS: usize = 4;
+
+/// Answers a single request, recording its path.
+fn answer(stream: TcpStream, paths: &Mutex<Vec<String>>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim_end().is_empty() {
+            break;
+        }
+    }
+    let path = request_line.split(' ').nth(1).unwrap_or_default();
+    paths.lock().unwrap().push(path.to_owned());
+
+    let body = r#"{"name":"Rick Astley"}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = (&stream).write_all(response.as_bytes());
+}
+
+/// The prefix of a server that records the paths requested from it.
+fn serve() -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let prefix = format!("http://{}/", listener.local_addr().unwrap());
+    let paths = Arc::new(Mutex::new(Vec::new()));
+    {
+        let paths = Arc::clone(&paths);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let paths = Arc::clone(&paths);
+                thread::spawn(move || answer(stream.unwrap(), &paths));
+            }
+        });
+    }
+    (prefix, paths)
+}
+
+fn client(prefix: &str, scheduler: &Scheduler, user: &str) -> Spotify {
+    let tok = TokenBuilder::default()
+        .access_token("dummy-token")
+        .expires_at(Utc::now() + ChronoDuration::seconds(3600))
+        .build()
+        .unwrap();
+    SpotifyBuilder::default()
+        .token(tok)
+        .prefix(prefix)
+        .scheduler(scheduler.session(user))
+        .build()
+        .unwrap()
+}
+
+/// The first request of the heavy client is sent right away, and then the
+/// light one takes turns with the rest.
+fn check_interleaved(paths: &Mutex<Vec<String>>) {
+    let paths = paths.lock().unwrap();
+    assert_eq!(
+        *paths,
+        [
+            "/artists/heavy",
+            "/artists/heavy",
+            "/artists/light",
+            "/artists/heavy",
+            "/artists/heavy",
+        ]
+    );
+}
+
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_fair_scheduling() {
+    let (prefix, paths) = serve();
+    let scheduler = Scheduler::new(10, Duration::from_secs(1));
+    let heavy = client(&prefix, &scheduler, "heavy");
+    let light = client(&prefix, &scheduler, "light");
+
+    let query = Query::new();
+    let heavy_requests = futures::future::join_all(
+        (0..HEAVY_REQUESTS).map(|_| heavy.endpoint_get_as::<Value>("artists/heavy", &query)),
+    );
+    let light_request = light.endpoint_get_as::<Value>("artists/light", &query);
+    let (heavy_fetched, light_fetched) = futures::join!(heavy_requests, light_request);
+    assert!(heavy_fetched.iter().all(Result::is_ok));
+    assert!(light_fetched.is_ok());
+    assert_eq!(scheduler.queued(), 0);
+    check_interleaved(&paths);
+}
+
+#[cfg(feature = "__sync")]
+#[test]
+fn test_fair_scheduling() {
+    let (prefix, paths) = serve();
+    let scheduler = Scheduler::new(10, Duration::from_secs(1));
+    let heavy = client(&prefix, &scheduler, "heavy");
+    let light = client(&prefix, &scheduler, "light");
+
+    let mut requests = (0..HEAVY_REQUESTS)
+        .map(|_| {
+            let heavy = heavy.clone();
+            thread::spawn(move || heavy.endpoint_get_as::<Value>("artists/heavy", &Query::new()))
+        })
+        .collect::<Vec<_>>();
+    // The light request is only made once the heavy ones are waiting.
+    while scheduler.queued() < HEAVY_REQUESTS - 1 {
+        thread::sleep(Duration::from_millis(1));
+    }
+    requests.push(thread::spawn(move || {
+        light.endpoint_get_as::<Value>("artists/light", &Query::new())
+    }));
+    for request in requests {
+        assert!(request.join().unwrap().is_ok());
+    }
+    assert_eq!(scheduler.queued(), 0);
+    check_interleaved(&paths);
+}
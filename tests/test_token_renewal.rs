@@ -0,0 +1,137 @@
+//! Tests for the automatic renewal of the client tokens, against a local
+//! server that takes a while to grant them.
+
+mod common;
+
+use common::maybe_async_test;
+use rspotify::client::{Spotify, SpotifyBuilder};
+use rspotify::oauth2::CredentialsBuilder;
+
+use maybe_async::maybe_async;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How many requests are made at the same time.
+const REQUESTS: usize = 4;
+
+/// Answers a single request, slowly if it's for a token, which is counted.
+fn answer(stream: TcpStream, tokens: &AtomicUsize) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end().to_ascii_lowercase();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(len) = line.strip_prefix("content-length:") {
+            length = len.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    let body = if request_line.starts_with("POST /api/token") {
+        tokens.fetch_add(1, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(200));
+        r#"{"access_token":"token","token_type":"Bearer","expires_in":3600,"scope":""}"#
+    } else {
+        r#"{"name":"Rick Astley"}"#
+    };
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = (&stream).write_all(response.as_bytes());
+}
+
+/// A client with credentials but no token, for a server that counts the
+/// tokens requested from it.
+fn client() -> (Spotify, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let tokens = Arc::new(AtomicUsize::new(0));
+    {
+        let tokens = Arc::clone(&tokens);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let tokens = Arc::clone(&tokens);
+                thread::spawn(move || answer(stream.unwrap(), &tokens));
+            }
+        });
+    }
+
+    let creds = CredentialsBuilder::default()
+        .id("dummy-id")
+        .secret("dummy-secret")
+        .build()
+        .unwrap();
+    let spotify = SpotifyBuilder::default()
+        .credentials(creds)
+        .prefix(format!("http://{}/", addr))
+        .token_url(format!("http://{}/api/token", addr))
+        .build()
+        .unwrap();
+    (spotify, tokens)
+}
+
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_concurrent_renewal() {
+    let (spotify, tokens) = client();
+    let requests = (0..REQUESTS).map(|_| spotify.get_if_none_match::<Value>("artists/id", None));
+    let fetched = futures::future::join_all(requests).await;
+    assert!(fetched.iter().all(Result::is_ok), "{:?}", fetched);
+    assert_eq!(tokens.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(feature = "__sync")]
+#[test]
+fn test_concurrent_renewal() {
+    let (spotify, tokens) = client();
+    let requests = (0..REQUESTS)
+        .map(|_| {
+            let spotify = spotify.clone();
+            thread::spawn(move || spotify.get_if_none_match::<Value>("artists/id", None))
+        })
+        .collect::<Vec<_>>();
+    for request in requests {
+        assert!(request.join().unwrap().is_ok());
+    }
+    assert_eq!(tokens.load(Ordering::SeqCst), 1);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_renewed_once() {
+    let (spotify, tokens) = client();
+    for _ in 0..2 {
+        let fetched = spotify.get_if_none_match::<Value>("artists/id", None).await;
+        assert!(fetched.is_ok());
+    }
+    assert_eq!(tokens.load(Ordering::SeqCst), 1);
+}
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_clone_shares_renewed_token() {
+    let (spotify, tokens) = client();
+    let clone = spotify.clone();
+    let fetched = clone.get_if_none_match::<Value>("artists/id", None).await;
+    assert!(fetched.is_ok());
+
+    // The token renewed by the clone is used by the original client too.
+    let fetched = spotify.get_if_none_match::<Value>("artists/id", None).await;
+    assert!(fetched.is_ok());
+    assert!(spotify.token.is_none());
+    assert_eq!(tokens.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,130 @@
+//! Tests for the timeout of the requests to the accounts service, against a
+//! local server that never responds, and of the prompts.
+
+mod common;
+
+use common::maybe_async_test;
+use rspotify::client::{ClientError, ClientResult, Spotify, SpotifyBuilder};
+#[cfg(feature = "__async")]
+use rspotify::oauth2::AuthorizationPrompt;
+use rspotify::oauth2::{CredentialsBuilder, MemoryTokenCache, OAuthBuilder};
+
+use maybe_async::maybe_async;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[maybe_async]
+#[maybe_async_test]
+async fn test_token_timeout() {
+    // The connections are accepted by the OS, but nothing is ever answered.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let token_url = format!("http://{}/api/token", listener.local_addr().unwrap());
+
+    let creds = CredentialsBuilder::default()
+        .id("dummy-id")
+        .secret("dummy-secret")
+        .build()
+        .unwrap();
+    let mut spotify = SpotifyBuilder::default()
+        .credentials(creds)
+        .token_url(token_url)
+        .token_timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    let result = spotify.request_client_token_without_cache().await;
+    assert!(matches!(result, Err(ClientError::Timeout)), "{:?}", result);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(spotify.token.is_none());
+}
+
+/// A prompt whose user never answers.
+#[cfg(feature = "__async")]
+struct AbandonedPrompt;
+
+#[cfg(feature = "__async")]
+#[maybe_async]
+impl AuthorizationPrompt for AbandonedPrompt {
+    async fn prompt(&self, _authorize_url: &str) -> ClientResult<String> {
+        futures::future::pending().await
+    }
+}
+
+/// A client with OAuth configured and an empty token cache, so that the user
+/// is always prompted.
+fn oauth_client() -> (Spotify, Arc<MemoryTokenCache>) {
+    let creds = CredentialsBuilder::default()
+        .id("dummy-id")
+        .secret("dummy-secret")
+        .build()
+        .unwrap();
+    let oauth = OAuthBuilder::default()
+        .redirect_uri("http://localhost:8888/callback")
+        .build()
+        .unwrap();
+    let spotify = SpotifyBuilder::default()
+        .credentials(creds)
+        .oauth(oauth)
+        .prompt_timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+    (spotify, Arc::new(MemoryTokenCache::default()))
+}
+
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_prompt_timeout() {
+    let (mut spotify, cache) = oauth_client();
+    let start = Instant::now();
+    let result = spotify.resume(cache, &AbandonedPrompt).await;
+    assert!(matches!(result, Err(ClientError::Timeout)), "{:?}", result);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(spotify.token.is_none());
+}
+
+/// An input where the user never enters anything.
+#[cfg(all(feature = "__sync", feature = "cli"))]
+struct AbandonedInput;
+
+#[cfg(all(feature = "__sync", feature = "cli"))]
+impl std::io::Read for AbandonedInput {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            std::thread::park();
+        }
+    }
+}
+
+#[cfg(all(feature = "__sync", feature = "cli"))]
+#[test]
+fn test_prompt_timeout() {
+    use rspotify::oauth2::StreamPrompt;
+    use std::io::BufReader;
+
+    let (mut spotify, cache) = oauth_client();
+    let prompt = StreamPrompt::new(BufReader::new(AbandonedInput), Vec::new());
+    let start = Instant::now();
+    let result = spotify.resume(cache, &prompt);
+    assert!(matches!(result, Err(ClientError::Timeout)), "{:?}", result);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(spotify.token.is_none());
+
+    // The input is still being read in the thread left behind.
+    assert!(prompt.into_inner().is_none());
+}
+
+/// The custom blocking prompts can't be interrupted.
+#[cfg(feature = "__sync")]
+#[test]
+fn test_prompt_timeout_unsupported() {
+    let (mut spotify, cache) = oauth_client();
+    let prompt = |_: &str| -> ClientResult<String> { unreachable!() };
+    let result = spotify.resume(cache, &prompt);
+    assert!(
+        matches!(result, Err(ClientError::InvalidAuth(_))),
+        "{:?}",
+        result
+    );
+}
@@ -1,8 +1,9 @@
 mod common;
 
 use common::maybe_async_test;
-use rspotify::model::{AlbumType, Country};
+use rspotify::model::{AlbumGroup, Country};
 use rspotify::oauth2::CredentialsBuilder;
+use rspotify::prelude::*;
 use rspotify::{
     client::{Spotify, SpotifyBuilder},
     model::Market,
@@ -86,7 +87,7 @@ async fn test_artists_albums() {
         .await
         .artist_albums(
             birdy_uri,
-            Some(AlbumType::Album),
+            Some(AlbumGroup::Album),
             Some(Market::Country(Country::UnitedStates)),
             Some(10),
             None,
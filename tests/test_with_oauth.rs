@@ -12,14 +12,16 @@
 //! to authenticate with the access token from the `RSPOTIFY_ACCESS_TOKEN`
 //! environment variable or the refresh token from `RSPOTIFY_REFRESH_TOKEN`
 //! (these tokens must have been generated for all available scopes, see
-//! the `oauth_tokens` example).
+//! the `oauth_tokens` example). With the `cli` feature, the token cache or
+//! the authorization prompt are used otherwise.
 
 mod common;
 
 use common::maybe_async_test;
-use rspotify::model::offset::for_position;
+use rspotify::model::offset::Offset;
 use rspotify::model::{Country, RepeatState, SearchType, TimeRange};
 use rspotify::oauth2::{CredentialsBuilder, OAuthBuilder, TokenBuilder};
+use rspotify::prelude::*;
 use rspotify::{
     client::{Spotify, SpotifyBuilder},
     model::Market,
@@ -29,6 +31,7 @@ use chrono::prelude::*;
 use maybe_async::maybe_async;
 use serde_json::map::Map;
 use std::env;
+use std::time::Duration;
 
 /// Generating a new OAuth client for the requests.
 #[maybe_async]
@@ -75,6 +78,12 @@ pub async fn oauth_client() -> Spotify {
 
         spotify
     } else {
+        // Otherwise the cached token is used, or the user is asked for
+        // authorization in the terminal, with the scopes in `RSPOTIFY_SCOPE`.
+        #[cfg(feature = "cli")]
+        return Spotify::from_env_interactive().await.unwrap();
+
+        #[cfg(not(feature = "cli"))]
         panic!(
             "No access tokens configured. Please set `RSPOTIFY_ACCESS_TOKEN` \
              or `RSPOTIFY_REFRESH_TOKEN`, which can be obtained with the \
@@ -166,7 +175,7 @@ async fn test_current_user_playlists() {
 async fn test_current_user_recently_played() {
     oauth_client()
         .await
-        .current_user_recently_played(10)
+        .current_user_recently_played(10, None)
         .await
         .unwrap();
 }
@@ -209,7 +218,7 @@ async fn test_current_user_saved_albums_delete() {
 async fn test_current_user_saved_albums() {
     oauth_client()
         .await
-        .current_user_saved_albums(10, 0)
+        .current_user_saved_albums(10, 0, None)
         .await
         .unwrap();
 }
@@ -486,7 +495,11 @@ async fn test_search_track() {
 #[maybe_async_test]
 #[ignore]
 async fn test_seek_track() {
-    oauth_client().await.seek_track(25000, None).await.unwrap();
+    oauth_client()
+        .await
+        .seek_track(Duration::from_secs(25), None)
+        .await
+        .unwrap();
 }
 
 #[maybe_async]
@@ -504,7 +517,13 @@ async fn test_start_playback() {
     let uris = vec!["spotify:track:4iV5W9uYEdYUVa79Axb7Rh".to_owned()];
     oauth_client()
         .await
-        .start_playback(Some(device_id), None, Some(uris), for_position(0), None)
+        .start_playback(
+            Some(device_id),
+            None,
+            Some(uris),
+            Some(Offset::Position(0)),
+            None,
+        )
         .await
         .unwrap();
 }